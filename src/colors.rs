@@ -18,4 +18,12 @@ limitations under the License.
 
 //a Imports
 mod color_database;
-pub use color_database::{Color, ColorDatabase};
+mod color_database_owned;
+#[cfg(feature = "color-databases")]
+mod color_databases;
+mod color_ramp;
+pub use color_database::{BlendMode, Color, ColorDatabase, ColorKeyword, Rgba};
+pub use color_database_owned::ColorDatabaseOwned;
+#[cfg(feature = "color-databases")]
+pub use color_databases::{MaterialColorDatabase, TailwindColorDatabase, X11ColorDatabase};
+pub use color_ramp::ColorRamp;
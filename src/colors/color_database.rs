@@ -1,9 +1,90 @@
 //a Imports
 use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::Error;
+
+//tp BlendMode
+/// The manner in which a source color painted over a backdrop color
+/// is blended with it, as for the CSS 'mix-blend-mode'
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendMode {
+    /// The source is painted over the backdrop (composited by its
+    /// alpha only)
+    #[default]
+    AlphaOver,
+    /// The colors are multiplied, darkening the backdrop
+    Multiply,
+    /// The inverses of the colors are multiplied, lightening the
+    /// backdrop
+    Screen,
+    /// Multiply or screen, depending on the backdrop
+    Overlay,
+}
+
+//ip BlendMode
+impl BlendMode {
+    //fi blend_channel
+    /// Blend a backdrop and source channel (0 to 1)
+    fn blend_channel(self, b: f64, s: f64) -> f64 {
+        match self {
+            Self::AlphaOver => s,
+            Self::Multiply => b * s,
+            Self::Screen => b + s - b * s,
+            Self::Overlay => {
+                if b <= 0.5 {
+                    2. * b * s
+                } else {
+                    1. - 2. * (1. - b) * (1. - s)
+                }
+            }
+        }
+    }
+}
+
+//tp ColorKeyword
+/// A CSS color keyword that is not a color value, but is resolved by
+/// the renderer
+///
+/// These may be used wherever a color is added to an element (as
+/// '(ColorKeyword, &ColorDatabase)' is convertible to a [Color]), so
+/// that themed icons can take their color from the 'color' property
+/// set by the consumer through CSS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorKeyword {
+    /// The value of the 'color' property of the element ('currentColor')
+    CurrentColor,
+    /// The value of the property for the parent ('inherit')
+    Inherit,
+}
+
+//ip ColorKeyword
+impl ColorKeyword {
+    //ap as_str
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CurrentColor => "currentColor",
+            Self::Inherit => "inherit",
+        }
+    }
+
+    //fp of_name
+    /// Get the keyword for a name (ignoring case), if it is one
+    pub fn of_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "currentcolor" => Some(Self::CurrentColor),
+            "inherit" => Some(Self::Inherit),
+            _ => None,
+        }
+    }
+}
 
 //tp Rgba
 /// Stored as a u32 with (255-alpha) in top 8 bits, then R, then G, then B in bottom 8 bits
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rgba(u32);
 impl From<u32> for Rgba {
     #[inline]
@@ -26,19 +107,19 @@ impl From<(u8, u8, u8, u8)> for Rgba {
 impl From<(f32, f32, f32)> for Rgba {
     #[inline]
     fn from((r, g, b): (f32, f32, f32)) -> Self {
-        let r = (r * 255.9).floor() as u8;
-        let g = (g * 255.9).floor() as u8;
-        let b = (b * 255.9).floor() as u8;
+        let r = unit_to_u8(r as f64);
+        let g = unit_to_u8(g as f64);
+        let b = unit_to_u8(b as f64);
         (r, g, b).into()
     }
 }
 impl From<(f32, f32, f32, f32)> for Rgba {
     #[inline]
     fn from((r, g, b, a): (f32, f32, f32, f32)) -> Self {
-        let r = (r * 255.9).floor() as u8;
-        let g = (g * 255.9).floor() as u8;
-        let b = (b * 255.9).floor() as u8;
-        let a = (a * 255.9).floor() as u8;
+        let r = unit_to_u8(r as f64);
+        let g = unit_to_u8(g as f64);
+        let b = unit_to_u8(b as f64);
+        let a = unit_to_u8(a as f64);
         (r, g, b, a).into()
     }
 }
@@ -57,7 +138,142 @@ impl From<Rgba> for String {
         }
     }
 }
+
+//fi color_error
+fn color_error(color: &str, reason: &str) -> Error {
+    Error::BadColor {
+        color: color.into(),
+        reason: reason.into(),
+    }
+}
+
+//fi hsl_to_rgb
+/// Convert hue (in degrees), saturation and lightness (0 to 1) to RGB
+/// values (0 to 1)
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (f64, f64, f64) {
+    let h = h.rem_euclid(360.);
+    let c = (1. - (2. * l - 1.).abs()) * s;
+    let x = c * (1. - ((h / 60.) % 2. - 1.).abs());
+    let m = l - c / 2.;
+    let (r, g, b) = match (h / 60.) as usize {
+        0 => (c, x, 0.),
+        1 => (x, c, 0.),
+        2 => (0., c, x),
+        3 => (0., x, c),
+        4 => (x, 0., c),
+        _ => (c, 0., x),
+    };
+    (r + m, g + m, b + m)
+}
+
+//fi parse_component
+/// Parse a component of a CSS functional color, returning a value
+/// scaled so that 100% is 'full'
+///
+/// Percentages are divided by 100 before scaling, so that 50% is
+/// exactly half of 'full' (and rounds consistently with 0.5)
+fn parse_component(color: &str, arg: &str, full: f64) -> Result<f64, Error> {
+    let (v, percent) = {
+        if let Some(v) = arg.strip_suffix('%') {
+            (v, true)
+        } else if let Some(v) = arg.strip_suffix("deg") {
+            (v, false)
+        } else {
+            (arg, false)
+        }
+    };
+    v.parse::<f64>()
+        .map(|v| if percent { v / 100. * full } else { v })
+        .map_err(|_| color_error(color, &format!("bad value '{}'", arg)))
+}
+
+//fi unit_to_u8
+fn unit_to_u8(v: f64) -> u8 {
+    (v.clamp(0., 1.) * 255.).round() as u8
+}
+
+impl FromStr for Rgba {
+    type Err = Error;
+    /// Parse a hex color of the form '#rgb', '#rrggbb' or
+    /// '#rrggbbaa', or a CSS functional color such as 'rgb(255 0 0)',
+    /// 'rgba(100%, 0%, 0%, 0.5)' or 'hsl(120deg 50% 25% / 50%)'
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let s = s.trim();
+        if let Some(hex) = s.strip_prefix('#') {
+            Self::parse_hex(s, hex)
+        } else if let Some((func, args)) = s.strip_suffix(')').and_then(|s| s.split_once('(')) {
+            Self::parse_functional(s, func.trim(), args)
+        } else {
+            Err(color_error(
+                s,
+                "expected '#' hex or a functional color such as 'rgb(...)'",
+            ))
+        }
+    }
+}
 impl Rgba {
+    //fi parse_hex
+    fn parse_hex(s: &str, hex: &str) -> Result<Self, Error> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(color_error(s, "expected only hex digits"));
+        }
+        let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap();
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        match hex.len() {
+            3 => Ok((digit(0) * 17, digit(1) * 17, digit(2) * 17).into()),
+            6 => Ok((byte(0), byte(2), byte(4)).into()),
+            8 => Ok((byte(0), byte(2), byte(4), byte(6)).into()),
+            _ => Err(color_error(s, "expected 3, 6 or 8 hex digits")),
+        }
+    }
+
+    //fi parse_functional
+    /// Parse the arguments of an 'rgb', 'rgba', 'hsl' or 'hsla' color;
+    /// these may be separated by commas or whitespace, with an
+    /// optional alpha after a ',' or '/'
+    fn parse_functional(s: &str, func: &str, args: &str) -> Result<Self, Error> {
+        let args: Vec<&str> = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|a| !a.is_empty())
+            .collect();
+        if args.len() != 3 && args.len() != 4 {
+            return Err(color_error(s, "expected three or four values"));
+        }
+        let alpha = {
+            if args.len() == 4 {
+                parse_component(s, args[3], 1.)?
+            } else {
+                1.
+            }
+        };
+        let (r, g, b) = match func.to_ascii_lowercase().as_str() {
+            "rgb" | "rgba" => (
+                parse_component(s, args[0], 255.)? / 255.,
+                parse_component(s, args[1], 255.)? / 255.,
+                parse_component(s, args[2], 255.)? / 255.,
+            ),
+            "hsl" | "hsla" => hsl_to_rgb(
+                parse_component(s, args[0], 360.)?,
+                parse_component(s, args[1], 1.)?,
+                parse_component(s, args[2], 1.)?,
+            ),
+            _ => {
+                return Err(color_error(
+                    s,
+                    &format!("unknown color function '{}'", func),
+                ));
+            }
+        };
+        Ok((
+            unit_to_u8(r),
+            unit_to_u8(g),
+            unit_to_u8(b),
+            unit_to_u8(alpha),
+        )
+            .into())
+    }
+
+    //fp of_rgba
     pub fn of_rgba(rgba: u32) -> Self {
         let r: Self = (rgba & 0xffffff).into();
         r.set_alpha((rgba >> 24) as u8)
@@ -71,7 +287,7 @@ impl Rgba {
         Self(rgba)
     }
 
-    fn as_tuple_rgba(self) -> (u8, u8, u8, u8) {
+    pub fn as_tuple_rgba(self) -> (u8, u8, u8, u8) {
         (
             ((self.0 >> 16) & 0xff) as u8,         // r
             ((self.0 >> 8) & 0xff) as u8,          // g
@@ -86,8 +302,119 @@ impl Rgba {
         self.0 = (self.0 & 0xffffff) | (((255 - alpha) as u32) << 24);
         self
     }
+
+    //fi as_unit_rgb
+    /// Get the red, green and blue as values from 0 to 1, and the
+    /// maximum and minimum of those
+    fn as_unit_rgb(self) -> (f64, f64, f64, f64, f64) {
+        let (r, g, b, _) = self.as_tuple_rgba();
+        let (r, g, b) = (r as f64 / 255., g as f64 / 255., b as f64 / 255.);
+        (r, g, b, r.max(g).max(b), r.min(g).min(b))
+    }
+
+    //fi hue
+    /// Get the hue in degrees (0 to 360) of the color
+    fn hue(self) -> f64 {
+        let (r, g, b, max, min) = self.as_unit_rgb();
+        let c = max - min;
+        if c <= 0. {
+            0.
+        } else if max == r {
+            (60. * (g - b) / c).rem_euclid(360.)
+        } else if max == g {
+            60. * (b - r) / c + 120.
+        } else {
+            60. * (r - g) / c + 240.
+        }
+    }
+
+    //mp to_hsl
+    /// Get the hue (in degrees), saturation and lightness (0 to 1) of
+    /// the color; the alpha is ignored
+    pub fn to_hsl(self) -> (f64, f64, f64) {
+        let (_, _, _, max, min) = self.as_unit_rgb();
+        let l = (max + min) / 2.;
+        let s = {
+            if max <= min {
+                0.
+            } else {
+                (max - min) / (1. - (2. * l - 1.).abs())
+            }
+        };
+        (self.hue(), s, l)
+    }
+
+    //fp from_hsl
+    /// Create an opaque color from a hue (in degrees), saturation and
+    /// lightness (0 to 1)
+    pub fn from_hsl(h: f64, s: f64, l: f64) -> Self {
+        let (r, g, b) = hsl_to_rgb(h, s.clamp(0., 1.), l.clamp(0., 1.));
+        (unit_to_u8(r), unit_to_u8(g), unit_to_u8(b)).into()
+    }
+
+    //mp to_hsv
+    /// Get the hue (in degrees), saturation and value (0 to 1) of the
+    /// color; the alpha is ignored
+    pub fn to_hsv(self) -> (f64, f64, f64) {
+        let (_, _, _, max, min) = self.as_unit_rgb();
+        let s = if max <= 0. { 0. } else { (max - min) / max };
+        (self.hue(), s, max)
+    }
+
+    //fp from_hsv
+    /// Create an opaque color from a hue (in degrees), saturation and
+    /// value (0 to 1)
+    pub fn from_hsv(h: f64, s: f64, v: f64) -> Self {
+        let (s, v) = (s.clamp(0., 1.), v.clamp(0., 1.));
+        let l = v * (1. - s / 2.);
+        let s_l = {
+            if l <= 0. || l >= 1. {
+                0.
+            } else {
+                (v - l) / l.min(1. - l)
+            }
+        };
+        Self::from_hsl(h, s_l, l)
+    }
+
+    //mp blend
+    /// Blend a source color painted over this (backdrop) color with a
+    /// blend mode, compositing the result by the alpha of each
+    #[must_use]
+    pub fn blend(self, other: Rgba, mode: BlendMode) -> Self {
+        let (br, bg, bb, ba) = self.as_tuple_rgba();
+        let (sr, sg, sb, sa) = other.as_tuple_rgba();
+        let (ab, as_) = ((ba as f64) / 255., (sa as f64) / 255.);
+        let alpha = as_ + ab * (1. - as_);
+        if alpha <= 0. {
+            return (0, 0, 0, 0).into();
+        }
+        let channel = |b: u8, s: u8| {
+            let (b, s) = ((b as f64) / 255., (s as f64) / 255.);
+            let mixed = mode.blend_channel(b, s);
+            let c = as_ * (1. - ab) * s + as_ * ab * mixed + (1. - as_) * ab * b;
+            unit_to_u8(c / alpha)
+        };
+        (
+            channel(br, sr),
+            channel(bg, sg),
+            channel(bb, sb),
+            unit_to_u8(alpha),
+        )
+            .into()
+    }
+
+    //mp flatten_on
+    /// Get the opaque color that this color appears as when painted
+    /// over an (opaque) background, for output formats without
+    /// transparency
+    #[must_use]
+    pub fn flatten_on(self, background: Rgba) -> Self {
+        background.set_alpha(255).blend(self, BlendMode::AlphaOver)
+    }
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
     /// String representation (if transparency is 0)
     text: Rc<String>,
@@ -102,10 +429,16 @@ impl Color {
         let rgba = rgba.into();
         Self { text, rgba }
     }
+    /// Set the alpha of the color; if this changes the color then its
+    /// text is regenerated, as any name no longer describes it
     #[inline]
     #[must_use]
     pub fn set_alpha(mut self, alpha: u8) -> Self {
-        self.rgba = self.rgba.set_alpha(alpha);
+        let rgba = self.rgba.set_alpha(alpha);
+        if rgba.as_tuple_rgba() != self.rgba.as_tuple_rgba() {
+            self.rgba = rgba;
+            self.text = Rc::new(rgba.into());
+        }
         self
     }
     #[inline]
@@ -115,12 +448,146 @@ impl Color {
         let text = Rc::new(rgba.into());
         Self { text, rgba }
     }
+    /// The color 'none', for no paint
+    #[inline]
+    #[must_use]
+    pub fn none() -> Self {
+        Self::new("none", (0, 0, 0, 255))
+    }
+    /// The color 'black', as named in the SVG color database
+    #[inline]
+    #[must_use]
+    pub fn black() -> Self {
+        Self::new("black", 0x000000)
+    }
     pub fn name_is_none(name: &str) -> Option<Self> {
         match name {
-            "None" | "none" | "NONE" => Some(Self::new("none", (0, 0, 0, 255))),
+            "None" | "none" | "NONE" => Some(Self::none()),
             _ => None,
         }
     }
+    pub fn as_tuple_rgba(&self) -> (u8, u8, u8, u8) {
+        self.rgba.as_tuple_rgba()
+    }
+
+    //fp of_keyword
+    /// Create a color for a keyword such as 'currentColor'; its RGB is
+    /// black, as the actual color is only known to the renderer
+    #[must_use]
+    pub fn of_keyword(keyword: ColorKeyword) -> Self {
+        Self::new(keyword.as_str(), (0, 0, 0, 255))
+    }
+
+    //ap keyword
+    /// Get the keyword of the color, if it is one
+    pub fn keyword(&self) -> Option<ColorKeyword> {
+        ColorKeyword::of_name(&self.text)
+    }
+
+    //fp try_from_name
+    /// Find a color by name (or hex or CSS functional color) in a
+    /// database, returning an error if it is not found
+    pub fn try_from_name(name: &str, db: &ColorDatabase) -> Result<Self, Error> {
+        db.find_color(name)
+            .ok_or_else(|| Error::UnknownColor { name: name.into() })
+    }
+
+    //fi map_hsl
+    /// Create a new color by mapping the hue, saturation and
+    /// lightness, keeping the alpha
+    fn map_hsl<F: FnOnce(f64, f64, f64) -> (f64, f64, f64)>(&self, f: F) -> Self {
+        let (h, s, l) = self.rgba.to_hsl();
+        let (h, s, l) = f(h, s, l);
+        Self::of_rgb(Rgba::from_hsl(h, s, l).set_alpha(self.rgba.alpha()))
+    }
+
+    //mp lighten
+    /// Create a lighter color, adding `f` (0 to 1) to the lightness
+    #[must_use]
+    pub fn lighten(&self, f: f64) -> Self {
+        self.map_hsl(|h, s, l| (h, s, l + f))
+    }
+
+    //mp darken
+    /// Create a darker color, subtracting `f` (0 to 1) from the lightness
+    #[must_use]
+    pub fn darken(&self, f: f64) -> Self {
+        self.map_hsl(|h, s, l| (h, s, l - f))
+    }
+
+    //mp saturate
+    /// Create a more saturated color, adding `f` (-1 to 1) to the
+    /// saturation; a negative value desaturates
+    #[must_use]
+    pub fn saturate(&self, f: f64) -> Self {
+        self.map_hsl(|h, s, l| (h, s + f, l))
+    }
+
+    //mp lerp
+    /// Interpolate linearly (in sRGB, including alpha) between this
+    /// color (t=0) and another (t=1); t is clamped to 0 to 1
+    #[must_use]
+    pub fn lerp(&self, other: &Color, t: f64) -> Self {
+        let t = t.clamp(0., 1.);
+        let (r0, g0, b0, a0) = self.as_tuple_rgba();
+        let (r1, g1, b1, a1) = other.as_tuple_rgba();
+        let mix = |v0: u8, v1: u8| (v0 as f64 + (v1 as f64 - v0 as f64) * t).round() as u8;
+        Self::of_rgb((mix(r0, r1), mix(g0, g1), mix(b0, b1), mix(a0, a1)))
+    }
+
+    //mp rotate_hue
+    /// Create a color with the hue rotated by a number of degrees
+    #[must_use]
+    pub fn rotate_hue(&self, degrees: f64) -> Self {
+        self.map_hsl(|h, s, l| (h + degrees, s, l))
+    }
+
+    //mp blend
+    /// Create the color of another color painted over this one with a
+    /// blend mode (see [Rgba::blend]), such as a highlight variant
+    #[must_use]
+    pub fn blend(&self, other: &Color, mode: BlendMode) -> Self {
+        Self::of_rgb(self.rgba.blend(other.rgba, mode))
+    }
+
+    //mp relative_luminance
+    /// Relative luminance of the color (0 to 1) as defined by WCAG 2;
+    /// alpha is ignored
+    pub fn relative_luminance(&self) -> f64 {
+        let (r, g, b, _) = self.as_tuple_rgba();
+        let lin = |c: u8| {
+            let c = (c as f64) / 255.;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * lin(r) + 0.7152 * lin(g) + 0.0722 * lin(b)
+    }
+
+    //mp contrast_ratio
+    /// Contrast ratio (1 to 21) of this color with another as defined
+    /// by WCAG 2; 4.5 is the minimum for normal text at level AA
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let la = self.relative_luminance();
+        let lb = other.relative_luminance();
+        (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+    }
+
+    //fp best_text_color_on
+    /// Return black or white, whichever has the greater contrast with
+    /// a background color, for readable labels on arbitrary fills
+    #[must_use]
+    pub fn best_text_color_on(background: &Color) -> Self {
+        let black = Self::new("black", (0, 0, 0, 255));
+        let white = Self::new("white", (255, 255, 255, 255));
+        if black.contrast_ratio(background) >= white.contrast_ratio(background) {
+            black
+        } else {
+            white
+        }
+    }
     pub fn as_str(&self) -> Rc<String> {
         if self.rgba.alpha() == 255 {
             self.text.clone()
@@ -129,6 +596,14 @@ impl Color {
         }
     }
 }
+impl FromStr for Color {
+    type Err = Error;
+    /// Parse a hex or CSS functional color, as for [Rgba]
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let rgba: Rgba = s.parse()?;
+        Ok(Self::of_rgb(rgba))
+    }
+}
 pub struct ColorDatabase<'a> {
     pub colors: &'a [(&'a str, u32)],
 }
@@ -141,7 +616,7 @@ impl<'a> ColorDatabase<'a> {
         }
         None
     }
-    fn canonicalize_name(name: &str) -> Option<String> {
+    pub(crate) fn canonicalize_name(name: &str) -> Option<String> {
         let mut r = String::new();
         for mut c in name.chars() {
             if c == '_' {
@@ -161,31 +636,173 @@ impl<'a> ColorDatabase<'a> {
     pub fn find_color_rgb(&self, name: &str) -> Option<u32> {
         self.find_color_index(name).map(|i| self.colors[i].1)
     }
+    /// Find a color by name, or from a hex string such as '#rrggbb'
+    /// or a CSS functional color such as 'rgb(0, 128, 255)'
     pub fn find_color(&self, name: &str) -> Option<Color> {
         if let Some(color_none) = Color::name_is_none(name) {
             Some(color_none)
+        } else if let Some(keyword) = ColorKeyword::of_name(name) {
+            Some(Color::of_keyword(keyword))
+        } else if name.starts_with('#') || name.contains('(') {
+            name.parse().ok()
         } else {
             self.find_color_index(name)
                 .map(|i| Color::new(self.colors[i].0, self.colors[i].1))
         }
     }
 }
-impl<'a> From<(&str, &'a ColorDatabase<'a>)> for Color {
+// The conversions of a color description in the context of a
+// database are all fallible, so that a color name that is not in the
+// database is an error (as is needed for the generic methods that
+// take a color)
+impl<'a> TryFrom<(&str, &'a ColorDatabase<'a>)> for Color {
+    type Error = Error;
+    /// Find a color by name in a database
+    #[inline]
+    fn try_from((s, db): (&str, &'a ColorDatabase<'a>)) -> Result<Self, Error> {
+        Color::try_from_name(s, db)
+    }
+}
+impl<'a> TryFrom<(ColorKeyword, &'a ColorDatabase<'a>)> for Color {
+    type Error = Error;
     #[inline]
-    fn from((s, db): (&str, &'a ColorDatabase<'a>)) -> Self {
-        db.find_color(s)
-            .unwrap_or_else(|| panic!("Color must be found in the database, but '{}' was not", s))
+    fn try_from((keyword, _db): (ColorKeyword, &'a ColorDatabase<'a>)) -> Result<Self, Error> {
+        Ok(Color::of_keyword(keyword))
     }
 }
-impl<'a> From<(&Color, &'a ColorDatabase<'a>)> for Color {
+impl<'a> TryFrom<(&Color, &'a ColorDatabase<'a>)> for Color {
+    type Error = Error;
     #[inline]
-    fn from((c, _db): (&Color, &'a ColorDatabase<'a>)) -> Self {
-        c.clone()
+    fn try_from((c, _db): (&Color, &'a ColorDatabase<'a>)) -> Result<Self, Error> {
+        Ok(c.clone())
     }
 }
-impl<'a, I: Into<Rgba>> From<(I, &'a ColorDatabase<'a>)> for Color {
+impl<'a, I: Into<Rgba>> TryFrom<(I, &'a ColorDatabase<'a>)> for Color {
+    type Error = Error;
     #[inline]
-    fn from((rgb, _db): (I, &'a ColorDatabase<'a>)) -> Self {
-        Color::of_rgb(rgb.into())
+    fn try_from((rgb, _db): (I, &'a ColorDatabase<'a>)) -> Result<Self, Error> {
+        Ok(Color::of_rgb(rgb.into()))
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_colors {
+    use super::*;
+    #[test]
+    fn test_contrast() {
+        let black = Color::of_rgb((0, 0, 0, 255));
+        let white = Color::of_rgb((255, 255, 255, 255));
+        assert!((white.relative_luminance() - 1.).abs() < 1E-9);
+        assert!((black.contrast_ratio(&white) - 21.).abs() < 1E-9);
+        assert!((white.contrast_ratio(&black) - 21.).abs() < 1E-9);
+        let yellow = Color::of_rgb((255, 255, 0, 255));
+        let navy = Color::of_rgb((0, 0, 128, 255));
+        assert_eq!(
+            Color::best_text_color_on(&yellow).as_str().as_str(),
+            "black"
+        );
+        assert_eq!(Color::best_text_color_on(&navy).as_str().as_str(), "white");
+    }
+    #[test]
+    fn test_keyword() {
+        let db = ColorDatabase { colors: &[] };
+        let c: Color = (ColorKeyword::CurrentColor, &db).try_into().unwrap();
+        assert_eq!(c.as_str().as_str(), "currentColor");
+        assert_eq!(c.keyword(), Some(ColorKeyword::CurrentColor));
+        let c = db.find_color("inherit").unwrap();
+        assert_eq!(c.keyword(), Some(ColorKeyword::Inherit));
+        assert_eq!(
+            db.find_color("currentcolor").unwrap().as_str().as_str(),
+            "currentColor"
+        );
+        assert_eq!(Color::of_rgb((1, 2, 3)).keyword(), None);
+    }
+    #[test]
+    fn test_blend() {
+        let grey: Rgba = (128, 128, 128).into();
+        let red: Rgba = (255, 0, 0).into();
+        let t = |c: Rgba| c.as_tuple_rgba();
+        assert_eq!(t(grey.blend(red, BlendMode::AlphaOver)), (255, 0, 0, 255));
+        assert_eq!(t(grey.blend(red, BlendMode::Multiply)), (128, 0, 0, 255));
+        assert_eq!(t(grey.blend(red, BlendMode::Screen)), (255, 128, 128, 255));
+        assert_eq!(t(grey.blend(red, BlendMode::Overlay)), (255, 1, 1, 255));
+        let half_red = red.set_alpha(128);
+        let white: Rgba = (255, 255, 255).into();
+        assert_eq!(t(half_red.flatten_on(white)), (255, 127, 127, 255));
+    }
+    #[test]
+    fn test_hex() {
+        let c: Rgba = "#f80".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (255, 136, 0, 255));
+        let c: Rgba = "#12aBcd".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (0x12, 0xab, 0xcd, 255));
+        let c: Rgba = "#12abcd80".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (0x12, 0xab, 0xcd, 0x80));
+        assert!("12abcd".parse::<Rgba>().is_err());
+        assert!("#12abc".parse::<Rgba>().is_err());
+        assert!("#12abcg".parse::<Rgba>().is_err());
+        let c: Color = "#12abcd".parse().unwrap();
+        assert_eq!(c.as_str().as_str(), "#12abcd");
+        let db = ColorDatabase { colors: &[] };
+        let c: Color = ("#fff", &db).try_into().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (255, 255, 255, 255));
+    }
+    #[test]
+    fn test_hsl() {
+        let c: Rgba = (255, 128, 0).into();
+        let (h, s, l) = c.to_hsl();
+        assert!((h - 30.1).abs() < 0.1, "{h}");
+        assert!((s - 1.).abs() < 1E-6);
+        assert!((l - 0.5).abs() < 1E-6);
+        assert_eq!(Rgba::from_hsl(h, s, l).as_tuple_rgba(), (255, 128, 0, 255));
+        let (h, s, v) = c.to_hsv();
+        assert_eq!(Rgba::from_hsv(h, s, v).as_tuple_rgba(), (255, 128, 0, 255));
+        let c = Color::of_rgb((0, 0, 255)).set_alpha(128);
+        assert_eq!(c.rotate_hue(120.).as_tuple_rgba(), (255, 0, 0, 128));
+        assert_eq!(c.lighten(0.5).as_tuple_rgba(), (255, 255, 255, 128));
+        assert_eq!(c.darken(0.25).as_tuple_rgba(), (0, 0, 128, 128));
+        assert_eq!(c.saturate(-1.).as_tuple_rgba(), (128, 128, 128, 128));
+    }
+    #[test]
+    fn test_try_from_name() {
+        let db = ColorDatabase {
+            colors: &[("brand", 0x123456)],
+        };
+        assert_eq!(
+            Color::try_from_name("Brand", &db).unwrap().as_tuple_rgba(),
+            (0x12, 0x34, 0x56, 255)
+        );
+        assert!(Color::try_from_name("#fff", &db).is_ok());
+        assert!(matches!(
+            Color::try_from_name("nope", &db),
+            Err(Error::UnknownColor { .. })
+        ));
+    }
+    #[test]
+    fn test_lerp() {
+        let c0 = Color::of_rgb((0, 0, 0));
+        let c1 = Color::of_rgb((255, 100, 10)).set_alpha(0);
+        assert_eq!(c0.lerp(&c1, 0.5).as_tuple_rgba(), (128, 50, 5, 128));
+        assert_eq!(c0.lerp(&c1, 2.).as_tuple_rgba(), (255, 100, 10, 0));
+    }
+    #[test]
+    fn test_functional() {
+        let c: Rgba = "rgb(255, 128, 0)".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (255, 128, 0, 255));
+        let c: Rgba = "rgba(100%, 0%, 50%, 0.5)".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (255, 0, 128, 128));
+        // Percentages and fractions round the same way
+        let c: Rgba = "rgba(50%, 127.5, 0, 50%)".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (128, 128, 0, 128));
+        let c: Rgba = "rgb(0 0 255 / 25%)".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (0, 0, 255, 64));
+        let c: Rgba = "hsl(120, 100%, 50%)".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (0, 255, 0, 255));
+        let c: Rgba = "hsla(240deg 100% 25% / 1)".parse().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (0, 0, 128, 255));
+        assert!("rgb(1, 2)".parse::<Rgba>().is_err());
+        assert!("cmyk(1, 2, 3, 4)".parse::<Rgba>().is_err());
+        assert!("rgb(1, x, 3)".parse::<Rgba>().is_err());
     }
 }
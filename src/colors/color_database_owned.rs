@@ -0,0 +1,209 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    color_database_owned.rs
+@brief   A color database that may be built at runtime
+ */
+
+//a Imports
+use std::collections::BTreeMap;
+
+use crate::{Color, ColorDatabase, Error, Rgba};
+
+//a ColorDatabaseOwned
+//tp ColorDatabaseOwned
+/// A [ColorDatabaseOwned] is a color database whose names and colors
+/// are owned, so that it can be built or loaded at runtime (for
+/// example a brand palette), unlike a [ColorDatabase] which borrows a
+/// static table
+///
+/// Names are matched as for a [ColorDatabase], ignoring case and
+/// underscores. Hex and CSS functional colors, and 'none', are also
+/// accepted when finding a color.
+///
+/// A palette may be loaded from text with lines of 'name = color'
+/// (blank lines and lines starting with '#' are ignored), or (with
+/// the `serde` feature) from a map of names to colors.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "BTreeMap<String, String>",
+        into = "BTreeMap<String, String>"
+    )
+)]
+pub struct ColorDatabaseOwned {
+    colors: Vec<(String, Rgba)>,
+}
+
+//ip ColorDatabaseOwned
+impl ColorDatabaseOwned {
+    //fp new
+    /// Create a new empty database
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //fp of_database
+    /// Create a database with all of the colors of a [ColorDatabase],
+    /// to which more may be added
+    pub fn of_database(db: &ColorDatabase) -> Self {
+        let mut s = Self::new();
+        for (name, rgb) in db.colors {
+            s.insert(name, *rgb);
+        }
+        s
+    }
+
+    //fp parse
+    /// Parse a database from text with lines of 'name = color', where
+    /// the color is hex (such as '#rrggbb') or a CSS functional color
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut s = Self::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name, color)) = line.split_once('=') else {
+                return Err(Error::BadColor {
+                    color: line.into(),
+                    reason: "expected 'name = color'".into(),
+                });
+            };
+            let rgba: Rgba = color.trim().parse()?;
+            s.insert(name.trim(), rgba);
+        }
+        Ok(s)
+    }
+
+    //mp insert
+    /// Insert a color, replacing any of the same name
+    pub fn insert<I: Into<Rgba>>(&mut self, name: &str, rgba: I) {
+        let name = ColorDatabase::canonicalize_name(name).unwrap_or_default();
+        let rgba = rgba.into();
+        match self.colors.iter_mut().find(|(n, _)| *n == name) {
+            Some(c) => c.1 = rgba,
+            None => self.colors.push((name, rgba)),
+        }
+    }
+
+    //ap len
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    //ap is_empty
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    //mp find_color_rgba
+    /// Find the color of a name
+    pub fn find_color_rgba(&self, name: &str) -> Option<Rgba> {
+        let name = ColorDatabase::canonicalize_name(name)?;
+        self.colors
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, c)| *c)
+    }
+
+    //mp find_color
+    /// Find a color by name, or from a hex string such as '#rrggbb'
+    /// or a CSS functional color such as 'rgb(0, 128, 255)'
+    pub fn find_color(&self, name: &str) -> Option<Color> {
+        if let Some(color_none) = Color::name_is_none(name) {
+            Some(color_none)
+        } else if name.starts_with('#') || name.contains('(') {
+            name.parse().ok()
+        } else {
+            self.find_color_rgba(name).map(Color::of_rgb)
+        }
+    }
+
+    //mp try_find_color
+    /// Find a color as [Self::find_color] does, with an error if it is
+    /// not found
+    pub fn try_find_color(&self, name: &str) -> Result<Color, Error> {
+        self.find_color(name)
+            .ok_or_else(|| Error::UnknownColor { name: name.into() })
+    }
+}
+
+//ip TryFrom<BTreeMap<String, String>> for ColorDatabaseOwned
+impl TryFrom<BTreeMap<String, String>> for ColorDatabaseOwned {
+    type Error = Error;
+    fn try_from(map: BTreeMap<String, String>) -> Result<Self, Error> {
+        let mut s = Self::new();
+        for (name, color) in map {
+            let rgba: Rgba = color.parse()?;
+            s.insert(&name, rgba);
+        }
+        Ok(s)
+    }
+}
+
+//ip From<ColorDatabaseOwned> for BTreeMap<String, String>
+impl From<ColorDatabaseOwned> for BTreeMap<String, String> {
+    fn from(db: ColorDatabaseOwned) -> Self {
+        db.colors
+            .into_iter()
+            .map(|(n, c)| {
+                let (r, g, b, a) = c.as_tuple_rgba();
+                (n, format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a))
+            })
+            .collect()
+    }
+}
+
+//ip TryFrom<(&str, &ColorDatabaseOwned)> for Color
+impl TryFrom<(&str, &ColorDatabaseOwned)> for Color {
+    type Error = Error;
+    /// Find a color by name in the database
+    #[inline]
+    fn try_from((s, db): (&str, &ColorDatabaseOwned)) -> Result<Self, Error> {
+        db.try_find_color(s)
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_owned {
+    use super::*;
+    use crate::SvgColorDatabase;
+    #[test]
+    fn test_parse() {
+        let db = ColorDatabaseOwned::parse(
+            "# Brand palette\n\nbrand-blue = #1040c0\nBrand_Red = rgb(200, 0, 0)\n",
+        )
+        .unwrap();
+        assert_eq!(db.len(), 2);
+        let c: Color = ("Brand-Blue", &db).try_into().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (0x10, 0x40, 0xc0, 255));
+        let c: Color = ("brandred", &db).try_into().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (200, 0, 0, 255));
+        assert!(db.find_color("red").is_none());
+        assert!(ColorDatabaseOwned::parse("oops").is_err());
+        assert!(ColorDatabaseOwned::parse("x = #12").is_err());
+
+        let mut db = ColorDatabaseOwned::of_database(&SvgColorDatabase);
+        db.insert("red", (1_u8, 2_u8, 3_u8));
+        assert_eq!(
+            db.find_color("red").unwrap().as_tuple_rgba(),
+            (1, 2, 3, 255)
+        );
+        assert!(db.find_color("blue").is_some());
+    }
+}
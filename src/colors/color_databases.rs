@@ -0,0 +1,693 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    color_databases.rs
+@brief   Additional color databases (X11, Material and Tailwind)
+ */
+
+//a Imports
+use crate::ColorDatabase;
+
+//a Color tables
+//ci X11_COLORS
+/// The X11 colors; these differ from the SVG colors for 'gray',
+/// 'green', 'maroon' and 'purple', which have 'web' variants
+const X11_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0xBEBEBE),
+    ("green", 0x00FF00),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0xBEBEBE),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrod", 0xEEDD82),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslateblue", 0x8470FF),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0xB03060),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("navyblue", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0xA020F0),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0x2E8B57),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("violetred", 0xD02090),
+    ("webgray", 0x808080),
+    ("webgreen", 0x008000),
+    ("webgrey", 0x808080),
+    ("webmaroon", 0x800000),
+    ("webpurple", 0x800080),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("x11gray", 0xBEBEBE),
+    ("x11green", 0x00FF00),
+    ("x11grey", 0xBEBEBE),
+    ("x11maroon", 0xB03060),
+    ("x11purple", 0xA020F0),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+//ci MATERIAL_COLORS
+/// The Material Design (2014) palette, as '<hue>-<shade>' (such as
+/// 'deep-orange-300'); the hue alone is the 500 shade
+const MATERIAL_COLORS: &[(&str, u32)] = &[
+    ("red-50", 0xFFEBEE),
+    ("red-100", 0xFFCDD2),
+    ("red-200", 0xEF9A9A),
+    ("red-300", 0xE57373),
+    ("red-400", 0xEF5350),
+    ("red-500", 0xF44336),
+    ("red-600", 0xE53935),
+    ("red-700", 0xD32F2F),
+    ("red-800", 0xC62828),
+    ("red-900", 0xB71C1C),
+    ("red", 0xF44336),
+    ("pink-50", 0xFCE4EC),
+    ("pink-100", 0xF8BBD0),
+    ("pink-200", 0xF48FB1),
+    ("pink-300", 0xF06292),
+    ("pink-400", 0xEC407A),
+    ("pink-500", 0xE91E63),
+    ("pink-600", 0xD81B60),
+    ("pink-700", 0xC2185B),
+    ("pink-800", 0xAD1457),
+    ("pink-900", 0x880E4F),
+    ("pink", 0xE91E63),
+    ("purple-50", 0xF3E5F5),
+    ("purple-100", 0xE1BEE7),
+    ("purple-200", 0xCE93D8),
+    ("purple-300", 0xBA68C8),
+    ("purple-400", 0xAB47BC),
+    ("purple-500", 0x9C27B0),
+    ("purple-600", 0x8E24AA),
+    ("purple-700", 0x7B1FA2),
+    ("purple-800", 0x6A1B9A),
+    ("purple-900", 0x4A148C),
+    ("purple", 0x9C27B0),
+    ("deep-purple-50", 0xEDE7F6),
+    ("deep-purple-100", 0xD1C4E9),
+    ("deep-purple-200", 0xB39DDB),
+    ("deep-purple-300", 0x9575CD),
+    ("deep-purple-400", 0x7E57C2),
+    ("deep-purple-500", 0x673AB7),
+    ("deep-purple-600", 0x5E35B1),
+    ("deep-purple-700", 0x512DA8),
+    ("deep-purple-800", 0x4527A0),
+    ("deep-purple-900", 0x311B92),
+    ("deep-purple", 0x673AB7),
+    ("indigo-50", 0xE8EAF6),
+    ("indigo-100", 0xC5CAE9),
+    ("indigo-200", 0x9FA8DA),
+    ("indigo-300", 0x7986CB),
+    ("indigo-400", 0x5C6BC0),
+    ("indigo-500", 0x3F51B5),
+    ("indigo-600", 0x3949AB),
+    ("indigo-700", 0x303F9F),
+    ("indigo-800", 0x283593),
+    ("indigo-900", 0x1A237E),
+    ("indigo", 0x3F51B5),
+    ("blue-50", 0xE3F2FD),
+    ("blue-100", 0xBBDEFB),
+    ("blue-200", 0x90CAF9),
+    ("blue-300", 0x64B5F6),
+    ("blue-400", 0x42A5F5),
+    ("blue-500", 0x2196F3),
+    ("blue-600", 0x1E88E5),
+    ("blue-700", 0x1976D2),
+    ("blue-800", 0x1565C0),
+    ("blue-900", 0x0D47A1),
+    ("blue", 0x2196F3),
+    ("light-blue-50", 0xE1F5FE),
+    ("light-blue-100", 0xB3E5FC),
+    ("light-blue-200", 0x81D4FA),
+    ("light-blue-300", 0x4FC3F7),
+    ("light-blue-400", 0x29B6F6),
+    ("light-blue-500", 0x03A9F4),
+    ("light-blue-600", 0x039BE5),
+    ("light-blue-700", 0x0288D1),
+    ("light-blue-800", 0x0277BD),
+    ("light-blue-900", 0x01579B),
+    ("light-blue", 0x03A9F4),
+    ("cyan-50", 0xE0F7FA),
+    ("cyan-100", 0xB2EBF2),
+    ("cyan-200", 0x80DEEA),
+    ("cyan-300", 0x4DD0E1),
+    ("cyan-400", 0x26C6DA),
+    ("cyan-500", 0x00BCD4),
+    ("cyan-600", 0x00ACC1),
+    ("cyan-700", 0x0097A7),
+    ("cyan-800", 0x00838F),
+    ("cyan-900", 0x006064),
+    ("cyan", 0x00BCD4),
+    ("teal-50", 0xE0F2F1),
+    ("teal-100", 0xB2DFDB),
+    ("teal-200", 0x80CBC4),
+    ("teal-300", 0x4DB6AC),
+    ("teal-400", 0x26A69A),
+    ("teal-500", 0x009688),
+    ("teal-600", 0x00897B),
+    ("teal-700", 0x00796B),
+    ("teal-800", 0x00695C),
+    ("teal-900", 0x004D40),
+    ("teal", 0x009688),
+    ("green-50", 0xE8F5E9),
+    ("green-100", 0xC8E6C9),
+    ("green-200", 0xA5D6A7),
+    ("green-300", 0x81C784),
+    ("green-400", 0x66BB6A),
+    ("green-500", 0x4CAF50),
+    ("green-600", 0x43A047),
+    ("green-700", 0x388E3C),
+    ("green-800", 0x2E7D32),
+    ("green-900", 0x1B5E20),
+    ("green", 0x4CAF50),
+    ("light-green-50", 0xF1F8E9),
+    ("light-green-100", 0xDCEDC8),
+    ("light-green-200", 0xC5E1A5),
+    ("light-green-300", 0xAED581),
+    ("light-green-400", 0x9CCC65),
+    ("light-green-500", 0x8BC34A),
+    ("light-green-600", 0x7CB342),
+    ("light-green-700", 0x689F38),
+    ("light-green-800", 0x558B2F),
+    ("light-green-900", 0x33691E),
+    ("light-green", 0x8BC34A),
+    ("lime-50", 0xF9FBE7),
+    ("lime-100", 0xF0F4C3),
+    ("lime-200", 0xE6EE9C),
+    ("lime-300", 0xDCE775),
+    ("lime-400", 0xD4E157),
+    ("lime-500", 0xCDDC39),
+    ("lime-600", 0xC0CA33),
+    ("lime-700", 0xAFB42B),
+    ("lime-800", 0x9E9D24),
+    ("lime-900", 0x827717),
+    ("lime", 0xCDDC39),
+    ("yellow-50", 0xFFFDE7),
+    ("yellow-100", 0xFFF9C4),
+    ("yellow-200", 0xFFF59D),
+    ("yellow-300", 0xFFF176),
+    ("yellow-400", 0xFFEE58),
+    ("yellow-500", 0xFFEB3B),
+    ("yellow-600", 0xFDD835),
+    ("yellow-700", 0xFBC02D),
+    ("yellow-800", 0xF9A825),
+    ("yellow-900", 0xF57F17),
+    ("yellow", 0xFFEB3B),
+    ("amber-50", 0xFFF8E1),
+    ("amber-100", 0xFFECB3),
+    ("amber-200", 0xFFE082),
+    ("amber-300", 0xFFD54F),
+    ("amber-400", 0xFFCA28),
+    ("amber-500", 0xFFC107),
+    ("amber-600", 0xFFB300),
+    ("amber-700", 0xFFA000),
+    ("amber-800", 0xFF8F00),
+    ("amber-900", 0xFF6F00),
+    ("amber", 0xFFC107),
+    ("orange-50", 0xFFF3E0),
+    ("orange-100", 0xFFE0B2),
+    ("orange-200", 0xFFCC80),
+    ("orange-300", 0xFFB74D),
+    ("orange-400", 0xFFA726),
+    ("orange-500", 0xFF9800),
+    ("orange-600", 0xFB8C00),
+    ("orange-700", 0xF57C00),
+    ("orange-800", 0xEF6C00),
+    ("orange-900", 0xE65100),
+    ("orange", 0xFF9800),
+    ("deep-orange-50", 0xFBE9E7),
+    ("deep-orange-100", 0xFFCCBC),
+    ("deep-orange-200", 0xFFAB91),
+    ("deep-orange-300", 0xFF8A65),
+    ("deep-orange-400", 0xFF7043),
+    ("deep-orange-500", 0xFF5722),
+    ("deep-orange-600", 0xF4511E),
+    ("deep-orange-700", 0xE64A19),
+    ("deep-orange-800", 0xD84315),
+    ("deep-orange-900", 0xBF360C),
+    ("deep-orange", 0xFF5722),
+    ("brown-50", 0xEFEBE9),
+    ("brown-100", 0xD7CCC8),
+    ("brown-200", 0xBCAAA4),
+    ("brown-300", 0xA1887F),
+    ("brown-400", 0x8D6E63),
+    ("brown-500", 0x795548),
+    ("brown-600", 0x6D4C41),
+    ("brown-700", 0x5D4037),
+    ("brown-800", 0x4E342E),
+    ("brown-900", 0x3E2723),
+    ("brown", 0x795548),
+    ("grey-50", 0xFAFAFA),
+    ("grey-100", 0xF5F5F5),
+    ("grey-200", 0xEEEEEE),
+    ("grey-300", 0xE0E0E0),
+    ("grey-400", 0xBDBDBD),
+    ("grey-500", 0x9E9E9E),
+    ("grey-600", 0x757575),
+    ("grey-700", 0x616161),
+    ("grey-800", 0x424242),
+    ("grey-900", 0x212121),
+    ("grey", 0x9E9E9E),
+    ("blue-grey-50", 0xECEFF1),
+    ("blue-grey-100", 0xCFD8DC),
+    ("blue-grey-200", 0xB0BEC5),
+    ("blue-grey-300", 0x90A4AE),
+    ("blue-grey-400", 0x78909C),
+    ("blue-grey-500", 0x607D8B),
+    ("blue-grey-600", 0x546E7A),
+    ("blue-grey-700", 0x455A64),
+    ("blue-grey-800", 0x37474F),
+    ("blue-grey-900", 0x263238),
+    ("blue-grey", 0x607D8B),
+    ("black", 0x000000),
+    ("white", 0xFFFFFF),
+];
+
+//ci TAILWIND_COLORS
+/// The Tailwind CSS (v3) palette, as '<hue>-<shade>' (such as 'slate-500')
+const TAILWIND_COLORS: &[(&str, u32)] = &[
+    ("slate-50", 0xF8FAFC),
+    ("slate-100", 0xF1F5F9),
+    ("slate-200", 0xE2E8F0),
+    ("slate-300", 0xCBD5E1),
+    ("slate-400", 0x94A3B8),
+    ("slate-500", 0x64748B),
+    ("slate-600", 0x475569),
+    ("slate-700", 0x334155),
+    ("slate-800", 0x1E293B),
+    ("slate-900", 0x0F172A),
+    ("slate-950", 0x020617),
+    ("gray-50", 0xF9FAFB),
+    ("gray-100", 0xF3F4F6),
+    ("gray-200", 0xE5E7EB),
+    ("gray-300", 0xD1D5DB),
+    ("gray-400", 0x9CA3AF),
+    ("gray-500", 0x6B7280),
+    ("gray-600", 0x4B5563),
+    ("gray-700", 0x374151),
+    ("gray-800", 0x1F2937),
+    ("gray-900", 0x111827),
+    ("gray-950", 0x030712),
+    ("zinc-50", 0xFAFAFA),
+    ("zinc-100", 0xF4F4F5),
+    ("zinc-200", 0xE4E4E7),
+    ("zinc-300", 0xD4D4D8),
+    ("zinc-400", 0xA1A1AA),
+    ("zinc-500", 0x71717A),
+    ("zinc-600", 0x52525B),
+    ("zinc-700", 0x3F3F46),
+    ("zinc-800", 0x27272A),
+    ("zinc-900", 0x18181B),
+    ("zinc-950", 0x09090B),
+    ("neutral-50", 0xFAFAFA),
+    ("neutral-100", 0xF5F5F5),
+    ("neutral-200", 0xE5E5E5),
+    ("neutral-300", 0xD4D4D4),
+    ("neutral-400", 0xA3A3A3),
+    ("neutral-500", 0x737373),
+    ("neutral-600", 0x525252),
+    ("neutral-700", 0x404040),
+    ("neutral-800", 0x262626),
+    ("neutral-900", 0x171717),
+    ("neutral-950", 0x0A0A0A),
+    ("stone-50", 0xFAFAF9),
+    ("stone-100", 0xF5F5F4),
+    ("stone-200", 0xE7E5E4),
+    ("stone-300", 0xD6D3D1),
+    ("stone-400", 0xA8A29E),
+    ("stone-500", 0x78716C),
+    ("stone-600", 0x57534E),
+    ("stone-700", 0x44403C),
+    ("stone-800", 0x292524),
+    ("stone-900", 0x1C1917),
+    ("stone-950", 0x0C0A09),
+    ("red-50", 0xFEF2F2),
+    ("red-100", 0xFEE2E2),
+    ("red-200", 0xFECACA),
+    ("red-300", 0xFCA5A5),
+    ("red-400", 0xF87171),
+    ("red-500", 0xEF4444),
+    ("red-600", 0xDC2626),
+    ("red-700", 0xB91C1C),
+    ("red-800", 0x991B1B),
+    ("red-900", 0x7F1D1D),
+    ("red-950", 0x450A0A),
+    ("orange-50", 0xFFF7ED),
+    ("orange-100", 0xFFEDD5),
+    ("orange-200", 0xFED7AA),
+    ("orange-300", 0xFDBA74),
+    ("orange-400", 0xFB923C),
+    ("orange-500", 0xF97316),
+    ("orange-600", 0xEA580C),
+    ("orange-700", 0xC2410C),
+    ("orange-800", 0x9A3412),
+    ("orange-900", 0x7C2D12),
+    ("orange-950", 0x431407),
+    ("amber-50", 0xFFFBEB),
+    ("amber-100", 0xFEF3C7),
+    ("amber-200", 0xFDE68A),
+    ("amber-300", 0xFCD34D),
+    ("amber-400", 0xFBBF24),
+    ("amber-500", 0xF59E0B),
+    ("amber-600", 0xD97706),
+    ("amber-700", 0xB45309),
+    ("amber-800", 0x92400E),
+    ("amber-900", 0x78350F),
+    ("amber-950", 0x451A03),
+    ("yellow-50", 0xFEFCE8),
+    ("yellow-100", 0xFEF9C3),
+    ("yellow-200", 0xFEF08A),
+    ("yellow-300", 0xFDE047),
+    ("yellow-400", 0xFACC15),
+    ("yellow-500", 0xEAB308),
+    ("yellow-600", 0xCA8A04),
+    ("yellow-700", 0xA16207),
+    ("yellow-800", 0x854D0E),
+    ("yellow-900", 0x713F12),
+    ("yellow-950", 0x422006),
+    ("lime-50", 0xF7FEE7),
+    ("lime-100", 0xECFCCB),
+    ("lime-200", 0xD9F99D),
+    ("lime-300", 0xBEF264),
+    ("lime-400", 0xA3E635),
+    ("lime-500", 0x84CC16),
+    ("lime-600", 0x65A30D),
+    ("lime-700", 0x4D7C0F),
+    ("lime-800", 0x3F6212),
+    ("lime-900", 0x365314),
+    ("lime-950", 0x1A2E05),
+    ("green-50", 0xF0FDF4),
+    ("green-100", 0xDCFCE7),
+    ("green-200", 0xBBF7D0),
+    ("green-300", 0x86EFAC),
+    ("green-400", 0x4ADE80),
+    ("green-500", 0x22C55E),
+    ("green-600", 0x16A34A),
+    ("green-700", 0x15803D),
+    ("green-800", 0x166534),
+    ("green-900", 0x14532D),
+    ("green-950", 0x052E16),
+    ("emerald-50", 0xECFDF5),
+    ("emerald-100", 0xD1FAE5),
+    ("emerald-200", 0xA7F3D0),
+    ("emerald-300", 0x6EE7B7),
+    ("emerald-400", 0x34D399),
+    ("emerald-500", 0x10B981),
+    ("emerald-600", 0x059669),
+    ("emerald-700", 0x047857),
+    ("emerald-800", 0x065F46),
+    ("emerald-900", 0x064E3B),
+    ("emerald-950", 0x022C22),
+    ("teal-50", 0xF0FDFA),
+    ("teal-100", 0xCCFBF1),
+    ("teal-200", 0x99F6E4),
+    ("teal-300", 0x5EEAD4),
+    ("teal-400", 0x2DD4BF),
+    ("teal-500", 0x14B8A6),
+    ("teal-600", 0x0D9488),
+    ("teal-700", 0x0F766E),
+    ("teal-800", 0x115E59),
+    ("teal-900", 0x134E4A),
+    ("teal-950", 0x042F2E),
+    ("cyan-50", 0xECFEFF),
+    ("cyan-100", 0xCFFAFE),
+    ("cyan-200", 0xA5F3FC),
+    ("cyan-300", 0x67E8F9),
+    ("cyan-400", 0x22D3EE),
+    ("cyan-500", 0x06B6D4),
+    ("cyan-600", 0x0891B2),
+    ("cyan-700", 0x0E7490),
+    ("cyan-800", 0x155E75),
+    ("cyan-900", 0x164E63),
+    ("cyan-950", 0x083344),
+    ("sky-50", 0xF0F9FF),
+    ("sky-100", 0xE0F2FE),
+    ("sky-200", 0xBAE6FD),
+    ("sky-300", 0x7DD3FC),
+    ("sky-400", 0x38BDF8),
+    ("sky-500", 0x0EA5E9),
+    ("sky-600", 0x0284C7),
+    ("sky-700", 0x0369A1),
+    ("sky-800", 0x075985),
+    ("sky-900", 0x0C4A6E),
+    ("sky-950", 0x082F49),
+    ("blue-50", 0xEFF6FF),
+    ("blue-100", 0xDBEAFE),
+    ("blue-200", 0xBFDBFE),
+    ("blue-300", 0x93C5FD),
+    ("blue-400", 0x60A5FA),
+    ("blue-500", 0x3B82F6),
+    ("blue-600", 0x2563EB),
+    ("blue-700", 0x1D4ED8),
+    ("blue-800", 0x1E40AF),
+    ("blue-900", 0x1E3A8A),
+    ("blue-950", 0x172554),
+    ("indigo-50", 0xEEF2FF),
+    ("indigo-100", 0xE0E7FF),
+    ("indigo-200", 0xC7D2FE),
+    ("indigo-300", 0xA5B4FC),
+    ("indigo-400", 0x818CF8),
+    ("indigo-500", 0x6366F1),
+    ("indigo-600", 0x4F46E5),
+    ("indigo-700", 0x4338CA),
+    ("indigo-800", 0x3730A3),
+    ("indigo-900", 0x312E81),
+    ("indigo-950", 0x1E1B4B),
+    ("violet-50", 0xF5F3FF),
+    ("violet-100", 0xEDE9FE),
+    ("violet-200", 0xDDD6FE),
+    ("violet-300", 0xC4B5FD),
+    ("violet-400", 0xA78BFA),
+    ("violet-500", 0x8B5CF6),
+    ("violet-600", 0x7C3AED),
+    ("violet-700", 0x6D28D9),
+    ("violet-800", 0x5B21B6),
+    ("violet-900", 0x4C1D95),
+    ("violet-950", 0x2E1065),
+    ("purple-50", 0xFAF5FF),
+    ("purple-100", 0xF3E8FF),
+    ("purple-200", 0xE9D5FF),
+    ("purple-300", 0xD8B4FE),
+    ("purple-400", 0xC084FC),
+    ("purple-500", 0xA855F7),
+    ("purple-600", 0x9333EA),
+    ("purple-700", 0x7E22CE),
+    ("purple-800", 0x6B21A8),
+    ("purple-900", 0x581C87),
+    ("purple-950", 0x3B0764),
+    ("fuchsia-50", 0xFDF4FF),
+    ("fuchsia-100", 0xFAE8FF),
+    ("fuchsia-200", 0xF5D0FE),
+    ("fuchsia-300", 0xF0ABFC),
+    ("fuchsia-400", 0xE879F9),
+    ("fuchsia-500", 0xD946EF),
+    ("fuchsia-600", 0xC026D3),
+    ("fuchsia-700", 0xA21CAF),
+    ("fuchsia-800", 0x86198F),
+    ("fuchsia-900", 0x701A75),
+    ("fuchsia-950", 0x4A044E),
+    ("pink-50", 0xFDF2F8),
+    ("pink-100", 0xFCE7F3),
+    ("pink-200", 0xFBCFE8),
+    ("pink-300", 0xF9A8D4),
+    ("pink-400", 0xF472B6),
+    ("pink-500", 0xEC4899),
+    ("pink-600", 0xDB2777),
+    ("pink-700", 0xBE185D),
+    ("pink-800", 0x9D174D),
+    ("pink-900", 0x831843),
+    ("pink-950", 0x500724),
+    ("rose-50", 0xFFF1F2),
+    ("rose-100", 0xFFE4E6),
+    ("rose-200", 0xFECDD3),
+    ("rose-300", 0xFDA4AF),
+    ("rose-400", 0xFB7185),
+    ("rose-500", 0xF43F5E),
+    ("rose-600", 0xE11D48),
+    ("rose-700", 0xBE123C),
+    ("rose-800", 0x9F1239),
+    ("rose-900", 0x881337),
+    ("rose-950", 0x4C0519),
+    ("black", 0x000000),
+    ("white", 0xFFFFFF),
+];
+
+//a Databases
+/// The X11 color database
+#[allow(non_upper_case_globals)]
+pub const X11ColorDatabase: ColorDatabase<'static> = ColorDatabase { colors: X11_COLORS };
+
+/// The Material Design color database
+#[allow(non_upper_case_globals)]
+pub const MaterialColorDatabase: ColorDatabase<'static> = ColorDatabase {
+    colors: MATERIAL_COLORS,
+};
+
+/// The Tailwind CSS color database
+#[allow(non_upper_case_globals)]
+pub const TailwindColorDatabase: ColorDatabase<'static> = ColorDatabase {
+    colors: TAILWIND_COLORS,
+};
+
+//a Test
+#[cfg(test)]
+mod test_databases {
+    use super::*;
+    use crate::Color;
+    #[test]
+    fn test_lookup() {
+        assert_eq!(X11ColorDatabase.find_color_rgb("Green"), Some(0x00FF00));
+        assert_eq!(X11ColorDatabase.find_color_rgb("web_green"), Some(0x008000));
+        assert_eq!(
+            MaterialColorDatabase.find_color_rgb("deep-orange-500"),
+            Some(0xFF5722)
+        );
+        assert_eq!(
+            MaterialColorDatabase.find_color_rgb("indigo"),
+            Some(0x3F51B5)
+        );
+        let c: Color = ("slate-500", &TailwindColorDatabase).try_into().unwrap();
+        assert_eq!(c.as_tuple_rgba(), (0x64, 0x74, 0x8b, 255));
+    }
+}
@@ -0,0 +1,368 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    color_ramp.rs
+@brief   Ramps of colors to map values to colors
+ */
+
+//a Imports
+use crate::{BBox, Color, ColorDatabase, Error, SvgColorDatabase};
+use crate::{SvgElement, SvgGroup, SvgPath, SvgTag, SvgText};
+
+//a Colormaps
+//ci VIRIDIS
+/// Samples of the matplotlib 'viridis' colormap
+const VIRIDIS: &[&str] = &[
+    "#440154", "#482878", "#3e4989", "#31688e", "#26828e", "#1f9e89", "#35b779", "#6ece58",
+    "#b5de2b", "#fde725",
+];
+
+//ci MAGMA
+/// Samples of the matplotlib 'magma' colormap
+const MAGMA: &[&str] = &[
+    "#000004", "#180f3d", "#440f76", "#721f81", "#9e2f7f", "#cd4071", "#f1605d", "#fd9668",
+    "#feca8d", "#fcfdbf",
+];
+
+//ci PLASMA
+/// Samples of the matplotlib 'plasma' colormap
+const PLASMA: &[&str] = &[
+    "#0d0887", "#46039f", "#7201a8", "#9c179e", "#bd3786", "#d8576b", "#ed7953", "#fb9f3a",
+    "#fdca26", "#f0f921",
+];
+
+//ci COOLWARM
+/// Samples of the Moreland 'coolwarm' diverging colormap
+const COOLWARM: &[&str] = &["#3b4cc0", "#8db0fe", "#dddddd", "#f49a7b", "#b40426"];
+
+//ci TURBO
+/// Samples of the Google 'turbo' colormap
+const TURBO: &[&str] = &[
+    "#30123b", "#4662d7", "#36aaf9", "#1ae4b6", "#72fe5e", "#c8ef34", "#faba39", "#f66b19",
+    "#ca2a04", "#7a0403",
+];
+
+//a ColorRamp
+//tp ColorRamp
+/// A [ColorRamp] is an ordered list of color stops at positions,
+/// which may be sampled at any position to map data values to colors
+/// (for heatmaps and gradient legends)
+///
+/// Colors between stops are interpolated linearly; positions before
+/// the first stop or after the last take the color of that stop
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorRamp {
+    stops: Vec<(f64, Color)>,
+}
+
+//ip ColorRamp
+impl ColorRamp {
+    //fp new
+    /// Create a new empty color ramp
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //fp of_colors
+    /// Create a color ramp of colors evenly spaced from 0 to 1
+    ///
+    /// An error is returned if a color name is not known
+    pub fn of_colors<'c, T, I>(colors: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = T>,
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        let colors = colors
+            .into_iter()
+            .map(|c| (c, &SvgColorDatabase).try_into())
+            .collect::<Result<Vec<Color>, Error>>()?;
+        Ok(Self::of_stops_evenly(colors))
+    }
+
+    //fi of_stops_evenly
+    fn of_stops_evenly(colors: Vec<Color>) -> Self {
+        let n = (colors.len().max(2) - 1) as f64;
+        let stops = colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| (i as f64 / n, c))
+            .collect();
+        Self { stops }
+    }
+
+    //fi of_colormap
+    /// Create a color ramp from the hex colors of a built-in colormap
+    /// (which are all valid)
+    fn of_colormap(colors: &[&str]) -> Self {
+        Self::of_stops_evenly(colors.iter().filter_map(|c| c.parse().ok()).collect())
+    }
+
+    //fp viridis
+    /// The perceptually uniform 'viridis' colormap (dark blue to yellow)
+    pub fn viridis() -> Self {
+        Self::of_colormap(VIRIDIS)
+    }
+
+    //fp magma
+    /// The perceptually uniform 'magma' colormap (black to pale yellow)
+    pub fn magma() -> Self {
+        Self::of_colormap(MAGMA)
+    }
+
+    //fp plasma
+    /// The perceptually uniform 'plasma' colormap (blue to yellow)
+    pub fn plasma() -> Self {
+        Self::of_colormap(PLASMA)
+    }
+
+    //fp coolwarm
+    /// The diverging 'coolwarm' colormap (blue through grey to red)
+    pub fn coolwarm() -> Self {
+        Self::of_colormap(COOLWARM)
+    }
+
+    //fp turbo
+    /// The 'turbo' rainbow colormap
+    pub fn turbo() -> Self {
+        Self::of_colormap(TURBO)
+    }
+
+    //fp of_name
+    /// Get a built-in colormap by name ('viridis', 'magma', 'plasma',
+    /// 'coolwarm' or 'turbo')
+    pub fn of_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "viridis" => Some(Self::viridis()),
+            "magma" => Some(Self::magma()),
+            "plasma" => Some(Self::plasma()),
+            "coolwarm" => Some(Self::coolwarm()),
+            "turbo" => Some(Self::turbo()),
+            _ => None,
+        }
+    }
+
+    //bp add_stop
+    /// Add a color stop at a position
+    ///
+    /// An error is returned if a color name is not known
+    pub fn add_stop<'c, T>(mut self, position: f64, color: T) -> Result<Self, Error>
+    where
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        let color = (color, &SvgColorDatabase).try_into()?;
+        let n = self.stops.partition_point(|(p, _)| *p <= position);
+        self.stops.insert(n, (position, color));
+        Ok(self)
+    }
+
+    //ap stops
+    /// Get the stops of the ramp, in order of position
+    pub fn stops(&self) -> &[(f64, Color)] {
+        &self.stops
+    }
+
+    //mp insert_stop
+    /// Insert a color stop at a position, after any stops at the same
+    /// position, returning its index
+    pub fn insert_stop(&mut self, position: f64, color: Color) -> usize {
+        let n = self.stops.partition_point(|(p, _)| *p <= position);
+        self.stops.insert(n, (position, color));
+        n
+    }
+
+    //mp remove_stop
+    /// Remove the stop at an index, if there is one
+    pub fn remove_stop(&mut self, n: usize) -> Option<(f64, Color)> {
+        if n < self.stops.len() {
+            Some(self.stops.remove(n))
+        } else {
+            None
+        }
+    }
+
+    //mp set_stop_color
+    /// Recolor the stop at an index, returning false if there is none
+    pub fn set_stop_color(&mut self, n: usize, color: Color) -> bool {
+        if let Some(stop) = self.stops.get_mut(n) {
+            stop.1 = color;
+            true
+        } else {
+            false
+        }
+    }
+
+    //mp set_stop_position
+    /// Move the stop at an index to a new position, returning its new
+    /// index (if there is such a stop)
+    pub fn set_stop_position(&mut self, n: usize, position: f64) -> Option<usize> {
+        let (_, color) = self.remove_stop(n)?;
+        Some(self.insert_stop(position, color))
+    }
+
+    //mp sample
+    /// Sample the ramp at a position; an empty ramp, or a position
+    /// that is not a number, yields 'none'
+    pub fn sample(&self, t: f64) -> Color {
+        let Some((first, last)) = self.stops.first().zip(self.stops.last()) else {
+            return Color::none();
+        };
+        if t.is_nan() {
+            return Color::none();
+        }
+        if t <= first.0 {
+            return first.1.clone();
+        }
+        if t >= last.0 {
+            return last.1.clone();
+        }
+        let n = self.stops.partition_point(|(p, _)| *p <= t);
+        let (p0, c0) = &self.stops[n - 1];
+        let (p1, c1) = &self.stops[n];
+        if p1 <= p0 {
+            c1.clone()
+        } else {
+            c0.lerp(c1, (t - p0) / (p1 - p0))
+        }
+    }
+
+    //mp gradient
+    /// Create a horizontal 'linearGradient' definition of the ramp
+    /// with an id; stops are clamped to positions 0 to 1
+    pub fn gradient<'a>(&self, id: &str) -> SvgElement<'a> {
+        let mut gradient = SvgTag::new("linearGradient", None);
+        gradient.add_attribute("id", None, id);
+        for (p, c) in self.stops.iter() {
+            let mut stop = SvgTag::new("stop", None);
+            stop.add_size("offset", p.clamp(0., 1.));
+            stop.add_attribute("stop-color", None, &c.as_str());
+            gradient.push_content(stop);
+        }
+        gradient
+    }
+
+    //mp swatch
+    /// Create a labelled swatch of the ramp, for a legend or colorbar:
+    /// a group containing the gradient definition (with an id), a bar
+    /// filled with it across the top of a region, and labels below the
+    /// bar at positions from 0 (left) to 1 (right)
+    pub fn swatch<'a>(
+        &self,
+        id: &str,
+        region: BBox,
+        labels: &[(f64, String)],
+        font_size: f64,
+    ) -> SvgElement<'a> {
+        let mut group = SvgGroup::new();
+        group.add_attribute("class", None, "swatch");
+        let mut defs = SvgTag::new("defs", None);
+        defs.push_content(self.gradient(id));
+        group.push_content(defs);
+
+        let (x0, y0, x1, y1) = (region.x[0], region.y[0], region.x[1], region.y[1]);
+        let bar_y1 = if labels.is_empty() {
+            y1
+        } else {
+            (y1 - font_size * 1.5).max(y0)
+        };
+        let mut bar = SvgPath::new_box(BBox::new(x0, y0, x1, bar_y1));
+        bar.add_attribute("fill", None, &format!("url(#{})", id));
+        bar.add_attribute("stroke", None, "none");
+        group.push_content(bar);
+
+        for (t, text) in labels {
+            let pos = [x0 + (x1 - x0) * t, bar_y1 + font_size].into();
+            let mut label = SvgText::new(pos, text);
+            label.add_size("font-size", font_size);
+            label.add_attribute("text-anchor", None, "middle");
+            group.push_content(label);
+        }
+        group
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_ramp {
+    use super::*;
+    #[test]
+    fn test_sample() {
+        let ramp = ColorRamp::new()
+            .add_stop(1., (255_u8, 255_u8, 255_u8))
+            .unwrap()
+            .add_stop(0., (0_u8, 0_u8, 0_u8))
+            .unwrap()
+            .add_stop(0.5, (255_u8, 0_u8, 0_u8))
+            .unwrap();
+        assert_eq!(ramp.stops().len(), 3);
+        assert_eq!(ramp.sample(-1.).as_tuple_rgba(), (0, 0, 0, 255));
+        assert_eq!(ramp.sample(0.25).as_tuple_rgba(), (128, 0, 0, 255));
+        assert_eq!(ramp.sample(0.75).as_tuple_rgba(), (255, 128, 128, 255));
+        assert_eq!(ramp.sample(2.).as_tuple_rgba(), (255, 255, 255, 255));
+
+        let ramp = ColorRamp::of_colors(["black", "white"]).unwrap();
+        assert_eq!(ramp.sample(0.5).as_tuple_rgba(), (128, 128, 128, 255));
+        assert!(matches!(
+            ColorRamp::of_colors(["black", "blurple"]),
+            Err(Error::UnknownColor { .. })
+        ));
+        assert_eq!(ColorRamp::new().sample(0.5).as_str().as_str(), "none");
+        assert_eq!(ramp.sample(f64::NAN).as_str().as_str(), "none");
+        assert_eq!(
+            ramp.sample(f64::INFINITY).as_tuple_rgba(),
+            (255, 255, 255, 255)
+        );
+        assert_eq!(
+            ramp.sample(f64::NEG_INFINITY).as_tuple_rgba(),
+            (0, 0, 0, 255)
+        );
+    }
+    #[test]
+    fn test_edit() {
+        let mut ramp = ColorRamp::of_colors(["black", "white"]).unwrap();
+        let red = Color::of_rgb((255, 0, 0));
+        assert_eq!(ramp.insert_stop(0.5, red.clone()), 1);
+        assert!(ramp.set_stop_color(2, red));
+        assert!(!ramp.set_stop_color(3, Color::of_rgb((0, 0, 0))));
+        assert_eq!(ramp.set_stop_position(0, 0.75), Some(1));
+        assert_eq!(ramp.stops()[0].0, 0.5);
+        assert!(ramp.remove_stop(0).is_some());
+        assert!(ramp.remove_stop(2).is_none());
+        assert_eq!(ramp.stops().len(), 2);
+
+        let swatch = ramp.swatch(
+            "ramp",
+            BBox::new(0., 0., 20., 5.),
+            &[(0., "low".into()), (1., "high".into())],
+            2.,
+        );
+        assert_eq!(swatch.contents().len(), 4);
+        assert_eq!(swatch.contents()[0].contents()[0].contents().len(), 2);
+        assert_eq!(swatch.contents()[3].characters(), "high");
+    }
+    #[test]
+    fn test_colormaps() {
+        let viridis = ColorRamp::viridis();
+        assert_eq!(viridis.sample(0.).as_tuple_rgba(), (0x44, 0x01, 0x54, 255));
+        assert_eq!(viridis.sample(1.).as_tuple_rgba(), (0xfd, 0xe7, 0x25, 255));
+        assert_eq!(
+            ColorRamp::coolwarm().sample(0.5).as_tuple_rgba(),
+            (0xdd, 0xdd, 0xdd, 255)
+        );
+        for name in ["viridis", "Magma", "plasma", "coolwarm", "turbo"] {
+            assert!(ColorRamp::of_name(name).is_some());
+        }
+        assert!(ColorRamp::of_name("jet").is_none());
+    }
+}
@@ -20,8 +20,10 @@ limitations under the License.
 mod traits;
 pub use traits::{Attributes, Config, LayoutElement, PreLayoutElement};
 mod group;
+mod overlaps;
 mod path;
 pub use group::Group;
+pub use overlaps::resolve_overlaps;
 pub use path::Path;
 
 use crate::BBox;
@@ -21,14 +21,30 @@ where
     {
         let contents: Vec<Element<A>> = contents.collect();
         let mut attr = A::default();
-        let mut bbox = BBox::none();
-        for c in contents.iter() {
-            bbox = bbox.union(PreLayoutElement::attr(c).bbox());
-        }
+        let bbox: BBox = contents
+            .iter()
+            .map(|c| PreLayoutElement::attr(c).outer_bbox())
+            .collect();
         attr.set_bbox(bbox);
         Self { attr, contents }
     }
 
+    //cp with_opacity
+    /// Make the group translucent as one unit, so that overlapping
+    /// contents composite with each other before the group opacity is
+    /// applied (rather than each child being translucent separately)
+    #[must_use]
+    pub fn with_opacity(mut self, opacity: f64) -> Self {
+        self.attr.set_opacity(opacity.clamp(0., 1.));
+        self
+    }
+
+    //ap contents
+    /// Get the contents of the group
+    pub fn contents(&self) -> &[Element<A>] {
+        &self.contents
+    }
+
     //zz All done
 }
 
@@ -0,0 +1,178 @@
+//a Imports
+use crate::{Attributes, BBox, Error, Point, PreLayoutElement, Transform};
+
+//a Functions
+//fi placed_bbox
+/// The outer bbox of an element as placed by its transform
+fn placed_bbox<A: Attributes>(attr: &A) -> BBox {
+    attr.transform().apply_bbox(&attr.outer_bbox())
+}
+
+//fi separation
+/// Find the smallest move of the second of two boxes that separates
+/// them, if they overlap
+fn separation(a: &BBox, b: &BBox) -> Option<Point> {
+    if a.is_none() || b.is_none() {
+        return None;
+    }
+    let dx = a.x[1].min(b.x[1]) - a.x[0].max(b.x[0]);
+    let dy = a.y[1].min(b.y[1]) - a.y[0].max(b.y[0]);
+    if dx <= 0. || dy <= 0. {
+        return None;
+    }
+    let (ca, cb) = (a.center(), b.center());
+    if dx < dy {
+        let sign = if cb[0] >= ca[0] { 1. } else { -1. };
+        Some([sign * dx, 0.].into())
+    } else {
+        let sign = if cb[1] >= ca[1] { 1. } else { -1. };
+        Some([0., sign * dy].into())
+    }
+}
+
+//fi nudge
+fn nudge<A: Attributes>(attr: &mut A, by: Point) {
+    let t = Transform::of_translation(by).apply_to_transform(&attr.transform());
+    attr.set_transform(t);
+}
+
+//fp resolve_overlaps
+/// Nudge overlapping elements (such as labels or nodes) apart after
+/// an initial layout, by repeatedly moving each overlapping pair
+/// apart along the axis of least overlap
+///
+/// Elements whose attributes are pinned are not moved; if one of a
+/// pair is pinned then the other is moved all of the way. The outer
+/// bbox (including margins) of each element, as placed by its
+/// transform, is used.
+///
+/// An error is returned if overlaps remain after the number of
+/// iterations, such as between two pinned elements
+pub fn resolve_overlaps<A, E>(elements: &mut [E], iterations: usize) -> Result<(), Error>
+where
+    A: Attributes,
+    E: PreLayoutElement<A>,
+{
+    for _ in 0..iterations {
+        let mut moved = false;
+        for i in 0..elements.len() {
+            for j in (i + 1)..elements.len() {
+                let a = elements[i].attr();
+                let b = elements[j].attr();
+                let (pin_a, pin_b) = (a.is_pinned(), b.is_pinned());
+                if pin_a && pin_b {
+                    continue;
+                }
+                let Some(d) = separation(&placed_bbox(a), &placed_bbox(b)) else {
+                    continue;
+                };
+                let (fa, fb) = match (pin_a, pin_b) {
+                    (true, _) => (0., 1.),
+                    (_, true) => (1., 0.),
+                    _ => (0.5, 0.5),
+                };
+                if fa > 0. {
+                    nudge(elements[i].attr_mut(), d * -fa);
+                }
+                if fb > 0. {
+                    nudge(elements[j].attr_mut(), d * fb);
+                }
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    for i in 0..elements.len() {
+        for j in (i + 1)..elements.len() {
+            if separation(
+                &placed_bbox(elements[i].attr()),
+                &placed_bbox(elements[j].attr()),
+            )
+            .is_some()
+            {
+                return Err(Error::BadLayout {
+                    reason: format!(
+                        "elements {} and {} still overlap after {} iterations",
+                        i, j, iterations
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+//a Test
+#[cfg(test)]
+mod test_overlaps {
+    use super::*;
+    use crate::Path;
+    #[derive(Debug, Default)]
+    struct Attr {
+        bbox: BBox,
+        transform: Transform,
+        pinned: bool,
+        opacity: Option<f64>,
+    }
+    impl Attributes for Attr {
+        fn bbox(&self) -> BBox {
+            self.bbox
+        }
+        fn set_bbox(&mut self, bbox: BBox) {
+            self.bbox = bbox;
+        }
+        fn transform(&self) -> Transform {
+            self.transform
+        }
+        fn set_transform(&mut self, transform: Transform) {
+            self.transform = transform;
+        }
+        fn is_pinned(&self) -> bool {
+            self.pinned
+        }
+        fn set_pinned(&mut self, pinned: bool) {
+            self.pinned = pinned;
+        }
+        fn opacity(&self) -> Option<f64> {
+            self.opacity
+        }
+        fn set_opacity(&mut self, opacity: f64) {
+            self.opacity = Some(opacity);
+        }
+    }
+    #[test]
+    fn test_resolve() {
+        let mut elements: Vec<Path<Attr>> = [
+            BBox::new(0., 0., 10., 4.),
+            BBox::new(8., 1., 18., 5.),
+            BBox::new(30., 0., 40., 4.),
+        ]
+        .into_iter()
+        .map(|bbox| {
+            let mut p: Path<Attr> = Path::new_box(bbox);
+            p.attr_mut().set_bbox(bbox);
+            p
+        })
+        .collect();
+        elements[0].attr_mut().set_pinned(true);
+        assert!(resolve_overlaps(&mut elements, 10).is_ok());
+        assert_eq!(elements[0].attr().transform().translation()[0], 0.);
+        assert_eq!(elements[1].attr().transform().translation()[0], 2.);
+        assert_eq!(elements[2].attr().transform().translation()[0], 0.);
+
+        elements[1].attr_mut().set_pinned(true);
+        elements[1].attr_mut().set_transform(Transform::default());
+        let e = resolve_overlaps(&mut elements, 10).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "Bad layout, elements 0 and 1 still overlap after 10 iterations"
+        );
+    }
+    #[test]
+    fn test_group_opacity() {
+        let g: crate::Group<Attr> = crate::Group::new(std::iter::empty()).with_opacity(2.);
+        assert_eq!(PreLayoutElement::attr(&g).opacity(), Some(1.));
+    }
+}
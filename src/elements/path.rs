@@ -29,6 +29,18 @@ where
         let rect = Polygon::new_rect(w, h) + c;
         Self::new_polygon(rect, true)
     }
+
+    //ap path
+    /// Get the path of the element
+    pub fn path(&self) -> &BezierPath {
+        &self.path
+    }
+
+    //ap closed
+    /// Return true if the path is closed
+    pub fn closed(&self) -> bool {
+        self.closed
+    }
 }
 
 //ip PreLayoutElement for Path
@@ -17,7 +17,7 @@ limitations under the License.
  */
 
 //a Imports
-use crate::{BBox, Transform};
+use crate::{BBox, Margins, Transform};
 
 //a Traits
 //tt Config
@@ -32,6 +32,32 @@ pub trait Attributes: std::default::Default + Sized + std::fmt::Debug {
     fn set_bbox(&mut self, bbox: BBox);
     fn transform(&self) -> Transform;
     fn set_transform(&mut self, transform: Transform);
+    /// Margins to be left around the element when it is laid out
+    fn margins(&self) -> Margins {
+        Margins::default()
+    }
+    /// Set the margins, if the attributes support them
+    fn set_margins(&mut self, _margins: Margins) {}
+    /// True if the element must not be moved after layout (for
+    /// example by [resolve_overlaps](crate::resolve_overlaps))
+    fn is_pinned(&self) -> bool {
+        false
+    }
+    /// Pin the element, if the attributes support it
+    fn set_pinned(&mut self, _pinned: bool) {}
+    /// The opacity of the element as a whole, if it is translucent;
+    /// this is applied to the composited contents of a group, not to
+    /// each child separately
+    fn opacity(&self) -> Option<f64> {
+        None
+    }
+    /// Set the opacity of the element as a whole, if the attributes
+    /// support it
+    fn set_opacity(&mut self, _opacity: f64) {}
+    /// The bbox of the element including its margins
+    fn outer_bbox(&self) -> BBox {
+        self.bbox().expand(self.margins(), 1.)
+    }
 }
 
 //tt PreLayoutElement
@@ -0,0 +1,198 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    choropleth.rs
+@brief   Regions colored by data values
+ */
+
+//a Imports
+use std::collections::HashMap;
+
+use crate::{BBox, Color, ColorRamp, LabelFormat, SvgElement, SvgTag};
+
+//a Constants
+/// Id of the gradient used by the colorbar of the legend
+const COLORBAR_ID: &str = "choropleth-colorbar";
+
+//a Choropleth
+//tp Choropleth
+/// A [Choropleth] joins a table of values (by id) to the regions of a
+/// map, coloring each region through a [ColorRamp]
+///
+/// Regions are the elements of a tree (such as the paths of a
+/// [MapProjection](crate::MapProjection) layer) whose 'id' attribute
+/// is a key of the table; each is given a 'fill' and a 'title' child
+/// with the formatted value, which browsers show on hover
+#[derive(Debug, Clone)]
+pub struct Choropleth {
+    ramp: ColorRamp,
+    range: Option<(f64, f64)>,
+    label_format: LabelFormat,
+    missing: Option<Color>,
+}
+
+//ip Choropleth
+impl Choropleth {
+    //fp new
+    /// Create a choropleth using a color ramp from 0 to 1
+    pub fn new(ramp: ColorRamp) -> Self {
+        Self {
+            ramp,
+            range: None,
+            label_format: LabelFormat::default(),
+            missing: None,
+        }
+    }
+
+    //bp set_range
+    /// Set the range of values mapped to the ramp; by default it is
+    /// the range of the values
+    pub fn set_range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    //bp set_label_format
+    /// Set the format of the values in titles and the legend
+    pub fn set_label_format(mut self, label_format: LabelFormat) -> Self {
+        self.label_format = label_format;
+        self
+    }
+
+    //bp set_missing_color
+    /// Set the fill of regions with an id that has no value; by
+    /// default they are left unchanged
+    pub fn set_missing_color(mut self, color: Color) -> Self {
+        self.missing = Some(color);
+        self
+    }
+
+    //mp range
+    /// Get the range of values mapped to the ramp
+    pub fn range(&self, values: &HashMap<String, f64>) -> (f64, f64) {
+        if let Some(range) = self.range {
+            return range;
+        }
+        let min = values.values().copied().fold(f64::MAX, f64::min);
+        let max = values.values().copied().fold(f64::MIN, f64::max);
+        if min > max {
+            (0., 1.)
+        } else {
+            (min, max)
+        }
+    }
+
+    //mp color
+    /// Get the color of a value given the range
+    pub fn color(&self, value: f64, range: (f64, f64)) -> Color {
+        let t = if range.1 > range.0 {
+            (value - range.0) / (range.1 - range.0)
+        } else {
+            0.5
+        };
+        self.ramp.sample(t)
+    }
+
+    //mp apply
+    /// Color the regions of an element tree by their values,
+    /// returning the number of regions that had a value
+    pub fn apply(&self, element: &mut SvgElement, values: &HashMap<String, f64>) -> usize {
+        let range = self.range(values);
+        self.apply_range(element, values, range)
+    }
+
+    //fi apply_range
+    fn apply_range(
+        &self,
+        element: &mut SvgElement,
+        values: &HashMap<String, f64>,
+        range: (f64, f64),
+    ) -> usize {
+        let mut n = 0;
+        let id = element.get_attribute("id").map(|s| s.to_string());
+        if let Some(id) = id {
+            if let Some(v) = values.get(&id) {
+                element.set_attribute("fill", None, &self.color(*v, range).as_str());
+                let mut title = SvgTag::new("title", None);
+                title.push_string(&format!("{}: {}", id, self.label_format.format(*v)));
+                element.insert_content(0, title);
+                n += 1;
+            } else if let Some(missing) = &self.missing {
+                element.set_attribute("fill", None, &missing.as_str());
+            }
+        }
+        for c in element.contents_mut() {
+            n += self.apply_range(c, values, range);
+        }
+        n
+    }
+
+    //mp legend
+    /// Create a legend - a horizontal colorbar filling the top of a
+    /// region, with `num_labels` values labelled below it
+    pub fn legend<'a>(
+        &self,
+        values: &HashMap<String, f64>,
+        region: BBox,
+        num_labels: usize,
+        font_size: f64,
+    ) -> SvgElement<'a> {
+        let (min, max) = self.range(values);
+        let labels: Vec<(f64, String)> = (0..num_labels)
+            .map(|i| {
+                let t = if num_labels > 1 {
+                    i as f64 / (num_labels - 1) as f64
+                } else {
+                    0.5
+                };
+                (t, self.label_format.format(min + (max - min) * t))
+            })
+            .collect();
+        let mut group = self.ramp.swatch(COLORBAR_ID, region, &labels, font_size);
+        group.set_attribute("class", None, "legend");
+        group
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_choropleth {
+    use super::*;
+    use crate::{SvgGroup, SvgPath};
+    #[test]
+    fn test_apply() {
+        let mut map = SvgGroup::new();
+        for id in ["a", "b", "c"] {
+            let mut p = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+            p.add_attribute("id", None, id);
+            p.add_attribute("fill", None, "grey");
+            map.push_content(p);
+        }
+        let values: HashMap<String, f64> = [("a".to_string(), 0.), ("b".to_string(), 10.)]
+            .into_iter()
+            .collect();
+        let ramp = ColorRamp::of_colors(["black", "white"]).unwrap();
+        let c =
+            Choropleth::new(ramp).set_label_format(LabelFormat::default().set_decimal_places(1));
+        assert_eq!(c.apply(&mut map, &values), 2);
+        assert_eq!(map.contents()[0].get_attribute("fill"), Some("black"));
+        assert_eq!(map.contents()[1].get_attribute("fill"), Some("white"));
+        assert_eq!(map.contents()[1].contents()[0].characters(), "b: 10.0");
+        assert_eq!(map.contents()[2].get_attribute("fill"), Some("grey"));
+
+        let legend = c.legend(&values, BBox::new(0., 0., 50., 10.), 3, 2.);
+        assert_eq!(legend.contents().len(), 5);
+        assert_eq!(legend.contents()[3].characters(), "5.0");
+    }
+}
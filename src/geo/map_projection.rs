@@ -0,0 +1,271 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    map_projection.rs
+@brief   Projections of longitude and latitude to the plane
+ */
+
+//a Imports
+use crate::{Bezier, BezierPath, Point, SvgElement, SvgLayer, SvgPath};
+
+//a Constants
+/// Maximum latitude for the Mercator projection, which is infinite at
+/// the poles
+const MERCATOR_MAX_LAT: f64 = 85.051_129;
+
+//a MapProjectionKind
+//tp MapProjectionKind
+/// The kind of a [MapProjection]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MapProjectionKind {
+    /// Longitude and latitude map linearly to X and Y
+    Equirectangular,
+    /// Conformal cylindrical projection; latitudes are limited to
+    /// about +-85 degrees
+    Mercator,
+    /// View of the globe from infinitely far away, centered on a
+    /// longitude and latitude (in degrees); only the visible
+    /// hemisphere is drawn
+    Orthographic { lon: f64, lat: f64 },
+}
+
+//a MapProjection
+//tp MapProjection
+/// A [MapProjection] maps (longitude, latitude) coordinates in
+/// degrees onto the plane, for a globe of a given radius, with north
+/// upwards (negative Y, as for SVG)
+///
+/// Lines (such as the rings of geographic features, or the lines of a
+/// graticule) are projected to polylines, which are split where they
+/// leave the visible part of the projection; for an orthographic
+/// projection they are clipped at the edge of the visible
+/// hemisphere.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapProjection {
+    kind: MapProjectionKind,
+    radius: f64,
+}
+
+//ip MapProjection
+impl MapProjection {
+    //fp new
+    /// Create a projection for a globe of a given radius
+    pub fn new(kind: MapProjectionKind, radius: f64) -> Self {
+        Self { kind, radius }
+    }
+
+    //fp equirectangular
+    pub fn equirectangular(radius: f64) -> Self {
+        Self::new(MapProjectionKind::Equirectangular, radius)
+    }
+
+    //fp mercator
+    pub fn mercator(radius: f64) -> Self {
+        Self::new(MapProjectionKind::Mercator, radius)
+    }
+
+    //fp orthographic
+    /// Create an orthographic projection centered on a longitude and
+    /// latitude
+    pub fn orthographic(radius: f64, lon: f64, lat: f64) -> Self {
+        Self::new(MapProjectionKind::Orthographic { lon, lat }, radius)
+    }
+
+    //ap kind
+    pub fn kind(&self) -> MapProjectionKind {
+        self.kind
+    }
+
+    //mp project
+    /// Project a longitude and latitude (in degrees), returning None if
+    /// the point is not visible
+    pub fn project(&self, lon: f64, lat: f64) -> Option<Point> {
+        let r = self.radius;
+        match self.kind {
+            MapProjectionKind::Equirectangular => {
+                Some([r * lon.to_radians(), -r * lat.to_radians()].into())
+            }
+            MapProjectionKind::Mercator => {
+                if lat.abs() > MERCATOR_MAX_LAT {
+                    return None;
+                }
+                let phi = lat.to_radians();
+                let y = (std::f64::consts::FRAC_PI_4 + phi / 2.).tan().ln();
+                Some([r * lon.to_radians(), -r * y].into())
+            }
+            MapProjectionKind::Orthographic {
+                lon: lon0,
+                lat: lat0,
+            } => {
+                let (sp, cp) = lat.to_radians().sin_cos();
+                let (sp0, cp0) = lat0.to_radians().sin_cos();
+                let (sl, cl) = (lon - lon0).to_radians().sin_cos();
+                let cos_c = sp0 * sp + cp0 * cp * cl;
+                if cos_c < 0. {
+                    return None;
+                }
+                Some([r * cp * sl, -r * (cp0 * sp - sp0 * cp * cl)].into())
+            }
+        }
+    }
+
+    //fi boundary
+    /// Find the point between a visible and an invisible point that is
+    /// (approximately) on the boundary of the visible region
+    fn boundary(&self, visible: (f64, f64), invisible: (f64, f64)) -> Option<Point> {
+        let (mut v, mut i) = (visible, invisible);
+        for _ in 0..20 {
+            let m = ((v.0 + i.0) / 2., (v.1 + i.1) / 2.);
+            if self.project(m.0, m.1).is_some() {
+                v = m;
+            } else {
+                i = m;
+            }
+        }
+        self.project(v.0, v.1)
+    }
+
+    //mp project_line
+    /// Project a line of (longitude, latitude) points, returning the
+    /// visible polylines; the line is split where it is not visible,
+    /// and clipped to the edge of the visible region
+    pub fn project_line(&self, line: &[(f64, f64)]) -> Vec<Vec<Point>> {
+        let mut r = vec![];
+        let mut current: Vec<Point> = vec![];
+        let mut last: Option<((f64, f64), bool)> = None;
+        for ll in line {
+            let p = self.project(ll.0, ll.1);
+            match (last, p) {
+                (Some((l, false)), Some(p)) => {
+                    current.extend(self.boundary(*ll, l));
+                    current.push(p);
+                }
+                (Some((l, true)), None) => {
+                    current.extend(self.boundary(l, *ll));
+                    if current.len() > 1 {
+                        r.push(std::mem::take(&mut current));
+                    }
+                    current.clear();
+                }
+                (_, Some(p)) => current.push(p),
+                (_, None) => (),
+            }
+            last = Some((*ll, p.is_some()));
+        }
+        if current.len() > 1 {
+            r.push(current);
+        }
+        r
+    }
+
+    //mp graticule
+    /// Generate the lines of a graticule - meridians and parallels
+    /// every `step` degrees, with points every `resolution` degrees
+    /// along them
+    pub fn graticule(&self, step: f64, resolution: f64) -> Vec<Vec<Point>> {
+        let mut r = vec![];
+        if step <= 0. || resolution <= 0. {
+            return r;
+        }
+        let samples = |from: f64, to: f64| {
+            let n = ((to - from) / resolution).ceil().max(1.) as usize;
+            (0..=n).map(move |i| from + (to - from) * (i as f64) / (n as f64))
+        };
+        let max_lat = match self.kind {
+            MapProjectionKind::Mercator => MERCATOR_MAX_LAT,
+            _ => 90.,
+        };
+        let mut lon = -180.;
+        while lon <= 180. {
+            let line: Vec<(f64, f64)> = samples(-max_lat, max_lat).map(|lat| (lon, lat)).collect();
+            r.append(&mut self.project_line(&line));
+            lon += step;
+        }
+        let mut lat = -90. + step;
+        while lat < 90. {
+            if lat.abs() <= max_lat {
+                let line: Vec<(f64, f64)> = samples(-180., 180.).map(|lon| (lon, lat)).collect();
+                r.append(&mut self.project_line(&line));
+            }
+            lat += step;
+        }
+        r
+    }
+
+    //mp layer
+    /// Create a map layer (an [SvgLayer]) of polylines, each as an
+    /// unfilled path with a class for styling
+    pub fn layer<'a>(
+        name: &str,
+        class: &'a str,
+        lines: &[Vec<Point>],
+        closed: bool,
+    ) -> SvgElement<'a> {
+        let mut layer = SvgLayer::new(name);
+        for l in lines {
+            let mut bp = BezierPath::default();
+            for s in l.windows(2) {
+                bp.add_bezier(Bezier::line(&s[0], &s[1]));
+            }
+            let mut path = SvgPath::new_path(bp, closed);
+            path.add_attribute("class", None, class);
+            if !closed {
+                path.add_attribute("fill", None, "none");
+            }
+            layer.push_content(path);
+        }
+        layer
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_map_projection {
+    use super::*;
+    #[test]
+    fn test_project() {
+        let p = MapProjection::equirectangular(1.)
+            .project(90., 45.)
+            .unwrap();
+        assert!((p[0] - std::f64::consts::FRAC_PI_2).abs() < 1E-9);
+        assert!((p[1] + std::f64::consts::FRAC_PI_4).abs() < 1E-9);
+
+        let m = MapProjection::mercator(1.);
+        assert!(m.project(0., 89.).is_none());
+        assert!(m.project(0., 0.).unwrap()[1].abs() < 1E-9);
+
+        let o = MapProjection::orthographic(1., 0., 0.);
+        let p = o.project(90., 0.).unwrap();
+        assert!((p[0] - 1.).abs() < 1E-9);
+        assert!(o.project(120., 0.).is_none());
+        let p = o.project(0., 90.).unwrap();
+        assert!((p[1] + 1.).abs() < 1E-9);
+    }
+    #[test]
+    fn test_clip() {
+        let o = MapProjection::orthographic(1., 0., 0.);
+        let lines = o.project_line(&[(0., 0.), (60., 0.), (120., 0.), (180., 0.)]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].len(), 3);
+        assert!((lines[0][2][0] - 1.).abs() < 1E-4);
+
+        let g = MapProjection::equirectangular(1.).graticule(30., 10.);
+        // 13 meridians and 5 parallels
+        assert_eq!(g.len(), 18);
+        let layer = MapProjection::layer("graticule", "graticule", &g, false);
+        assert_eq!(layer.contents().len(), 18);
+    }
+}
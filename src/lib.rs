@@ -18,13 +18,26 @@ limitations under the License.
 
 //a Imports
 mod types;
-pub use types::{Attr, BBox, Bezier, Error, IndentOpt, Point, Range, Transform};
+pub use types::{
+    Affine, Attr, BBox, Bezier, Error, ErrorContext, IndentOpt, Margins, Point, Point3D,
+    PolarPoint, Range,
+};
+pub use types::{FitMode, Length, LengthUnit, Snap, Transform};
 
 mod colors;
-pub use colors::{Color, ColorDatabase};
+pub use colors::{
+    BlendMode, Color, ColorDatabase, ColorDatabaseOwned, ColorKeyword, ColorRamp, Rgba,
+};
+#[cfg(feature = "color-databases")]
+pub use colors::{MaterialColorDatabase, TailwindColorDatabase, X11ColorDatabase};
 
 mod shapes;
-pub use shapes::{BezierPath, Polygon};
+#[cfg(feature = "trace")]
+pub use shapes::ImageTrace;
+pub use shapes::{
+    BezierPath, Gear, GearProfile, GridLines, Hatch, PathBuilder, Polygon, Projection, Route,
+    Shape, ShapeKind, Wireframe,
+};
 
 mod traits;
 
@@ -33,8 +46,54 @@ pub use namespace::NamespaceName;
 
 pub use traits::{CreateSvg, SvgAttributes};
 mod elements;
+pub use elements::{resolve_overlaps, Element, Group, Path};
 pub use elements::{Attributes, Config, LayoutElement, PreLayoutElement};
-pub use elements::{Element, Group, Path};
 
-pub struct Svg {}
-pub struct SvgElement {}
+mod svg;
+pub use svg::CompatChange;
+pub use svg::FrameRegistry;
+pub use svg::IdAllocator;
+pub use svg::Measurement;
+pub use svg::Morph;
+pub use svg::Ruler;
+pub use svg::SvgBatch;
+pub use svg::SvgBuilder;
+pub use svg::SvgColorDatabase;
+pub use svg::SvgCompoundPath;
+#[cfg(feature = "serde")]
+pub use svg::SvgElementData;
+pub use svg::SvgStreamWriter;
+pub use svg::SvgView;
+pub use svg::Timeline;
+pub use svg::Visitor;
+pub use svg::{AccessibilityAudit, AccessibilityIssue};
+pub use svg::{Attribution, Corner};
+pub use svg::{Degradation, SizeBudget};
+pub use svg::{ElementIter, Indent, Newline, XmlEvent};
+pub use svg::{GridMode, SvgDefs, SvgGrid, SvgGroup, SvgPath, SvgRegion, SvgSvg};
+pub use svg::{LabelFormat, NumberFormat};
+pub use svg::{LayerToggles, SvgLayer};
+pub use svg::{MultiStroke, StrokePass};
+pub use svg::{PackMode, SvgImage};
+pub use svg::{PresentationMode, StackSavepoint, Svg, SvgConfig, SvgError, SvgVersion};
+pub use svg::{Sparkfill, SparkfillKind};
+pub use svg::{SvgElement, SvgElementType};
+pub use svg::{SvgLines, SvgTag, SvgText};
+pub use svg::{TextAnimation, TextAnimationKind};
+pub use svg::{XmlProlog, SVG11_DOCTYPE};
+
+mod geo;
+pub use geo::{Choropleth, MapProjection, MapProjectionKind};
+
+#[cfg(feature = "xml-rs")]
+mod xml;
+
+#[cfg(feature = "quick-xml")]
+mod quick_xml;
+
+mod plot;
+#[cfg(feature = "gcode")]
+pub use plot::GcodeExport;
+#[cfg(feature = "pdf")]
+pub use plot::PdfExport;
+pub use plot::{Embroidery, HpglExport, PlotPath, PlotPaths, PlotStats};
@@ -20,6 +20,7 @@ limitations under the License.
 use std::borrow::Cow;
 
 //a NamespaceName
+#[derive(Clone, PartialEq, Eq)]
 pub struct NamespaceName<'a> {
     name: Cow<'a, str>,
     ns: Option<Cow<'a, str>>,
@@ -27,15 +28,33 @@ pub struct NamespaceName<'a> {
 
 //ip NamespaceName
 impl<'a> NamespaceName<'a> {
-    fn local<I: Into<Cow<'a, str>>>(name: I) -> Self {
+    pub fn local<I: Into<Cow<'a, str>>>(name: I) -> Self {
         let name = name.into();
         Self { name, ns: None }
     }
-    fn new(name: &'a str, ns: Option<&'a str>) -> Self {
+    pub fn new(name: &'a str, ns: Option<&'a str>) -> Self {
         let name = name.into();
         let ns = ns.map(|ns| ns.into());
         Self { name, ns }
     }
+    /// Create from a qualified name 'prefix:name' or 'name'
+    pub fn of_qualified(qname: &str) -> NamespaceName<'static> {
+        match qname.split_once(':') {
+            Some((ns, name)) => NamespaceName {
+                name: Cow::Owned(name.to_string()),
+                ns: Some(Cow::Owned(ns.to_string())),
+            },
+            None => NamespaceName::local(qname.to_string()),
+        }
+    }
+    /// Get the local name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the namespace prefix, if any
+    pub fn prefix(&self) -> Option<&str> {
+        self.ns.as_deref()
+    }
 }
 impl<'a> std::fmt::Display for NamespaceName<'a> {
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
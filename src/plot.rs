@@ -0,0 +1,33 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    plot.rs
+@brief   Output of diagrams to pen plotters and similar devices
+ */
+
+//a Imports
+mod embroidery;
+#[cfg(feature = "gcode")]
+mod gcode;
+mod hpgl;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod plot_paths;
+pub use embroidery::Embroidery;
+#[cfg(feature = "gcode")]
+pub use gcode::GcodeExport;
+pub use hpgl::HpglExport;
+#[cfg(feature = "pdf")]
+pub use pdf::PdfExport;
+pub use plot_paths::{PlotPath, PlotPaths, PlotStats};
@@ -0,0 +1,253 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    embroidery.rs
+@brief   Conversion of diagrams to stitches for embroidery
+ */
+
+//a Imports
+use crate::{Bezier, BezierPath, Color, Hatch, Point, SvgColorDatabase, SvgElement, Transform};
+use crate::{SvgLayer, SvgPath};
+
+//a Embroidery
+//tp Embroidery
+/// An [Embroidery] converts the strokes of a diagram to running
+/// stitches of a maximum stitch length, and its fills to zig-zag
+/// satin stitches, for embroidery digitizers
+///
+/// Only explicit 'stroke' and 'fill' attributes (which may be
+/// inherited from groups) are converted; an element with no fill
+/// given is not filled with stitches
+#[derive(Debug, Clone, Copy)]
+pub struct Embroidery {
+    /// Maximum length of a running stitch
+    stitch_length: f64,
+    /// Spacing of the zig-zag of satin stitches
+    satin_spacing: f64,
+    /// Angle (in degrees) of the satin stitches
+    satin_angle: f64,
+    /// Tolerance for flattening curves
+    tolerance: f64,
+}
+
+//ip Default for Embroidery
+impl std::default::Default for Embroidery {
+    fn default() -> Self {
+        Self {
+            stitch_length: 2.5,
+            satin_spacing: 0.4,
+            satin_angle: 45.,
+            tolerance: 0.1,
+        }
+    }
+}
+
+//ip Embroidery
+impl Embroidery {
+    //fp new
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //bp set_stitch_length
+    /// Set the maximum length of running stitches
+    pub fn set_stitch_length(mut self, stitch_length: f64) -> Self {
+        self.stitch_length = stitch_length;
+        self
+    }
+
+    //bp set_satin
+    /// Set the spacing and angle (in degrees) of satin stitches
+    pub fn set_satin(mut self, spacing: f64, angle: f64) -> Self {
+        self.satin_spacing = spacing;
+        self.satin_angle = angle;
+        self
+    }
+
+    //mp running_stitch
+    /// Convert a polyline to a running stitch, splitting each segment
+    /// into stitches no longer than the stitch length
+    pub fn running_stitch(&self, points: &[Point]) -> Vec<Point> {
+        let mut r = vec![];
+        let Some(first) = points.first() else {
+            return r;
+        };
+        r.push(*first);
+        for s in points.windows(2) {
+            let (p0, p1) = (s[0], s[1]);
+            let l = (p1[0] - p0[0]).hypot(p1[1] - p0[1]);
+            let n = {
+                if self.stitch_length > 0. {
+                    (l / self.stitch_length).ceil().max(1.) as usize
+                } else {
+                    1
+                }
+            };
+            for i in 1..=n {
+                let t = i as f64 / n as f64;
+                r.push([p0[0] + (p1[0] - p0[0]) * t, p0[1] + (p1[1] - p0[1]) * t].into());
+            }
+        }
+        r
+    }
+
+    //mp satin
+    /// Convert closed outlines to a single zig-zag satin stitch path
+    /// across them, using the even-odd rule for holes
+    pub fn satin(&self, outlines: &[Vec<Point>]) -> Vec<Point> {
+        let lines = Hatch::new(self.satin_angle, self.satin_spacing).lines(outlines);
+        let mut r = vec![];
+        for (i, (p0, p1)) in lines.into_iter().enumerate() {
+            if i % 2 == 0 {
+                r.push(p0);
+                r.push(p1);
+            } else {
+                r.push(p1);
+                r.push(p0);
+            }
+        }
+        r
+    }
+
+    //mp stitches
+    /// Convert an element (and its contents) to stitch paths, each
+    /// with its thread color, in document order
+    pub fn stitches(&self, element: &SvgElement) -> Vec<(Color, Vec<Point>)> {
+        let mut r = vec![];
+        self.add_element(&mut r, element, &Transform::default(), None, None);
+        r
+    }
+
+    //mi add_element
+    fn add_element(
+        &self,
+        r: &mut Vec<(Color, Vec<Point>)>,
+        element: &SvgElement,
+        transform: &Transform,
+        mut stroke: Option<Color>,
+        mut fill: Option<Color>,
+    ) {
+        let name = element.ns_name().to_string();
+        if ["defs", "pattern", "marker", "clipPath", "mask", "symbol"].contains(&name.as_str()) {
+            return;
+        }
+        let transform = transform.apply_to_transform(element.transform());
+        if let Some(s) = element.get_attribute("stroke") {
+            stroke = SvgColorDatabase.find_color(s).filter(|_| s != "none");
+        }
+        if let Some(f) = element.get_attribute("fill") {
+            fill = SvgColorDatabase
+                .find_color(f)
+                .filter(|_| !f.eq_ignore_ascii_case("none"));
+        }
+        let mut polylines = element.shape_polylines(self.tolerance);
+        for p in polylines.iter_mut() {
+            transform.apply_slice(p);
+        }
+        if let Some(fill) = &fill {
+            let closed: Vec<Vec<Point>> = polylines
+                .iter()
+                .filter(|p| {
+                    let (first, last) = (p[0], p[p.len() - 1]);
+                    p.len() > 2 && first[0] == last[0] && first[1] == last[1]
+                })
+                .cloned()
+                .collect();
+            if !closed.is_empty() {
+                let satin = self.satin(&closed);
+                if satin.len() > 1 {
+                    r.push((fill.clone(), satin));
+                }
+            }
+        }
+        if let Some(stroke) = &stroke {
+            for p in polylines.iter().filter(|p| p.len() > 1) {
+                r.push((stroke.clone(), self.running_stitch(p)));
+            }
+        }
+        for c in element.contents() {
+            self.add_element(r, c, &transform, stroke.clone(), fill.clone());
+        }
+    }
+
+    //mp element
+    /// Convert an element (and its contents) to stitch paths, as a
+    /// group with a layer ('thread-1', etc) for each thread color
+    ///
+    /// The stitch paths are drawn with a thin stroke of the thread
+    /// color; each layer has a 'data-thread' attribute of the color
+    pub fn element<'a>(&self, element: &SvgElement) -> SvgElement<'a> {
+        let mut threads: Vec<(Color, SvgElement<'a>)> = vec![];
+        for (color, stitches) in self.stitches(element) {
+            let mut bp = BezierPath::default();
+            for s in stitches.windows(2) {
+                bp.add_bezier(Bezier::line(&s[0], &s[1]));
+            }
+            let mut path = SvgPath::new_path(bp, false);
+            path.add_attribute("fill", None, "none");
+            path.add_color_value("stroke", color.clone());
+            path.add_attribute("stroke-width", None, "0.1");
+            let rgba = color.as_tuple_rgba();
+            match threads.iter_mut().find(|(c, _)| c.as_tuple_rgba() == rgba) {
+                Some((_, layer)) => layer.push_content(path),
+                None => {
+                    let mut layer = SvgLayer::new(&format!("thread-{}", threads.len() + 1));
+                    layer.add_attribute("data-thread", None, &color.as_str());
+                    layer.push_content(path);
+                    threads.push((color, layer));
+                }
+            }
+        }
+        let mut group = crate::SvgGroup::new();
+        group.add_attribute("class", None, "embroidery");
+        for (_, layer) in threads {
+            group.push_content(layer);
+        }
+        group
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_embroidery {
+    use super::*;
+    use crate::{BBox, SvgGroup};
+    #[test]
+    fn test_stitches() {
+        let e = Embroidery::new().set_stitch_length(1.);
+        let stitches = e.running_stitch(&[[0., 0.].into(), [2.5, 0.].into()]);
+        assert_eq!(stitches.len(), 4);
+        assert!((stitches[1][0] - 2.5 / 3.).abs() < 1E-9);
+
+        let square: Vec<Point> = [[0., 0.], [4., 0.], [4., 4.], [0., 4.], [0., 0.]]
+            .into_iter()
+            .map(|p| p.into())
+            .collect();
+        let satin = e.set_satin(1., 0.).satin(&[square]);
+        assert_eq!(satin.len(), 8);
+        assert!((satin[1][0] - 4.).abs() < 1E-9);
+        assert!((satin[2][0] - 4.).abs() < 1E-9);
+
+        let mut g = SvgGroup::new();
+        g.add_attribute("fill", None, "red");
+        g.add_attribute("stroke", None, "blue");
+        g.push_content(SvgPath::new_box(BBox::new(0., 0., 4., 4.)));
+        let s = e.stitches(&g);
+        assert_eq!(s.len(), 2);
+        assert_eq!(s[0].0.as_tuple_rgba(), (255, 0, 0, 255));
+        assert_eq!(s[1].0.as_tuple_rgba(), (0, 0, 255, 255));
+        let layers = e.element(&g);
+        assert_eq!(layers.contents().len(), 2);
+    }
+}
@@ -0,0 +1,228 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    gcode.rs
+@brief   Export of plot paths as G-code for CNC machines and lasers
+ */
+
+//a Imports
+use crate::{Color, NumberFormat, PlotPath, PlotPaths, Point};
+
+//a GcodeExport
+//tp GcodeExport
+/// A [GcodeExport] generates basic G-code for [PlotPaths], for CNC
+/// machines and laser cutters/engravers
+///
+/// Paths are emitted layer by layer (in order of first appearance of
+/// each layer), as G0 moves to the start of each path followed by G1
+/// moves along it. The spindle or laser power for a path is mapped
+/// from its stroke: black at full opacity is full power, and lighter
+/// or more transparent strokes have proportionally less power.
+///
+/// Document units are taken to be millimeters; the output may be
+/// scaled, and may be in inches. The Y axis is flipped (as G-code has
+/// it upwards) within the bounding box of the paths.
+#[derive(Debug, Clone)]
+pub struct GcodeExport {
+    /// Feed rate for cutting moves (G1), in output units per minute
+    feed_rate: f64,
+    /// Feed rate for travel moves (G0), if it should be given
+    travel_rate: Option<f64>,
+    /// Power ('S' value) for a black, opaque stroke
+    max_power: f64,
+    /// Scale from document units to millimeters
+    scale: f64,
+    /// If true then the output is in inches
+    inches: bool,
+    /// If true then flip the Y axis
+    flip_y: bool,
+    /// Format of coordinates
+    number_format: NumberFormat,
+}
+
+//ip Default for GcodeExport
+impl std::default::Default for GcodeExport {
+    fn default() -> Self {
+        Self {
+            feed_rate: 1000.,
+            travel_rate: None,
+            max_power: 1000.,
+            scale: 1.,
+            inches: false,
+            flip_y: true,
+            number_format: NumberFormat::default(),
+        }
+    }
+}
+
+//ip GcodeExport
+impl GcodeExport {
+    //fp new
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //bp set_feed_rate
+    /// Set the feed rate for cutting (G1) moves, and optionally that
+    /// for travel (G0) moves
+    pub fn set_feed_rate(mut self, feed_rate: f64, travel_rate: Option<f64>) -> Self {
+        self.feed_rate = feed_rate;
+        self.travel_rate = travel_rate;
+        self
+    }
+
+    //bp set_max_power
+    /// Set the power ('S' value) used for a black, opaque stroke
+    pub fn set_max_power(mut self, max_power: f64) -> Self {
+        self.max_power = max_power;
+        self
+    }
+
+    //bp set_scale
+    /// Set the scale from document units to millimeters
+    pub fn set_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    //bp set_inches
+    /// Set whether the output is in inches (G20) rather than
+    /// millimeters (G21)
+    pub fn set_inches(mut self, inches: bool) -> Self {
+        self.inches = inches;
+        self
+    }
+
+    //bp set_flip_y
+    /// Set whether the Y axis is flipped
+    pub fn set_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    //bp set_number_format
+    /// Set the format of coordinates, by default four decimal places
+    pub fn set_number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    //mp power
+    /// Get the power for a stroke; a path without a known stroke is
+    /// given full power
+    pub fn power(&self, stroke: Option<&Color>) -> f64 {
+        let Some(c) = stroke else {
+            return self.max_power;
+        };
+        let (r, g, b, a) = c.as_tuple_rgba();
+        let lightness = (r as f64 + g as f64 + b as f64) / (3. * 255.);
+        self.max_power * (1. - lightness) * (a as f64 / 255.)
+    }
+
+    //mp export
+    /// Generate the G-code for the paths
+    pub fn export(&self, paths: &PlotPaths) -> String {
+        let bbox = paths.bbox();
+        let y_flip = bbox.y[0] + bbox.y[1];
+        let unit_scale = if self.inches {
+            self.scale / 25.4
+        } else {
+            self.scale
+        };
+        let coord = |pt: &Point| {
+            let y = if self.flip_y { y_flip - pt[1] } else { pt[1] };
+            let nf = &self.number_format;
+            format!(
+                "X{} Y{}",
+                nf.number(pt[0] * unit_scale),
+                nf.number(y * unit_scale)
+            )
+        };
+        let mut layers: Vec<(Option<&str>, Vec<&PlotPath>)> = vec![];
+        for p in paths.paths() {
+            let layer = p.layer.as_deref();
+            match layers.iter_mut().find(|(l, _)| *l == layer) {
+                Some((_, ps)) => ps.push(p),
+                None => layers.push((layer, vec![p])),
+            }
+        }
+
+        let mut r = String::new();
+        r.push_str(if self.inches { "G20\n" } else { "G21\n" });
+        r.push_str("G90\nM5\n");
+        for (layer, ps) in layers {
+            if let Some(layer) = layer {
+                r.push_str(&format!("; layer {}\n", layer));
+            }
+            for p in ps {
+                match self.travel_rate {
+                    Some(f) => r.push_str(&format!("G0 {} F{}\n", coord(&p.points[0]), f)),
+                    None => r.push_str(&format!("G0 {}\n", coord(&p.points[0]))),
+                }
+                r.push_str(&format!(
+                    "M3 S{}\n",
+                    self.power(p.stroke.as_ref()).round() as i64
+                ));
+                for (i, pt) in p.points[1..].iter().enumerate() {
+                    if i == 0 {
+                        r.push_str(&format!("G1 {} F{}\n", coord(pt), self.feed_rate));
+                    } else {
+                        r.push_str(&format!("G1 {}\n", coord(pt)));
+                    }
+                }
+                r.push_str("M5\n");
+            }
+        }
+        r.push_str("M2\n");
+        r
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_gcode {
+    use super::*;
+    use crate::SvgColorDatabase;
+    #[test]
+    fn test_export() {
+        let mut paths = PlotPaths::default();
+        paths.push(PlotPath {
+            points: vec![[0., 0.].into(), [1., 0.].into(), [1., 1.].into()],
+            stroke: SvgColorDatabase.find_color("black"),
+            layer: Some("cut".into()),
+        });
+        paths.push(PlotPath {
+            points: vec![[0., 1.].into(), [0., 0.].into()],
+            stroke: SvgColorDatabase.find_color("grey"),
+            layer: None,
+        });
+        let gcode = GcodeExport::new().set_flip_y(false).export(&paths);
+        assert!(gcode.starts_with("G21\nG90\nM5\n; layer cut\nG0 X0.0000 Y0.0000\nM3 S1000\n"));
+        assert!(gcode.contains("G1 X1.0000 Y0.0000 F1000\nG1 X1.0000 Y1.0000\nM5\n"));
+        assert!(gcode.contains("M3 S498\n"));
+        assert!(gcode.ends_with("M5\nM2\n"));
+        let gcode = GcodeExport::new()
+            .set_inches(true)
+            .set_scale(25.4)
+            .export(&paths);
+        assert!(gcode.starts_with("G20\n"));
+        assert!(gcode.contains("G0 X0.0000 Y1.0000\n"));
+        let nf = NumberFormat::default().set_trim_zeros(true);
+        let gcode = GcodeExport::new()
+            .set_flip_y(false)
+            .set_number_format(nf)
+            .export(&paths);
+        assert!(gcode.contains("G1 X1 Y0 F1000\nG1 X1 Y1\n"));
+    }
+}
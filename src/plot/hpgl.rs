@@ -0,0 +1,195 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    hpgl.rs
+@brief   Export of plot paths as HPGL
+ */
+
+//a Imports
+use crate::{Color, ColorDatabase, Error, PlotPaths, SvgColorDatabase};
+
+//a HpglExport
+//tp HpglExport
+/// An [HpglExport] generates HPGL for [PlotPaths], for driving pen
+/// and vinyl plotters
+///
+/// Document units are taken to be millimeters; HPGL plotter units
+/// are 0.025mm. As HPGL has the Y axis upwards, the Y coordinates are
+/// by default flipped within the bounding box of the paths.
+///
+/// Pens are selected from the stroke colors of the paths: if pen
+/// colors are given then the nearest of those is used; otherwise each
+/// distinct stroke color is given the next pen number, up to the
+/// number of pens of the plotter
+#[derive(Debug, Clone)]
+pub struct HpglExport {
+    /// Plotter units per document unit
+    units_per_mm: f64,
+    /// If true then flip the Y axis
+    flip_y: bool,
+    /// Colors of pens 1 upwards; if empty then pens are allocated to
+    /// colors in order
+    pens: Vec<Color>,
+    /// Number of pens the plotter has (if allocating)
+    num_pens: usize,
+}
+
+//ip Default for HpglExport
+impl std::default::Default for HpglExport {
+    fn default() -> Self {
+        Self {
+            units_per_mm: 40.,
+            flip_y: true,
+            pens: vec![],
+            num_pens: 8,
+        }
+    }
+}
+
+//ip HpglExport
+impl HpglExport {
+    //fp new
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //bp set_units_per_mm
+    /// Set the number of plotter units per document unit (mm)
+    pub fn set_units_per_mm(mut self, units_per_mm: f64) -> Self {
+        self.units_per_mm = units_per_mm;
+        self
+    }
+
+    //bp set_flip_y
+    /// Set whether the Y axis is flipped
+    pub fn set_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    //bp set_num_pens
+    /// Set the number of pens to allocate to stroke colors
+    pub fn set_num_pens(mut self, num_pens: usize) -> Self {
+        self.num_pens = num_pens.max(1);
+        self
+    }
+
+    //bp add_pen
+    /// Add a pen of a given color; pens are numbered from 1 in the
+    /// order they are added
+    ///
+    /// An error is returned if a color name is not known
+    pub fn add_pen<'c, T>(mut self, color: T) -> Result<Self, Error>
+    where
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        self.pens.push((color, &SvgColorDatabase).try_into()?);
+        Ok(self)
+    }
+
+    //fi nearest_pen
+    /// Find the pen (numbered from 1) whose color is nearest
+    fn nearest_pen(&self, color: &Color) -> usize {
+        let (r, g, b, _) = color.as_tuple_rgba();
+        let mut best = (1, i32::MAX);
+        for (i, p) in self.pens.iter().enumerate() {
+            let (pr, pg, pb, _) = p.as_tuple_rgba();
+            let d = (r as i32 - pr as i32).pow(2)
+                + (g as i32 - pg as i32).pow(2)
+                + (b as i32 - pb as i32).pow(2);
+            if d < best.1 {
+                best = (i + 1, d);
+            }
+        }
+        best.0
+    }
+
+    //mp export
+    /// Generate the HPGL for the paths, in the order of the paths
+    pub fn export(&self, paths: &PlotPaths) -> String {
+        let bbox = paths.bbox();
+        let y_flip = bbox.y[0] + bbox.y[1];
+        let coord = |pt: &crate::Point| {
+            let y = if self.flip_y { y_flip - pt[1] } else { pt[1] };
+            format!(
+                "{},{}",
+                (pt[0] * self.units_per_mm).round() as i64,
+                (y * self.units_per_mm).round() as i64
+            )
+        };
+        let mut allocated: Vec<(u8, u8, u8, u8)> = vec![];
+        let mut r = String::from("IN;\n");
+        let mut pen = 0;
+        for p in paths.paths() {
+            let p_pen = match &p.stroke {
+                None => 1,
+                Some(c) if !self.pens.is_empty() => self.nearest_pen(c),
+                Some(c) => {
+                    let rgba = c.as_tuple_rgba();
+                    let n = match allocated.iter().position(|a| *a == rgba) {
+                        Some(n) => n,
+                        None => {
+                            allocated.push(rgba);
+                            allocated.len() - 1
+                        }
+                    };
+                    n % self.num_pens + 1
+                }
+            };
+            if p_pen != pen {
+                pen = p_pen;
+                r.push_str(&format!("SP{};\n", pen));
+            }
+            r.push_str(&format!("PU{};\n", coord(&p.points[0])));
+            let pts: Vec<String> = p.points[1..].iter().map(coord).collect();
+            r.push_str(&format!("PD{};\n", pts.join(",")));
+        }
+        r.push_str("PU;\nSP0;\n");
+        r
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_hpgl {
+    use super::*;
+    use crate::{PlotPath, Point};
+    use geo_nd::Vector;
+    fn path(p: &[[f64; 2]], stroke: &str) -> PlotPath {
+        PlotPath {
+            points: p.iter().map(|p| Point::from_array(*p)).collect(),
+            stroke: SvgColorDatabase.find_color(stroke),
+            layer: None,
+        }
+    }
+    #[test]
+    fn test_export() {
+        let mut paths = PlotPaths::default();
+        paths.push(path(&[[0., 0.], [1., 0.], [1., 1.]], "red"));
+        paths.push(path(&[[0., 1.], [0., 0.5]], "blue"));
+        let hpgl = HpglExport::new().export(&paths);
+        assert_eq!(
+            hpgl,
+            "IN;\nSP1;\nPU0,40;\nPD40,40,40,0;\nSP2;\nPU0,0;\nPD0,20;\nPU;\nSP0;\n"
+        );
+        let hpgl = HpglExport::new()
+            .add_pen("black")
+            .unwrap()
+            .add_pen("darkblue")
+            .unwrap()
+            .set_flip_y(false)
+            .export(&paths);
+        assert!(hpgl.contains("SP1;\nPU0,0;\nPD40,0,40,40;\nSP2;"));
+    }
+}
@@ -0,0 +1,394 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    pdf.rs
+@brief   Export of a finalized element tree as a single page PDF
+ */
+
+//a Imports
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str};
+
+use crate::{BBox, Color, Error, SvgColorDatabase, SvgElement, Transform};
+
+//a Constants
+/// Names of elements whose contents are not drawn directly
+const UNDRAWN_ELEMENTS: &[&str] = &["pattern", "marker", "clipPath", "mask", "symbol"];
+
+/// Points per document unit (mm)
+const PT_PER_MM: f64 = 72. / 25.4;
+
+/// Name of the font resource used for text
+const FONT_NAME: Name<'static> = Name(b"F1");
+
+/// Base font names of the standard Type 1 fonts, which every PDF
+/// reader provides
+const STANDARD_FONTS: &[&str] = &[
+    "Times-Roman",
+    "Times-Bold",
+    "Times-Italic",
+    "Times-BoldItalic",
+    "Helvetica",
+    "Helvetica-Bold",
+    "Helvetica-Oblique",
+    "Helvetica-BoldOblique",
+    "Courier",
+    "Courier-Bold",
+    "Courier-Oblique",
+    "Courier-BoldOblique",
+    "Symbol",
+    "ZapfDingbats",
+];
+
+//a Useful functions
+//fi paint
+/// Get the color of a 'fill' or 'stroke' attribute value, or None if
+/// it is 'none', fully transparent, or not a color (such as a
+/// gradient)
+fn paint(value: &str) -> Option<Color> {
+    SvgColorDatabase
+        .find_color(value)
+        .filter(|c| c.as_tuple_rgba().3 > 0)
+}
+
+//fi rgb
+fn rgb(color: &Color) -> (f32, f32, f32) {
+    let (r, g, b, _) = color.as_tuple_rgba();
+    (r as f32 / 255., g as f32 / 255., b as f32 / 255.)
+}
+
+//fi size
+/// Parse a length attribute, ignoring any units
+fn size(value: &str) -> Option<f64> {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+//a Style
+//ti Style
+/// The inherited presentation attributes of an element
+#[derive(Debug, Clone)]
+struct Style {
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    stroke_width: f64,
+    even_odd: bool,
+    font_size: f64,
+    anchor: f64,
+}
+
+//ii Default for Style
+impl std::default::Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: SvgColorDatabase.find_color("black"),
+            stroke: None,
+            stroke_width: 1.,
+            even_odd: false,
+            font_size: 16.,
+            anchor: 0.,
+        }
+    }
+}
+
+//ii Style
+impl Style {
+    //mi inherit
+    /// Update the style from the attributes of an element
+    fn inherit(&mut self, element: &SvgElement) {
+        if let Some(f) = element.get_attribute("fill") {
+            self.fill = paint(f);
+        }
+        if let Some(s) = element.get_attribute("stroke") {
+            self.stroke = paint(s);
+        }
+        if let Some(w) = element.get_attribute("stroke-width").and_then(size) {
+            self.stroke_width = w;
+        }
+        if let Some(r) = element.get_attribute("fill-rule") {
+            self.even_odd = r == "evenodd";
+        }
+        if let Some(s) = element.get_attribute("font-size").and_then(size) {
+            self.font_size = s;
+        }
+        if let Some(a) = element.get_attribute("text-anchor") {
+            self.anchor = match a {
+                "middle" => 0.5,
+                "end" => 1.,
+                _ => 0.,
+            };
+        }
+    }
+}
+
+//a PdfExport
+//tp PdfExport
+/// A [PdfExport] generates a single page PDF from a finalized element
+/// tree (such as the root element of an [crate::Svg] after
+/// generate_diagram), for print workflows that require PDF directly
+///
+/// Document units are taken to be millimeters, and the page is the
+/// bounding box given; as PDF has the Y axis upwards the content is
+/// flipped within the page.
+///
+/// Shapes are flattened to polylines, and filled and stroked with
+/// solid colors; paints that are not colors (such as gradients) are
+/// not drawn, and opacity is not supported. Text is drawn with a
+/// standard Type 1 font (Helvetica by default), with characters
+/// outside of ASCII replaced by '?'. As with [crate::PlotPaths],
+/// patterns, markers, clip paths, masks and symbols are not drawn,
+/// and 'use' elements are not resolved.
+#[derive(Debug, Clone)]
+pub struct PdfExport {
+    /// Tolerance to flatten curves with, in document units
+    tolerance: f64,
+    /// Base font name of the standard Type 1 font for text
+    font: String,
+}
+
+//ip Default for PdfExport
+impl std::default::Default for PdfExport {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.01,
+            font: "Helvetica".into(),
+        }
+    }
+}
+
+//ip PdfExport
+impl PdfExport {
+    //fp new
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //bp set_tolerance
+    /// Set the tolerance to flatten curves with
+    pub fn set_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    //bp set_font
+    /// Set the standard Type 1 font used for text, such as 'Times-Roman'
+    /// or 'Courier'
+    ///
+    /// Fonts are not embedded, so only the fourteen standard fonts may
+    /// be used
+    pub fn set_font(mut self, font: &str) -> Result<Self, Error> {
+        if !STANDARD_FONTS.contains(&font) {
+            return Err(Error::UnknownFont { name: font.into() });
+        }
+        self.font = font.into();
+        Ok(self)
+    }
+
+    //mp export
+    /// Generate the PDF for an element tree, with a page of the given
+    /// bounding box
+    pub fn export(&self, element: &SvgElement, bbox: &BBox) -> Vec<u8> {
+        let (x0, y1) = (bbox.x[0], bbox.y[1]);
+        let width = (bbox.x[1] - bbox.x[0]) * PT_PER_MM;
+        let height = (bbox.y[1] - bbox.y[0]) * PT_PER_MM;
+
+        let mut content = Content::new();
+        content.transform([
+            PT_PER_MM as f32,
+            0.,
+            0.,
+            -PT_PER_MM as f32,
+            (-x0 * PT_PER_MM) as f32,
+            (y1 * PT_PER_MM) as f32,
+        ]);
+        self.add_element(
+            &mut content,
+            element,
+            &Transform::default(),
+            Style::default(),
+        );
+
+        let catalog_id = Ref::new(1);
+        let pages_id = Ref::new(2);
+        let page_id = Ref::new(3);
+        let font_id = Ref::new(4);
+        let content_id = Ref::new(5);
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(pages_id);
+        pdf.pages(pages_id).kids([page_id]).count(1);
+        {
+            // The page is written out when it is dropped
+            let mut page = pdf.page(page_id);
+            page.media_box(Rect::new(0., 0., width as f32, height as f32));
+            page.parent(pages_id);
+            page.contents(content_id);
+            page.resources().fonts().pair(FONT_NAME, font_id);
+        }
+        pdf.type1_font(font_id)
+            .base_font(Name(self.font.as_bytes()));
+        pdf.stream(content_id, &content.finish());
+        pdf.finish()
+    }
+
+    //mi add_element
+    fn add_element(
+        &self,
+        content: &mut Content,
+        element: &SvgElement,
+        transform: &Transform,
+        mut style: Style,
+    ) {
+        let name = element.ns_name().to_string();
+        if UNDRAWN_ELEMENTS.contains(&name.as_str()) {
+            return;
+        }
+        let transform = transform.apply_to_transform(element.transform());
+        style.inherit(element);
+        if name == "text" {
+            self.add_text(content, element, &transform, &style);
+        } else {
+            self.add_shape(content, element, &transform, &style);
+        }
+        for c in element.contents() {
+            self.add_element(content, c, &transform, style.clone());
+        }
+    }
+
+    //mi add_shape
+    fn add_shape(
+        &self,
+        content: &mut Content,
+        element: &SvgElement,
+        transform: &Transform,
+        style: &Style,
+    ) {
+        let fill = style.fill.as_ref();
+        let stroke = style.stroke.as_ref().filter(|_| style.stroke_width > 0.);
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+        let mut any = false;
+        for mut points in element.shape_polylines(self.tolerance) {
+            if points.len() < 2 {
+                continue;
+            }
+            transform.apply_slice(&mut points);
+            content.move_to(points[0][0] as f32, points[0][1] as f32);
+            for p in &points[1..] {
+                content.line_to(p[0] as f32, p[1] as f32);
+            }
+            let (first, last) = (points[0], points[points.len() - 1]);
+            if first[0] == last[0] && first[1] == last[1] {
+                content.close_path();
+            }
+            any = true;
+        }
+        if !any {
+            return;
+        }
+        if let Some(c) = fill {
+            let (r, g, b) = rgb(c);
+            content.set_fill_rgb(r, g, b);
+        }
+        if let Some(c) = stroke {
+            let (r, g, b) = rgb(c);
+            content.set_stroke_rgb(r, g, b);
+            content.set_line_width(style.stroke_width as f32);
+        }
+        match (fill.is_some(), stroke.is_some(), style.even_odd) {
+            (true, true, false) => content.fill_nonzero_and_stroke(),
+            (true, true, true) => content.fill_even_odd_and_stroke(),
+            (true, false, false) => content.fill_nonzero(),
+            (true, false, true) => content.fill_even_odd(),
+            _ => content.stroke(),
+        };
+    }
+
+    //mi add_text
+    fn add_text(
+        &self,
+        content: &mut Content,
+        element: &SvgElement,
+        transform: &Transform,
+        style: &Style,
+    ) {
+        let Some(fill) = style.fill.as_ref() else {
+            return;
+        };
+        let text: Vec<u8> = element
+            .characters()
+            .chars()
+            .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+            .collect();
+        if text.is_empty() {
+            return;
+        }
+        let x = element.get_attribute("x").and_then(size).unwrap_or(0.);
+        let y = element.get_attribute("y").and_then(size).unwrap_or(0.);
+        // Approximate the width of the text for the anchor, as the
+        // metrics of the font are not available
+        let x = x - style.anchor * style.font_size * 0.5 * (text.len() as f64);
+        let mut points = [[x, y].into()];
+        transform.apply_slice(&mut points);
+        let (r, g, b) = rgb(fill);
+        content.set_fill_rgb(r, g, b);
+        content.begin_text();
+        content.set_font(FONT_NAME, style.font_size as f32);
+        // The page is flipped, so flip the text back to be upright
+        content.set_text_matrix([1., 0., 0., -1., points[0][0] as f32, points[0][1] as f32]);
+        content.show(Str(&text));
+        content.end_text();
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_pdf {
+    use super::*;
+    use crate::{SvgGroup, SvgPath, SvgText};
+    #[test]
+    fn test_export() {
+        let mut g = SvgGroup::new();
+        let mut p = SvgPath::new_box(BBox::new(10., 10., 20., 20.));
+        p.add_attribute("fill", None, "red");
+        p.add_attribute("stroke", None, "blue");
+        g.push_content(p);
+        let mut t = SvgText::new([5., 5.].into(), "Hello");
+        t.add_attribute("font-size", None, "3");
+        g.push_content(t);
+        g.finalize(&crate::SvgConfig::default()).unwrap();
+
+        let pdf = PdfExport::new().export(&g, &BBox::new(0., 0., 100., 50.));
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(contains(&pdf, b"/Helvetica"));
+        assert!(contains(&pdf, b"(Hello) Tj"));
+        assert!(contains(&pdf, b"1 0 0 rg"));
+        assert!(contains(&pdf, b"0 0 1 RG"));
+        assert!(!contains(&pdf, b"/Times-Roman"));
+
+        let pdf = PdfExport::new()
+            .set_font("Times-Roman")
+            .unwrap()
+            .export(&g, &BBox::new(0., 0., 100., 50.));
+        assert!(contains(&pdf, b"/Times-Roman"));
+        assert!(matches!(
+            PdfExport::new().set_font("Comic Sans"),
+            Err(Error::UnknownFont { .. })
+        ));
+    }
+    fn contains(pdf: &[u8], s: &[u8]) -> bool {
+        pdf.windows(s.len()).any(|w| w == s)
+    }
+}
@@ -0,0 +1,368 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    plot_paths.rs
+@brief   Polylines to be drawn by a pen plotter, and their optimization
+ */
+
+//a Imports
+use geo_nd::Vector;
+
+use crate::{BBox, Color, Point, SvgColorDatabase, SvgElement, Transform};
+
+//a Constants
+/// Names of elements whose contents are not drawn directly
+const UNDRAWN_ELEMENTS: &[&str] = &["pattern", "marker", "clipPath", "mask", "symbol"];
+
+//a Useful functions
+//fi distance
+fn distance(a: &Point, b: &Point) -> f64 {
+    (a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+//fi is_collinear
+/// Return true if `b` lies on the straight line from `a` to `c`
+/// (within epsilon), between them
+fn is_collinear(a: &Point, b: &Point, c: &Point, epsilon: f64) -> bool {
+    let l = distance(a, c);
+    if l <= epsilon {
+        return false;
+    }
+    let cross = (c[0] - a[0]) * (b[1] - a[1]) - (c[1] - a[1]) * (b[0] - a[0]);
+    let dot = (c[0] - a[0]) * (b[0] - a[0]) + (c[1] - a[1]) * (b[1] - a[1]);
+    (cross / l).abs() <= epsilon && dot >= 0. && dot <= l * l
+}
+
+//a PlotStats
+//tp PlotStats
+/// Statistics of the drawing of [PlotPaths], starting with the pen
+/// at the origin
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PlotStats {
+    /// Number of polylines (and hence pen lifts)
+    pub paths: usize,
+    /// Number of straight segments drawn
+    pub segments: usize,
+    /// Distance travelled with the pen down
+    pub pen_down: f64,
+    /// Distance travelled with the pen up
+    pub pen_up: f64,
+}
+
+//a PlotPath
+//tp PlotPath
+/// A single polyline of [PlotPaths], with the stroke and layer that
+/// it is drawn with
+#[derive(Debug, Clone, Default)]
+pub struct PlotPath {
+    /// Points of the polyline, of which there are at least two
+    pub points: Vec<Point>,
+    /// Stroke color (including any stroke opacity), if known
+    pub stroke: Option<Color>,
+    /// Layer (from a 'data-layer' attribute) that the path is in
+    pub layer: Option<String>,
+}
+
+//ip PlotPath
+impl PlotPath {
+    //fi start
+    fn start(&self) -> &Point {
+        &self.points[0]
+    }
+
+    //fi end
+    fn end(&self) -> &Point {
+        self.points.last().unwrap()
+    }
+
+    //fi same_pen
+    /// Return true if the path is drawn with the same stroke and layer
+    /// as another
+    fn same_pen(&self, other: &Self) -> bool {
+        self.layer == other.layer
+            && self.stroke.as_ref().map(|c| c.as_tuple_rgba())
+                == other.stroke.as_ref().map(|c| c.as_tuple_rgba())
+    }
+}
+
+//a PlotPaths
+//tp PlotPaths
+/// A [PlotPaths] is an ordered set of polylines to be drawn by a pen
+/// plotter (or laser engraver, etc), each with the pen down
+///
+/// The paths may be optimized to reduce the travel of the pen between
+/// them, and to reduce the number of segments and pen lifts; paths
+/// are kept grouped by stroke and layer, so that pen changes are not
+/// increased
+#[derive(Debug, Clone, Default)]
+pub struct PlotPaths {
+    paths: Vec<PlotPath>,
+}
+
+//ip PlotPaths
+impl PlotPaths {
+    //fp new
+    /// Create from a set of polylines; any with fewer than two points
+    /// are dropped
+    pub fn new(paths: Vec<Vec<Point>>) -> Self {
+        let mut s = Self::default();
+        for points in paths {
+            s.push(PlotPath {
+                points,
+                ..Default::default()
+            });
+        }
+        s
+    }
+
+    //fp of_element
+    /// Create from the flattened geometry of an element (such as the
+    /// root element of an [crate::Svg] after generate_diagram),
+    /// including its contents
+    ///
+    /// Patterns, markers, clip paths, masks and symbols are not
+    /// included, as they are not drawn directly; 'use' elements are not
+    /// resolved
+    pub fn of_element(element: &SvgElement, tolerance: f64) -> Self {
+        let mut s = Self::default();
+        s.add_element(element, tolerance, &Transform::default(), None, 1., None);
+        s
+    }
+
+    //mi add_element
+    fn add_element(
+        &mut self,
+        element: &SvgElement,
+        tolerance: f64,
+        transform: &Transform,
+        mut stroke: Option<Color>,
+        mut opacity: f64,
+        mut layer: Option<String>,
+    ) {
+        let name = element.ns_name().to_string();
+        if UNDRAWN_ELEMENTS.contains(&name.as_str()) {
+            return;
+        }
+        let transform = transform.apply_to_transform(element.transform());
+        if let Some(s) = element.get_attribute("stroke") {
+            stroke = SvgColorDatabase.find_color(s);
+        }
+        if let Some(o) = element.get_attribute("stroke-opacity") {
+            opacity = o.parse().unwrap_or(opacity);
+        }
+        if let Some(l) = element.get_attribute("data-layer") {
+            layer = Some(l.into());
+        }
+        let stroke_with_opacity = stroke.as_ref().map(|c| {
+            let alpha = c.as_tuple_rgba().3 as f64 * opacity.clamp(0., 1.);
+            c.clone().set_alpha(alpha.round() as u8)
+        });
+        for mut points in element.shape_polylines(tolerance) {
+            transform.apply_slice(&mut points);
+            self.push(PlotPath {
+                points,
+                stroke: stroke_with_opacity.clone(),
+                layer: layer.clone(),
+            });
+        }
+        for c in element.contents() {
+            self.add_element(
+                c,
+                tolerance,
+                &transform,
+                stroke.clone(),
+                opacity,
+                layer.clone(),
+            );
+        }
+    }
+
+    //mp push
+    /// Add a path; it is dropped if it has fewer than two points
+    pub fn push(&mut self, path: PlotPath) {
+        if path.points.len() > 1 {
+            self.paths.push(path);
+        }
+    }
+
+    //ap paths
+    pub fn paths(&self) -> &[PlotPath] {
+        &self.paths
+    }
+
+    //ap bbox
+    /// Get the bounding box of all of the paths
+    pub fn bbox(&self) -> BBox {
+        self.paths
+            .iter()
+            .flat_map(|p| p.points.iter().copied())
+            .collect()
+    }
+
+    //mp stats
+    /// Get the statistics of drawing the paths in order
+    pub fn stats(&self) -> PlotStats {
+        let mut stats = PlotStats {
+            paths: self.paths.len(),
+            ..Default::default()
+        };
+        let mut pen = Point::zero();
+        for p in self.paths.iter() {
+            stats.pen_up += distance(&pen, p.start());
+            for s in p.points.windows(2) {
+                stats.segments += 1;
+                stats.pen_down += distance(&s[0], &s[1]);
+            }
+            pen = *p.end();
+        }
+        stats
+    }
+
+    //mp optimize
+    /// Optimize the paths: reorder them to reduce pen travel, join
+    /// paths where one ends at (within epsilon of) the start of the
+    /// next, and remove intermediate points of collinear segments
+    pub fn optimize(&mut self, epsilon: f64) {
+        self.order();
+        self.merge(epsilon);
+        self.simplify(epsilon);
+    }
+
+    //mp order
+    /// Reorder the paths to reduce pen travel, using a greedy nearest
+    /// neighbor from the origin; paths are reversed if their end is
+    /// nearer than their start
+    ///
+    /// Paths of each stroke and layer are kept together, in order of
+    /// the first appearance of that stroke and layer
+    pub fn order(&mut self) {
+        let mut groups: Vec<Vec<PlotPath>> = vec![];
+        for p in std::mem::take(&mut self.paths) {
+            match groups.iter_mut().find(|g| g[0].same_pen(&p)) {
+                Some(g) => g.push(p),
+                None => groups.push(vec![p]),
+            }
+        }
+        let mut pen = Point::zero();
+        for mut remaining in groups {
+            while !remaining.is_empty() {
+                let mut best = (0, false, f64::MAX);
+                for (i, p) in remaining.iter().enumerate() {
+                    let d_start = distance(&pen, p.start());
+                    let d_end = distance(&pen, p.end());
+                    if d_start < best.2 {
+                        best = (i, false, d_start);
+                    }
+                    if d_end < best.2 {
+                        best = (i, true, d_end);
+                    }
+                }
+                let mut p = remaining.swap_remove(best.0);
+                if best.1 {
+                    p.points.reverse();
+                }
+                pen = *p.end();
+                self.paths.push(p);
+            }
+        }
+    }
+
+    //mp merge
+    /// Join consecutive paths of the same stroke and layer where one
+    /// ends within epsilon of the start of the next
+    pub fn merge(&mut self, epsilon: f64) {
+        let mut merged: Vec<PlotPath> = vec![];
+        for p in std::mem::take(&mut self.paths) {
+            if let Some(last) = merged.last_mut() {
+                if last.same_pen(&p) && distance(last.end(), p.start()) <= epsilon {
+                    last.points.extend(p.points.into_iter().skip(1));
+                    continue;
+                }
+            }
+            merged.push(p);
+        }
+        self.paths = merged;
+    }
+
+    //mp simplify
+    /// Remove points of paths that lie on the straight line between
+    /// their neighbors (within epsilon)
+    pub fn simplify(&mut self, epsilon: f64) {
+        for p in self.paths.iter_mut() {
+            let mut r: Vec<Point> = Vec::with_capacity(p.points.len());
+            for pt in p.points.iter() {
+                if r.len() >= 2 && is_collinear(&r[r.len() - 2], &r[r.len() - 1], pt, epsilon) {
+                    r.pop();
+                }
+                r.push(*pt);
+            }
+            p.points = r;
+        }
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_plot_paths {
+    use super::*;
+    fn pts(p: &[[f64; 2]]) -> Vec<Point> {
+        p.iter().map(|p| (*p).into()).collect()
+    }
+    fn paths_eq(path: &[Point], expected: &[[f64; 2]]) {
+        assert_eq!(path.len(), expected.len(), "Path length mismatch");
+        for (p, e) in path.iter().zip(expected.iter()) {
+            assert!(
+                (p[0] - e[0]).abs() < 1E-8 && (p[1] - e[1]).abs() < 1E-8,
+                "Point mismatch {p:?} {e:?}"
+            );
+        }
+    }
+    #[test]
+    fn test_optimize() {
+        let mut plot = PlotPaths::new(vec![
+            pts(&[[10., 0.], [10., 10.]]),
+            pts(&[[5., 0.], [10., 0.]]),
+            pts(&[[0., 0.], [5., 0.]]),
+            pts(&[[3., 3.]]),
+        ]);
+        assert_eq!(plot.paths().len(), 3);
+        let before = plot.stats();
+        assert_eq!(before.segments, 3);
+        assert!((before.pen_down - 20.).abs() < 1E-9);
+        plot.optimize(1E-6);
+        let after = plot.stats();
+        assert_eq!(after.paths, 1);
+        assert_eq!(after.segments, 2);
+        assert!((after.pen_down - 20.).abs() < 1E-9);
+        assert!(after.pen_up < before.pen_up);
+        paths_eq(&plot.paths()[0].points, &[[0., 0.], [10., 0.], [10., 10.]]);
+    }
+    #[test]
+    fn test_of_element() {
+        use crate::{SvgGroup, SvgLines};
+        let mut g = SvgGroup::new();
+        g.add_attribute("stroke", None, "red");
+        g.add_attribute("stroke-opacity", None, "0.5");
+        g.apply_transform(&Transform::of_translation([1., 2.].into()));
+        let lines = SvgLines::new(vec![([0., 0.].into(), [1., 0.].into())]);
+        g.push_content(lines);
+        let plot = PlotPaths::of_element(&g, 0.1);
+        assert_eq!(plot.paths().len(), 1);
+        paths_eq(&plot.paths()[0].points, &[[1., 2.], [2., 2.]]);
+        assert_eq!(
+            plot.paths()[0].stroke.as_ref().unwrap().as_tuple_rgba(),
+            (255, 0, 0, 128)
+        );
+    }
+}
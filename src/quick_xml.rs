@@ -0,0 +1,121 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    quick_xml.rs
+@brief   Output of the events of a document through the quick-xml writer
+ */
+
+//a Imports
+use std::io::Write;
+
+use ::quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use ::quick_xml::Writer;
+
+use crate::svg::is_self_closing;
+use crate::{SvgError, XmlEvent, XmlProlog};
+
+//a Functions
+//fp write_events
+/// Write the events of a document through a quick-xml writer
+///
+/// Attribute names (including any prefix, such as 'xmlns:svg' or
+/// 'xlink:href') are written as they are; escaping of attribute
+/// values and characters is performed by quick-xml. Elements with no
+/// contents or characters are written as empty (self-closing) tags.
+/// The document starts with the prolog given.
+pub(crate) fn write_events<'a: 'x, 'x, W: Write, I: Iterator<Item = XmlEvent<'a, 'x>>>(
+    events: I,
+    writer: &mut Writer<W>,
+    prolog: &XmlProlog,
+) -> Result<(), SvgError> {
+    for event in events {
+        match event {
+            XmlEvent::StartDocument => {
+                if prolog.declaration {
+                    let standalone = prolog.standalone.map(|s| if s { "yes" } else { "no" });
+                    writer.write_event(Event::Decl(BytesDecl::new(
+                        &prolog.version,
+                        prolog.encoding.as_deref(),
+                        standalone,
+                    )))?;
+                }
+                if let Some(doctype) = &prolog.doctype {
+                    writer
+                        .write_event(Event::DocType(BytesText::from_escaped(doctype.as_str())))?;
+                }
+            }
+            XmlEvent::EndDocument => (),
+            XmlEvent::StartElement(e) => {
+                let name = e.ns_name().to_string();
+                let mut start = BytesStart::new(name.as_str());
+                for (n, v) in e.written_attributes().iter() {
+                    start.push_attribute((n.to_string().as_str(), v.as_str()));
+                }
+                if is_self_closing(e) {
+                    writer.write_event(Event::Empty(start))?;
+                } else {
+                    writer.write_event(Event::Start(start))?;
+                }
+            }
+            XmlEvent::Characters(e) => {
+                writer.write_event(Event::Text(BytesText::new(e.characters())))?
+            }
+            XmlEvent::EndElement(e) if is_self_closing(e) => (),
+            XmlEvent::EndElement(e) => {
+                let name = e.ns_name().to_string();
+                writer.write_event(Event::End(BytesEnd::new(name.as_str())))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+//a Test
+#[cfg(test)]
+mod test_quick_xml {
+    use ::quick_xml::Writer;
+
+    use crate::{Svg, SvgConfig, SvgText};
+    #[test]
+    fn test_write() {
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut t = SvgText::new([0., 0.].into(), "Fish & Chips");
+        t.add_attribute("class", None, "a\"b");
+        svg.contents_add_element(t);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut writer = Writer::new_with_indent(vec![], b' ', 2);
+        svg.write_quick_xml(&mut writer).unwrap();
+        let xml = String::from_utf8(writer.into_inner()).unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(r#"xmlns="http://www.w3.org/2000/svg""#));
+        assert!(xml.contains("Fish &amp; Chips"));
+        assert!(xml.contains(r#"class="a&quot;b""#));
+        assert!(xml.trim_end().ends_with("</svg>"));
+    }
+    #[test]
+    fn test_prolog() {
+        let prolog = crate::XmlProlog::new()
+            .set_standalone(Some(false))
+            .set_svg11_doctype();
+        let mut svg = Svg::new(SvgConfig::default().set_prolog(prolog));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut writer = Writer::new(vec![]);
+        svg.write_quick_xml(&mut writer).unwrap();
+        let xml = String::from_utf8(writer.into_inner()).unwrap();
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#));
+        assert!(xml.contains(r#"<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN""#));
+    }
+}
@@ -18,6 +18,24 @@ limitations under the License.
 
 //a Imports
 mod bezier_path;
+mod gear;
+mod grid_lines;
+mod hatch;
+mod path_builder;
 mod polygon;
+mod route;
+mod shape;
+#[cfg(feature = "trace")]
+mod trace;
+mod wireframe;
 pub use bezier_path::BezierPath;
+pub use gear::{Gear, GearProfile};
+pub use grid_lines::GridLines;
+pub use hatch::Hatch;
+pub use path_builder::PathBuilder;
 pub use polygon::Polygon;
+pub use route::Route;
+pub use shape::{Shape, ShapeKind};
+#[cfg(feature = "trace")]
+pub use trace::ImageTrace;
+pub use wireframe::{Projection, Wireframe};
@@ -19,7 +19,205 @@ limitations under the License.
 //a Imports
 use geo_nd::Vector;
 
-use crate::{Bezier, Point};
+use crate::{Bezier, Error, PathBuilder, Point};
+
+//a Offsetting
+//fi control_points
+/// Get the control polygon of a Bezier, in order from start to end
+fn control_points(b: &Bezier) -> Vec<Point> {
+    match b.degree() {
+        1 => vec![*b.borrow_pt(0), *b.borrow_pt(1)],
+        2 => vec![*b.borrow_pt(0), *b.borrow_pt(2), *b.borrow_pt(1)],
+        _ => vec![
+            *b.borrow_pt(0),
+            *b.borrow_pt(2),
+            *b.borrow_pt(3),
+            *b.borrow_pt(1),
+        ],
+    }
+}
+
+//fi of_control_points
+/// Create a Bezier from its control polygon
+fn of_control_points(pts: &[Point]) -> Bezier {
+    match pts.len() {
+        2 => Bezier::line(&pts[0], &pts[1]),
+        3 => Bezier::quadratic(&pts[0], &pts[1], &pts[2]),
+        _ => Bezier::cubic(&pts[0], &pts[1], &pts[2], &pts[3]),
+    }
+}
+
+//fi de_casteljau
+/// Split a control polygon at a parameter, returning the control
+/// polygons of the two halves; the point at the parameter is the end
+/// of the first (and start of the second)
+fn de_casteljau(pts: &[Point], t: f64) -> (Vec<Point>, Vec<Point>) {
+    let mut level = pts.to_vec();
+    let mut first = vec![level[0]];
+    let mut second = vec![*level.last().unwrap()];
+    while level.len() > 1 {
+        level = level
+            .windows(2)
+            .map(|w| w[0] * (1. - t) + w[1] * t)
+            .collect();
+        first.push(level[0]);
+        second.push(*level.last().unwrap());
+    }
+    second.reverse();
+    (first, second)
+}
+
+//fi unit_normal
+/// Get the unit normal to the left of the direction from a to b (the
+/// direction rotated by +90 degrees), if they differ
+fn unit_normal(a: &Point, b: &Point) -> Option<Point> {
+    let d = *b - *a;
+    let l = d.length();
+    if l < 1E-12 {
+        None
+    } else {
+        Some(Point::from_array([-d[1] / l, d[0] / l]))
+    }
+}
+
+//fi intersect
+/// Find the intersection of the line through p0 and q0 with that
+/// through p1 and q1, if they are not parallel
+fn intersect(p0: &Point, q0: &Point, p1: &Point, q1: &Point) -> Option<Point> {
+    let d0 = *q0 - *p0;
+    let d1 = *q1 - *p1;
+    let cross = d0[0] * d1[1] - d0[1] * d1[0];
+    if cross.abs() <= 1E-9 * d0.length() * d1.length() {
+        return None;
+    }
+    let e = *p1 - *p0;
+    let t = (e[0] * d1[1] - e[1] * d1[0]) / cross;
+    Some(*p0 + d0 * t)
+}
+
+//fi offset_control_points
+/// Offset a control polygon by offsetting each of its legs along its
+/// normal and intersecting adjacent legs (the Tiller-Hanson method)
+fn offset_control_points(pts: &[Point], distance: f64) -> Vec<Point> {
+    let n = pts.len();
+    let normals: Vec<Option<Point>> = pts.windows(2).map(|w| unit_normal(&w[0], &w[1])).collect();
+    // Degenerate legs take the normal of the nearest leg that is not
+    let Some(first) = normals.iter().flatten().next().copied() else {
+        return pts.to_vec();
+    };
+    let mut last = first;
+    let normals: Vec<Point> = normals
+        .into_iter()
+        .map(|nm| {
+            last = nm.unwrap_or(last);
+            last
+        })
+        .collect();
+    let legs: Vec<(Point, Point)> = (0..n - 1)
+        .map(|i| {
+            let o = normals[i] * distance;
+            (pts[i] + o, pts[i + 1] + o)
+        })
+        .collect();
+    let mut r = vec![legs[0].0];
+    for j in 1..n - 1 {
+        let (p0, q0) = &legs[j - 1];
+        let (p1, q1) = &legs[j];
+        r.push(intersect(p0, q0, p1, q1).unwrap_or(*q0));
+    }
+    r.push(legs[n - 2].1);
+    r
+}
+
+//fi offset_bezier
+/// Offset a Bezier (given by its control polygon), subdividing it
+/// until the offset is within a tolerance at the middle of each part
+fn offset_bezier(pts: &[Point], distance: f64, tolerance: f64, depth: usize, r: &mut Vec<Bezier>) {
+    let offset = offset_control_points(pts, distance);
+    if pts.len() > 2 && depth < 8 {
+        let (first, second) = de_casteljau(pts, 0.5);
+        let mid = second[0];
+        let expected =
+            unit_normal(&first[first.len() - 2], &second[1]).map(|nm| mid + nm * distance);
+        let (offset_first, _) = de_casteljau(&offset, 0.5);
+        let actual = offset_first[offset_first.len() - 1];
+        if expected.is_some_and(|e| (e - actual).length() > tolerance) {
+            offset_bezier(&first, distance, tolerance, depth + 1, r);
+            offset_bezier(&second, distance, tolerance, depth + 1, r);
+            return;
+        }
+    }
+    r.push(of_control_points(&offset));
+}
+
+//a Constructions
+//fi bad_construction
+fn bad_construction(reason: &str) -> Error {
+    Error::BadConstruction {
+        reason: reason.into(),
+    }
+}
+
+//fi cross
+/// Get the cross product of two vectors - positive if the second is
+/// anticlockwise of the first
+fn cross(a: &Point, b: &Point) -> f64 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+//fi circumcircle
+/// Get the center and radius of the circle through three points, or
+/// None if they are (nearly) collinear
+fn circumcircle(p0: &Point, p1: &Point, p2: &Point) -> Option<(Point, f64)> {
+    let b = *p1 - *p0;
+    let c = *p2 - *p0;
+    let d = 2. * cross(&b, &c);
+    let scale = b.length() * c.length();
+    if d.abs() <= 1E-12 * scale || scale == 0. {
+        return None;
+    }
+    let bb = b.length_sq();
+    let cc = c.length_sq();
+    let offset = Point::from_array([c[1] * bb - b[1] * cc, b[0] * cc - c[0] * bb]) * (1. / d);
+    Some((*p0 + offset, offset.length()))
+}
+
+//fi arc_around
+/// Create the path of a circular arc from a point around a center, of
+/// an angle in radians (anticlockwise if positive)
+///
+/// The arc is split into cubic Beziers of at most 90 degrees, each
+/// with control points at the standard distance of 4/3.tan(a/4) of the
+/// radius along the tangents, so that the arc is within 0.03% of the
+/// circle
+fn arc_around(start: Point, center: Point, angle: f64) -> BezierPath {
+    let mut bp = BezierPath::default();
+    let radius = (start - center).length();
+    if radius == 0. || angle == 0. {
+        return bp;
+    }
+    let ux = (start - center) * (1. / radius);
+    let uy = Point::from_array([-ux[1], ux[0]]);
+    let n = (angle.abs() / std::f64::consts::FRAC_PI_2).ceil() as usize;
+    let step = angle / (n as f64);
+    let lambda = radius * 4. / 3. * (step / 4.).tan();
+    let at = |a: f64| {
+        let (s, c) = a.sin_cos();
+        (center + (ux * c + uy * s) * radius, uy * c - ux * s)
+    };
+    let (mut p0, mut t0) = (start, uy);
+    for i in 1..=n {
+        let (p1, t1) = at(step * (i as f64));
+        bp.add_bezier(Bezier::cubic(
+            &p0,
+            &(p0 + t0 * lambda),
+            &(p1 - t1 * lambda),
+            &p1,
+        ));
+        (p0, t0) = (p1, t1);
+    }
+    bp
+}
 
 //a BezierPath
 //tp BezierPath
@@ -49,6 +247,144 @@ impl BezierPath {
         Self { elements: v }
     }
 
+    //fp of_ellipse_arc
+    /// Create the path of an arc of an axis-aligned ellipse (of a
+    /// radius in Y, with the X radius being that scaled by the
+    /// eccentricity) from a start angle through an angle, both in
+    /// radians (anticlockwise if positive)
+    pub fn of_ellipse_arc(
+        origin: Point,
+        radius: f64,
+        eccentricity: f64,
+        start: f64,
+        angle: f64,
+    ) -> Self {
+        let (s, c) = start.sin_cos();
+        let arc = arc_around(
+            Point::from_array([c * radius, s * radius]),
+            Point::zero(),
+            angle,
+        );
+        let map = |p: &Point| origin + Point::from_array([p[0] * eccentricity, p[1]]);
+        let elements = arc
+            .elements
+            .iter()
+            .map(|b| {
+                Bezier::cubic(
+                    &map(b.borrow_pt(0)),
+                    &map(b.borrow_pt(2)),
+                    &map(b.borrow_pt(3)),
+                    &map(b.borrow_pt(1)),
+                )
+            })
+            .collect();
+        Self { elements }
+    }
+
+    //fp of_circle_through
+    /// Create a circle that passes through three points
+    ///
+    /// Returns an error if the points are collinear (or coincident)
+    pub fn of_circle_through(p0: Point, p1: Point, p2: Point) -> Result<Self, Error> {
+        let (center, radius) = circumcircle(&p0, &p1, &p2)
+            .ok_or_else(|| bad_construction("circle through collinear points"))?;
+        let start = center + Point::from_array([radius, 0.]);
+        Ok(arc_around(start, center, std::f64::consts::TAU))
+    }
+
+    //fp of_arc_through
+    /// Create a circular arc that starts at one point, passes through a
+    /// second, and ends at a third
+    ///
+    /// Returns an error if the points are collinear (or coincident)
+    pub fn of_arc_through(p0: Point, p1: Point, p2: Point) -> Result<Self, Error> {
+        let (center, _) = circumcircle(&p0, &p1, &p2)
+            .ok_or_else(|| bad_construction("arc through collinear points"))?;
+        let angle_of = |p: &Point| (p[1] - center[1]).atan2(p[0] - center[0]);
+        let tau = std::f64::consts::TAU;
+        let mut angle = (angle_of(&p2) - angle_of(&p0)).rem_euclid(tau);
+        if cross(&(p1 - p0), &(p2 - p1)) < 0. {
+            angle -= tau;
+        }
+        Ok(arc_around(p0, center, angle))
+    }
+
+    //fp of_arc_endpoints
+    /// Create a circular arc of a radius between two points, with the
+    /// flags of an SVG elliptical arc: whether the arc is the larger of
+    /// the two possible, and whether it sweeps in the direction of
+    /// positive angle (anticlockwise with the Y axis upwards, and hence
+    /// clockwise as drawn in SVG)
+    ///
+    /// Returns an error if the points coincide or are further apart
+    /// than the diameter
+    pub fn of_arc_endpoints(
+        p0: Point,
+        p1: Point,
+        radius: f64,
+        large_arc: bool,
+        sweep: bool,
+    ) -> Result<Self, Error> {
+        let chord = p1 - p0;
+        let d = chord.length();
+        if d == 0. {
+            return Err(bad_construction("arc between coincident points"));
+        }
+        if 2. * radius < d * (1. - 1E-9) {
+            return Err(bad_construction("arc radius too small for its endpoints"));
+        }
+        let half = (d / 2.).min(radius);
+        let h = (radius * radius - half * half).max(0.).sqrt();
+        let n = Point::from_array([-chord[1], chord[0]]) * (1. / d);
+        let mid = (p0 + p1) * 0.5;
+        let center = if large_arc != sweep {
+            mid + n * h
+        } else {
+            mid - n * h
+        };
+        let minor = 2. * (half / radius).asin();
+        let angle = if large_arc {
+            std::f64::consts::TAU - minor
+        } else {
+            minor
+        };
+        Ok(arc_around(p0, center, if sweep { angle } else { -angle }))
+    }
+
+    //fp of_arc_tangent
+    /// Create a circular arc of a radius that is tangent to two lines
+    /// (each given by two points, in order) - a fillet at the corner
+    /// where the first line turns into the second
+    ///
+    /// The arc starts on the first line and ends on the second, with
+    /// the directions of the lines at its ends. Returns an error if the
+    /// lines are parallel or a line has no length
+    pub fn of_arc_tangent(
+        line0: (Point, Point),
+        line1: (Point, Point),
+        radius: f64,
+    ) -> Result<Self, Error> {
+        let d0 = line0.1 - line0.0;
+        let d1 = line1.1 - line1.0;
+        let (l0, l1) = (d0.length(), d1.length());
+        if l0 == 0. || l1 == 0. {
+            return Err(bad_construction("tangent to a line of no length"));
+        }
+        let u0 = d0 * (1. / l0);
+        let u1 = d1 * (1. / l1);
+        let turn = cross(&u0, &u1);
+        if turn.abs() < 1E-12 {
+            return Err(bad_construction("arc tangent to parallel lines"));
+        }
+        let corner = line0.0 + u0 * (cross(&(line1.0 - line0.0), &u1) / turn);
+        let angle = turn.atan2(u0.dot(&u1));
+        let t = radius * (angle.abs() / 2.).tan();
+        let start = corner - u0 * t;
+        let left = Point::from_array([-u0[1], u0[0]]);
+        let center = start + left * (radius * turn.signum());
+        Ok(arc_around(start, center, angle))
+    }
+
     //fp of_points
     /// Generate a set of Beziers that join the corners
     pub fn of_points(corners: &[Point], rounding: f64) -> Self {
@@ -63,6 +399,112 @@ impl BezierPath {
         bp
     }
 
+    //fp of_dsl
+    /// Create a path from a simple description, such as
+    /// 'M 0 0 L 10 0 arc 5 90 forward 10 round 1 z'
+    ///
+    /// The description is a sequence of commands, each followed by
+    /// its numeric arguments, separated by whitespace or commas;
+    /// command names are case-insensitive. The commands (with the
+    /// [PathBuilder] method they use) are:
+    ///
+    /// * M|move x y - move the pen ([PathBuilder::move_to]); this is
+    ///   only permitted before anything is drawn
+    ///
+    /// * L|line x y - line to a point ([PathBuilder::line_to])
+    ///
+    /// * Q|quad cx cy x y - quadratic Bezier ([PathBuilder::quadratic_to])
+    ///
+    /// * C|curve c0x c0y c1x c1y x y - cubic Bezier ([PathBuilder::curve_to])
+    ///
+    /// * arcto cx cy degrees - arc around a center ([PathBuilder::arc_to])
+    ///
+    /// * arc radius degrees - turtle arc ([PathBuilder::arc])
+    ///
+    /// * F|forward distance - turtle line ([PathBuilder::forward])
+    ///
+    /// * T|turn degrees - turn the pen ([PathBuilder::turn])
+    ///
+    /// * H|heading degrees - set the pen heading ([PathBuilder::set_heading])
+    ///
+    /// * round radius - round the corners between lines of the
+    ///   complete path with the radius (see [BezierPath::round])
+    ///
+    /// * Z|close - close the path ([PathBuilder::close])
+    pub fn of_dsl(description: &str) -> Result<Self, Error> {
+        let mut tokens = description
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|t| !t.is_empty())
+            .peekable();
+        let mut pb = PathBuilder::new();
+        let mut drawn = false;
+        let mut closed = false;
+        let mut rounding = 0.;
+        while let Some(cmd) = tokens.next() {
+            let mut args = vec![];
+            while let Some(v) = tokens.peek().and_then(|t| t.parse::<f64>().ok()) {
+                args.push(v);
+                tokens.next();
+            }
+            let cmd_lc = cmd.to_ascii_lowercase();
+            let num_args = match cmd_lc.as_str() {
+                "m" | "move" | "l" | "line" | "arc" => 2,
+                "q" | "quad" => 4,
+                "c" | "curve" => 6,
+                "arcto" => 3,
+                "f" | "forward" | "t" | "turn" | "h" | "heading" | "round" => 1,
+                "z" | "close" => 0,
+                _ => {
+                    return Err(Error::BadPathDescription {
+                        token: cmd.into(),
+                        reason: "expected a path command".into(),
+                    });
+                }
+            };
+            if args.len() != num_args {
+                return Err(Error::BadPathDescription {
+                    token: cmd.into(),
+                    reason: format!("expected {} arguments but found {}", num_args, args.len()),
+                });
+            }
+            let pt = |i: usize| Point::from_array([args[i], args[i + 1]]);
+            pb = match cmd_lc.as_str() {
+                "m" | "move" => {
+                    if drawn {
+                        return Err(Error::BadPathDescription {
+                            token: cmd.into(),
+                            reason: "a path may only move before it is drawn".into(),
+                        });
+                    }
+                    pb.move_to(pt(0))
+                }
+                "l" | "line" => pb.line_to(pt(0)),
+                "q" | "quad" => pb.quadratic_to(pt(0), pt(2)),
+                "c" | "curve" => pb.curve_to(pt(0), pt(2), pt(4)),
+                "arcto" => pb.arc_to(pt(0), args[2]),
+                "arc" => pb.arc(args[0], args[1]),
+                "f" | "forward" => pb.forward(args[0]),
+                "t" | "turn" => pb.turn(args[0]),
+                "h" | "heading" => pb.set_heading(args[0]),
+                "round" => {
+                    rounding = args[0];
+                    pb
+                }
+                _ => {
+                    closed = true;
+                    pb.close()
+                }
+            };
+            drawn |= !matches!(
+                cmd_lc.as_str(),
+                "m" | "move" | "t" | "turn" | "h" | "heading" | "round"
+            );
+        }
+        let mut path = pb.build();
+        path.round(rounding, closed);
+        Ok(path)
+    }
+
     //mp round
     /// Run through the path; for every adjacent pair of Beziers that
     /// are *line*s add an intermediate Bezier that is a rounded
@@ -164,11 +606,71 @@ impl BezierPath {
         }
     }
 
+    //mp parallel
+    /// Create a path parallel to this one, at a distance from it, in
+    /// the same direction - as for the second line of a double-line
+    /// wall, the rails of a railway track symbol, or an underline
+    ///
+    /// A positive distance is to the left of the direction of travel
+    /// when the Y axis is up (so a path along +X is offset towards
+    /// +Y). Curves are subdivided until their offset is within 1% of
+    /// the distance at the middle of each part. Where two lines meet at a corner their
+    /// offsets are extended (or trimmed) to meet; at other corners the
+    /// offsets are joined by a straight line.
+    ///
+    /// The result is an open path; it is not the outline of a stroke
+    pub fn parallel(&self, distance: f64) -> Self {
+        let tolerance = (distance.abs() * 0.01).max(1E-9);
+        let mut elements: Vec<Bezier> = vec![];
+        for b in self.elements.iter() {
+            let mut offset = vec![];
+            offset_bezier(&control_points(b), distance, tolerance, 0, &mut offset);
+            let Some(last) = elements.last().cloned() else {
+                elements = offset;
+                continue;
+            };
+            let first = offset[0];
+            let end = *last.borrow_pt(1);
+            let start = *first.borrow_pt(0);
+            if (end - start).length() > tolerance {
+                let miter = if last.degree() == 1 && first.degree() == 1 {
+                    intersect(last.borrow_pt(0), &end, &start, first.borrow_pt(1))
+                } else {
+                    None
+                };
+                match miter {
+                    Some(m) => {
+                        let n = elements.len();
+                        elements[n - 1] = Bezier::line(last.borrow_pt(0), &m);
+                        offset[0] = Bezier::line(&m, first.borrow_pt(1));
+                    }
+                    None => elements.push(Bezier::line(&end, &start)),
+                }
+            }
+            elements.append(&mut offset);
+        }
+        Self { elements }
+    }
+
     //mp iter_beziers
     /// Iterate through all the Beziers
     pub fn iter_beziers(&self) -> impl Iterator<Item = &Bezier> {
         self.elements.iter()
     }
+
+    //mp length
+    /// Get the length of the path, approximating each Bezier by
+    /// straight lines to within a tolerance
+    pub fn length(&self, tolerance: f64) -> f64 {
+        let mut length = 0.;
+        for b in self.elements.iter() {
+            let pts: Vec<Point> = b.as_points(tolerance).collect();
+            for p in pts.windows(2) {
+                length += (p[1] - p[0]).length();
+            }
+        }
+        length
+    }
 }
 
 //ip std::ops::Index<Idx>
@@ -219,6 +721,85 @@ mod test_path {
         }
     }
     #[test]
+    fn test_parallel() {
+        // An L of lines is offset with a mitered corner
+        let mut bp = BezierPath::default();
+        let p = |x, y| Point::from_array([x, y]);
+        bp.add_bezier(Bezier::line(&p(0., 0.), &p(10., 0.)));
+        bp.add_bezier(Bezier::line(&p(10., 0.), &p(10., 10.)));
+        let par = bp.parallel(1.);
+        let v: Vec<&Bezier> = par.iter_beziers().collect();
+        assert_eq!(v.len(), 2);
+        bezier_eq(v[0], vec![(0., 1.), (9., 1.)]);
+        bezier_eq(v[1], vec![(9., 1.), (9., 10.)]);
+        let par = bp.parallel(-1.);
+        bezier_eq(&par[0], vec![(0., -1.), (11., -1.)]);
+        bezier_eq(&par[1], vec![(11., -1.), (11., 10.)]);
+
+        // A quarter circle of radius 10 is offset to radius 9 or 11
+        let mut bp = BezierPath::default();
+        bp.add_bezier(Bezier::arc(
+            std::f64::consts::FRAC_PI_2,
+            10.,
+            &p(0., 0.),
+            &p(1., 0.),
+            &p(0., 1.),
+            0.,
+        ));
+        for (d, r) in [(1., 9.), (-1., 11.)] {
+            let par = bp.parallel(d);
+            pt_eq(&par.get_pt(0), r, 0.);
+            pt_eq(&par.get_pt(1), 0., r);
+            for b in par.iter_beziers() {
+                for pt in b.as_points(0.001) {
+                    assert!((pt.length() - r).abs() < 0.05 * d.abs());
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_constructions() {
+        let p = |x, y| Point::from_array([x, y]);
+        let on_circle = |bp: &BezierPath, c: Point, r: f64| {
+            for b in bp.iter_beziers() {
+                for i in 0..=4 {
+                    let d = (b.point_at(i as f64 / 4.) - c).length();
+                    assert!((d - r).abs() < 1E-3 * r, "{d} vs {r}");
+                }
+            }
+        };
+        let bp = BezierPath::of_circle_through(p(1., 0.), p(-1., 0.), p(0., 1.)).unwrap();
+        on_circle(&bp, p(0., 0.), 1.);
+        assert!(BezierPath::of_circle_through(p(0., 0.), p(1., 1.), p(2., 2.)).is_err());
+
+        // Clockwise from (0,1) through (1,0) to (0,-1)
+        let bp = BezierPath::of_arc_through(p(0., 1.), p(1., 0.), p(0., -1.)).unwrap();
+        on_circle(&bp, p(0., 0.), 1.);
+        pt_eq(&bp.get_pt(0), 0., 1.);
+        pt_eq(&bp.get_pt(1), 0., -1.);
+        assert!((bp.iter_beziers().next().unwrap().point_at(0.5)[0]) > 0.);
+
+        let bp = BezierPath::of_arc_endpoints(p(1., 0.), p(0., 1.), 1., false, true).unwrap();
+        on_circle(&bp, p(0., 0.), 1.);
+        pt_eq(&bp.get_pt(1), 0., 1.);
+        let bp = BezierPath::of_arc_endpoints(p(1., 0.), p(0., 1.), 1., true, false).unwrap();
+        on_circle(&bp, p(0., 0.), 1.);
+        assert_eq!(bp.iter_beziers().count(), 3);
+        let bp = BezierPath::of_arc_endpoints(p(1., 0.), p(0., 1.), 1., false, false).unwrap();
+        on_circle(&bp, p(1., 1.), 1.);
+        assert!(BezierPath::of_arc_endpoints(p(0., 0.), p(3., 0.), 1., false, true).is_err());
+
+        // Fillet of radius 1 between +X and +Y lines meeting at (2,0)
+        let bp =
+            BezierPath::of_arc_tangent((p(0., 0.), p(1., 0.)), (p(2., 1.), p(2., 3.)), 1.).unwrap();
+        on_circle(&bp, p(1., 1.), 1.);
+        pt_eq(&bp.get_pt(0), 1., 0.);
+        pt_eq(&bp.get_pt(1), 2., 1.);
+        assert!(
+            BezierPath::of_arc_tangent((p(0., 0.), p(1., 0.)), (p(0., 1.), p(1., 1.)), 1.).is_err()
+        );
+    }
+    #[test]
     fn test_round_open() {
         let p0 = Point::zero();
         let p1 = Point::from_array([1., 0.]);
@@ -243,6 +824,21 @@ mod test_path {
         assert_eq!(bp.elements.len(), 7, "Path should be 7 elements");
     }
     #[test]
+    fn test_dsl() {
+        let bp = BezierPath::of_dsl("M 1,1 L 3 1\n forward 2  TURN 90 f 2 z").unwrap();
+        assert_eq!(bp.elements.len(), 4);
+        bezier_eq(&bp.elements[1], vec![(3., 1.), (5., 1.)]);
+        bezier_eq(&bp.elements[2], vec![(5., 1.), (5., 3.)]);
+        pt_eq(&bp.get_pt(1), 1., 1.);
+        let bp = BezierPath::of_dsl("line 1 0 line 1 1 line 0 1 close round 0.1").unwrap();
+        assert_eq!(bp.elements.len(), 8);
+        let bp = BezierPath::of_dsl("arc 1 90").unwrap();
+        pt_eq(&bp.get_pt(1), 1., 1.);
+        assert!(BezierPath::of_dsl("L 1").is_err());
+        assert!(BezierPath::of_dsl("L 1 1 M 2 2").is_err());
+        assert!(BezierPath::of_dsl("jump 1 1").is_err());
+    }
+    #[test]
     fn test_round_closed() {
         let p0 = Point::zero();
         let p1 = Point::from_array([1., 0.]);
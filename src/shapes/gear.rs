@@ -0,0 +1,310 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    gear.rs
+@brief   Gear and sprocket profiles
+ */
+
+//a Imports
+use std::f64::consts::PI;
+
+use geo_nd::Vector;
+
+use crate::{BBox, BezierPath, PathBuilder, Point};
+
+//a Constants
+/// Number of straight segments used to approximate each involute flank
+const FLANK_SEGMENTS: usize = 6;
+
+//a GearProfile
+//tp GearProfile
+/// The tooth form of a [Gear]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GearProfile {
+    /// An involute spur gear with a module (pitch diameter / teeth)
+    /// and pressure angle in degrees
+    Involute { module: f64, pressure_angle: f64 },
+    /// A simplified roller chain sprocket with a chain pitch and
+    /// roller diameter; the rollers seat in semicircular notches
+    /// between pointed teeth
+    Sprocket { pitch: f64, roller_diameter: f64 },
+}
+
+//a Gear
+//tp Gear
+/// A [Gear] generates the outline of a gear or sprocket as a single
+/// closed [BezierPath], for mechanical diagrams and laser-cut output
+#[derive(Debug, Clone, Copy)]
+pub struct Gear {
+    teeth: usize,
+    profile: GearProfile,
+    center: Point,
+    /// Rotation in degrees; with no rotation the first tooth is
+    /// centered on the +X axis
+    rotation: f64,
+}
+
+//ip Gear
+impl Gear {
+    //fp new_involute
+    /// Create a new involute spur gear with a number of teeth, a
+    /// module, and a pressure angle in degrees (commonly 20)
+    pub fn new_involute(teeth: usize, module: f64, pressure_angle: f64) -> Self {
+        Self {
+            teeth,
+            profile: GearProfile::Involute {
+                module,
+                pressure_angle,
+            },
+            center: Point::zero(),
+            rotation: 0.,
+        }
+    }
+
+    //fp new_sprocket
+    /// Create a new sprocket for a roller chain with a number of
+    /// teeth, a chain pitch, and the diameter of the rollers
+    pub fn new_sprocket(teeth: usize, pitch: f64, roller_diameter: f64) -> Self {
+        Self {
+            teeth,
+            profile: GearProfile::Sprocket {
+                pitch,
+                roller_diameter,
+            },
+            center: Point::zero(),
+            rotation: 0.,
+        }
+    }
+
+    //bp set_center
+    /// Set the center of the gear
+    pub fn set_center(mut self, center: Point) -> Self {
+        self.center = center;
+        self
+    }
+
+    //bp set_rotation
+    /// Set the rotation of the gear in degrees
+    pub fn set_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    //ap name
+    /// Get the name of the kind of gear - 'gear' or 'sprocket'
+    pub fn name(&self) -> &'static str {
+        match self.profile {
+            GearProfile::Involute { .. } => "gear",
+            GearProfile::Sprocket { .. } => "sprocket",
+        }
+    }
+
+    //ap parameters
+    /// Get the number of teeth and the dimensions of the tooth form,
+    /// by name
+    pub fn parameters(&self) -> Vec<(&'static str, f64)> {
+        let teeth = ("teeth", self.teeth as f64);
+        match self.profile {
+            GearProfile::Involute {
+                module,
+                pressure_angle,
+            } => vec![
+                teeth,
+                ("module", module),
+                ("pressure-angle", pressure_angle),
+            ],
+            GearProfile::Sprocket {
+                pitch,
+                roller_diameter,
+            } => vec![
+                teeth,
+                ("pitch", pitch),
+                ("roller-diameter", roller_diameter),
+            ],
+        }
+    }
+
+    //mp pitch_radius
+    /// Get the radius of the pitch circle
+    pub fn pitch_radius(&self) -> f64 {
+        let z = self.teeth as f64;
+        match self.profile {
+            GearProfile::Involute { module, .. } => module * z / 2.,
+            GearProfile::Sprocket { pitch, .. } => pitch / 2. / (PI / z).sin(),
+        }
+    }
+
+    //mp outer_radius
+    /// Get the radius of the tips of the teeth
+    pub fn outer_radius(&self) -> f64 {
+        match self.profile {
+            GearProfile::Involute { module, .. } => self.pitch_radius() + module,
+            GearProfile::Sprocket {
+                roller_diameter, ..
+            } => self.pitch_radius() + roller_diameter * 0.5,
+        }
+    }
+
+    //fi polar
+    /// Get the point at a radius and angle (in radians) relative to
+    /// the gear
+    fn polar(&self, r: f64, radians: f64) -> Point {
+        let a = radians + self.rotation.to_radians();
+        self.center + Point::from_array([r * a.cos(), r * a.sin()])
+    }
+
+    //mp as_paths
+    /// Generate the outline of the gear as a closed path
+    ///
+    /// A gear with fewer than three teeth generates an empty path
+    pub fn as_paths(&self) -> BezierPath {
+        if self.teeth < 3 {
+            return BezierPath::default();
+        }
+        match self.profile {
+            GearProfile::Involute {
+                module,
+                pressure_angle,
+            } => self.involute_path(module, pressure_angle.to_radians()),
+            GearProfile::Sprocket {
+                roller_diameter, ..
+            } => self.sprocket_path(roller_diameter / 2.),
+        }
+    }
+
+    //fi involute_path
+    /// Generate an involute gear outline
+    ///
+    /// The half-angle subtended by a tooth at radius r (at or above
+    /// the base circle) is pi/2z + inv(pa) - inv(acos(rb/r)), where
+    /// inv(a) = tan(a) - a; below the base circle the flanks are
+    /// radial
+    fn involute_path(&self, module: f64, pressure_angle: f64) -> BezierPath {
+        let z = self.teeth as f64;
+        let rp = self.pitch_radius();
+        let ra = rp + module;
+        let rf = (rp - 1.25 * module).max(0.);
+        let rb = rp * pressure_angle.cos();
+        let r0 = rb.max(rf);
+        let inv = |a: f64| a.tan() - a;
+        let half_angle = |r: f64| PI / (2. * z) + inv(pressure_angle) - inv((rb / r).acos());
+        let radii: Vec<f64> = (0..=FLANK_SEGMENTS)
+            .map(|i| r0 + (ra - r0) * (i as f64) / (FLANK_SEGMENTS as f64))
+            .collect();
+        let pitch_angle = 2. * PI / z;
+        let psi_0 = half_angle(r0);
+        let psi_a = half_angle(ra);
+        let mut pb = PathBuilder::new().move_to(self.polar(rf, -psi_0));
+        for i in 0..self.teeth {
+            let theta = pitch_angle * (i as f64);
+            if rb > rf {
+                pb = pb.line_to(self.polar(rb, theta - psi_0));
+            }
+            for r in radii.iter().skip(1) {
+                pb = pb.line_to(self.polar(*r, theta - half_angle(*r)));
+            }
+            pb = pb.arc_to(self.center, (2. * psi_a).to_degrees());
+            for r in radii.iter().rev().skip(1) {
+                pb = pb.line_to(self.polar(*r, theta + half_angle(*r)));
+            }
+            if rb > rf {
+                pb = pb.line_to(self.polar(rf, theta + psi_0));
+            }
+            let root = pitch_angle - 2. * psi_0;
+            if root > 0. {
+                pb = pb.arc_to(self.center, root.to_degrees());
+            } else {
+                pb = pb.line_to(self.polar(rf, theta + pitch_angle - psi_0));
+            }
+        }
+        pb.build()
+    }
+
+    //fi sprocket_path
+    /// Generate a simplified sprocket outline, with a semicircular
+    /// roller seat centered on each pitch point and a pointed tooth
+    /// between adjacent seats
+    fn sprocket_path(&self, roller_radius: f64) -> BezierPath {
+        let z = self.teeth as f64;
+        let rp = self.pitch_radius();
+        let ro = self.outer_radius();
+        let pitch_angle = 2. * PI / z;
+        let seat_start = |theta: f64| {
+            let c = self.polar(rp, theta);
+            let a = theta + self.rotation.to_radians() - PI / 2.;
+            (c, c + Point::from_array([a.cos(), a.sin()]) * roller_radius)
+        };
+        let (_, start) = seat_start(0.);
+        let mut pb = PathBuilder::new().move_to(start);
+        for i in 0..self.teeth {
+            let theta = pitch_angle * (i as f64);
+            let (c, _) = seat_start(theta);
+            pb = pb.arc_to(c, -180.);
+            pb = pb.line_to(self.polar(ro, theta + pitch_angle / 2.));
+            pb = pb.line_to(seat_start(theta + pitch_angle).1);
+        }
+        pb.build()
+    }
+
+    //mp get_bbox
+    /// Get the bounding box for the gear (it may be pessimistic)
+    pub fn get_bbox(&self) -> BBox {
+        BBox::of_cwh(
+            self.center,
+            self.outer_radius() * 2.,
+            self.outer_radius() * 2.,
+        )
+    }
+
+    //zz All done
+}
+
+//a Test
+#[cfg(test)]
+mod tests_gear {
+    use super::*;
+    pub fn pt_eq(pt: &Point, x: f64, y: f64) {
+        assert!(
+            (pt[0] - x).abs() < 1E-6 && (pt[1] - y).abs() < 1E-6,
+            "mismatch in {:?} {} {}",
+            pt,
+            x,
+            y
+        );
+    }
+    #[test]
+    fn test_involute() {
+        let g = Gear::new_involute(12, 2., 20.);
+        assert!((g.pitch_radius() - 12.).abs() < 1E-8);
+        let p = g.as_paths();
+        let s = p.get_pt(0);
+        let e = p.get_pt(1);
+        pt_eq(&e, s[0], s[1]);
+        for b in p.iter_beziers() {
+            let r = b.borrow_pt(1).length();
+            assert!((12. - 2.5 - 1E-6..=14. + 1E-6).contains(&r), "radius {}", r);
+        }
+    }
+    #[test]
+    fn test_sprocket() {
+        let g = Gear::new_sprocket(6, 1., 0.5);
+        assert!((g.pitch_radius() - 1.).abs() < 1E-8);
+        let p = g.as_paths();
+        assert_eq!(p.iter_beziers().count(), 6 * 4);
+        let s = p.get_pt(0);
+        let e = p.get_pt(1);
+        pt_eq(&s, 1., -0.25);
+        pt_eq(&e, s[0], s[1]);
+    }
+}
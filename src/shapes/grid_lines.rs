@@ -0,0 +1,123 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    grid_lines.rs
+@brief   Grid line geometry for the SVG library
+ */
+
+//a Imports
+use geo_nd::Vector;
+
+use crate::{BBox, Bezier, BezierPath, Point, Range};
+
+//a GridLines
+//tp GridLines
+/// A [GridLines] describes the lines of a rectangular grid within a
+/// bounding box, with lines placed at integer multiples of the
+/// spacings from an origin
+///
+/// It is used for debug grids and for the grids of axes, so that they
+/// place their lines identically
+#[derive(Debug, Clone, Copy)]
+pub struct GridLines {
+    /// Region to be covered by the grid lines
+    bbox: BBox,
+    /// X and Y spacing of grid lines; a spacing of 0. or less
+    /// indicates no lines in that direction
+    spacings: (f64, f64),
+    /// Origin of the grid; lines are placed at integer multiples of
+    /// the spacing from this
+    origin: Point,
+}
+
+//ip GridLines
+impl GridLines {
+    //fp new
+    /// Create a new set of grid lines covering a region with a given
+    /// spacing, with grid lines at integer multiples of the spacings
+    pub fn new(bbox: BBox, spacings: (f64, f64)) -> Self {
+        Self {
+            bbox,
+            spacings,
+            origin: Point::zero(),
+        }
+    }
+
+    //bp set_origin
+    /// Set the origin of the grid
+    pub fn set_origin(mut self, origin: Point) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    //fi coords
+    /// Get the coordinates within a range at integer multiples of the
+    /// spacing from the origin
+    fn coords(range: &Range, origin: f64, spacing: f64) -> Vec<f64> {
+        range.ticks_step_from(origin, spacing)
+    }
+
+    //mp x_coords
+    /// Get the X coordinates of the vertical grid lines
+    pub fn x_coords(&self) -> Vec<f64> {
+        Self::coords(&self.bbox.x, self.origin[0], self.spacings.0)
+    }
+
+    //mp y_coords
+    /// Get the Y coordinates of the horizontal grid lines
+    pub fn y_coords(&self) -> Vec<f64> {
+        Self::coords(&self.bbox.y, self.origin[1], self.spacings.1)
+    }
+
+    //mp as_paths
+    /// Generate the grid lines as a vector of paths, each of a single
+    /// line; vertical lines first, then horizontal lines
+    pub fn as_paths(&self) -> Vec<BezierPath> {
+        let mut paths = vec![];
+        let (y0, y1) = (self.bbox.y[0], self.bbox.y[1]);
+        for x in self.x_coords() {
+            let mut bp = BezierPath::default();
+            bp.add_bezier(Bezier::line(&[x, y0].into(), &[x, y1].into()));
+            paths.push(bp);
+        }
+        let (x0, x1) = (self.bbox.x[0], self.bbox.x[1]);
+        for y in self.y_coords() {
+            let mut bp = BezierPath::default();
+            bp.add_bezier(Bezier::line(&[x0, y].into(), &[x1, y].into()));
+            paths.push(bp);
+        }
+        paths
+    }
+
+    //zz All done
+}
+
+//a Test
+#[cfg(test)]
+mod test_grid_lines {
+    use super::*;
+    #[test]
+    fn test_coords() {
+        let g = GridLines::new(BBox::new(-5., 1., 21., 9.), (10., 4.));
+        assert_eq!(g.x_coords(), vec![0., 10., 20.]);
+        assert_eq!(g.y_coords(), vec![4., 8.]);
+        assert_eq!(g.as_paths().len(), 5);
+        let g = g.set_origin([1., 1.].into());
+        assert_eq!(g.x_coords(), vec![1., 11., 21.]);
+        assert_eq!(g.y_coords(), vec![1., 5., 9.]);
+        let g = GridLines::new(BBox::new(0., 0., 10., 10.), (0., 5.));
+        assert!(g.x_coords().is_empty());
+        assert_eq!(g.y_coords(), vec![0., 5., 10.]);
+    }
+}
@@ -0,0 +1,173 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    hatch.rs
+@brief   Hatch lines to emulate the fill of shapes
+ */
+
+//a Imports
+use geo_nd::Vector;
+
+use crate::{BezierPath, Point};
+
+//a Hatch
+//tp Hatch
+/// A [Hatch] describes parallel lines at an angle and spacing that
+/// emulate the fill of a closed shape, for output devices such as pen
+/// plotters and laser engravers that can only draw strokes
+///
+/// Hatch lines are clipped to the shape using the even-odd rule, so
+/// that the holes of a shape given by more than one outline are not
+/// hatched
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hatch {
+    /// Angle of the lines in degrees (0 is horizontal)
+    pub angle: f64,
+    /// Spacing between the lines
+    pub spacing: f64,
+    /// Stroke width to draw the lines with
+    pub line_width: f64,
+}
+
+//ip Default for Hatch
+impl std::default::Default for Hatch {
+    fn default() -> Self {
+        Self {
+            angle: 45.,
+            spacing: 0.5,
+            line_width: 0.2,
+        }
+    }
+}
+
+//ip Hatch
+impl Hatch {
+    //fp new
+    /// Create a new hatch with lines at an angle (in degrees) and spacing
+    pub fn new(angle: f64, spacing: f64) -> Self {
+        Self {
+            angle,
+            spacing,
+            ..Default::default()
+        }
+    }
+
+    //bp set_line_width
+    /// Set the stroke width of the hatch lines
+    pub fn set_line_width(mut self, line_width: f64) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    //mp lines_of_paths
+    /// Generate the hatch lines for closed paths
+    pub fn lines_of_paths(&self, paths: &[&BezierPath]) -> Vec<(Point, Point)> {
+        let outlines: Vec<Vec<Point>> = paths
+            .iter()
+            .map(|p| p.iter_beziers().flat_map(|b| b.as_points(0.05)).collect())
+            .collect();
+        self.lines(&outlines)
+    }
+
+    //mp lines
+    /// Generate the hatch lines for closed outlines (each a list of
+    /// points)
+    pub fn lines(&self, outlines: &[Vec<Point>]) -> Vec<(Point, Point)> {
+        let mut r = vec![];
+        if self.spacing <= 0. {
+            return r;
+        }
+        let (s, c) = self.angle.to_radians().sin_cos();
+        // Rotate by -angle so that hatch lines are horizontal
+        let to_hatch = |p: &Point| (p[0] * c + p[1] * s, p[1] * c - p[0] * s);
+        let from_hatch = |x: f64, y: f64| Point::from_array([x * c - y * s, x * s + y * c]);
+        let mut edges = vec![];
+        for o in outlines {
+            let n = o.len();
+            for (i, p) in o.iter().enumerate() {
+                let p0 = to_hatch(p);
+                let p1 = to_hatch(&o[(i + 1) % n]);
+                if p0.1 != p1.1 {
+                    edges.push((p0, p1));
+                }
+            }
+        }
+        if edges.is_empty() {
+            return r;
+        }
+        let ys = edges.iter().flat_map(|(p0, p1)| [p0.1, p1.1]);
+        let y_min = ys.clone().fold(f64::MAX, f64::min);
+        let y_max = ys.fold(f64::MIN, f64::max);
+        let mut y = (y_min / self.spacing).floor() * self.spacing + self.spacing / 2.;
+        while y < y_max {
+            let mut xs: Vec<f64> = edges
+                .iter()
+                .filter(|(p0, p1)| (p0.1 <= y) != (p1.1 <= y))
+                .map(|(p0, p1)| p0.0 + (y - p0.1) * (p1.0 - p0.0) / (p1.1 - p0.1))
+                .collect();
+            xs.sort_by(f64::total_cmp);
+            for x in xs.chunks_exact(2) {
+                r.push((from_hatch(x[0], y), from_hatch(x[1], y)));
+            }
+            y += self.spacing;
+        }
+        r
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_hatch {
+    use super::*;
+    #[test]
+    fn test_square() {
+        let square: Vec<Point> = [[0., 0.], [10., 0.], [10., 10.], [0., 10.]]
+            .into_iter()
+            .map(|p| p.into())
+            .collect();
+        let hatch = Hatch::new(0., 1.);
+        let lines = hatch.lines(std::slice::from_ref(&square));
+        assert_eq!(lines.len(), 10);
+        for (p0, p1) in lines {
+            assert!((p0[0] - 0.).abs() < 1E-6);
+            assert!((p1[0] - 10.).abs() < 1E-6);
+        }
+
+        // A hole in the middle splits the central lines in two
+        let hole: Vec<Point> = [[4., 4.], [6., 4.], [6., 6.], [4., 6.]]
+            .into_iter()
+            .map(|p| p.into())
+            .collect();
+        let lines = hatch.lines(&[square.clone(), hole]);
+        assert_eq!(lines.len(), 12);
+
+        let lines = Hatch::new(90., 2.).lines(&[square]);
+        assert_eq!(lines.len(), 5);
+        for (p0, p1) in lines {
+            assert!((p0[0] - p1[0]).abs() < 1E-6);
+        }
+    }
+    #[test]
+    fn test_element() {
+        use crate::{BBox, SvgConfig, SvgPath};
+        let cfg = SvgConfig::default().set_hatch_fill(Hatch::new(45., 1.));
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        e.add_attribute("fill", None, "red");
+        e.finalize(&cfg).unwrap();
+        assert_eq!(e.contents().len(), 2);
+        assert_eq!(e.contents()[0].get_attribute("stroke"), Some("red"));
+        assert_eq!(e.contents()[1].get_attribute("fill"), Some("none"));
+    }
+}
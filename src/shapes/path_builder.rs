@@ -0,0 +1,299 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    path_builder.rs
+@brief   Pen-based construction of Bezier paths
+ */
+
+//a Imports
+use geo_nd::Vector;
+
+use crate::{Bezier, BezierPath, Point};
+
+//a PathBuilder
+//tp PathBuilder
+/// A [PathBuilder] constructs a [BezierPath] by moving a pen
+///
+/// The pen has a position and a heading (in degrees, anticlockwise
+/// from the X axis); absolute commands (such as
+/// [PathBuilder::line_to]) move the pen to a point, and turtle-style
+/// commands (such as [PathBuilder::forward]) move it relative to its
+/// current position and heading. Every drawing command leaves the
+/// pen heading along the tangent at the end of what it drew.
+///
+/// A [BezierPath] is a single chain of Beziers; a
+/// [PathBuilder::move_to] after drawing starts a new subpath, and the
+/// complete set can be retrieved with [PathBuilder::build_all]
+///
+/// ```ignore
+/// let path = PathBuilder::new()
+///     .move_to([0., 0.].into())
+///     .forward(10.)
+///     .turn(90.)
+///     .arc(5., 90.)
+///     .close()
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathBuilder {
+    /// Subpaths completed by a subsequent move_to
+    completed: Vec<BezierPath>,
+    /// Path currently being drawn
+    path: BezierPath,
+    /// Start of the current subpath
+    start: Point,
+    /// Current pen position
+    pen: Point,
+    /// Current heading in degrees
+    heading: f64,
+    /// True if the current subpath has had any Beziers added
+    drawn: bool,
+}
+
+//ip PathBuilder
+impl PathBuilder {
+    //fp new
+    /// Create a new path builder with the pen at the origin heading
+    /// along the X axis
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //fi add
+    /// Add a Bezier to the current subpath, moving the pen to its end
+    fn add(&mut self, bezier: Bezier, end: Point) {
+        self.path.add_bezier(bezier);
+        self.pen = end;
+        self.drawn = true;
+    }
+
+    //fi head_towards
+    /// Set the heading to be along a vector, if it is not zero
+    fn head_towards(&mut self, v: Point) {
+        if !v.is_zero() {
+            self.heading = v[1].atan2(v[0]).to_degrees();
+        }
+    }
+
+    //mp pen
+    /// Get the current pen position
+    pub fn pen(&self) -> Point {
+        self.pen
+    }
+
+    //mp heading
+    /// Get the current heading in degrees
+    pub fn heading(&self) -> f64 {
+        self.heading
+    }
+
+    //bp move_to
+    /// Move the pen to a point without drawing
+    ///
+    /// If anything has been drawn this starts a new subpath
+    pub fn move_to(mut self, pt: Point) -> Self {
+        if self.drawn {
+            self.completed.push(std::mem::take(&mut self.path));
+            self.drawn = false;
+        }
+        self.start = pt;
+        self.pen = pt;
+        self
+    }
+
+    //bp line_to
+    /// Draw a straight line from the pen to a point
+    pub fn line_to(mut self, pt: Point) -> Self {
+        let pen = self.pen;
+        self.head_towards(pt - pen);
+        self.add(Bezier::line(&pen, &pt), pt);
+        self
+    }
+
+    //bp quadratic_to
+    /// Draw a quadratic Bezier from the pen to a point with a single
+    /// control point
+    pub fn quadratic_to(mut self, c: Point, pt: Point) -> Self {
+        let pen = self.pen;
+        self.head_towards(pt - c);
+        self.add(Bezier::quadratic(&pen, &c, &pt), pt);
+        self
+    }
+
+    //bp curve_to
+    /// Draw a cubic Bezier from the pen to a point with two control
+    /// points
+    pub fn curve_to(mut self, c0: Point, c1: Point, pt: Point) -> Self {
+        let pen = self.pen;
+        self.head_towards(pt - c1);
+        self.add(Bezier::cubic(&pen, &c0, &c1, &pt), pt);
+        self
+    }
+
+    //bp arc_to
+    /// Draw a circular arc from the pen around a center point,
+    /// sweeping anticlockwise by `degrees` (clockwise if negative)
+    ///
+    /// The arc is split into Beziers of at most 90 degrees each
+    pub fn arc_to(mut self, center: Point, degrees: f64) -> Self {
+        let radius = (self.pen - center).length();
+        if radius == 0. || degrees == 0. {
+            return self;
+        }
+        let ux = (self.pen - center) * (1. / radius);
+        let mut uy = Point::from_array([-ux[1], ux[0]]);
+        if degrees < 0. {
+            uy = -uy;
+        }
+        let sweep = degrees.abs().to_radians();
+        let n = (degrees.abs() / 90.).ceil() as usize;
+        let step = sweep / (n as f64);
+        for i in 0..n {
+            let a = step * ((i + 1) as f64);
+            let end = center + (ux * a.cos() + uy * a.sin()) * radius;
+            self.add(
+                Bezier::arc(step, radius, &center, &ux, &uy, step * (i as f64)),
+                end,
+            );
+        }
+        self.head_towards(uy * sweep.cos() - ux * sweep.sin());
+        self
+    }
+
+    //bp close
+    /// Draw a straight line back to the start of the current subpath,
+    /// if the pen is not already there
+    pub fn close(self) -> Self {
+        if (self.pen - self.start).is_zero() {
+            self
+        } else {
+            let start = self.start;
+            self.line_to(start)
+        }
+    }
+
+    //bp forward
+    /// Draw a straight line of a given length along the current
+    /// heading
+    pub fn forward(mut self, distance: f64) -> Self {
+        let h = self.heading.to_radians();
+        let pen = self.pen;
+        let pt = pen + Point::from_array([h.cos(), h.sin()]) * distance;
+        self.add(Bezier::line(&pen, &pt), pt);
+        self
+    }
+
+    //bp turn
+    /// Turn the pen anticlockwise by a number of degrees (clockwise if
+    /// negative) without drawing
+    pub fn turn(mut self, degrees: f64) -> Self {
+        self.heading += degrees;
+        self
+    }
+
+    //bp set_heading
+    /// Set the heading of the pen in degrees without drawing
+    pub fn set_heading(mut self, degrees: f64) -> Self {
+        self.heading = degrees;
+        self
+    }
+
+    //bp arc
+    /// Draw a circular arc of a given radius starting along the
+    /// current heading, turning anticlockwise by `degrees` (clockwise
+    /// if negative)
+    pub fn arc(self, radius: f64, degrees: f64) -> Self {
+        let h = self.heading.to_radians();
+        let left = Point::from_array([-h.sin(), h.cos()]);
+        let center = if degrees < 0. {
+            self.pen - left * radius
+        } else {
+            self.pen + left * radius
+        };
+        let heading = self.heading + degrees;
+        let mut s = self.arc_to(center, degrees);
+        s.heading = heading;
+        s
+    }
+
+    //mp build
+    /// Finish building, returning the current subpath
+    ///
+    /// Subpaths completed by earlier [PathBuilder::move_to] commands
+    /// are discarded; use [PathBuilder::build_all] to retrieve them
+    pub fn build(self) -> BezierPath {
+        self.path
+    }
+
+    //mp build_all
+    /// Finish building, returning all of the subpaths that have had
+    /// something drawn
+    pub fn build_all(mut self) -> Vec<BezierPath> {
+        if self.drawn {
+            self.completed.push(self.path);
+        }
+        self.completed
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_path_builder {
+    use super::*;
+    pub fn pt_eq(pt: &Point, x: f64, y: f64) {
+        assert!(
+            (pt[0] - x).abs() < 1E-8 && (pt[1] - y).abs() < 1E-8,
+            "mismatch in {:?} {} {}",
+            pt,
+            x,
+            y
+        );
+    }
+    #[test]
+    fn test_turtle() {
+        let pb = PathBuilder::new()
+            .move_to([1., 1.].into())
+            .forward(2.)
+            .turn(90.)
+            .forward(2.)
+            .turn(90.)
+            .forward(2.);
+        pt_eq(&pb.pen(), 1., 3.);
+        let path = pb.close().build();
+        assert_eq!(path.iter_beziers().count(), 4);
+        pt_eq(&path.get_pt(0), 1., 1.);
+        pt_eq(&path.get_pt(1), 1., 1.);
+    }
+    #[test]
+    fn test_arc() {
+        let pb = PathBuilder::new().forward(1.).arc(1., 180.);
+        pt_eq(&pb.pen(), 1., 2.);
+        assert!((pb.heading() - 180.).abs() < 1E-8);
+        let pb = pb.arc_to([1., 1.].into(), -90.);
+        pt_eq(&pb.pen(), 2., 1.);
+        assert!((pb.heading() + 90.).abs() < 1E-8);
+        assert_eq!(pb.build().iter_beziers().count(), 4);
+    }
+    #[test]
+    fn test_subpaths() {
+        let paths = PathBuilder::new()
+            .line_to([1., 0.].into())
+            .move_to([5., 5.].into())
+            .move_to([6., 6.].into())
+            .line_to([7., 6.].into())
+            .build_all();
+        assert_eq!(paths.len(), 2);
+        pt_eq(&paths[1].get_pt(0), 6., 6.);
+    }
+}
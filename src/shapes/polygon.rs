@@ -19,7 +19,7 @@ limitations under the License.
 //a Imports
 use geo_nd::Vector;
 
-use crate::{BBox, BezierPath, Point};
+use crate::{BBox, BezierPath, Error, Point};
 
 //tp Polygon
 /// A polygon here defines an n-gon, from which one can generate a bezier path
@@ -29,7 +29,9 @@ use crate::{BBox, BezierPath, Point};
 /// Nominally it is a regular n-gon, but it may have an eccentricity
 ///
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polygon {
+    #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_point"))]
     center: Point,
     vertices: usize,
     size: f64,          // height
@@ -194,6 +196,33 @@ impl Polygon {
         }
     }
 
+    //mp try_as_paths
+    /// Append the polygon as a set of Beziers, with an error if its
+    /// size, eccentricity, rounding or stellation is not finite, or
+    /// its size is negative
+    pub fn try_as_paths(&self) -> Result<BezierPath, Error> {
+        let finite = [
+            self.size,
+            self.eccentricity,
+            self.rotation,
+            self.rounding,
+            self.stellate_size,
+        ]
+        .iter()
+        .all(|v| v.is_finite());
+        if !finite {
+            return Err(Error::BadConstruction {
+                reason: format!("polygon has a non-finite parameter, {self}"),
+            });
+        }
+        if self.size < 0. {
+            return Err(Error::BadConstruction {
+                reason: format!("polygon has negative size {}", self.size),
+            });
+        }
+        Ok(self.as_paths())
+    }
+
     //mp get_bbox
     /// Get the bounding box for the polygon (it may be pessimistic)
     pub fn get_bbox(&self) -> BBox {
@@ -217,8 +246,10 @@ impl Polygon {
     //mp get_points
     /// Get the points that make up the corners of the polygon, in
     /// anticlockwise order
+    ///
+    /// Circles and single points (fewer than two vertices) are handled
+    /// by the callers
     fn get_points(&self) -> Vec<Point> {
-        assert!(self.vertices > 1);
         let origin = Point::zero();
         let mut corners = Vec::new();
         let delta_angle = (360.0f64).to_radians() / (self.vertices as f64);
@@ -332,7 +363,7 @@ mod tests_polygon {
         let x = Polygon::new_circle(1.0);
         let mut v = Vec::new();
         for b in x.as_paths().iter_beziers() {
-            v.push(b.clone());
+            v.push(*b);
         }
         let magic = 0.5571469;
         bezier_eq(&v[0], vec![(1., 0.), (1., magic), (magic, 1.), (0., 1.)]);
@@ -0,0 +1,407 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    route.rs
+@brief   Editable orthogonal polylines for connector routing
+ */
+
+//a Imports
+use geo_nd::Vector;
+
+use crate::{Bezier, BezierPath, Error, Point};
+
+//a Orientation
+//ti Orientation
+/// The orientation of a segment of a [Route]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+//a Route
+//tp Route
+/// A [Route] is an orthogonal polyline - every segment horizontal or
+/// vertical - between a fixed start and end, as the editable backbone
+/// of a connector between two elements of a diagram
+///
+/// Vertices may be named as waypoints, so that they can be found
+/// after editing. The route may be constrained to have its vertices
+/// on a grid, and to have a minimum segment length; the edits
+/// (inserting and shifting segments) snap to the grid and are
+/// rejected if they would make a segment too short.
+///
+/// The path of the route is regenerated from the vertices, with its
+/// corners rounded
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Route {
+    /// Vertices of the polyline, from start to end
+    points: Vec<Point>,
+    /// Names of the vertices that are waypoints
+    names: Vec<Option<String>>,
+    /// Grid spacing that vertices are snapped to, if any
+    grid: Option<f64>,
+    /// Minimum length of a segment
+    min_segment: f64,
+    /// Radius of the rounding of the corners of the path
+    rounding: f64,
+}
+
+//ip Route
+impl Route {
+    //fp new
+    /// Create a route from a start to an end, horizontal first and
+    /// then vertical (with a single segment if they are aligned)
+    pub fn new(start: Point, end: Point) -> Self {
+        let mut points = vec![start];
+        if start[0] != end[0] && start[1] != end[1] {
+            points.push([end[0], start[1]].into());
+        }
+        points.push(end);
+        let names = vec![None; points.len()];
+        Self {
+            points,
+            names,
+            grid: None,
+            min_segment: 0.,
+            rounding: 0.,
+        }
+    }
+
+    //bp set_grid
+    /// Set the grid spacing that the intermediate vertices are snapped
+    /// to (the start and end are fixed)
+    pub fn set_grid(mut self, grid: f64) -> Self {
+        self.grid = if grid > 0. { Some(grid) } else { None };
+        let n = self.points.len();
+        if n > 2 {
+            // Snap the intermediate vertices by shifting the segments
+            // that do not touch the ends
+            for i in 1..(n - 2) {
+                let p = self.points[i];
+                let v = match self.orientation(i) {
+                    Orientation::Horizontal => p[1],
+                    Orientation::Vertical => p[0],
+                };
+                self.move_segment(i, self.snap(v) - v);
+            }
+        }
+        self
+    }
+
+    //bp set_min_segment
+    /// Set the minimum length of a segment
+    pub fn set_min_segment(mut self, min_segment: f64) -> Self {
+        self.min_segment = min_segment;
+        self
+    }
+
+    //bp set_rounding
+    /// Set the radius of the rounding of the corners of the path
+    pub fn set_rounding(mut self, rounding: f64) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    //ap points
+    /// Get the vertices of the route, from start to end
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    //ap num_segments
+    pub fn num_segments(&self) -> usize {
+        self.points.len() - 1
+    }
+
+    //mp name_waypoint
+    /// Name the vertex at an index as a waypoint
+    pub fn name_waypoint(&mut self, index: usize, name: &str) {
+        if let Some(n) = self.names.get_mut(index) {
+            *n = Some(name.into());
+        }
+    }
+
+    //ap waypoint_index
+    /// Get the index of the vertex of a named waypoint
+    pub fn waypoint_index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n.as_deref() == Some(name))
+    }
+
+    //ap waypoint
+    /// Get the position of a named waypoint
+    pub fn waypoint(&self, name: &str) -> Option<Point> {
+        self.waypoint_index(name).map(|i| self.points[i])
+    }
+
+    //fi snap
+    fn snap(&self, v: f64) -> f64 {
+        match self.grid {
+            Some(g) => (v / g).round() * g,
+            None => v,
+        }
+    }
+
+    //fi orientation
+    /// Get the orientation of a segment; a segment of zero length is
+    /// perpendicular to its neighbours
+    fn orientation(&self, segment: usize) -> Orientation {
+        let n = self.num_segments();
+        let mut s = segment;
+        let mut flips = 0;
+        loop {
+            let (p0, p1) = (self.points[s], self.points[s + 1]);
+            if p0[1] == p1[1] && p0[0] != p1[0] {
+                return if flips % 2 == 0 {
+                    Orientation::Horizontal
+                } else {
+                    Orientation::Vertical
+                };
+            }
+            if p0[0] == p1[0] && p0[1] != p1[1] {
+                return if flips % 2 == 0 {
+                    Orientation::Vertical
+                } else {
+                    Orientation::Horizontal
+                };
+            }
+            if s + 1 >= n {
+                return Orientation::Horizontal;
+            }
+            s += 1;
+            flips += 1;
+        }
+    }
+
+    //fi segment_length
+    fn segment_length(&self, segment: usize) -> f64 {
+        let (p0, p1) = (self.points[segment], self.points[segment + 1]);
+        (p1[0] - p0[0]).abs() + (p1[1] - p0[1]).abs()
+    }
+
+    //mi move_segment
+    /// Move a segment perpendicular to itself by an offset, stretching
+    /// its neighbours, without checking the constraints
+    fn move_segment(&mut self, segment: usize, offset: f64) {
+        let axis = match self.orientation(segment) {
+            Orientation::Horizontal => 1,
+            Orientation::Vertical => 0,
+        };
+        self.points[segment][axis] += offset;
+        self.points[segment + 1][axis] += offset;
+    }
+
+    //mi check
+    /// Check the constraints of the route
+    fn check(&self) -> Result<(), Error> {
+        for s in 0..self.num_segments() {
+            let (p0, p1) = (self.points[s], self.points[s + 1]);
+            if p0[0] != p1[0] && p0[1] != p1[1] {
+                return Err(Error::BadRoute {
+                    reason: format!("segment {} is not orthogonal", s),
+                });
+            }
+            if self.segment_length(s) < self.min_segment {
+                return Err(Error::BadRoute {
+                    reason: format!("segment {} is shorter than the minimum", s),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    //mp shift_segment
+    /// Shift a segment perpendicular to itself by an offset (snapped
+    /// to the grid), stretching the segments either side of it
+    ///
+    /// If the segment is the first or last then a new segment is
+    /// inserted to keep the start or end fixed. The route is unchanged
+    /// if the result would break its constraints.
+    pub fn shift_segment(&mut self, segment: usize, offset: f64) -> Result<(), Error> {
+        if segment >= self.num_segments() {
+            return Err(Error::BadRoute {
+                reason: format!("no segment {}", segment),
+            });
+        }
+        let mut route = self.clone();
+        let mut segment = segment;
+        let axis = match route.orientation(segment) {
+            Orientation::Horizontal => 1,
+            Orientation::Vertical => 0,
+        };
+        let offset = route.snap(route.points[segment][axis] + offset) - route.points[segment][axis];
+        if segment == 0 {
+            route.points.insert(0, route.points[0]);
+            route.names.insert(1, None);
+            segment += 1;
+        }
+        if segment + 1 == route.num_segments() {
+            let end = route.points[segment + 1];
+            route.points.push(end);
+            route.names.insert(segment + 1, None);
+        }
+        route.points[segment][axis] += offset;
+        route.points[segment + 1][axis] += offset;
+        route.check()?;
+        *self = route;
+        Ok(())
+    }
+
+    //mp insert_segment
+    /// Split a segment at a distance along it (snapped to the grid),
+    /// inserting a zero-length segment perpendicular to it at that
+    /// point; returns the index of the remainder of the split
+    /// segment, which can then be shifted to create a jog
+    ///
+    /// The constraints are checked only when the new segments are
+    /// shifted
+    pub fn insert_segment(&mut self, segment: usize, at: f64) -> Result<usize, Error> {
+        if segment >= self.num_segments() {
+            return Err(Error::BadRoute {
+                reason: format!("no segment {}", segment),
+            });
+        }
+        let (p0, p1) = (self.points[segment], self.points[segment + 1]);
+        let length = self.segment_length(segment);
+        let p = match self.orientation(segment) {
+            Orientation::Horizontal => {
+                let x = p0[0] + at.clamp(0., length) * (p1[0] - p0[0]).signum();
+                Point::from_array([self.snap(x), p0[1]])
+            }
+            Orientation::Vertical => {
+                let y = p0[1] + at.clamp(0., length) * (p1[1] - p0[1]).signum();
+                Point::from_array([p0[0], self.snap(y)])
+            }
+        };
+        self.points.insert(segment + 1, p);
+        self.points.insert(segment + 1, p);
+        self.names.insert(segment + 1, None);
+        self.names.insert(segment + 1, None);
+        Ok(segment + 2)
+    }
+
+    //mp simplify
+    /// Remove segments of zero length and merge consecutive segments
+    /// with the same orientation, keeping named waypoints
+    pub fn simplify(&mut self) {
+        let mut i = 1;
+        while i + 1 < self.points.len() {
+            let (p0, p, p1) = (self.points[i - 1], self.points[i], self.points[i + 1]);
+            let collinear = (p0[0] == p[0] && p[0] == p1[0]) || (p0[1] == p[1] && p[1] == p1[1]);
+            if collinear && self.names[i].is_none() {
+                self.points.remove(i);
+                self.names.remove(i);
+                i = i.max(2) - 1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    //mp is_valid
+    /// Return true if the route is orthogonal and meets its minimum
+    /// segment length
+    pub fn is_valid(&self) -> bool {
+        self.check().is_ok()
+    }
+
+    //mp as_path
+    /// Generate the path of the route, with its corners rounded;
+    /// segments of zero length are skipped
+    pub fn as_path(&self) -> BezierPath {
+        let mut path = BezierPath::default();
+        for s in self.points.windows(2) {
+            if s[0][0] != s[1][0] || s[0][1] != s[1][1] {
+                path.add_bezier(Bezier::line(&s[0], &s[1]));
+            }
+        }
+        path.round(self.rounding, false);
+        path
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_route {
+    use super::*;
+    fn pt(x: f64, y: f64) -> Point {
+        [x, y].into()
+    }
+    pub fn pt_eq(pt: &Point, x: f64, y: f64) {
+        assert!(
+            (pt[0] - x).abs() < 1E-8 && (pt[1] - y).abs() < 1E-8,
+            "mismatch in {:?} {} {}",
+            pt,
+            x,
+            y
+        );
+    }
+    fn pts_eq(pts: &[Point], expected: &[(f64, f64)]) {
+        assert_eq!(pts.len(), expected.len(), "mismatch in {:?}", pts);
+        for (p, (x, y)) in pts.iter().zip(expected.iter()) {
+            pt_eq(p, *x, *y);
+        }
+    }
+    #[test]
+    fn test_new() {
+        let r = Route::new(pt(0., 0.), pt(10., 5.));
+        pts_eq(r.points(), &[(0., 0.), (10., 0.), (10., 5.)]);
+        assert!(r.is_valid());
+        let r = Route::new(pt(0., 0.), pt(10., 0.));
+        assert_eq!(r.num_segments(), 1);
+        assert_eq!(r.as_path().iter_beziers().count(), 1);
+    }
+    #[test]
+    fn test_edit() {
+        let mut r = Route::new(pt(0., 0.), pt(10., 5.))
+            .set_grid(1.)
+            .set_min_segment(1.)
+            .set_rounding(0.5);
+        r.name_waypoint(1, "corner");
+
+        // Split the first segment and jog its remainder down
+        let s = r.insert_segment(0, 4.3).unwrap();
+        assert_eq!(s, 2);
+        pt_eq(&r.points()[1], 4., 0.);
+        r.shift_segment(s, 2.2).unwrap();
+        pts_eq(
+            r.points(),
+            &[(0., 0.), (4., 0.), (4., 2.), (10., 2.), (10., 5.)],
+        );
+        pt_eq(&r.waypoint("corner").unwrap(), 10., 2.);
+        assert!(r.is_valid());
+
+        // Too short a segment is rejected
+        assert!(r.shift_segment(2, 2.6).is_err());
+        pt_eq(&r.points()[2], 4., 2.);
+
+        // Shifting the first segment keeps the start fixed
+        r.shift_segment(0, -1.).unwrap();
+        pt_eq(&r.points()[0], 0., 0.);
+        pt_eq(&r.points()[1], 0., -1.);
+        pt_eq(&r.waypoint("corner").unwrap(), 10., 2.);
+        assert!(r.is_valid());
+
+        // The path has rounded corners between each pair of lines
+        assert_eq!(r.as_path().iter_beziers().count(), 2 * r.num_segments() - 1);
+
+        let mut r = Route::new(pt(0., 0.), pt(10., 0.));
+        let s = r.insert_segment(0, 5.).unwrap();
+        assert_eq!(r.num_segments(), 3);
+        r.simplify();
+        assert_eq!(r.num_segments(), 1);
+        assert!(r.shift_segment(s, 1.).is_err());
+    }
+}
@@ -0,0 +1,492 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    shape.rs
+@brief   Parametric primitive shapes for flowcharts and icons
+ */
+
+//a Imports
+use geo_nd::Vector;
+
+use crate::{BBox, BezierPath, PathBuilder, Point};
+
+//a ShapeKind
+//tp ShapeKind
+/// The kind of a [Shape], with its dimensions
+///
+/// The shapes are centered on the origin before the rotation and
+/// center of the [Shape] are applied, except for the markers (pins and
+/// flags) which have their tip at the origin, so that they are placed
+/// (and rotated) by their tip
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeKind {
+    /// A stadium - a rectangle with semicircular ends on its shorter
+    /// sides
+    Capsule { width: f64, height: f64 },
+    /// A block arrow pointing along +X, with a notch at the back
+    /// matching the point at the front
+    Chevron { width: f64, height: f64, depth: f64 },
+    /// A parallelogram whose edge at +Y is offset by `skew` in X
+    /// relative to its edge at -Y
+    Parallelogram { width: f64, height: f64, skew: f64 },
+    /// A trapezoid with edges of width `width_0` at -Y and `width_1`
+    /// at +Y
+    Trapezoid {
+        width_0: f64,
+        width_1: f64,
+        height: f64,
+    },
+    /// A plus-shaped cross of overall size with arms of a given
+    /// thickness
+    Cross { size: f64, thickness: f64 },
+    /// An annulus between two circles
+    Ring { outer: f64, inner: f64 },
+    /// A map pin with a circular head of a radius, whose tip is
+    /// `height` below (+Y) the top of the head
+    Pin { radius: f64, height: f64 },
+    /// A flag of a width and height at the top of a pole of a given
+    /// length, with the bottom of the pole as the tip
+    Flag { width: f64, height: f64, pole: f64 },
+}
+
+//ip ShapeKind
+impl ShapeKind {
+    //ap name
+    /// Get the name of the kind of shape, such as 'chevron'
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Capsule { .. } => "capsule",
+            Self::Chevron { .. } => "chevron",
+            Self::Parallelogram { .. } => "parallelogram",
+            Self::Trapezoid { .. } => "trapezoid",
+            Self::Cross { .. } => "cross",
+            Self::Ring { .. } => "ring",
+            Self::Pin { .. } => "pin",
+            Self::Flag { .. } => "flag",
+        }
+    }
+
+    //ap parameters
+    /// Get the dimensions of the shape, by name
+    pub fn parameters(&self) -> Vec<(&'static str, f64)> {
+        match *self {
+            Self::Capsule { width, height } => vec![("width", width), ("height", height)],
+            Self::Chevron {
+                width,
+                height,
+                depth,
+            } => vec![("width", width), ("height", height), ("depth", depth)],
+            Self::Parallelogram {
+                width,
+                height,
+                skew,
+            } => vec![("width", width), ("height", height), ("skew", skew)],
+            Self::Trapezoid {
+                width_0,
+                width_1,
+                height,
+            } => vec![
+                ("width-0", width_0),
+                ("width-1", width_1),
+                ("height", height),
+            ],
+            Self::Cross { size, thickness } => vec![("size", size), ("thickness", thickness)],
+            Self::Ring { outer, inner } => vec![("outer", outer), ("inner", inner)],
+            Self::Pin { radius, height } => vec![("radius", radius), ("height", height)],
+            Self::Flag {
+                width,
+                height,
+                pole,
+            } => vec![("width", width), ("height", height), ("pole", pole)],
+        }
+    }
+}
+
+//a Shape
+//tp Shape
+/// A [Shape] is one of a library of parametric primitives commonly
+/// needed for flowcharts and icons, with a center, rotation and
+/// corner rounding
+///
+/// Rounding applies only to the corners of the straight-edged shapes
+#[derive(Debug, Clone, Copy)]
+pub struct Shape {
+    kind: ShapeKind,
+    center: Point,
+    rotation: f64,
+    rounding: f64,
+}
+
+//ip Shape
+impl Shape {
+    //fp new
+    /// Create a new shape of a given kind centered on the origin, with
+    /// no rotation or rounding
+    pub fn new(kind: ShapeKind) -> Self {
+        Self {
+            kind,
+            center: Point::zero(),
+            rotation: 0.,
+            rounding: 0.,
+        }
+    }
+
+    //fp new_capsule
+    /// Create a new capsule (stadium) of a given width and height
+    pub fn new_capsule(width: f64, height: f64) -> Self {
+        Self::new(ShapeKind::Capsule { width, height })
+    }
+
+    //fp new_chevron
+    /// Create a new chevron pointing along +X, where `depth` is the
+    /// length of the point (and of the notch)
+    pub fn new_chevron(width: f64, height: f64, depth: f64) -> Self {
+        Self::new(ShapeKind::Chevron {
+            width,
+            height,
+            depth,
+        })
+    }
+
+    //fp new_parallelogram
+    /// Create a new parallelogram, with its +Y edge offset by `skew`
+    pub fn new_parallelogram(width: f64, height: f64, skew: f64) -> Self {
+        Self::new(ShapeKind::Parallelogram {
+            width,
+            height,
+            skew,
+        })
+    }
+
+    //fp new_trapezoid
+    /// Create a new trapezoid with edge widths at -Y and +Y
+    pub fn new_trapezoid(width_0: f64, width_1: f64, height: f64) -> Self {
+        Self::new(ShapeKind::Trapezoid {
+            width_0,
+            width_1,
+            height,
+        })
+    }
+
+    //fp new_cross
+    /// Create a new plus-shaped cross
+    pub fn new_cross(size: f64, thickness: f64) -> Self {
+        Self::new(ShapeKind::Cross { size, thickness })
+    }
+
+    //fp new_ring
+    /// Create a new ring with outer and inner radii
+    pub fn new_ring(outer: f64, inner: f64) -> Self {
+        Self::new(ShapeKind::Ring { outer, inner })
+    }
+
+    //fp new_pin
+    /// Create a new map pin marker, with its tip at its center
+    pub fn new_pin(radius: f64, height: f64) -> Self {
+        Self::new(ShapeKind::Pin { radius, height })
+    }
+
+    //fp new_flag
+    /// Create a new flag marker, with the bottom of its pole at its
+    /// center
+    pub fn new_flag(width: f64, height: f64, pole: f64) -> Self {
+        Self::new(ShapeKind::Flag {
+            width,
+            height,
+            pole,
+        })
+    }
+
+    //bp set_center
+    /// Set the center of the shape; for markers this is the position
+    /// of the tip
+    pub fn set_center(mut self, center: Point) -> Self {
+        self.center = center;
+        self
+    }
+
+    //bp set_rotation
+    /// Set the rotation of the shape in degrees
+    pub fn set_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    //bp set_rounding
+    /// Set the rounding of the corners
+    pub fn set_rounding(mut self, rounding: f64) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    //mp kind
+    /// Get the kind of the shape
+    pub fn kind(&self) -> &ShapeKind {
+        &self.kind
+    }
+
+    //fi place
+    /// Map a point relative to the shape to its rotated and centered
+    /// position
+    fn place(&self, x: f64, y: f64) -> Point {
+        Point::from_array([x, y]).rotate_around(&Point::zero(), self.rotation.to_radians(), 0, 1)
+            + self.center
+    }
+
+    //fi corners
+    /// Get the corners of a straight-edged shape, relative to its
+    /// center, in anticlockwise order
+    fn corners(&self) -> Vec<(f64, f64)> {
+        match self.kind {
+            ShapeKind::Chevron {
+                width,
+                height,
+                depth,
+            } => {
+                let (w, h) = (width / 2., height / 2.);
+                vec![
+                    (-w, -h),
+                    (w - depth, -h),
+                    (w, 0.),
+                    (w - depth, h),
+                    (-w, h),
+                    (-w + depth, 0.),
+                ]
+            }
+            ShapeKind::Parallelogram {
+                width,
+                height,
+                skew,
+            } => {
+                let (w, h, s) = (width / 2., height / 2., skew / 2.);
+                vec![(-w - s, -h), (w - s, -h), (w + s, h), (-w + s, h)]
+            }
+            ShapeKind::Trapezoid {
+                width_0,
+                width_1,
+                height,
+            } => {
+                let (w0, w1, h) = (width_0 / 2., width_1 / 2., height / 2.);
+                vec![(-w0, -h), (w0, -h), (w1, h), (-w1, h)]
+            }
+            ShapeKind::Cross { size, thickness } => {
+                let (a, t) = (size / 2., thickness / 2.);
+                vec![
+                    (-t, -a),
+                    (t, -a),
+                    (t, -t),
+                    (a, -t),
+                    (a, t),
+                    (t, t),
+                    (t, a),
+                    (-t, a),
+                    (-t, t),
+                    (-a, t),
+                    (-a, -t),
+                    (-t, -t),
+                ]
+            }
+            ShapeKind::Flag {
+                width,
+                height,
+                pole,
+            } => {
+                vec![
+                    (0., 0.),
+                    (0., -pole),
+                    (width, -pole),
+                    (width, height - pole),
+                    (0., height - pole),
+                ]
+            }
+            _ => vec![],
+        }
+    }
+
+    //mp as_paths
+    /// Generate the shape as a set of closed Bezier paths
+    ///
+    /// All shapes generate a single path except a ring, whose inner
+    /// circle is a second path drawn in the opposite direction (so
+    /// that it is a hole with either fill rule)
+    pub fn as_paths(&self) -> Vec<BezierPath> {
+        match self.kind {
+            ShapeKind::Capsule { width, height } => {
+                let (l, r) = if width >= height {
+                    (width / 2. - height / 2., height / 2.)
+                } else {
+                    (height / 2. - width / 2., width / 2.)
+                };
+                let place = |x: f64, y: f64| {
+                    if width >= height {
+                        self.place(x, y)
+                    } else {
+                        self.place(-y, x)
+                    }
+                };
+                vec![PathBuilder::new()
+                    .move_to(place(-l, -r))
+                    .line_to(place(l, -r))
+                    .arc_to(place(l, 0.), 180.)
+                    .line_to(place(-l, r))
+                    .arc_to(place(-l, 0.), 180.)
+                    .build()]
+            }
+            ShapeKind::Pin { radius, height } => {
+                let d = height - radius;
+                if d <= radius {
+                    return vec![PathBuilder::new()
+                        .move_to(self.place(radius, -d))
+                        .arc_to(self.place(0., -d), 360.)
+                        .build()];
+                }
+                let phi = (radius / d).acos();
+                let (s, c) = phi.sin_cos();
+                vec![PathBuilder::new()
+                    .move_to(self.place(0., 0.))
+                    .line_to(self.place(radius * s, radius * c - d))
+                    .arc_to(self.place(0., -d), -(360. - 2. * phi.to_degrees()))
+                    .close()
+                    .build()]
+            }
+            ShapeKind::Ring { outer, inner } => {
+                let mut paths = vec![PathBuilder::new()
+                    .move_to(self.place(outer, 0.))
+                    .arc_to(self.center, 360.)
+                    .build()];
+                if inner > 0. {
+                    paths.push(
+                        PathBuilder::new()
+                            .move_to(self.place(inner, 0.))
+                            .arc_to(self.center, -360.)
+                            .build(),
+                    );
+                }
+                paths
+            }
+            _ => {
+                let corners: Vec<Point> = self
+                    .corners()
+                    .into_iter()
+                    .map(|(x, y)| self.place(x, y))
+                    .collect();
+                vec![BezierPath::of_points(&corners, self.rounding)]
+            }
+        }
+    }
+
+    //mp anchor
+    /// Get the anchor point of the shape - the tip of a marker, or the
+    /// center of any other shape
+    pub fn anchor(&self) -> Point {
+        self.center
+    }
+
+    //mp get_bbox
+    /// Get the bounding box for the shape (it may be pessimistic)
+    pub fn get_bbox(&self) -> BBox {
+        let mut bbox = BBox::none();
+        for p in self.as_paths() {
+            for b in p.iter_beziers() {
+                for i in 0..=b.degree() {
+                    bbox = bbox.include(*b.borrow_pt(i));
+                }
+            }
+        }
+        bbox
+    }
+
+    //zz All done
+}
+
+//ip std::ops::Add<Point> for Shape
+impl std::ops::Add<Point> for Shape {
+    type Output = Self;
+    #[inline]
+    fn add(mut self, dxy: Point) -> Self {
+        self.center += dxy;
+        self
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod tests_shape {
+    use super::*;
+    pub fn pt_eq(pt: &Point, x: f64, y: f64) {
+        assert!(
+            (pt[0] - x).abs() < 1E-7 && (pt[1] - y).abs() < 1E-7,
+            "mismatch in {:?} {} {}",
+            pt,
+            x,
+            y
+        );
+    }
+    #[test]
+    fn test_shapes() {
+        let p = Shape::new_trapezoid(4., 2., 2.).as_paths();
+        assert_eq!(p.len(), 1);
+        assert_eq!(p[0].iter_beziers().count(), 4);
+        pt_eq(&p[0].get_pt(0), -2., -1.);
+        let p = Shape::new_cross(4., 2.).set_rounding(0.1).as_paths();
+        assert_eq!(p[0].iter_beziers().count(), 24);
+        let c = Shape::new_capsule(2., 6.) + Point::from_array([1., 1.]);
+        let p = c.as_paths();
+        pt_eq(&p[0].get_pt(0), 2., -1.);
+        pt_eq(&p[0].get_pt(1), 2., -1.);
+        let p = Shape::new_ring(2., 1.).as_paths();
+        assert_eq!(p.len(), 2);
+        pt_eq(&p[1].get_pt(1), 1., 0.);
+        let pin = Shape::new_pin(1., 4.).set_center([5., 5.].into());
+        let p = pin.as_paths();
+        assert_eq!(p.len(), 1);
+        pt_eq(&p[0].get_pt(0), 5., 5.);
+        pt_eq(&p[0].get_pt(1), 5., 5.);
+        pt_eq(&pin.anchor(), 5., 5.);
+        let b = pin.get_bbox();
+        assert!(b.y[0] <= 1. + 1E-7 && b.y[0] > 0.5 && (b.y[1] - 5.).abs() < 1E-7);
+        let p = Shape::new_flag(3., 2., 5.).as_paths();
+        pt_eq(&p[0].get_pt(0), 0., 0.);
+        let b = Shape::new_chevron(4., 2., 1.).set_rotation(90.).get_bbox();
+        pt_eq(&b.center(), 0., 0.);
+        assert!((b.width() - 2.).abs() < 1E-7);
+    }
+    #[test]
+    fn test_marker_bboxes() {
+        // The pin hangs above its tip, with a head of radius 1
+        let b = Shape::new_pin(1., 4.)
+            .set_center([5., 5.].into())
+            .get_bbox();
+        assert!((b.center()[0] - 5.).abs() < 1E-7);
+        assert!(b.width() >= 2. - 1E-7 && b.width() < 2.5, "{}", b);
+        assert!((b.y[1] - 5.).abs() < 1E-7);
+        assert!(b.y[0] <= 1. + 1E-7 && b.y[0] > 0.5, "{}", b);
+
+        // A pin shorter than its head is just the head
+        let b = Shape::new_pin(1., 1.5).get_bbox();
+        assert!(b.y[0] <= -1.5 + 1E-7 && b.y[0] > -2., "{}", b);
+        assert!(b.y[1] >= 0.5 - 1E-7 && b.y[1] < 1., "{}", b);
+
+        // The flag is at the top of its pole, to the right
+        let b = Shape::new_flag(3., 2., 5.).get_bbox();
+        pt_eq(&[b.x[0], b.y[0]].into(), 0., -5.);
+        pt_eq(&[b.x[1], b.y[1]].into(), 3., 0.);
+        let b = Shape::new_flag(3., 2., 5.)
+            .set_center([1., 1.].into())
+            .set_rotation(90.)
+            .get_bbox();
+        assert!((b.width() - 5.).abs() < 1E-7 && (b.height() - 3.).abs() < 1E-7);
+        assert!((b.x[0] - 1.).abs() < 1E-7 || (b.x[1] - 1.).abs() < 1E-7);
+    }
+}
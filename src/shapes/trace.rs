@@ -0,0 +1,356 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    trace.rs
+@brief   Tracing of raster masks to vector outlines
+ */
+
+//a Imports
+use std::collections::BTreeMap;
+
+use geo_nd::Vector;
+
+use crate::{Bezier, BezierPath, Point, SvgCompoundPath, SvgElement};
+
+//a Types
+/// Key of the midpoint of the edge of a marching squares cell, in
+/// units of half a pixel
+type Key = (i64, i64);
+
+//a ImageTrace
+//tp ImageTrace
+/// An [ImageTrace] converts a monochrome raster mask into closed
+/// vector outlines
+///
+/// The outlines are found by marching squares through the pixel
+/// centers, simplified (Douglas-Peucker) to within a tolerance, and
+/// then (optionally) smoothed by fitting cubic Beziers through the
+/// remaining points, keeping sharp corners where the outline turns
+/// by more than the corner angle
+///
+/// Outer outlines are counter-clockwise (in SVG coordinates, with Y
+/// down) and holes are clockwise; the outlines of a mask are best
+/// drawn as one [SvgCompoundPath], so that holes are not filled
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImageTrace {
+    /// Size of a pixel in the output
+    pub scale: f64,
+    /// Maximum distance (in pixels) of the simplified outline from
+    /// the traced outline
+    pub tolerance: f64,
+    /// If true then fit Beziers through the simplified points;
+    /// otherwise the outlines are polygons
+    pub smooth: bool,
+    /// Turn (in degrees) at a point beyond which it is kept as a
+    /// corner when smoothing
+    pub corner_angle: f64,
+}
+
+//ip Default for ImageTrace
+impl std::default::Default for ImageTrace {
+    fn default() -> Self {
+        Self {
+            scale: 1.,
+            tolerance: 0.75,
+            smooth: true,
+            corner_angle: 60.,
+        }
+    }
+}
+
+//ip ImageTrace
+impl ImageTrace {
+    //fp new
+    /// Create a new image trace with a given pixel size in the output
+    pub fn new(scale: f64) -> Self {
+        Self {
+            scale,
+            ..Default::default()
+        }
+    }
+
+    //bp set_tolerance
+    pub fn set_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    //bp set_smooth
+    /// Set whether Beziers are fitted, and the corner angle to keep
+    pub fn set_smooth(mut self, smooth: bool, corner_angle: f64) -> Self {
+        self.smooth = smooth;
+        self.corner_angle = corner_angle;
+        self
+    }
+
+    //mp contours
+    /// Find the outlines of a mask (given in rows, of `width` pixels
+    /// each) as closed polygons in pixel coordinates, prior to
+    /// simplification
+    ///
+    /// Pixel (x, y) covers x to x+1 and y to y+1
+    pub fn contours(&self, width: usize, mask: &[bool]) -> Vec<Vec<Point>> {
+        if width == 0 {
+            return vec![];
+        }
+        let height = mask.len() / width;
+        let inside = |x: i64, y: i64| {
+            x >= 0
+                && y >= 0
+                && (x as usize) < width
+                && (y as usize) < height
+                && mask[(y as usize) * width + (x as usize)]
+        };
+        let mut segments: BTreeMap<Key, Key> = BTreeMap::new();
+        for y in -1..(height as i64) {
+            for x in -1..(width as i64) {
+                let (tl, tr, br, bl) = (
+                    inside(x, y),
+                    inside(x + 1, y),
+                    inside(x + 1, y + 1),
+                    inside(x, y + 1),
+                );
+                let top = (2 * x + 1, 2 * y);
+                let right = (2 * x + 2, 2 * y + 1);
+                let bottom = (2 * x + 1, 2 * y + 2);
+                let left = (2 * x, 2 * y + 1);
+                let tl_k = (2 * x, 2 * y);
+                let tr_k = (2 * x + 2, 2 * y);
+                let br_k = (2 * x + 2, 2 * y + 2);
+                let bl_k = (2 * x, 2 * y + 2);
+                // Each pair of edge midpoints, with a corner on one
+                // side of the segment between them and whether it is
+                // inside
+                let pairs: &[(Key, Key, Key, bool)] = match (tl, tr, br, bl) {
+                    (false, false, false, false) | (true, true, true, true) => &[],
+                    (true, false, true, false) => {
+                        &[(top, left, tl_k, true), (bottom, right, br_k, true)]
+                    }
+                    (false, true, false, true) => {
+                        &[(top, right, tr_k, true), (bottom, left, bl_k, true)]
+                    }
+                    _ if tl != tr && tl != bl => &[(top, left, tl_k, tl)],
+                    _ if tr != tl && tr != br => &[(top, right, tr_k, tr)],
+                    _ if br != tr && br != bl => &[(bottom, right, br_k, br)],
+                    _ if bl != tl && bl != br => &[(bottom, left, bl_k, bl)],
+                    _ if tl == tr => &[(left, right, tl_k, tl)],
+                    _ => &[(top, bottom, tl_k, tl)],
+                };
+                for (a, b, corner, corner_inside) in pairs {
+                    // Orient the segment so that the inside is on the
+                    // left (with Y down)
+                    let cross = (b.0 - a.0) * (corner.1 - a.1) - (b.1 - a.1) * (corner.0 - a.0);
+                    if (cross < 0) == *corner_inside {
+                        segments.insert(*a, *b);
+                    } else {
+                        segments.insert(*b, *a);
+                    }
+                }
+            }
+        }
+        // Start each contour at the smallest remaining key, so that
+        // the contours and their start points are reproducible
+        let mut r = vec![];
+        while let Some(start) = segments.keys().next().copied() {
+            let mut contour = vec![];
+            let mut k = start;
+            while let Some(next) = segments.remove(&k) {
+                contour.push(Point::from_array([
+                    (k.0 as f64) / 2. + 0.5,
+                    (k.1 as f64) / 2. + 0.5,
+                ]));
+                k = next;
+            }
+            r.push(contour);
+        }
+        r
+    }
+
+    //mp outlines
+    /// Trace a mask to simplified closed outlines, scaled to the output
+    pub fn outlines(&self, width: usize, mask: &[bool]) -> Vec<BezierPath> {
+        self.contours(width, mask)
+            .into_iter()
+            .map(|c| simplify_closed(&c, self.tolerance))
+            .filter(|c| c.len() >= 3)
+            .map(|c| {
+                let c: Vec<Point> = c.into_iter().map(|p| p * self.scale).collect();
+                if self.smooth {
+                    self.fit(&c)
+                } else {
+                    BezierPath::of_points(&c, 0.)
+                }
+            })
+            .collect()
+    }
+
+    //mp element
+    /// Trace a mask to an [SvgCompoundPath] element
+    pub fn element<'a>(&self, width: usize, mask: &[bool]) -> SvgElement<'a> {
+        SvgCompoundPath::new(self.outlines(width, mask))
+    }
+
+    //fi fit
+    /// Fit a closed loop of cubic Beziers (Catmull-Rom) through the
+    /// points, with zero-length tangents at corners
+    fn fit(&self, pts: &[Point]) -> BezierPath {
+        let n = pts.len();
+        let cos_corner = self.corner_angle.to_radians().cos();
+        let tangent = |i: usize| {
+            let p0 = pts[(i + n - 1) % n];
+            let p1 = pts[i];
+            let p2 = pts[(i + 1) % n];
+            let (d0, d1) = (p1 - p0, p2 - p1);
+            let l = (d0.length() * d1.length()).max(f64::EPSILON);
+            if (d0[0] * d1[0] + d0[1] * d1[1]) / l < cos_corner {
+                Point::zero()
+            } else {
+                (p2 - p0) / 6.
+            }
+        };
+        let mut bp = BezierPath::default();
+        for i in 0..n {
+            let p0 = pts[i];
+            let p1 = pts[(i + 1) % n];
+            let c0 = p0 + tangent(i);
+            let c1 = p1 - tangent((i + 1) % n);
+            bp.add_bezier(Bezier::cubic(&p0, &c0, &c1, &p1));
+        }
+        bp
+    }
+}
+
+//a Functions
+//fi distance_to_line
+fn distance_to_line(p: &Point, a: &Point, b: &Point) -> f64 {
+    let d = *b - *a;
+    let l = d.length();
+    if l < f64::EPSILON {
+        (*p - *a).length()
+    } else {
+        ((p[0] - a[0]) * d[1] - (p[1] - a[1]) * d[0]).abs() / l
+    }
+}
+
+//fi simplify
+/// Douglas-Peucker simplification of an open polyline, pushing all
+/// but its last point
+fn simplify(pts: &[Point], tolerance: f64, r: &mut Vec<Point>) {
+    let n = pts.len();
+    let mut worst = (0, 0.);
+    for (i, p) in pts.iter().enumerate().take(n - 1).skip(1) {
+        let d = distance_to_line(p, &pts[0], &pts[n - 1]);
+        if d > worst.1 {
+            worst = (i, d);
+        }
+    }
+    if worst.1 > tolerance {
+        simplify(&pts[0..=worst.0], tolerance, r);
+        simplify(&pts[worst.0..], tolerance, r);
+    } else {
+        r.push(pts[0]);
+    }
+}
+
+//fi furthest
+/// Find the index of the point furthest from a point
+fn furthest(pts: &[Point], from: &Point) -> usize {
+    (0..pts.len())
+        .max_by(|a, b| {
+            let da = (pts[*a] - *from).length();
+            let db = (pts[*b] - *from).length();
+            da.total_cmp(&db)
+        })
+        .unwrap_or(0)
+}
+
+//fi simplify_closed
+/// Simplify a closed polygon, splitting it at two points that are far
+/// apart (and hence corners that are kept)
+fn simplify_closed(pts: &[Point], tolerance: f64) -> Vec<Point> {
+    if pts.len() < 4 {
+        return pts.to_vec();
+    }
+    let start = furthest(pts, &pts[0]);
+    let mut loop_pts = pts[start..].to_vec();
+    loop_pts.extend_from_slice(&pts[..=start]);
+    let far = furthest(&loop_pts, &loop_pts[0]).max(1);
+    let mut r = vec![];
+    simplify(&loop_pts[0..=far], tolerance, &mut r);
+    simplify(&loop_pts[far..], tolerance, &mut r);
+    r
+}
+
+//a Test
+#[cfg(test)]
+mod test_trace {
+    use super::*;
+    fn mask(rows: &[&str]) -> (usize, Vec<bool>) {
+        let width = rows[0].len();
+        let m = rows
+            .iter()
+            .flat_map(|r| r.chars().map(|c| c == '#'))
+            .collect();
+        (width, m)
+    }
+    #[test]
+    fn test_contours() {
+        let (w, m) = mask(&["....", ".##.", ".##.", "...."]);
+        let trace = ImageTrace::default();
+        let c = trace.contours(w, &m);
+        assert_eq!(c.len(), 1);
+        assert_eq!(c[0].len(), 8);
+
+        // A ring has an outline and a hole
+        let (w, m) = mask(&["###", "#.#", "###"]);
+        let c = trace.contours(w, &m);
+        assert_eq!(c.len(), 2);
+
+        // Two diagonal pixels are separate
+        let (w, m) = mask(&["#.", ".#"]);
+        assert_eq!(trace.contours(w, &m).len(), 2);
+
+        // Contours start at their top-left-most point, outline first
+        let (w, m) = mask(&["###", "#.#", "###"]);
+        let c = trace.contours(w, &m);
+        assert_eq!(c[0][0].into_array(), [0., 0.5]);
+        assert_eq!(c[1][0].into_array(), [1., 1.5]);
+        for _ in 0..4 {
+            let d = trace.contours(w, &m);
+            for (a, b) in c.iter().zip(d.iter()) {
+                assert!(a
+                    .iter()
+                    .zip(b.iter())
+                    .all(|(p, q)| p.into_array() == q.into_array()));
+            }
+        }
+    }
+    #[test]
+    fn test_outlines() {
+        let (w, m) = mask(&["......", ".####.", ".####.", ".####.", ".####.", "......"]);
+        let trace = ImageTrace::new(2.)
+            .set_smooth(false, 60.)
+            .set_tolerance(0.1);
+        let o = trace.outlines(w, &m);
+        assert_eq!(o.len(), 1);
+        // The square with cut corners is an octagon
+        assert_eq!(o[0].iter_beziers().count(), 8);
+        let mut e = trace.element(w, &m);
+        e.finalize(&crate::SvgConfig::default()).unwrap();
+        let x = e.bbox().x;
+        assert!((x[0] - 2.).abs() < 1E-8, "Min x {}", x[0]);
+        assert!((x[1] - 10.).abs() < 1E-8, "Max x {}", x[1]);
+    }
+}
@@ -0,0 +1,230 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    wireframe.rs
+@brief   Projection of 3D wireframes to 2D
+ */
+
+//a Imports
+use crate::{Bezier, BezierPath, Error, Point, Point3D};
+
+//a Projection
+//tp Projection
+/// A [Projection] maps 3D points to 2D points (with the Y axis
+/// downwards, as for SVG) and a depth, which is larger for points
+/// further from the viewer
+///
+/// The 3D coordinate system has Z upwards. Projections are
+/// orthographic (such as axonometric) unless a perspective distance
+/// is set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projection {
+    /// Rows of the matrix, for the screen X, screen up and depth,
+    /// each applied to (x, y, z, 1)
+    matrix: [[f64; 4]; 3],
+    /// Distance of the viewer from depth zero, for perspective
+    perspective: Option<f64>,
+}
+
+//ip Projection
+impl Projection {
+    //fp of_matrix
+    /// Create a projection from the rows of a matrix that give the
+    /// screen X, the screen 'up' and the depth of a point (x, y, z, 1)
+    pub fn of_matrix(matrix: [[f64; 4]; 3]) -> Self {
+        Self {
+            matrix,
+            perspective: None,
+        }
+    }
+
+    //fp axonometric
+    /// Create an axonometric projection viewing from an azimuth
+    /// (degrees anticlockwise around the Z axis, with 0 viewing along
+    /// the Y axis) and an elevation (degrees above the XY plane)
+    pub fn axonometric(azimuth: f64, elevation: f64) -> Self {
+        let (sa, ca) = azimuth.to_radians().sin_cos();
+        let (se, ce) = elevation.to_radians().sin_cos();
+        // Rotate about Z by azimuth: x1 = x.ca + y.sa, y1 = y.ca - x.sa
+        // then tilt by elevation: up = z.ce + y1.se, depth = y1.ce - z.se
+        Self::of_matrix([
+            [ca, sa, 0., 0.],
+            [-sa * se, ca * se, ce, 0.],
+            [-sa * ce, ca * ce, -se, 0.],
+        ])
+    }
+
+    //fp isometric
+    /// Create an isometric projection, viewing from the front right
+    pub fn isometric() -> Self {
+        Self::axonometric(45., (1. / 2_f64.sqrt()).atan().to_degrees())
+    }
+
+    //bp set_perspective
+    /// Use a perspective projection with the viewer at a distance in
+    /// front of depth zero
+    pub fn set_perspective(mut self, distance: f64) -> Self {
+        self.perspective = Some(distance);
+        self
+    }
+
+    //mp project
+    /// Project a 3D point to a 2D point and depth
+    pub fn project(&self, p: &Point3D) -> (Point, f64) {
+        let row = |r: &[f64; 4]| r[0] * p[0] + r[1] * p[1] + r[2] * p[2] + r[3];
+        let x = row(&self.matrix[0]);
+        let up = row(&self.matrix[1]);
+        let depth = row(&self.matrix[2]);
+        let scale = match self.perspective {
+            Some(d) if d + depth > 0. => d / (d + depth),
+            _ => 1.,
+        };
+        ([x * scale, -up * scale].into(), depth)
+    }
+}
+
+//a Wireframe
+//tp Wireframe
+/// A [Wireframe] is a set of 3D points and edges between them, that
+/// can be projected to 2D lines for axonometric (or perspective)
+/// illustrations
+///
+/// When projected, the edges may be sorted back to front (the
+/// painter's algorithm) so that nearer edges are drawn over further
+/// ones, for example when drawn with a casing
+#[derive(Debug, Clone, Default)]
+pub struct Wireframe {
+    points: Vec<Point3D>,
+    edges: Vec<(usize, usize)>,
+}
+
+//ip Wireframe
+impl Wireframe {
+    //fp new
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //fp new_cuboid
+    /// Create a cuboid wireframe between two opposite corners
+    pub fn new_cuboid(p0: Point3D, p1: Point3D) -> Self {
+        let mut w = Self::new();
+        for i in 0..8 {
+            let c = |n: usize| if (i >> n) & 1 == 0 { p0[n] } else { p1[n] };
+            w.add_point([c(0), c(1), c(2)].into());
+        }
+        for i in 0..8 {
+            for n in 0..3 {
+                if (i >> n) & 1 == 0 {
+                    w.edges.push((i, i | (1 << n)));
+                }
+            }
+        }
+        w
+    }
+
+    //mp add_point
+    /// Add a point, returning its index
+    pub fn add_point(&mut self, p: Point3D) -> usize {
+        self.points.push(p);
+        self.points.len() - 1
+    }
+
+    //mp add_edge
+    /// Add an edge between two points, given by index
+    pub fn add_edge(&mut self, p0: usize, p1: usize) -> Result<(), Error> {
+        let n = self.points.len();
+        if p0 >= n || p1 >= n {
+            return Err(Error::BadConstruction {
+                reason: format!(
+                    "edge ({}, {}) is not between points of the wireframe with {} points",
+                    p0, p1, n
+                ),
+            });
+        }
+        self.edges.push((p0, p1));
+        Ok(())
+    }
+
+    //mp project_edges
+    /// Project the edges to 2D lines with the depth of their
+    /// midpoint; if `depth_sort` is true then they are ordered back to
+    /// front, otherwise they are in the order they were added
+    pub fn project_edges(
+        &self,
+        projection: &Projection,
+        depth_sort: bool,
+    ) -> Vec<(Point, Point, f64)> {
+        let projected: Vec<(Point, f64)> =
+            self.points.iter().map(|p| projection.project(p)).collect();
+        let mut r: Vec<(Point, Point, f64)> = self
+            .edges
+            .iter()
+            .map(|(i0, i1)| {
+                let (p0, d0) = projected[*i0];
+                let (p1, d1) = projected[*i1];
+                (p0, p1, (d0 + d1) / 2.)
+            })
+            .collect();
+        if depth_sort {
+            r.sort_by(|a, b| b.2.total_cmp(&a.2));
+        }
+        r
+    }
+
+    //mp as_paths
+    /// Project the edges to a path for each edge
+    pub fn as_paths(&self, projection: &Projection, depth_sort: bool) -> Vec<BezierPath> {
+        self.project_edges(projection, depth_sort)
+            .into_iter()
+            .map(|(p0, p1, _)| {
+                let mut bp = BezierPath::default();
+                bp.add_bezier(Bezier::line(&p0, &p1));
+                bp
+            })
+            .collect()
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_wireframe {
+    use super::*;
+    #[test]
+    fn test_projection() {
+        let front = Projection::axonometric(0., 0.);
+        let (p, d) = front.project(&[1., 2., 3.].into());
+        assert!((p[0] - 1.).abs() < 1E-9);
+        assert!((p[1] + 3.).abs() < 1E-9);
+        assert!((d - 2.).abs() < 1E-9);
+
+        let top = Projection::axonometric(0., 90.);
+        let (p, d) = top.project(&[1., 2., 3.].into());
+        assert!((p[1] + 2.).abs() < 1E-9);
+        assert!((d + 3.).abs() < 1E-9);
+
+        let (p, _) = front.set_perspective(2.).project(&[1., 2., 3.].into());
+        assert!((p[0] - 0.5).abs() < 1E-9);
+    }
+    #[test]
+    fn test_cuboid() {
+        let w = Wireframe::new_cuboid([0., 0., 0.].into(), [1., 1., 1.].into());
+        let edges = w.project_edges(&Projection::isometric(), true);
+        assert_eq!(edges.len(), 12);
+        for e in edges.windows(2) {
+            assert!(e[0].2 >= e[1].2);
+        }
+        assert_eq!(w.as_paths(&Projection::isometric(), false).len(), 12);
+    }
+}
@@ -0,0 +1,91 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg.rs
+@brief   SVG output of diagrams
+ */
+
+//a Imports
+#[allow(clippy::module_inception)]
+mod svg;
+mod svg_assets;
+mod svg_attribution;
+mod svg_audit;
+mod svg_batch;
+mod svg_budget;
+mod svg_builder;
+mod svg_colors;
+mod svg_compat;
+mod svg_debug;
+mod svg_element;
+#[cfg(feature = "serde")]
+mod svg_element_data;
+mod svg_error;
+mod svg_event;
+mod svg_format;
+mod svg_frames;
+mod svg_ids;
+mod svg_layers;
+mod svg_measure;
+mod svg_morph;
+mod svg_preview;
+mod svg_prolog;
+mod svg_references;
+mod svg_ruler;
+mod svg_separation;
+mod svg_sparkfill;
+mod svg_stream;
+mod svg_stroke;
+mod svg_subtree;
+mod svg_text_animation;
+mod svg_timeline;
+mod svg_views;
+mod svg_walk;
+pub use svg::{PresentationMode, StackSavepoint, Svg, SvgConfig, SvgVersion};
+pub use svg_assets::{PackMode, SvgImage};
+pub use svg_attribution::{Attribution, Corner};
+pub use svg_audit::{AccessibilityAudit, AccessibilityIssue};
+pub use svg_batch::SvgBatch;
+pub use svg_budget::{Degradation, SizeBudget};
+pub use svg_builder::SvgBuilder;
+pub use svg_colors::SvgColorDatabase;
+pub use svg_compat::CompatChange;
+pub use svg_element::SvgCompoundPath;
+pub use svg_element::{GridMode, SvgDefs, SvgGrid, SvgGroup, SvgPath, SvgRegion, SvgSvg};
+pub use svg_element::{SvgElement, SvgElementType};
+pub use svg_element::{SvgLines, SvgTag, SvgText};
+#[cfg(feature = "serde")]
+pub use svg_element_data::SvgElementData;
+pub use svg_error::SvgError;
+#[cfg(feature = "quick-xml")]
+pub(crate) use svg_event::is_self_closing;
+pub use svg_event::{ElementIter, Indent, Newline, XmlEvent};
+pub(crate) use svg_format::display_number_format;
+#[cfg(test)]
+pub(crate) use svg_format::number_str;
+pub use svg_format::{LabelFormat, NumberFormat};
+pub use svg_frames::FrameRegistry;
+pub use svg_ids::IdAllocator;
+pub use svg_layers::{LayerToggles, SvgLayer};
+pub use svg_measure::Measurement;
+pub use svg_morph::Morph;
+pub use svg_prolog::{XmlProlog, SVG11_DOCTYPE};
+pub use svg_ruler::Ruler;
+pub use svg_sparkfill::{Sparkfill, SparkfillKind};
+pub use svg_stream::SvgStreamWriter;
+pub use svg_stroke::{MultiStroke, StrokePass};
+pub use svg_text_animation::{TextAnimation, TextAnimationKind};
+pub use svg_timeline::Timeline;
+pub use svg_views::SvgView;
+pub use svg_walk::Visitor;
@@ -19,9 +19,28 @@ limitations under the License.
 //a Imports
 use indent_display::{IndentedDisplay, Indenter};
 
+use super::svg_assets::Packer;
+use super::svg_compat::apply_compat;
+use super::svg_debug::{bbox_json, element_json};
+use super::svg_event::{content_hash, write_events};
+use super::svg_format::NumberFormat;
+use super::svg_layers::{layer_controls, layer_names};
+use super::svg_measure::add_measurements;
+use super::svg_preview::checkerboard;
+use super::svg_references::dangling_references;
+use super::svg_separation::{ink, separate_into_layers, separation_document, separation_inks};
+use super::svg_subtree::subtree_document;
 use crate::IndentOpt;
 use crate::{
-    BBox, Color, ColorDatabase, ElementIter, SvgColorDatabase, SvgElement, SvgError, SvgSvg,
+    AccessibilityAudit, AccessibilityIssue, Attribution, Hatch, LabelFormat, LayerToggles, Ruler,
+};
+use crate::{
+    BBox, Color, ColorDatabase, ElementIter, GridMode, SvgColorDatabase, SvgDefs, SvgElement,
+    SvgError, SvgSvg,
+};
+use crate::{
+    CompatChange, Degradation, Error, IdAllocator, Indent, Length, LengthUnit, Measurement,
+    Newline, PackMode, SizeBudget, SvgView, Transform, XmlProlog,
 };
 
 //a SvgVersion
@@ -34,16 +53,15 @@ pub enum SvgVersion {
     Version2_0,
 }
 
-//ip From<&str> for SvgVersion
-impl From<&str> for SvgVersion {
-    fn from(x: &str) -> Self {
+//ip FromStr for SvgVersion
+impl std::str::FromStr for SvgVersion {
+    type Err = Error;
+    fn from_str(x: &str) -> Result<Self, Error> {
         match x {
-            "1.0" => Self::Version1_0,
-            "1.1" => Self::Version1_1,
-            "2.0" => Self::Version2_0,
-            _ => {
-                panic!("Version supported are 1.0, 1.1 and 2.0")
-            }
+            "1.0" => Ok(Self::Version1_0),
+            "1.1" => Ok(Self::Version1_1),
+            "2.0" => Ok(Self::Version2_0),
+            _ => Err(Error::BadVersion { version: x.into() }),
         }
     }
 }
@@ -59,17 +77,89 @@ impl From<SvgVersion> for &str {
     }
 }
 
+//a PresentationMode
+//tp PresentationMode
+/// The manner in which presentation properties of elements (such as
+/// 'fill', 'stroke' and 'font-size') are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PresentationMode {
+    /// As individual presentation attributes, such as 'fill="red"'
+    #[default]
+    Attributes,
+    /// Folded into a single 'style' attribute, such as
+    /// 'style="fill:red;stroke:blue"', after the declarations of any
+    /// style attribute already present
+    StyleAttribute,
+}
+
 //a SvgConfig
 //tp SvgConfig
 /// Configuration of SVG output
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct SvgConfig {
     /// if asserted then show grid at the toplevel layout
     pub show_grid: bool,
+    /// style of grid to show at the toplevel layout
+    pub grid_mode: GridMode,
     /// if asserted then show layout of grids
     pub show_layout: bool,
     /// if asserted then show content rectangles as translucent green rectangles
     pub show_content_rectangles: Option<(f64, Color)>,
+    /// formatting of numbers in visible labels
+    pub label_format: LabelFormat,
+    /// if set then draw rulers along the edges of the document
+    pub ruler: Option<Ruler>,
+    /// if set then emit controls to toggle the visibility of layers
+    pub layer_toggles: Option<LayerToggles>,
+    /// if set then expand the viewBox to this width / height ratio
+    pub aspect_ratio: Option<f64>,
+    /// if set then show a checkerboard of this square size behind the
+    /// contents, to reveal transparency when previewing
+    pub checkerboard: Option<f64>,
+    /// if set then replace the fill of closed paths with hatch lines,
+    /// for pen plotters and laser engravers
+    pub hatch_fill: Option<Hatch>,
+    /// if asserted then colors with transparency added with
+    /// 'add_color' are emitted as an opaque color and a separate
+    /// opacity attribute (such as 'fill-opacity'), rather than as
+    /// 'rgba(...)' which SVG 1.1 renderers reject
+    pub opacity_attributes: bool,
+    /// whether presentation properties are written as attributes or
+    /// folded into a 'style' attribute
+    pub presentation_mode: PresentationMode,
+    /// if asserted then elements that draw a primitive (such as a
+    /// [crate::Shape] or [crate::Gear]) have a class naming it, and
+    /// its parameters as 'data-*' attributes
+    pub semantic_classes: bool,
+    /// unit of the width and height of the document; the coordinates
+    /// of the document (and its viewBox) are millimeters, converted to
+    /// the unit
+    pub units: LengthUnit,
+    /// indentation of nested elements when the document is written
+    pub indent: Indent,
+    /// newline policy when the document is written
+    pub newline: Newline,
+    /// formatting of numbers in the SVG (coordinates, sizes and
+    /// transforms) generated when the document is finalized and
+    /// written
+    pub number_format: NumberFormat,
+    /// if asserted then generating the diagram fails if any
+    /// 'url(#id)' or '#id' href references an id that is not in the
+    /// document; otherwise such references are only recorded
+    pub strict_references: bool,
+    /// limit on the size of the generated document, with the
+    /// degradations used to meet it
+    pub size_budget: Option<SizeBudget>,
+    /// the XML declaration and document type written at the start of
+    /// the document
+    pub prolog: XmlProlog,
+    /// if asserted then the document is written as a fragment for
+    /// inclusion in an HTML5 page: with no XML prolog, and no 'version'
+    /// or namespace declarations on the root element
+    pub html_fragment: bool,
 }
 
 //ip SvgConfig
@@ -79,20 +169,143 @@ impl SvgConfig {
         self.show_grid = show_grid;
         self
     }
+    //bp set_grid_mode
+    pub fn set_grid_mode(mut self, grid_mode: GridMode) -> Self {
+        self.grid_mode = grid_mode;
+        self
+    }
     //bp set_show_layout
     pub fn set_show_layout(mut self, show_layout: bool) -> Self {
         self.show_layout = show_layout;
         self
     }
     //bp set_content_rectangles
-    pub fn set_content_rectangles<'a, T>(mut self, width: f64, color: T) -> Self
+    pub fn set_content_rectangles<'a, T>(mut self, width: f64, color: T) -> Result<Self, Error>
     where
-        (T, &'a ColorDatabase<'a>): Into<Color>,
+        (T, &'a ColorDatabase<'a>): TryInto<Color, Error = Error>,
     {
-        let color = (color, &SvgColorDatabase).into();
+        let color = (color, &SvgColorDatabase).try_into()?;
         self.show_content_rectangles = Some((width, color));
+        Ok(self)
+    }
+    //bp set_label_format
+    pub fn set_label_format(mut self, label_format: LabelFormat) -> Self {
+        self.label_format = label_format;
+        self
+    }
+    //bp set_ruler
+    pub fn set_ruler(mut self, ruler: Ruler) -> Self {
+        self.ruler = Some(ruler);
         self
     }
+    //bp set_layer_toggles
+    pub fn set_layer_toggles(mut self, layer_toggles: LayerToggles) -> Self {
+        self.layer_toggles = Some(layer_toggles);
+        self
+    }
+    //bp set_aspect_ratio
+    pub fn set_aspect_ratio(mut self, aspect_ratio: f64) -> Self {
+        self.aspect_ratio = Some(aspect_ratio);
+        self
+    }
+    //bp set_checkerboard
+    pub fn set_checkerboard(mut self, size: f64) -> Self {
+        self.checkerboard = Some(size);
+        self
+    }
+    //bp clear_checkerboard
+    pub fn clear_checkerboard(mut self) -> Self {
+        self.checkerboard = None;
+        self
+    }
+    //bp set_hatch_fill
+    pub fn set_hatch_fill(mut self, hatch: Hatch) -> Self {
+        self.hatch_fill = Some(hatch);
+        self
+    }
+    //bp set_opacity_attributes
+    pub fn set_opacity_attributes(mut self, opacity_attributes: bool) -> Self {
+        self.opacity_attributes = opacity_attributes;
+        self
+    }
+    //bp set_presentation_mode
+    /// Set whether presentation properties are written as individual
+    /// attributes or folded into a single 'style' attribute, as some
+    /// CSS pipelines require
+    pub fn set_presentation_mode(mut self, presentation_mode: PresentationMode) -> Self {
+        self.presentation_mode = presentation_mode;
+        self
+    }
+    //bp set_semantic_classes
+    /// Set whether elements that draw a primitive are tagged with a
+    /// class naming it and its parameters as 'data-*' attributes
+    pub fn set_semantic_classes(mut self, semantic_classes: bool) -> Self {
+        self.semantic_classes = semantic_classes;
+        self
+    }
+    //bp set_units
+    /// Set the unit of the width and height of the document (by
+    /// default millimeters), keeping the scaling of its viewBox
+    pub fn set_units(mut self, units: LengthUnit) -> Self {
+        self.units = units;
+        self
+    }
+    //bp set_output_format
+    /// Set the indentation and newline policy used when the document
+    /// is written, so that generated files are diff-able
+    pub fn set_output_format(mut self, indent: Indent, newline: Newline) -> Self {
+        self.indent = indent;
+        self.newline = newline;
+        self
+    }
+    //bp set_decimal_places
+    /// Set the precision of numbers in the SVG - such as six places for
+    /// CAD, or one or two places (with trailing zeros trimmed) for the
+    /// web
+    ///
+    /// If `decimal_places` is None then four places are used
+    pub fn set_decimal_places(mut self, decimal_places: Option<usize>, trim_zeros: bool) -> Self {
+        self.number_format = NumberFormat {
+            decimal_places: decimal_places.unwrap_or(4),
+            trim_zeros,
+        };
+        self
+    }
+    //bp set_strict_references
+    pub fn set_strict_references(mut self, strict_references: bool) -> Self {
+        self.strict_references = strict_references;
+        self
+    }
+    //bp set_size_budget
+    pub fn set_size_budget(mut self, size_budget: SizeBudget) -> Self {
+        self.size_budget = Some(size_budget);
+        self
+    }
+    //bp set_prolog
+    /// Set the XML declaration (version, encoding and standalone) and
+    /// document type written at the start of the document
+    pub fn set_prolog(mut self, prolog: XmlProlog) -> Self {
+        self.prolog = prolog;
+        self
+    }
+    //bp set_html_fragment
+    /// Set whether the document is written as a fragment for inclusion
+    /// in an HTML5 page (or a template), which needs neither the XML
+    /// prolog nor the namespace declarations
+    pub fn set_html_fragment(mut self, html_fragment: bool) -> Self {
+        self.html_fragment = html_fragment;
+        self
+    }
+    //ap written_prolog
+    /// Get the prolog the document is written with - none if it is an
+    /// HTML fragment
+    pub(crate) fn written_prolog(&self) -> XmlProlog {
+        if self.html_fragment {
+            XmlProlog::new().set_declaration(false)
+        } else {
+            self.prolog.clone()
+        }
+    }
     //bp clear_content_rectangles
     pub fn clear_content_rectangles(mut self) -> Self {
         self.show_content_rectangles = None;
@@ -100,6 +313,43 @@ impl SvgConfig {
     }
 }
 
+//a StackSavepoint
+//tp StackSavepoint
+/// A record of the state of the construction stack of an [Svg],
+/// which can be used to roll back a partially built subtree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackSavepoint {
+    /// Depth of the stack
+    depth: usize,
+    /// Generation of the top element of the stack (if any), so that
+    /// it being popped and replaced can be detected
+    top_generation: usize,
+    /// Number of contents of the top element of the stack (if any)
+    top_contents: usize,
+    /// Number of elements in the Svg contents
+    contents: usize,
+    /// Number of elements in the Svg definitions
+    definitions: usize,
+}
+
+//a Post-processors
+//tp PostProcessor
+/// A pass run over every element of the contents and definitions of
+/// an [Svg] when it is finalized
+type PostProcessor = Box<dyn Fn(&mut SvgElement)>;
+
+//fi post_process
+/// Run post-processors over an element and its contents, contents
+/// first
+fn post_process(e: &mut SvgElement, post_processors: &[PostProcessor]) {
+    for c in e.contents_mut() {
+        post_process(c, post_processors);
+    }
+    for p in post_processors {
+        p(e);
+    }
+}
+
 //a Svg
 //tp Svg
 /// This structure is used to create SVG renderings of a `Diagram` It
@@ -120,12 +370,33 @@ pub struct Svg<'a> {
     definitions: Vec<SvgElement<'a>>,
     /// Stack of elements being created
     stack: Vec<SvgElement<'a>>,
+    /// Generation of the stack when each of its elements was pushed
+    stack_generations: Vec<usize>,
+    /// Generation of the stack, incremented on every push and pop
+    generation: usize,
+    /// License and attribution information
+    attribution: Option<Attribution>,
+    /// Named views of regions of the document
+    views: Vec<SvgView>,
+    /// Ids referenced by the generated document that are not in it
+    dangling: Vec<String>,
+    /// Passes run over the elements when finalized
+    post_processors: Vec<PostProcessor>,
+    /// Degradations applied to meet the size budget
+    degradations: Vec<Degradation>,
+    /// Allocator of the ids of elements
+    ids: IdAllocator,
 }
 
 //ip IndentedDisplay for Svg
 impl<'a, 'i> IndentedDisplay<'i, IndentOpt> for Svg<'a> {
     fn indent(&self, f: &mut Indenter<'i, IndentOpt>) -> Result<(), std::fmt::Error> {
-        "Svg".indent(f)?;
+        use std::fmt::Write;
+        if f.options().bboxes {
+            writeln!(f, "Svg bbox {}", self.bbox)?;
+        } else {
+            "Svg".indent(f)?;
+        }
         {
             let mut sub = f.push("...");
             for c in self.contents.iter() {
@@ -142,12 +413,20 @@ impl<'a> Svg<'a> {
     /// Create a new `Svg` instance, to render a `Diagram` into
     pub fn new(config: SvgConfig) -> Self {
         Self {
-            version: "2.0".into(),
+            version: SvgVersion::Version2_0,
             config,
             bbox: BBox::none(),
             contents: vec![],
             definitions: vec![],
             stack: vec![],
+            stack_generations: vec![],
+            generation: 0,
+            attribution: None,
+            views: vec![],
+            dangling: vec![],
+            post_processors: vec![],
+            degradations: vec![],
+            ids: IdAllocator::default(),
         }
     }
 
@@ -159,117 +438,539 @@ impl<'a> Svg<'a> {
         self
     }
 
+    //mp set_attribution
+    /// Set the license and attribution information for the document,
+    /// emitted as metadata and optionally as a visible credit line
+    pub fn set_attribution(&mut self, attribution: Attribution) {
+        self.attribution = Some(attribution);
+    }
+
+    //mp add_view
+    /// Add a named view of a region of the document (such as a panel
+    /// of a figure), emitted as a 'view' element so that the URL
+    /// fragment `#<name>` zooms to it
+    pub fn add_view(&mut self, name: &str, region: BBox) {
+        self.views.push(SvgView::new(name, region));
+    }
+
+    //ap ids
+    /// Get the allocator of ids of the document, to look up the ids
+    /// allocated for keys
+    pub fn ids(&self) -> &IdAllocator {
+        &self.ids
+    }
+
+    //mp ids_mut
+    /// Get the allocator of ids of the document, to allocate ids that
+    /// are stable from one build to the next
+    ///
+    /// The ids of elements added to the contents or definitions are
+    /// reserved, so that they are not allocated
+    pub fn ids_mut(&mut self) -> &mut IdAllocator {
+        &mut self.ids
+    }
+
+    //ap views
+    /// Get the named views of the document, as an outline (or table of
+    /// contents) of it
+    pub fn views(&self) -> &[SvgView] {
+        &self.views
+    }
+
+    //mp add_post_processor
+    /// Add a pass that is run over every element of the contents and
+    /// definitions (contents before their parents) when the document is
+    /// finalized, after the elements themselves are finalized
+    ///
+    /// This permits cross-cutting policies - such as injecting
+    /// analytics attributes, enforcing corporate styling, or stripping
+    /// classes - to be applied without changing the generation code;
+    /// passes are run in the order they are added
+    pub fn add_post_processor<F: Fn(&mut SvgElement) + 'static>(&mut self, f: F) {
+        self.post_processors.push(Box::new(f));
+    }
+
+    //mp stack_savepoint
+    /// Record the state of the stack (and contents and definitions)
+    /// so that it may be restored with [Svg::rollback]
+    pub fn stack_savepoint(&self) -> StackSavepoint {
+        let depth = self.stack.len();
+        let (top_generation, top_contents) = {
+            if depth > 0 {
+                (
+                    self.stack_generations[depth - 1],
+                    self.stack[depth - 1].contents().len(),
+                )
+            } else {
+                (0, 0)
+            }
+        };
+        StackSavepoint {
+            depth,
+            top_generation,
+            top_contents,
+            contents: self.contents.len(),
+            definitions: self.definitions.len(),
+        }
+    }
+
+    //mp rollback
+    /// Restore the stack to a savepoint, abandoning any elements
+    /// pushed or added since then
+    ///
+    /// This fails if the stack has been popped below the savepoint
+    /// (even if it has since been pushed back to the same depth), or
+    /// if contents or definitions have been removed since it was
+    /// taken; the document is unchanged if it fails
+    pub fn rollback(&mut self, savepoint: StackSavepoint) -> Result<(), SvgError> {
+        if !self.savepoint_is_valid(&savepoint) {
+            return Err(SvgError::InvalidSavepoint);
+        }
+        self.stack.truncate(savepoint.depth);
+        self.stack_generations.truncate(savepoint.depth);
+        if savepoint.depth > 0 {
+            self.stack[savepoint.depth - 1].truncate_contents(savepoint.top_contents);
+        }
+        self.contents.truncate(savepoint.contents);
+        self.definitions.truncate(savepoint.definitions);
+        Ok(())
+    }
+
+    //mi savepoint_is_valid
+    /// Return true if the document can be rolled back to a savepoint
+    fn savepoint_is_valid(&self, savepoint: &StackSavepoint) -> bool {
+        if self.stack.len() < savepoint.depth
+            || self.contents.len() < savepoint.contents
+            || self.definitions.len() < savepoint.definitions
+        {
+            return false;
+        }
+        if savepoint.depth > 0 {
+            let top = savepoint.depth - 1;
+            self.stack_generations[top] == savepoint.top_generation
+                && self.stack[top].contents().len() >= savepoint.top_contents
+        } else {
+            true
+        }
+    }
+
+    //ap stack_depth
+    /// Get the number of elements on the stack
+    pub fn stack_depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    //ap stack_top_mut
+    /// Get the element at the top of the stack, if any
+    pub fn stack_top_mut(&mut self) -> Option<&mut SvgElement<'a>> {
+        self.stack.last_mut()
+    }
+
+    //mi stack_require
+    /// Check that the stack has at least a number of elements for an
+    /// operation
+    fn stack_require(&self, operation: &'static str, required: usize) -> Result<(), SvgError> {
+        let depth = self.stack.len();
+        if depth < required {
+            Err(SvgError::StackUnderflow {
+                operation,
+                required,
+                depth,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    //mi stack_require_single
+    /// Check that the stack has just one element for an operation
+    fn stack_require_single(&self, operation: &'static str) -> Result<(), SvgError> {
+        let depth = self.stack.len();
+        if depth != 1 {
+            Err(SvgError::StackNotSingle { operation, depth })
+        } else {
+            Ok(())
+        }
+    }
+
     //mp stack_push
     pub fn stack_push(&mut self, e: SvgElement<'a>) {
+        self.generation += 1;
         self.stack.push(e);
+        self.stack_generations.push(self.generation);
     }
 
-    //mp stack_pop
-    pub fn stack_pop(&mut self) -> SvgElement<'a> {
+    //mi stack_pop_element
+    /// Pop the element at the top of the stack, which must exist
+    fn stack_pop_element(&mut self) -> SvgElement<'a> {
+        self.generation += 1;
+        self.stack_generations.pop();
         self.stack.pop().unwrap()
     }
 
+    //mp stack_pop
+    /// Pop the element at the top of the stack
+    pub fn stack_pop(&mut self) -> Result<SvgElement<'a>, SvgError> {
+        self.stack_require("stack_pop", 1)?;
+        Ok(self.stack_pop_element())
+    }
+
     //mp stack_add_subelement
-    pub fn stack_add_subelement(&mut self, e: SvgElement<'a>) {
-        #![track_caller]
-        assert!(
-            !self.stack.is_empty(),
-            "Stack cannot be empty when adding a subelement to the top entry of the stack"
-        );
-        let n = self.stack.len();
-        self.stack[n - 1].push_content(e);
+    /// Add an element to the contents of the element at the top of the
+    /// stack
+    pub fn stack_add_subelement(&mut self, e: SvgElement<'a>) -> Result<(), SvgError> {
+        self.stack_require("stack_add_subelement", 1)?;
+        self.stack.last_mut().unwrap().push_content(e);
+        Ok(())
     }
 
     //mp stack_pop_to_child
-    pub fn stack_pop_to_child(&mut self) {
-        #![track_caller]
-        assert!(self.stack.len() > 1, "Stack must have two elements to pop an element to be the child of the stack element above it");
-        let e = self.stack.pop().unwrap();
-        self.stack_add_subelement(e);
+    /// Pop the element at the top of the stack, adding it to the
+    /// contents of the element below it
+    pub fn stack_pop_to_child(&mut self) -> Result<(), SvgError> {
+        self.stack_require("stack_pop_to_child", 2)?;
+        let e = self.stack_pop_element();
+        self.stack.last_mut().unwrap().push_content(e);
+        Ok(())
     }
 
     //mp contents_add_element
     pub fn contents_add_element(&mut self, e: SvgElement<'a>) {
+        self.ids.reserve_element_ids(&e);
         self.contents.push(e);
     }
 
     //mp contents_take_stack
-    pub fn contents_take_stack(&mut self) {
-        assert_eq!(
-            self.stack.len(),
-            1,
-            "Stack must have just one element to be added to the contents"
-        );
-        self.contents.push(self.stack.pop().unwrap());
+    /// Pop the only element of the stack, adding it to the contents
+    pub fn contents_take_stack(&mut self) -> Result<(), SvgError> {
+        self.stack_require_single("contents_take_stack")?;
+        let e = self.stack_pop_element();
+        self.contents_add_element(e);
+        Ok(())
     }
 
     //mp definitions_add_element
     pub fn definitions_add_element(&mut self, e: SvgElement<'a>) {
+        self.ids.reserve_element_ids(&e);
         self.definitions.push(e);
     }
 
+    //mp definitions_add_hashed
+    /// Add an element to the definitions with an id derived from a
+    /// hash of its content (as `<prefix>-<hash>`), returning the id
+    ///
+    /// If an identical element has already been added in this manner
+    /// then the element is dropped, and the id of that is returned
+    pub fn definitions_add_hashed(&mut self, prefix: &str, mut e: SvgElement<'a>) -> String {
+        let (id, is_new) = self.ids.allocate_hashed(prefix, &e);
+        if is_new {
+            e.set_local_attribute("id", &id);
+            self.definitions.push(e);
+        }
+        id
+    }
+
     //mp definitions_take_stack
-    pub fn definitions_take_stack(&mut self) {
-        assert_eq!(
-            self.stack.len(),
-            1,
-            "Stack must have just one element to be added to the definitions"
-        );
-        self.definitions.push(self.stack.pop().unwrap());
+    /// Pop the only element of the stack, adding it to the definitions
+    pub fn definitions_take_stack(&mut self) -> Result<(), SvgError> {
+        self.stack_require_single("definitions_take_stack")?;
+        let e = self.stack_pop_element();
+        self.definitions_add_element(e);
+        Ok(())
     }
 
     //mp finalize
-    pub fn finalize(&mut self) {
-        assert!(
-            self.stack.is_empty(),
-            "The stack should be empty before finalizing"
-        );
+    /// Finalize the definitions and contents, determining the bounding
+    /// box of the document; the stack must be empty
+    ///
+    /// This must be invoked only once, as finalizing the contents adds
+    /// any extra elements they generate
+    pub fn finalize(&mut self) -> Result<(), SvgError> {
+        if !self.stack.is_empty() {
+            return Err(SvgError::StackInUse {
+                depth: self.stack.len(),
+            });
+        }
+        for d in self.definitions.iter_mut() {
+            d.finalize(&self.config)?;
+        }
 
         let mut child_extra = vec![];
-        let mut bbox = BBox::default();
         for c in self.contents.iter_mut() {
-            child_extra.append(&mut c.finalize(&self.config));
-            bbox = bbox.union(c.bbox());
+            child_extra.append(&mut c.finalize(&self.config)?);
+        }
+        let mut bbox: BBox = self.contents.iter().map(|c| c.bbox()).collect();
+        if let Some(ratio) = self.config.aspect_ratio {
+            bbox = bbox.expand_to_aspect(ratio);
         }
         self.bbox = bbox;
+        // Open-ended contents can only be clamped once the bbox is known
+        for c in self.contents.iter_mut() {
+            c.clamp_to_viewbox(&bbox, &self.config);
+        }
         // Children are finalized now
         for c in child_extra {
             self.contents.push(c);
         }
+        for e in self.definitions.iter_mut().chain(self.contents.iter_mut()) {
+            post_process(e, &self.post_processors);
+        }
+        Ok(())
+    }
+
+    //mp measure
+    /// Determine the geometry of the contents without generating any
+    /// attributes or XML, returning a manifest of the bounding box and
+    /// transform (in document coordinates) of every element, in
+    /// document order
+    ///
+    /// This is much cheaper than finalizing the document, for
+    /// applications that need the geometry (such as for hit areas or
+    /// pagination) more often than the SVG. The bounding box of the
+    /// document is set as it is by [Svg::finalize], which may still be
+    /// invoked afterwards; it must be invoked before generate_diagram,
+    /// after which the contents are in the generated document and are
+    /// not measured.
+    pub fn measure(&mut self) -> Vec<Measurement> {
+        for c in self.contents.iter_mut() {
+            c.measure(&self.config);
+        }
+        let mut bbox: BBox = self.contents.iter().map(|c| c.bbox()).collect();
+        if let Some(ratio) = self.config.aspect_ratio {
+            bbox = bbox.expand_to_aspect(ratio);
+        }
+        self.bbox = bbox;
+        let mut manifest = vec![];
+        for c in self.contents.iter() {
+            add_measurements(c, &Transform::default(), 0, &mut manifest);
+        }
+        manifest
     }
 
     //mp generate_diagram
+    /// Generate the document from the finalized definitions and
+    /// contents; the stack must be empty, and holds just the document
+    /// afterwards, so the diagram may only be generated once
     pub fn generate_diagram(&mut self) -> Result<(), SvgError> {
+        if !self.stack.is_empty() {
+            return Err(SvgError::StackInUse {
+                depth: self.stack.len(),
+            });
+        }
         let (x, y, w, h) = self.bbox.get_bounds();
         let mut ele = SvgSvg::new();
-        ele.add_attribute("svg", Some("xmlns"), "http://www.w3.org/2000/svg");
-        ele.add_attribute("xmlns", None, "http://www.w3.org/2000/svg");
-        ele.add_attribute("version", None, self.version.into());
-        ele.add_attribute("width", None, &format!("{}mm", w));
-        ele.add_attribute("height", None, &format!("{}mm", h));
-        ele.add_attribute("viewBox", None, &format!("{} {} {} {}", x, y, w, h));
+        if !self.config.html_fragment {
+            ele.add_attribute("svg", Some("xmlns"), "http://www.w3.org/2000/svg");
+            ele.add_attribute("xmlns", None, "http://www.w3.org/2000/svg");
+            ele.add_attribute("version", None, self.version.into());
+        }
+        let units = self.config.units;
+        let nf = &self.config.number_format;
+        ele.add_attribute("width", None, &Length::of_document(w, units).svg_string(nf));
+        ele.add_attribute(
+            "height",
+            None,
+            &Length::of_document(h, units).svg_string(nf),
+        );
+        ele.add_attribute(
+            "viewBox",
+            None,
+            &format!(
+                "{} {} {} {}",
+                nf.number(x),
+                nf.number(y),
+                nf.number(w),
+                nf.number(h)
+            ),
+        );
         self.stack_push(ele);
 
-        // let ele = SvgElement::new("defs");
-        // self.stack_push(ele);
-        // for d in std::mem::take(&mut self.definitions) {
-        // self.stack_add_subelement(d);
-        //}
-        // self.stack_pop_to_child();
+        if let Some(attribution) = &self.attribution {
+            let mut e = attribution.metadata_element();
+            e.finalize(&self.config)?;
+            self.stack_add_subelement(e)?;
+        }
+
+        let views = self
+            .views
+            .iter()
+            .map(|v| v.element(&self.config))
+            .collect::<Result<Vec<_>, _>>()?;
+        for e in views {
+            self.stack_add_subelement(e)?;
+        }
+
+        let backdrop = self
+            .config
+            .checkerboard
+            .map(|size| {
+                let (pattern, backdrop) = checkerboard(size, &self.bbox, &self.config)?;
+                self.definitions.push(pattern);
+                Ok::<_, Error>(backdrop)
+            })
+            .transpose()?;
+
+        if !self.definitions.is_empty() {
+            self.stack_push(SvgDefs::new());
+            for d in std::mem::take(&mut self.definitions) {
+                self.stack_add_subelement(d)?;
+            }
+            self.stack_pop_to_child()?;
+        }
 
+        if let Some(backdrop) = backdrop {
+            self.stack_add_subelement(backdrop)?;
+        }
+
+        let layers = layer_names(&self.contents);
         for d in std::mem::take(&mut self.contents) {
-            self.stack_add_subelement(d);
+            self.stack_add_subelement(d)?;
+        }
+
+        if let Some(toggles) = self.config.layer_toggles {
+            if !layers.is_empty() {
+                for e in layer_controls(&layers, toggles, &self.bbox, &self.config)? {
+                    self.stack_add_subelement(e)?;
+                }
+            }
         }
 
         if self.config.show_grid {
-            let mut e = SvgElement::new_grid(self.bbox, 10., 0.1, "grey");
-            let _ = e.finalize(&self.config);
-            self.stack_add_subelement(e);
+            let mut e =
+                SvgElement::new_grid_of_mode(self.bbox, self.config.grid_mode, 10., 0.1, "grey");
+            e.add_attribute("class", None, "grid");
+            e.finalize(&self.config)?;
+            self.stack_add_subelement(e)?;
+        }
+
+        if let Some(ruler) = &self.config.ruler {
+            let mut e = ruler.element(&self.bbox, &self.config.label_format);
+            e.finalize(&self.config)?;
+            self.stack_add_subelement(e)?;
         }
 
+        if let Some(mut e) = self
+            .attribution
+            .as_ref()
+            .and_then(|a| a.credit_element(&self.bbox))
+        {
+            e.finalize(&self.config)?;
+            self.stack_add_subelement(e)?;
+        }
+
+        if let Some(budget) = &self.config.size_budget {
+            self.degradations = budget.enforce(&mut self.stack[0], &self.config)?;
+        }
+
+        self.dangling = dangling_references(&self.stack[0]);
+        if self.config.strict_references && !self.dangling.is_empty() {
+            return Err(SvgError::DanglingReferences(self.dangling.clone()));
+        }
         Ok(())
     }
 
+    //mp to_debug_json
+    /// Dump the element tree as structured JSON - element names,
+    /// attributes, transforms, bboxes and characters - for debugging
+    /// and for assertions in tests
+    ///
+    /// The JSON is an object with the 'bbox' of the document and its
+    /// 'elements': the root 'svg' element once the diagram has been
+    /// generated, and otherwise the contents added so far
+    pub fn to_debug_json(&self) -> String {
+        let elements = {
+            if self.stack.is_empty() {
+                self.contents.as_slice()
+            } else {
+                &self.stack[0..1]
+            }
+        };
+        let mut r = format!(
+            "{{\n  \"bbox\": {},\n  \"elements\": [",
+            bbox_json(&self.bbox)
+        );
+        for (i, e) in elements.iter().enumerate() {
+            r.push_str(if i == 0 { "\n    " } else { ",\n    " });
+            element_json(e, &mut r, 2);
+        }
+        r.push_str("\n  ]\n}\n");
+        r
+    }
+
+    //ap applied_degradations
+    /// Get the degradations that were applied to the generated
+    /// document to meet the size budget of the configuration
+    pub fn applied_degradations(&self) -> &[Degradation] {
+        &self.degradations
+    }
+
+    //ap dangling_references
+    /// Get the ids referenced by the generated document (through
+    /// 'url(#id)' values and '#id' hrefs, such as those of markers)
+    /// that are not the id of any element in it
+    pub fn dangling_references(&self) -> &[String] {
+        &self.dangling
+    }
+
+    //ap bbox
+    /// Get the bounding box of the contents, once finalized
+    pub fn bbox(&self) -> BBox {
+        self.bbox
+    }
+
+    //ap root_element
+    /// Get the root 'svg' element, once the diagram has been generated
+    pub fn root_element(&self) -> Option<&SvgElement<'a>> {
+        self.stack.first()
+    }
+
+    //ap element_by_id
+    /// Find the element with an id in the definitions, contents or
+    /// stack - before or after the diagram has been generated
+    ///
+    /// This permits late passes (such as recoloring an element, or
+    /// attaching an animation to it) to target an element by id
+    pub fn element_by_id(&self, id: &str) -> Option<&SvgElement<'a>> {
+        self.definitions
+            .iter()
+            .chain(self.contents.iter())
+            .chain(self.stack.iter())
+            .find_map(|e| e.find_by_id(id))
+    }
+
+    //ap element_by_id_mut
+    /// Find the element with an id, mutably, as [Svg::element_by_id]
+    /// does
+    ///
+    /// If the diagram has been generated then any change is to the
+    /// generated element, and is written out; changes to the geometry
+    /// of a finalized element are not reflected in its attributes
+    pub fn element_by_id_mut(&mut self, id: &str) -> Option<&mut SvgElement<'a>> {
+        self.definitions
+            .iter_mut()
+            .chain(self.contents.iter_mut())
+            .chain(self.stack.iter_mut())
+            .find_map(|e| e.find_by_id_mut(id))
+    }
+
+    //mp audit_accessibility
+    /// Audit the document for accessibility issues, using the default
+    /// audit settings
+    pub fn audit_accessibility(&self) -> Vec<AccessibilityIssue> {
+        self.audit_accessibility_with(&AccessibilityAudit::default())
+    }
+
+    //mp audit_accessibility_with
+    /// Audit the document for accessibility issues
+    ///
+    /// This may be used before or after the diagram is generated
+    pub fn audit_accessibility_with(&self, audit: &AccessibilityAudit) -> Vec<AccessibilityIssue> {
+        match self.root_element() {
+            Some(root) => audit.audit(root.contents()),
+            None => audit.audit(&self.contents),
+        }
+    }
+
     //mp iter_events
     /// Iterate over all the XML events the Svg would generate if it
     /// were an SVG file being read in by xml-rs
@@ -280,5 +981,367 @@ impl<'a> Svg<'a> {
         ElementIter::new(&self.stack[0])
     }
 
+    //mp apply_compat_profile
+    /// Restrict the generated document to static SVG 1.1 features,
+    /// for email clients and very old renderers, returning a report
+    /// of the substitutions made
+    ///
+    /// CSS class rules and inline styles become presentation
+    /// attributes; 'rgba()' colors become an opaque color and an
+    /// opacity attribute; 'use' elements are replaced by copies of the
+    /// elements they reference; and styles, scripts, filters, blend
+    /// modes, animations and foreign objects are removed
+    pub fn apply_compat_profile(&mut self) -> Result<Vec<CompatChange>, SvgError> {
+        let root = self.stack.first_mut().ok_or(SvgError::NotGenerated)?;
+        self.version = SvgVersion::Version1_1;
+        Ok(apply_compat(root, &self.config.number_format))
+    }
+
+    //mp content_hash
+    /// Get a stable hash of the generated document, for use as a cache
+    /// key or (formatted as hex) an HTTP ETag without serializing it
+    ///
+    /// The hash covers the canonicalized document - element names,
+    /// attributes in order of name, and characters - and not the
+    /// whitespace or XML prolog of any particular output format
+    pub fn content_hash(&self) -> Result<u64, SvgError> {
+        if self.stack.is_empty() {
+            return Err(SvgError::NotGenerated);
+        }
+        Ok(content_hash(self.iter_events()))
+    }
+
+    //mp pack
+    /// Pack the external files referenced by the generated document -
+    /// the hrefs of images, and the 'url()'s of styles (such as font
+    /// files) - either embedding them as base64 or copying them into
+    /// an output directory, rewriting the references to suit
+    ///
+    /// References that are relative paths are taken relative to
+    /// `base`; fragments, 'data:' URLs and absolute URLs are left
+    /// unchanged. Returns the number of references rewritten.
+    pub fn pack(&mut self, base: &std::path::Path, mode: &PackMode) -> Result<usize, SvgError> {
+        let root = self.stack.first_mut().ok_or(SvgError::NotGenerated)?;
+        Packer::new(base, mode).pack(root)
+    }
+
+    //mp write_svgz
+    /// Write the generated document gzip-compressed, as for a '.svgz'
+    /// file; this is much smaller for plots with many path points
+    #[cfg(feature = "flate2")]
+    pub fn write_svgz<W: std::io::Write>(&self, writer: W) -> Result<(), SvgError> {
+        let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+        self.write_svg(&mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    //mp write_subtree
+    /// Write the element with an id, or the layer with a name, of the
+    /// generated document as a complete standalone document
+    ///
+    /// The document has the root attributes of this document, a
+    /// viewBox for the bounds of the subtree, and the definitions
+    /// (such as gradients and patterns) that the subtree references;
+    /// this permits individual panels of a larger composition to be
+    /// served separately
+    pub fn write_subtree<W: std::io::Write>(
+        &self,
+        id_or_layer: &str,
+        mut writer: W,
+    ) -> Result<(), SvgError> {
+        let root = self.stack.first().ok_or(SvgError::NotGenerated)?;
+        let doc = subtree_document(root, id_or_layer, &self.config)
+            .ok_or_else(|| SvgError::UnknownSubtree(id_or_layer.into()))?;
+        write_events(
+            ElementIter::new(&doc),
+            &mut writer,
+            &self.config.written_prolog(),
+            self.config.indent,
+            self.config.newline,
+        )?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    //mp separation_inks
+    /// Get the inks (fill and stroke colors, as '#rrggbb', and other
+    /// paints such as gradients) of the drawn elements of the generated
+    /// document, in order of first use
+    ///
+    /// These are the colors for which separations can be written, as
+    /// for screen-printing or multi-pen plotting
+    pub fn separation_inks(&self) -> Result<Vec<String>, SvgError> {
+        let root = self.stack.first().ok_or(SvgError::NotGenerated)?;
+        Ok(separation_inks(root))
+    }
+
+    //mp write_separation
+    /// Write the parts of the generated document drawn in a color as a
+    /// complete standalone document, preserving their geometry and
+    /// order
+    ///
+    /// Elements filled in the color keep their fill, and those stroked
+    /// in the color keep their stroke; the other paint of such an
+    /// element is removed. The root attributes and definitions of this
+    /// document are kept, so the separations overlay each other.
+    ///
+    /// This fails if the color is not one of the
+    /// [Svg::separation_inks] of the document
+    pub fn write_separation<W: std::io::Write>(
+        &self,
+        color: &str,
+        mut writer: W,
+    ) -> Result<(), SvgError> {
+        let root = self.stack.first().ok_or(SvgError::NotGenerated)?;
+        let color_ink = ink(color)
+            .filter(|i| separation_inks(root).contains(i))
+            .ok_or_else(|| SvgError::UnknownInk(color.into()))?;
+        let doc = separation_document(root, &color_ink);
+        write_events(
+            ElementIter::new(&doc),
+            &mut writer,
+            &self.config.written_prolog(),
+            self.config.indent,
+            self.config.newline,
+        )?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    //mp separate_into_layers
+    /// Split the generated document into a named layer per ink (see
+    /// [crate::SvgLayer]), named 'ink-rrggbb' for colors, in order of first
+    /// use; each layer has the parts of the document drawn in that
+    /// ink, so that they may be toggled or plotted separately
+    ///
+    /// Returns the inks of the layers
+    pub fn separate_into_layers(&mut self) -> Result<Vec<String>, SvgError> {
+        let root = self.stack.first_mut().ok_or(SvgError::NotGenerated)?;
+        Ok(separate_into_layers(root))
+    }
+
+    //mp write_xml_rs
+    /// Write the generated document through an xml-rs event writer,
+    /// whose configuration (such as indentation) is up to the caller
+    #[cfg(feature = "xml-rs")]
+    pub fn write_xml_rs<W: std::io::Write>(
+        &self,
+        writer: &mut ::xml::writer::EventWriter<W>,
+    ) -> Result<(), SvgError> {
+        if self.stack.is_empty() {
+            return Err(SvgError::NotGenerated);
+        }
+        crate::xml::write_events(self.iter_events(), writer, &self.config.written_prolog())
+    }
+
+    //mp write_quick_xml
+    /// Write the generated document through a quick-xml writer, whose
+    /// configuration (such as indentation) is up to the caller
+    #[cfg(feature = "quick-xml")]
+    pub fn write_quick_xml<W: std::io::Write>(
+        &self,
+        writer: &mut ::quick_xml::Writer<W>,
+    ) -> Result<(), SvgError> {
+        if self.stack.is_empty() {
+            return Err(SvgError::NotGenerated);
+        }
+        crate::quick_xml::write_events(self.iter_events(), writer, &self.config.written_prolog())
+    }
+
+    //mp write_svg
+    /// Write the generated document as an SVG file to a writer, with
+    /// attribute values and character data escaped
+    pub fn write_svg<W: std::io::Write>(&self, mut writer: W) -> Result<(), SvgError> {
+        if self.stack.is_empty() {
+            return Err(SvgError::NotGenerated);
+        }
+        write_events(
+            self.iter_events(),
+            &mut writer,
+            &self.config.written_prolog(),
+            self.config.indent,
+            self.config.newline,
+        )?;
+        writer.flush()?;
+        Ok(())
+    }
+
     //zz All done
 }
+
+//a Test
+#[cfg(test)]
+mod test_svg {
+    use super::*;
+    use crate::{BBox, SvgGroup, SvgPath, SvgText};
+    #[test]
+    fn test_number_format() {
+        // Documents with different precisions may be built interleaved
+        let document = |cfg: SvgConfig| {
+            let mut svg = Svg::new(cfg);
+            svg.contents_add_element(SvgPath::new_box(BBox::new(0., 0., 10., 5.)));
+            let mut g = SvgGroup::with_opacity(0.25);
+            g.set_transform(Transform::of_translation([1.25, 2.].into()));
+            let mut p = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+            p.add_size("stroke-width", 0.125);
+            p.animate_draw(1.5);
+            g.push_content(p);
+            svg.contents_add_element(g);
+            svg
+        };
+        let mut coarse = document(SvgConfig::default().set_decimal_places(Some(1), true));
+        let mut fine = document(SvgConfig::default());
+        coarse.finalize().unwrap();
+        fine.finalize().unwrap();
+        fine.generate_diagram().unwrap();
+        coarse.generate_diagram().unwrap();
+        let xml = |svg: &Svg| {
+            let mut buf = vec![];
+            svg.write_svg(&mut buf).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+        let (coarse, fine) = (xml(&coarse), xml(&fine));
+        assert!(coarse.contains(r#"viewBox="0 0 10 5""#));
+        assert!(coarse.contains(r#"d="M 10,5 L 10,0"#));
+        assert!(fine.contains(r#"viewBox="0.0000 0.0000 10.0000 5.0000""#));
+        assert!(fine.contains(r#"d="M 10.0000,5.0000 L 10.0000,0.0000"#));
+        // Sizes, durations and transforms follow the configuration too
+        assert!(coarse.contains(r#"opacity="0.2" transform="translate(1.2 2)"#));
+        assert!(coarse.contains(r#"stroke-width="0.1""#));
+        assert!(coarse.contains(r#"stroke-dasharray="4""#));
+        assert!(coarse.contains(r#"dur="1.5s""#));
+        assert!(fine.contains(r#"opacity="0.2500" transform="translate(1.2500 2.0000)"#));
+        assert!(fine.contains(r#"stroke-width="0.1250""#));
+        assert!(fine.contains(r#"dur="1.5000s""#));
+    }
+    #[test]
+    fn test_generate_stack() {
+        let mut svg = Svg::new(SvgConfig::default());
+        svg.contents_add_element(SvgPath::new_box(BBox::new(0., 0., 10., 5.)));
+        svg.finalize().unwrap();
+        svg.stack_push(SvgGroup::new());
+        assert!(matches!(
+            svg.generate_diagram(),
+            Err(SvgError::StackInUse { depth: 1 })
+        ));
+        svg.stack_pop_element();
+        svg.generate_diagram().unwrap();
+        assert_eq!(svg.stack_depth(), 1);
+        // A second generation does not add a second document
+        assert!(matches!(
+            svg.generate_diagram(),
+            Err(SvgError::StackInUse { depth: 1 })
+        ));
+        assert_eq!(svg.stack_depth(), 1);
+    }
+    #[test]
+    fn test_write_separation() {
+        let mut svg = Svg::new(SvgConfig::default());
+        for (color, x) in [("red", 0.), ("blue", 20.)] {
+            let mut p = SvgPath::new_box(BBox::new(x, 0., x + 10., 5.));
+            p.add_attribute("fill", None, color);
+            svg.contents_add_element(p);
+        }
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        assert_eq!(svg.separation_inks().unwrap(), vec!["#ff0000", "#0000ff"]);
+        let mut buf = vec![];
+        svg.write_separation("#f00", &mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.contains(r#"fill="red""#));
+        assert!(!xml.contains(r#"fill="blue""#));
+        for color in ["green", "none", "redd"] {
+            assert!(matches!(
+                svg.write_separation(color, &mut vec![]),
+                Err(SvgError::UnknownInk(_))
+            ));
+        }
+    }
+    #[test]
+    fn test_presentation_mode() {
+        let mut svg =
+            Svg::new(SvgConfig::default().set_presentation_mode(PresentationMode::StyleAttribute));
+        for (color, x) in [("red", 0.), ("#fefefe", 20.)] {
+            let mut p = SvgPath::new_box(BBox::new(x, 0., x + 10., 5.));
+            p.add_attribute("fill", None, color);
+            svg.contents_add_element(p);
+        }
+        let mut t = SvgText::new([0., 10.].into(), "faint");
+        t.add_attribute("fill", None, "#fefefe");
+        svg.contents_add_element(t);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        // The passes over the generated document see the colors
+        assert_eq!(svg.separation_inks().unwrap(), vec!["#ff0000", "#fefefe"]);
+        assert!(svg
+            .audit_accessibility()
+            .iter()
+            .any(|i| matches!(i, crate::AccessibilityIssue::LowContrast { .. })));
+        let mut buf = vec![];
+        svg.write_separation("red", &mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.contains(r#"style="fill:red""#));
+        assert!(!xml.contains(r#"fill="red""#));
+        assert!(!xml.contains("fefefe"));
+    }
+    #[test]
+    fn test_rollback() {
+        let mut svg = Svg::new(SvgConfig::default());
+        svg.stack_push(SvgGroup::new());
+        svg.stack_add_subelement(SvgGroup::new()).unwrap();
+        let sp = svg.stack_savepoint();
+        svg.stack_add_subelement(SvgGroup::new()).unwrap();
+        svg.stack_push(SvgGroup::new());
+        svg.stack_push(SvgGroup::new());
+        svg.rollback(sp).unwrap();
+        assert_eq!(svg.stack_depth(), 1);
+        assert_eq!(svg.stack_top_mut().unwrap().contents().len(), 1);
+        // A savepoint can be rolled back to more than once
+        svg.stack_push(SvgGroup::new());
+        svg.rollback(sp).unwrap();
+        assert_eq!(svg.stack_depth(), 1);
+    }
+    #[test]
+    fn test_rollback_invalid() {
+        let mut svg = Svg::new(SvgConfig::default());
+        svg.stack_push(SvgGroup::new());
+        svg.stack_push(SvgGroup::new());
+        svg.stack_add_subelement(SvgGroup::new()).unwrap();
+        let sp = svg.stack_savepoint();
+
+        // Popping below the savepoint and pushing back to the same
+        // depth invalidates it, and the failed rollback changes nothing
+        svg.stack_pop_to_child().unwrap();
+        svg.stack_push(SvgGroup::new());
+        svg.stack_add_subelement(SvgGroup::new()).unwrap();
+        svg.stack_add_subelement(SvgGroup::new()).unwrap();
+        assert!(matches!(svg.rollback(sp), Err(SvgError::InvalidSavepoint)));
+        assert_eq!(svg.stack_depth(), 2);
+        assert_eq!(svg.stack_top_mut().unwrap().contents().len(), 2);
+
+        // Removing contents of the top element invalidates it
+        let sp = svg.stack_savepoint();
+        svg.stack_top_mut().unwrap().truncate_contents(1);
+        assert!(matches!(svg.rollback(sp), Err(SvgError::InvalidSavepoint)));
+        assert_eq!(svg.stack_top_mut().unwrap().contents().len(), 1);
+
+        // Popping the top element and pushing it back invalidates it
+        let sp = svg.stack_savepoint();
+        let e = svg.stack_pop().unwrap();
+        svg.stack_push(e);
+        assert_eq!(svg.stack_depth(), 2);
+        assert!(matches!(svg.rollback(sp), Err(SvgError::InvalidSavepoint)));
+
+        // As does rolling back below it and pushing back to its depth
+        let sp_low = svg.stack_savepoint();
+        svg.stack_push(SvgGroup::new());
+        let sp = svg.stack_savepoint();
+        svg.rollback(sp_low).unwrap();
+        svg.stack_push(SvgGroup::new());
+        assert_eq!(svg.stack_depth(), 3);
+        assert!(matches!(svg.rollback(sp), Err(SvgError::InvalidSavepoint)));
+        svg.rollback(sp_low).unwrap();
+        assert_eq!(svg.stack_depth(), 2);
+    }
+}
@@ -0,0 +1,315 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_assets.rs
+@brief   Images and fonts in external files, and packing of them
+ */
+
+//a Imports
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    BBox, ErrorContext, NamespaceName, NumberFormat, SvgElement, SvgElementType, SvgError,
+};
+
+//a SvgImage
+//tp SvgImage
+/// An [SvgImage] is an 'image' element filling a region with an
+/// image given by an href
+///
+/// The href may be a relative path to a file during authoring;
+/// [Svg::pack](crate::Svg::pack) then embeds or copies the file for
+/// delivery
+#[derive(Debug)]
+pub struct SvgImage {
+    region: BBox,
+    href: String,
+}
+
+//ip SvgImage
+impl SvgImage {
+    //fp new
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>(region: BBox, href: &str) -> SvgElement<'a> {
+        SvgElement::new(Self {
+            region,
+            href: href.into(),
+        })
+    }
+}
+
+//ip SvgElementType for SvgImage
+impl<'a> SvgElementType<'a> for SvgImage {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::local("image")
+    }
+    fn bbox(&self) -> BBox {
+        self.region
+    }
+    fn push_attributes(&self, nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
+        let (x, y, w, h) = self.region.get_bounds();
+        attrs.push((NamespaceName::local("x"), nf.number(x)));
+        attrs.push((NamespaceName::local("y"), nf.number(y)));
+        attrs.push((NamespaceName::local("width"), nf.number(w)));
+        attrs.push((NamespaceName::local("height"), nf.number(h)));
+        attrs.push((NamespaceName::local("href"), self.href.clone()));
+    }
+}
+
+//a PackMode
+//tp PackMode
+/// The manner in which [Svg::pack](crate::Svg::pack) delivers the
+/// external files referenced by a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackMode {
+    /// Embed the files as base64 'data:' URLs, for a single-file
+    /// deliverable
+    Embed,
+    /// Copy the files into a directory - that in which the document
+    /// will be written - and rewrite the references to be relative to
+    /// it
+    Copy(PathBuf),
+}
+
+//a Functions
+//fi is_external
+/// Return true if a reference is to an external file (rather than a
+/// fragment, 'data:' URL, or absolute URL)
+fn is_external(href: &str) -> bool {
+    !(href.is_empty() || href.starts_with('#') || href.starts_with("data:") || href.contains("://"))
+}
+
+//fi mime_type
+fn mime_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
+
+//fi base64
+/// Encode bytes as (standard, padded) base64
+fn base64(data: &[u8]) -> String {
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut r = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = ((b[0] as usize) << 16) | ((b[1] as usize) << 8) | (b[2] as usize);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                r.push(CHARS[(n >> (18 - 6 * i)) & 63] as char);
+            } else {
+                r.push('=');
+            }
+        }
+    }
+    r
+}
+
+//a Packer
+//tp Packer
+/// Packs the external files referenced by a document
+pub(crate) struct Packer<'p> {
+    /// Directory that relative references are relative to
+    base: &'p Path,
+    /// How the files are delivered
+    mode: &'p PackMode,
+    /// Map from original reference to packed reference
+    packed: HashMap<String, String>,
+}
+
+//ip Packer
+impl<'p> Packer<'p> {
+    //fp new
+    pub(crate) fn new(base: &'p Path, mode: &'p PackMode) -> Self {
+        Self {
+            base,
+            mode,
+            packed: HashMap::new(),
+        }
+    }
+
+    //mi pack_href
+    /// Get the packed reference for an external reference, embedding
+    /// or copying its file the first time it is seen
+    fn pack_href(&mut self, href: &str) -> Result<String, SvgError> {
+        if let Some(p) = self.packed.get(href) {
+            return Ok(p.clone());
+        }
+        let path = self.base.join(href);
+        let packed = match self.mode {
+            PackMode::Embed => {
+                let data =
+                    std::fs::read(&path).with_context(|| format!("embedding asset '{}'", href))?;
+                format!("data:{};base64,{}", mime_type(&path), base64(&data))
+            }
+            PackMode::Copy(dir) => {
+                let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("asset");
+                let ext = path.extension().and_then(|s| s.to_str());
+                let mut n = 0;
+                let name = loop {
+                    let mut name = stem.to_string();
+                    if n > 0 {
+                        name.push_str(&format!("-{}", n));
+                    }
+                    if let Some(ext) = ext {
+                        name.push('.');
+                        name.push_str(ext);
+                    }
+                    if !self.packed.values().any(|p| *p == name) {
+                        break name;
+                    }
+                    n += 1;
+                };
+                std::fs::create_dir_all(dir)?;
+                std::fs::copy(&path, dir.join(&name))
+                    .with_context(|| format!("copying asset '{}'", href))?;
+                name
+            }
+        };
+        self.packed.insert(href.to_string(), packed.clone());
+        Ok(packed)
+    }
+
+    //mi pack_css
+    /// Pack the 'url(...)' references (such as those of '@font-face'
+    /// rules) of CSS
+    fn pack_css(&mut self, css: &str) -> Result<Option<String>, SvgError> {
+        let mut r = String::new();
+        let mut rest = css;
+        let mut changed = false;
+        while let Some(n) = rest.find("url(") {
+            r.push_str(&rest[..n + 4]);
+            rest = &rest[n + 4..];
+            let Some(end) = rest.find(')') else {
+                break;
+            };
+            let url = rest[..end].trim().trim_matches(|c| c == '"' || c == '\'');
+            if is_external(url) {
+                r.push_str(&format!("\"{}\"", self.pack_href(url)?));
+                changed = true;
+            } else {
+                r.push_str(&rest[..end]);
+            }
+            rest = &rest[end..];
+        }
+        r.push_str(rest);
+        Ok(changed.then_some(r))
+    }
+
+    //mp pack
+    /// Pack the external references of an element and its contents,
+    /// returning the number of references rewritten
+    pub(crate) fn pack(&mut self, e: &mut SvgElement) -> Result<usize, SvgError> {
+        let mut count = 0;
+        for name in ["href", "xlink:href"] {
+            let Some(href) = e.get_attribute(name).filter(|h| is_external(h)) else {
+                continue;
+            };
+            let packed = self.pack_href(href)?;
+            e.remove_attribute(name);
+            e.set_local_attribute("href", &packed);
+            count += 1;
+        }
+        if e.ns_name().to_string() == "style" {
+            if let Some(css) = self.pack_css(e.characters())? {
+                e.set_characters(&css);
+                count += 1;
+            }
+        }
+        for c in e.contents_mut() {
+            count += self.pack(c)?;
+        }
+        Ok(count)
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_assets {
+    use super::*;
+    use crate::{Svg, SvgConfig, SvgTag};
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(base64(b""), "");
+    }
+    #[test]
+    fn test_pack() {
+        let dir = std::env::temp_dir().join(format!("svg-nd-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("logo.png"), b"Man").unwrap();
+        std::fs::write(dir.join("font.woff2"), b"Ma").unwrap();
+
+        let build = || {
+            let mut svg = Svg::new(SvgConfig::default());
+            let mut style = SvgTag::new("style", None);
+            style.push_string("@font-face { font-family: F; src: url('font.woff2'); }");
+            svg.contents_add_element(style);
+            svg.contents_add_element(SvgImage::new(BBox::new(0., 0., 10., 10.), "logo.png"));
+            svg.contents_add_element(SvgImage::new(BBox::new(0., 0., 10., 10.), "#logo"));
+            svg.finalize().unwrap();
+            svg.generate_diagram().unwrap();
+            svg
+        };
+        let mut svg = build();
+        assert_eq!(svg.pack(&dir, &PackMode::Embed).unwrap(), 2);
+        let root = svg.root_element().unwrap();
+        assert!(root.contents()[0]
+            .characters()
+            .contains("url(\"data:font/woff2;base64,TWE=\")"));
+        assert_eq!(
+            root.contents()[1].get_attribute("href"),
+            Some("data:image/png;base64,TWFu")
+        );
+        assert_eq!(root.contents()[2].get_attribute("href"), Some("#logo"));
+
+        let out = dir.join("out");
+        let mut svg = build();
+        svg.pack(&dir, &PackMode::Copy(out.clone())).unwrap();
+        let root = svg.root_element().unwrap();
+        assert_eq!(root.contents()[1].get_attribute("href"), Some("logo.png"));
+        assert_eq!(std::fs::read(out.join("logo.png")).unwrap(), b"Man");
+
+        std::fs::remove_file(dir.join("logo.png")).unwrap();
+        let mut svg = build();
+        let e = svg.pack(&dir, &PackMode::Embed).unwrap_err();
+        assert_eq!(e.to_string(), "embedding asset 'logo.png'");
+        let SvgError::Construction(e) = e else {
+            panic!("expected a construction error, got {:?}", e);
+        };
+        assert!(matches!(e.root_cause(), crate::Error::Io(_)));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,219 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_attribution.rs
+@brief   License and attribution metadata for SVG output
+ */
+
+//a Imports
+use crate::{BBox, SvgElement, SvgTag, SvgText};
+
+//a Corner
+//tp Corner
+/// A corner of a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+//a Attribution
+//tp Attribution
+/// License and attribution information for a document
+///
+/// This is emitted as RDF (using Dublin Core and Creative Commons
+/// terms) in a `<metadata>` element, and optionally as a visible
+/// credit line in a corner of the document
+#[derive(Debug, Clone, Default)]
+pub struct Attribution {
+    /// SPDX license identifier, e.g. 'CC-BY-4.0'
+    pub spdx: Option<String>,
+    /// Author of the document
+    pub author: Option<String>,
+    /// URL of the source of the document
+    pub source_url: Option<String>,
+    /// Corner and font size of a visible credit line, if required
+    pub credit: Option<(Corner, f64)>,
+}
+
+//ip Attribution
+impl Attribution {
+    //fp new
+    /// Create a new, empty, attribution
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //bp set_spdx
+    pub fn set_spdx(mut self, spdx: &str) -> Self {
+        self.spdx = Some(spdx.into());
+        self
+    }
+
+    //bp set_author
+    pub fn set_author(mut self, author: &str) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    //bp set_source_url
+    pub fn set_source_url(mut self, source_url: &str) -> Self {
+        self.source_url = Some(source_url.into());
+        self
+    }
+
+    //bp set_credit
+    /// Request a visible credit line in a corner of the document
+    pub fn set_credit(mut self, corner: Corner, font_size: f64) -> Self {
+        self.credit = Some((corner, font_size));
+        self
+    }
+
+    //mp credit_text
+    /// Get the text for a credit line
+    pub fn credit_text(&self) -> String {
+        let mut parts = vec![];
+        if let Some(author) = &self.author {
+            parts.push(format!("\u{a9} {}", author));
+        }
+        if let Some(spdx) = &self.spdx {
+            parts.push(format!("License: {}", spdx));
+        }
+        if let Some(source_url) = &self.source_url {
+            parts.push(source_url.clone());
+        }
+        parts.join(", ")
+    }
+
+    //mp metadata_element
+    /// Create a `<metadata>` element containing the attribution
+    pub fn metadata_element<'a>(&self) -> SvgElement<'a> {
+        let mut work = SvgTag::new("Work", Some("cc"));
+        work.add_attribute("about", Some("rdf"), "");
+        if let Some(author) = &self.author {
+            let mut e = SvgTag::new("creator", Some("dc"));
+            e.push_string(author);
+            work.push_content(e);
+        }
+        if let Some(source_url) = &self.source_url {
+            let mut e = SvgTag::new("source", Some("dc"));
+            e.push_string(source_url);
+            work.push_content(e);
+        }
+        if let Some(spdx) = &self.spdx {
+            let mut e = SvgTag::new("license", Some("cc"));
+            e.add_attribute(
+                "resource",
+                Some("rdf"),
+                &format!("https://spdx.org/licenses/{}.html", spdx),
+            );
+            work.push_content(e);
+        }
+        let mut rdf = SvgTag::new("RDF", Some("rdf"));
+        rdf.add_attribute(
+            "rdf",
+            Some("xmlns"),
+            "http://www.w3.org/1999/02/22-rdf-syntax-ns#",
+        );
+        rdf.add_attribute("dc", Some("xmlns"), "http://purl.org/dc/elements/1.1/");
+        rdf.add_attribute("cc", Some("xmlns"), "http://creativecommons.org/ns#");
+        rdf.push_content(work);
+        let mut metadata = SvgTag::new("metadata", None);
+        metadata.push_content(rdf);
+        metadata
+    }
+
+    //mp credit_element
+    /// Create the visible credit line element, if required, placed in
+    /// a corner of the bounding box
+    pub fn credit_element<'a>(&self, bbox: &BBox) -> Option<SvgElement<'a>> {
+        let (corner, font_size) = self.credit?;
+        let margin = font_size / 2.;
+        let (x, anchor) = match corner {
+            Corner::TopLeft | Corner::BottomLeft => (bbox.x[0] + margin, "start"),
+            _ => (bbox.x[1] - margin, "end"),
+        };
+        let y = match corner {
+            Corner::TopLeft | Corner::TopRight => bbox.y[0] + margin + font_size,
+            _ => bbox.y[1] - margin,
+        };
+        let mut e = SvgText::new([x, y].into(), &self.credit_text());
+        e.add_size("font-size", font_size);
+        e.add_attribute("text-anchor", None, anchor);
+        Some(e)
+    }
+
+    //zz All done
+}
+
+//a Test
+#[cfg(test)]
+mod test_attribution {
+    use super::*;
+    use crate::{Svg, SvgConfig, SvgPath};
+    fn generate(attribution: Attribution) -> String {
+        let mut svg = Svg::new(SvgConfig::default());
+        svg.contents_add_element(SvgPath::new_box(BBox::new(0., 0., 100., 50.)));
+        svg.set_attribution(attribution);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+    #[test]
+    fn test_metadata() {
+        let svg = generate(
+            Attribution::new()
+                .set_spdx("CC-BY-4.0")
+                .set_author("A. Person")
+                .set_source_url("https://example.com/diagram"),
+        );
+        assert!(svg.contains("<metadata>"), "{svg}");
+        assert!(svg.contains(r#"xmlns:dc="http://purl.org/dc/elements/1.1/""#));
+        assert!(svg.contains("<dc:creator>A. Person</dc:creator>"), "{svg}");
+        assert!(svg.contains("<dc:source>https://example.com/diagram</dc:source>"));
+        assert!(svg
+            .contains(r#"<cc:license rdf:resource="https://spdx.org/licenses/CC-BY-4.0.html"/>"#));
+        // No credit line was requested
+        assert!(!svg.contains("<text"));
+    }
+    #[test]
+    fn test_credit() {
+        let attribution = Attribution::new()
+            .set_author("A. Person")
+            .set_spdx("MIT")
+            .set_credit(Corner::BottomRight, 2.);
+        assert_eq!(attribution.credit_text(), "\u{a9} A. Person, License: MIT");
+        let e = attribution
+            .credit_element(&BBox::new(0., 0., 100., 50.))
+            .unwrap();
+        assert_eq!(e.get_attribute("text-anchor"), Some("end"));
+
+        let svg = generate(attribution);
+        let text = &svg[svg.find("<text").unwrap()..];
+        assert!(text.contains(r#"text-anchor="end""#), "{text}");
+        assert!(
+            text.contains("\u{a9} A. Person, License: MIT</text>"),
+            "{text}"
+        );
+        // The credit is drawn over the contents, in the corner
+        assert!(svg.find("<path").unwrap() < svg.find("<text").unwrap());
+        assert!(Attribution::new()
+            .credit_element(&BBox::new(0., 0., 1., 1.))
+            .is_none());
+    }
+}
@@ -0,0 +1,350 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_audit.rs
+@brief   Accessibility audit of SVG output
+ */
+
+//a Imports
+use crate::{Color, Point, SvgColorDatabase, SvgElement};
+
+//a AccessibilityIssue
+//tp AccessibilityIssue
+/// An issue found by an accessibility audit
+///
+/// Elements are identified by their path - the indices of the
+/// element and its ancestors within the contents of the document
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessibilityIssue {
+    /// The document has no 'title' element
+    MissingTitle,
+    /// The document has no 'desc' element
+    MissingDesc,
+    /// A text element has too low a contrast with its background
+    LowContrast { path: Vec<usize>, ratio: f64 },
+    /// A text element has too small a font size
+    SmallFont { path: Vec<usize>, size: f64 },
+    /// Two sibling elements (such as two series in a chart) are
+    /// distinguished only by color, and the colors have similar
+    /// luminance (i.e. they differ only in hue)
+    ///
+    /// Series are expected to be siblings (elements in the same group),
+    /// so every pair of siblings is compared, but elements in different
+    /// groups are not
+    ColorOnly { path: Vec<usize>, other: Vec<usize> },
+}
+
+//a AccessibilityAudit
+//tp AccessibilityAudit
+/// The settings for an accessibility audit
+///
+/// The background of a text element is the fill of the last shape
+/// drawn before it (in the same group, or in an enclosing group) that
+/// covers the position of the text; if there is no such shape then it
+/// is the background color of the audit
+#[derive(Debug, Clone)]
+pub struct AccessibilityAudit {
+    /// Background color of the document, on which text is drawn
+    /// unless it is over a filled shape
+    pub background: Color,
+    /// Minimum contrast ratio of text with the background
+    pub min_contrast: f64,
+    /// Minimum font size (in user units)
+    pub min_font_size: f64,
+    /// Minimum contrast ratio between two series distinguished only by color
+    pub min_series_contrast: f64,
+}
+
+//ip Default for AccessibilityAudit
+impl std::default::Default for AccessibilityAudit {
+    fn default() -> Self {
+        Self {
+            background: Color::of_rgb((255_u8, 255_u8, 255_u8)),
+            min_contrast: 4.5,
+            min_font_size: 2.5,
+            min_series_contrast: 1.5,
+        }
+    }
+}
+
+//ip AccessibilityAudit
+impl AccessibilityAudit {
+    //fi color_of
+    fn color_of(value: &str) -> Option<Color> {
+        match value {
+            "none" | "None" | "NONE" => None,
+            _ => SvgColorDatabase.find_color(value),
+        }
+    }
+
+    //fi font_size
+    /// Parse a font size, ignoring any units
+    fn font_size(value: &str) -> Option<f64> {
+        value
+            .trim()
+            .trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%')
+            .parse::<f64>()
+            .ok()
+    }
+
+    //fi location
+    /// Get a point within an element, in the coordinates of its
+    /// parent, if it is known; text has no bounding box, so its
+    /// position (or that of its first content with a position) is used
+    fn location(e: &SvgElement) -> Option<Point> {
+        let bbox = e.bbox();
+        if !bbox.is_none() {
+            return Some(bbox.center());
+        }
+        let coord = |n| e.get_attribute(n).and_then(|v| v.parse::<f64>().ok());
+        let pt = match (coord("x"), coord("y")) {
+            (Some(x), Some(y)) => [x, y].into(),
+            _ => e.contents().iter().find_map(Self::location)?,
+        };
+        Some(e.transform().apply(pt))
+    }
+
+    //fi fill_behind
+    /// Get the fill color of a shape if it is drawn behind a point
+    fn fill_behind(e: &SvgElement, pt: Point) -> Option<Color> {
+        const SHAPES: &[&str] = &["path", "rect", "circle", "ellipse", "polygon"];
+        let name = e.ns_name().to_string();
+        if !SHAPES.contains(&name.as_str()) {
+            return None;
+        }
+        let bbox = e.bbox();
+        let covers = !bbox.is_none()
+            && (bbox.x[0]..=bbox.x[1]).contains(&pt[0])
+            && (bbox.y[0]..=bbox.y[1]).contains(&pt[1]);
+        if covers {
+            e.get_attribute("fill").and_then(Self::color_of)
+        } else {
+            None
+        }
+    }
+
+    //fi series_color
+    fn series_color(e: &SvgElement) -> Option<Color> {
+        e.get_attribute("stroke")
+            .and_then(Self::color_of)
+            .or_else(|| e.get_attribute("fill").and_then(Self::color_of))
+    }
+
+    //fi same_but_color
+    /// Return true if two elements have the same non-color styling
+    fn same_but_color(a: &SvgElement, b: &SvgElement) -> bool {
+        const STYLING: &[&str] = &[
+            "stroke-width",
+            "stroke-dasharray",
+            "marker-start",
+            "marker-mid",
+            "marker-end",
+            "fill-opacity",
+            "stroke-opacity",
+        ];
+        a.ns_name().to_string() == b.ns_name().to_string()
+            && STYLING
+                .iter()
+                .all(|n| a.get_attribute(n) == b.get_attribute(n))
+    }
+
+    //mi audit_element
+    fn audit_element(
+        &self,
+        e: &SvgElement,
+        background: &Color,
+        path: &mut Vec<usize>,
+        issues: &mut Vec<AccessibilityIssue>,
+    ) {
+        if e.ns_name().to_string() == "text" {
+            let fill = e
+                .get_attribute("fill")
+                .and_then(Self::color_of)
+                .unwrap_or_else(|| Color::of_rgb((0_u8, 0_u8, 0_u8)));
+            let ratio = fill.contrast_ratio(background);
+            if ratio < self.min_contrast {
+                issues.push(AccessibilityIssue::LowContrast {
+                    path: path.clone(),
+                    ratio,
+                });
+            }
+        }
+        if let Some(size) = e.get_attribute("font-size").and_then(Self::font_size) {
+            if size < self.min_font_size {
+                issues.push(AccessibilityIssue::SmallFont {
+                    path: path.clone(),
+                    size,
+                });
+            }
+        }
+        self.audit_contents(e.contents(), background, path, issues);
+    }
+
+    //mi audit_contents
+    fn audit_contents(
+        &self,
+        contents: &[SvgElement],
+        background: &Color,
+        path: &mut Vec<usize>,
+        issues: &mut Vec<AccessibilityIssue>,
+    ) {
+        for (i, c) in contents.iter().enumerate() {
+            let background = Self::location(c)
+                .and_then(|pt| {
+                    contents[..i]
+                        .iter()
+                        .rev()
+                        .find_map(|b| Self::fill_behind(b, pt))
+                })
+                .unwrap_or_else(|| background.clone());
+            path.push(i);
+            self.audit_element(c, &background, path, issues);
+            path.pop();
+        }
+        for j in 1..contents.len() {
+            for i in 0..j {
+                let (a, b) = (&contents[i], &contents[j]);
+                if !Self::same_but_color(a, b) {
+                    continue;
+                }
+                if let (Some(ca), Some(cb)) = (Self::series_color(a), Self::series_color(b)) {
+                    if *ca.as_str() != *cb.as_str()
+                        && ca.contrast_ratio(&cb) < self.min_series_contrast
+                    {
+                        let mut other = path.clone();
+                        other.push(j);
+                        let mut path = path.clone();
+                        path.push(i);
+                        issues.push(AccessibilityIssue::ColorOnly { path, other });
+                    }
+                }
+            }
+        }
+    }
+
+    //mp audit
+    /// Audit the contents of a document
+    pub fn audit(&self, contents: &[SvgElement]) -> Vec<AccessibilityIssue> {
+        let mut issues = vec![];
+        let has = |name: &str| contents.iter().any(|c| c.ns_name().to_string() == name);
+        if !has("title") {
+            issues.push(AccessibilityIssue::MissingTitle);
+        }
+        if !has("desc") {
+            issues.push(AccessibilityIssue::MissingDesc);
+        }
+        self.audit_contents(contents, &self.background, &mut vec![], &mut issues);
+        issues
+    }
+
+    //zz All done
+}
+
+//a Test
+#[cfg(test)]
+mod test_audit {
+    use super::*;
+    use crate::{BBox, SvgConfig, SvgGroup, SvgPath, SvgTag, SvgText, Transform};
+    fn finalized(mut contents: Vec<SvgElement>) -> Vec<SvgElement> {
+        let cfg = SvgConfig::default();
+        for c in contents.iter_mut() {
+            c.finalize(&cfg).unwrap();
+        }
+        contents
+    }
+    fn text<'a>(x: f64, y: f64, fill: &str) -> SvgElement<'a> {
+        let mut t = SvgText::new([x, y].into(), "label");
+        t.add_attribute("fill", None, fill);
+        t
+    }
+    fn series<'a>(stroke: &str) -> SvgElement<'a> {
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        p.add_attribute("fill", None, "none");
+        p.add_attribute("stroke", None, stroke);
+        p
+    }
+    #[test]
+    fn test_title_desc() {
+        let audit = AccessibilityAudit::default();
+        let issues = audit.audit(&[]);
+        assert_eq!(
+            issues,
+            vec![
+                AccessibilityIssue::MissingTitle,
+                AccessibilityIssue::MissingDesc
+            ]
+        );
+        let contents = vec![SvgTag::new("title", None), SvgTag::new("desc", None)];
+        assert!(audit.audit(&contents).is_empty());
+    }
+    #[test]
+    fn test_contrast() {
+        let audit = AccessibilityAudit::default();
+        let mut contents = vec![SvgTag::new("title", None), SvgTag::new("desc", None)];
+        contents.push(text(1., 1., "black"));
+        contents.push(text(1., 1., "#eeeeee"));
+        // Text over a dark shape is checked against the shape's fill
+        let mut back = SvgPath::new_box(BBox::new(20., 0., 40., 10.));
+        back.add_attribute("fill", None, "black");
+        contents.push(back);
+        contents.push(text(30., 5., "white"));
+        contents.push(text(30., 5., "black"));
+        // ...including text within a translated group over the shape
+        let mut g = SvgGroup::new();
+        g.apply_transform(&Transform::of_translation([10., 0.].into()));
+        g.push_content(text(20., 5., "black"));
+        contents.push(g);
+        let issues = audit.audit(&finalized(contents));
+        let low: Vec<&Vec<usize>> = issues
+            .iter()
+            .filter_map(|i| match i {
+                AccessibilityIssue::LowContrast { path, .. } => Some(path),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(low, vec![&vec![3], &vec![6], &vec![7, 0]]);
+    }
+    #[test]
+    fn test_small_font() {
+        let audit = AccessibilityAudit::default();
+        let mut t = text(0., 0., "black");
+        t.add_attribute("font-size", None, "2px");
+        let mut g = SvgGroup::new();
+        g.push_content(t);
+        let issues = audit.audit(&finalized(vec![g]));
+        assert!(issues.contains(&AccessibilityIssue::SmallFont {
+            path: vec![0, 0],
+            size: 2.
+        }));
+    }
+    #[test]
+    fn test_color_only() {
+        let audit = AccessibilityAudit::default();
+        // The first and last series differ only in hue, and are not
+        // adjacent
+        let contents = vec![series("#ff0000"), series("black"), series("#00a000")];
+        let issues = audit.audit(&finalized(contents));
+        let color_only: Vec<&AccessibilityIssue> = issues
+            .iter()
+            .filter(|i| matches!(i, AccessibilityIssue::ColorOnly { .. }))
+            .collect();
+        assert_eq!(
+            color_only,
+            vec![&AccessibilityIssue::ColorOnly {
+                path: vec![0],
+                other: vec![2]
+            }]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_batch.rs
+@brief   Render many SVG documents with shared definitions
+ */
+
+//a Imports
+use super::svg_event::is_self_closing;
+use crate::{ElementIter, Length, Svg, SvgConfig, SvgElement, SvgError, SvgVersion, XmlEvent};
+
+//a Useful functions
+//fi push_element_xml
+/// Push the XML for an element and its contents onto a string
+fn push_element_xml(r: &mut String, e: &SvgElement) {
+    for event in ElementIter::new(e) {
+        match event {
+            XmlEvent::StartDocument | XmlEvent::EndDocument => (),
+            _ => r.push_str(&event.as_xml()),
+        }
+    }
+}
+
+//a SvgBatch
+//tp SvgBatch
+/// An [SvgBatch] renders many documents that share a common library
+/// of definitions (markers, styles, and so on)
+///
+/// The shared definitions are finalized once, and are then emitted
+/// in every document (when rendered as separate documents) or just
+/// once (when rendered as a single multi-page container)
+pub struct SvgBatch<'a> {
+    /// Version of SVG for a multi-page container
+    version: SvgVersion,
+    /// Configuration used to finalize the shared definitions
+    config: SvgConfig,
+    /// Shared definitions
+    definitions: Vec<SvgElement<'a>>,
+    /// True if the shared definitions have been finalized
+    finalized: bool,
+    /// Documents; these are finalized and generated
+    documents: Vec<Svg<'a>>,
+}
+
+//ip SvgBatch
+impl<'a> SvgBatch<'a> {
+    //fp new
+    /// Create a new, empty, batch
+    pub fn new(config: SvgConfig) -> Self {
+        Self {
+            version: SvgVersion::Version2_0,
+            config,
+            definitions: vec![],
+            finalized: false,
+            documents: vec![],
+        }
+    }
+
+    //cp set_version
+    /// Set the version of SVG used for a multi-page container
+    pub fn set_version<I: Into<SvgVersion>>(mut self, version: I) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    //mp add_definition
+    /// Add a definition to the library shared by all the documents
+    pub fn add_definition(&mut self, e: SvgElement<'a>) {
+        self.definitions.push(e);
+        self.finalized = false;
+    }
+
+    //mp add_document
+    /// Add a document to the batch; it must have been finalized, but
+    /// it must not have had its diagram generated
+    pub fn add_document(&mut self, mut svg: Svg<'a>) -> Result<(), SvgError> {
+        svg.generate_diagram()?;
+        self.documents.push(svg);
+        Ok(())
+    }
+
+    //mp finalize
+    /// Finalize the shared definitions
+    ///
+    /// This need only be invoked once, however many documents are
+    /// rendered
+    pub fn finalize(&mut self) -> Result<(), SvgError> {
+        if !self.finalized {
+            for d in self.definitions.iter_mut() {
+                // The extra elements (such as debug content rectangles)
+                // are in the coordinates of where an element is drawn;
+                // definitions are only drawn where referenced, so they
+                // are dropped, as Svg::finalize does for its definitions
+                d.finalize(&self.config)?;
+            }
+            self.finalized = true;
+        }
+        Ok(())
+    }
+
+    //mi definitions_xml
+    /// Get the XML for the shared definitions, which must have been
+    /// finalized
+    fn definitions_xml(&self) -> Result<String, SvgError> {
+        if !self.finalized {
+            return Err(SvgError::NotFinalized);
+        }
+        let mut r = String::new();
+        if !self.definitions.is_empty() {
+            r.push_str("<defs>");
+            for d in self.definitions.iter() {
+                push_element_xml(&mut r, d);
+            }
+            r.push_str("</defs>");
+        }
+        Ok(r)
+    }
+
+    //mp documents_as_xml
+    /// Render each of the documents as a separate SVG document, each
+    /// including the shared definitions
+    ///
+    /// This fails if the shared definitions have not been finalized
+    pub fn documents_as_xml(&self) -> Result<Vec<String>, SvgError> {
+        let defs = self.definitions_xml()?;
+        let mut result = vec![];
+        for doc in self.documents.iter() {
+            let mut r = String::new();
+            let mut defs_done = false;
+            for event in doc.iter_events() {
+                match event {
+                    XmlEvent::StartElement(e) if !defs_done => {
+                        let start = event.as_xml();
+                        if is_self_closing(e) && !defs.is_empty() {
+                            // The root is empty, so it must be opened for the definitions
+                            r.push_str(start.trim_end_matches("/>"));
+                            r.push('>');
+                            r.push_str(&defs);
+                            r.push_str(&format!("</{}>", e.ns_name()));
+                        } else {
+                            r.push_str(&start);
+                            r.push_str(&defs);
+                        }
+                        defs_done = true;
+                    }
+                    _ => r.push_str(&event.as_xml()),
+                }
+            }
+            result.push(r);
+        }
+        Ok(result)
+    }
+
+    //mp write_documents
+    /// Write each of the documents as a separate SVG file in a
+    /// directory, named `<prefix><n>.svg`
+    pub fn write_documents(&self, dir: &std::path::Path, prefix: &str) -> Result<(), SvgError> {
+        for (i, xml) in self.documents_as_xml()?.iter().enumerate() {
+            let path = dir.join(format!("{}{}.svg", prefix, i));
+            std::fs::write(path, xml)?;
+        }
+        Ok(())
+    }
+
+    //mp multipage_as_xml
+    /// Render all the documents as a single SVG document, with each
+    /// document as a page placed below the previous with a `gap`
+    /// between them, and with the shared definitions emitted once
+    ///
+    /// The width and height of the container are in the units of the
+    /// configuration of the batch; this fails if the shared
+    /// definitions have not been finalized
+    pub fn multipage_as_xml(&self, gap: f64) -> Result<String, SvgError> {
+        let definitions = self.definitions_xml()?;
+        let nf = &self.config.number_format;
+        let mut pages = String::new();
+        let mut y = 0.;
+        let mut width: f64 = 0.;
+        for doc in self.documents.iter() {
+            let (x0, y0, w, h) = doc.bbox().get_bounds();
+            pages.push_str(&format!(
+                r#"<g transform="translate({} {})">"#,
+                nf.number(-x0),
+                nf.number(y - y0)
+            ));
+            if let Some(root) = doc.root_element() {
+                for c in root.contents() {
+                    push_element_xml(&mut pages, c);
+                }
+            }
+            pages.push_str("</g>");
+            y += h + gap;
+            width = width.max(w);
+        }
+        let height = (y - gap).max(0.);
+        let version: &str = self.version.into();
+        Ok(format!(
+            r#"{}<svg xmlns="http://www.w3.org/2000/svg" version="{}" width="{}" height="{}" viewBox="0 0 {} {}">{}{}</svg>"#,
+            XmlEvent::StartDocument.as_xml(),
+            version,
+            Length::of_document(width, self.config.units).svg_string(nf),
+            Length::of_document(height, self.config.units).svg_string(nf),
+            nf.number(width),
+            nf.number(height),
+            definitions,
+            pages
+        ))
+    }
+
+    //zz All done
+}
+
+//a Test
+#[cfg(test)]
+mod test_batch {
+    use super::*;
+    use crate::svg::number_str;
+    use crate::{BBox, LengthUnit, SvgPath, SvgTag};
+    fn new_batch<'a>(config: SvgConfig) -> SvgBatch<'a> {
+        let mut batch = SvgBatch::new(config.clone());
+        let mut marker = SvgTag::new("marker", None);
+        marker.add_attribute("id", None, "arrow");
+        batch.add_definition(marker);
+        for w in [10., 20.] {
+            let mut svg = Svg::new(config.clone());
+            svg.contents_add_element(SvgPath::new_box(BBox::new(0., 0., w, 5.)));
+            svg.finalize().unwrap();
+            batch.add_document(svg).unwrap();
+        }
+        batch
+    }
+    #[test]
+    fn test_documents() {
+        let mut batch = new_batch(SvgConfig::default());
+        assert!(matches!(
+            batch.documents_as_xml(),
+            Err(SvgError::NotFinalized)
+        ));
+        batch.finalize().unwrap();
+        let docs = batch.documents_as_xml().unwrap();
+        assert_eq!(docs.len(), 2);
+        for d in docs.iter() {
+            assert_eq!(d.matches(r#"<marker id="arrow""#).count(), 1, "{d}");
+            assert!(d.find("<defs>").unwrap() < d.find("<path").unwrap());
+        }
+    }
+    #[test]
+    fn test_multipage() {
+        let mut batch = new_batch(SvgConfig::default());
+        assert!(batch.multipage_as_xml(1.).is_err());
+        batch.finalize().unwrap();
+        let xml = batch.multipage_as_xml(1.).unwrap();
+        assert_eq!(xml.matches(r#"<marker id="arrow""#).count(), 1);
+        assert_eq!(xml.matches("<g transform=").count(), 2);
+        assert!(xml.contains(&format!(
+            r#"width="{}mm" height="{}mm""#,
+            number_str(20.),
+            number_str(11.)
+        )));
+        assert!(xml.contains(&format!(
+            r#"viewBox="0 0 {} {}""#,
+            number_str(20.),
+            number_str(11.)
+        )));
+
+        // The size of the container is in the units of the configuration
+        let mut batch = new_batch(SvgConfig::default().set_units(LengthUnit::In));
+        batch.finalize().unwrap();
+        let xml = batch.multipage_as_xml(1.).unwrap();
+        assert!(xml.contains(&format!(r#"width="{}in""#, number_str(20. / 25.4))));
+    }
+}
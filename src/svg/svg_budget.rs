@@ -0,0 +1,385 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_budget.rs
+@brief   Size budgets for generated documents, with degradation
+ */
+
+//a Imports
+use std::collections::HashMap;
+
+use geo_nd::Vector;
+
+use super::svg_event::write_events;
+use crate::{ElementIter, NamespaceName, NumberFormat, Point, SvgConfig, SvgElement, SvgError};
+
+//a Degradation
+//tp Degradation
+/// A strategy for reducing the size of a generated document that
+/// exceeds its [SizeBudget]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Degradation {
+    /// Replace the path data of 'path' elements with polylines
+    /// simplified to within a tolerance
+    Simplify(f64),
+    /// Remove the elements with any of the classes (such as 'grid',
+    /// the class of the grid added by [SvgConfig::show_grid])
+    DropClasses(Vec<String>),
+    /// Place repeated elements (such as markers) that differ only in
+    /// their transform in a 'defs' once, and 'use' them
+    ShareGeometry,
+}
+
+//a SizeBudget
+//tp SizeBudget
+/// A [SizeBudget] is a limit on the number of elements and the
+/// serialized size of a generated document, for services with hard
+/// payload limits
+///
+/// If a generated document exceeds the budget then the degradations
+/// are applied, in order, until it is within the budget; if it is
+/// still not within the budget then generation fails with
+/// [SvgError::OverBudget]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SizeBudget {
+    /// Maximum number of elements, if limited
+    pub max_elements: Option<usize>,
+    /// Maximum number of bytes when written, if limited
+    pub max_bytes: Option<usize>,
+    /// Degradations to apply, in order, to meet the budget
+    pub degradations: Vec<Degradation>,
+}
+
+//ip SizeBudget
+impl SizeBudget {
+    //fp new
+    /// Create a new budget of elements and bytes
+    pub fn new(max_elements: Option<usize>, max_bytes: Option<usize>) -> Self {
+        Self {
+            max_elements,
+            max_bytes,
+            degradations: vec![],
+        }
+    }
+
+    //bp add_degradation
+    /// Add a degradation to apply if the budget is exceeded
+    pub fn add_degradation(mut self, degradation: Degradation) -> Self {
+        self.degradations.push(degradation);
+        self
+    }
+
+    //mi is_met
+    fn is_met(&self, (elements, bytes): (usize, usize)) -> bool {
+        !matches!(self.max_elements, Some(m) if elements > m)
+            && !matches!(self.max_bytes, Some(m) if bytes > m)
+    }
+
+    //mp enforce
+    /// Enforce the budget on the root element of a generated document,
+    /// returning the degradations that were applied
+    pub(crate) fn enforce(
+        &self,
+        root: &mut SvgElement,
+        svg_cfg: &SvgConfig,
+    ) -> Result<Vec<Degradation>, SvgError> {
+        let mut applied = vec![];
+        let mut size = measure(root, svg_cfg)?;
+        for d in self.degradations.iter() {
+            if self.is_met(size) {
+                break;
+            }
+            match d {
+                Degradation::Simplify(tolerance) => {
+                    simplify_paths(root, *tolerance, &svg_cfg.number_format)
+                }
+                Degradation::DropClasses(classes) => drop_classes(root, classes),
+                Degradation::ShareGeometry => share_geometry(root),
+            }
+            applied.push(d.clone());
+            size = measure(root, svg_cfg)?;
+        }
+        if self.is_met(size) {
+            Ok(applied)
+        } else {
+            Err(SvgError::OverBudget {
+                elements: size.0,
+                bytes: size.1,
+            })
+        }
+    }
+}
+
+//a Functions
+//fi count_elements
+fn count_elements(e: &SvgElement) -> usize {
+    1 + e.contents().iter().map(count_elements).sum::<usize>()
+}
+
+//fi measure
+/// Measure the number of elements and the number of bytes written for
+/// an element
+fn measure(e: &SvgElement, svg_cfg: &SvgConfig) -> Result<(usize, usize), SvgError> {
+    struct Counter(usize);
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut counter = Counter(0);
+    write_events(
+        ElementIter::new(e),
+        &mut counter,
+        &svg_cfg.written_prolog(),
+        svg_cfg.indent,
+        svg_cfg.newline,
+    )?;
+    Ok((count_elements(e), counter.0))
+}
+
+//fi simplify
+/// Simplify a polyline with the Douglas-Peucker algorithm
+fn simplify(pts: &[Point], tolerance: f64) -> Vec<Point> {
+    if pts.len() < 3 {
+        return pts.to_vec();
+    }
+    let (p0, p1) = (pts[0], pts[pts.len() - 1]);
+    let d = p1 - p0;
+    let l = d.length();
+    let distance = |p: &Point| {
+        if l < 1E-12 {
+            (*p - p0).length()
+        } else {
+            ((p[0] - p0[0]) * d[1] - (p[1] - p0[1]) * d[0]).abs() / l
+        }
+    };
+    let (n, max) = pts[1..pts.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i + 1, distance(p)))
+        .fold((0, 0.), |a, b| if b.1 > a.1 { b } else { a });
+    if max <= tolerance {
+        vec![p0, p1]
+    } else {
+        let mut r = simplify(&pts[..=n], tolerance);
+        r.pop();
+        r.extend(simplify(&pts[n..], tolerance));
+        r
+    }
+}
+
+//fi simplify_paths
+/// Replace the path data of 'path' elements with simplified polylines
+fn simplify_paths(e: &mut SvgElement, tolerance: f64, nf: &NumberFormat) {
+    for c in e.contents_mut() {
+        simplify_paths(c, tolerance, nf);
+    }
+    if e.ns_name().to_string() != "path" || e.get_attribute("d").is_none() {
+        return;
+    }
+    let polylines = e.shape_polylines(tolerance / 4.);
+    if polylines.is_empty() {
+        return;
+    }
+    let mut d = String::new();
+    for pl in polylines.iter().filter(|pl| !pl.is_empty()) {
+        let closed = pl.len() > 2 && (pl[0] - pl[pl.len() - 1]).length() < 1E-9;
+        let pts = simplify(pl, tolerance);
+        let n = if closed { pts.len() - 1 } else { pts.len() };
+        for (i, p) in pts[..n].iter().enumerate() {
+            d.push_str(if i == 0 { "M " } else { " L " });
+            d.push_str(&nf.point(p));
+        }
+        d.push_str(if closed { " Z " } else { " " });
+    }
+    e.set_local_attribute("d", d.trim());
+}
+
+//fi drop_classes
+/// Remove the elements with any of the classes
+fn drop_classes(e: &mut SvgElement, classes: &[String]) {
+    e.retain_contents(|c| {
+        !c.get_attribute("class").is_some_and(|cls| {
+            cls.split_whitespace()
+                .any(|c| classes.iter().any(|x| x == c))
+        })
+    });
+    for c in e.contents_mut() {
+        drop_classes(c, classes);
+    }
+}
+
+//fi has_id
+fn has_id(e: &SvgElement) -> bool {
+    e.get_attribute("id").is_some() || e.contents().iter().any(has_id)
+}
+
+//fi geometry_key
+/// Get the key of an element for sharing - its XML without its
+/// transform - if it may be shared (it contains no ids)
+fn geometry_key(e: &SvgElement) -> Option<String> {
+    if has_id(e) {
+        return None;
+    }
+    let mut copy = e.finalized_copy();
+    copy.remove_attribute("transform");
+    let key: String = ElementIter::new(&copy).map(|ev| ev.as_xml()).collect();
+    Some(key)
+}
+
+//fi count_keys
+fn count_keys(e: &SvgElement, counts: &mut HashMap<String, usize>) {
+    for c in e.contents() {
+        if c.ns_name().to_string() == "defs" {
+            continue;
+        }
+        if let Some(key) = geometry_key(c) {
+            *counts.entry(key).or_default() += 1;
+        }
+        count_keys(c, counts);
+    }
+}
+
+//fi replace_shared
+fn replace_shared<'a>(
+    e: &mut SvgElement<'a>,
+    shared: &mut HashMap<String, (String, Option<SvgElement<'a>>)>,
+) {
+    for c in e.contents_mut() {
+        if c.ns_name().to_string() == "defs" {
+            continue;
+        }
+        let entry = match geometry_key(c) {
+            Some(k) => shared.get_mut(&k),
+            None => None,
+        };
+        let Some((id, def)) = entry else {
+            replace_shared(c, shared);
+            continue;
+        };
+        if def.is_none() {
+            let mut d = c.finalized_copy();
+            d.remove_attribute("transform");
+            d.set_local_attribute("id", id);
+            *def = Some(d);
+        }
+        let mut attributes = vec![(NamespaceName::local("href"), format!("#{}", id))];
+        if let Some(t) = c.get_attribute("transform") {
+            attributes.push((NamespaceName::local("transform"), t.to_string()));
+        }
+        *c = SvgElement::new_finalized(
+            NamespaceName::local("use"),
+            attributes,
+            String::new(),
+            c.bbox(),
+            vec![],
+        );
+    }
+}
+
+//fi share_geometry
+/// Place repeated elements that differ only in their transform in a
+/// 'defs' at the start of the root, and replace them with 'use's
+fn share_geometry(root: &mut SvgElement) {
+    let mut counts = HashMap::new();
+    count_keys(root, &mut counts);
+    let mut shared: HashMap<String, (String, Option<SvgElement>)> = counts
+        .into_iter()
+        .filter(|(k, n)| *n > 1 && k.len() > 64)
+        .enumerate()
+        .map(|(i, (k, _))| (k, (format!("shared-geometry-{}", i), None)))
+        .collect();
+    if shared.is_empty() {
+        return;
+    }
+    replace_shared(root, &mut shared);
+    let mut defs: Vec<_> = shared.into_values().filter_map(|(_, d)| d).collect();
+    defs.sort_by_key(|d| d.get_attribute("id").map(|s| s.to_string()));
+    let defs = SvgElement::new_finalized(
+        NamespaceName::local("defs"),
+        vec![],
+        String::new(),
+        crate::BBox::none(),
+        defs,
+    );
+    root.insert_content(0, defs);
+}
+
+//a Test
+#[cfg(test)]
+mod test_budget {
+    use super::*;
+    use crate::{BBox, Svg, SvgPath, Transform};
+    fn build(budget: SizeBudget) -> Svg<'static> {
+        let cfg = SvgConfig::default()
+            .set_show_grid(true)
+            .set_size_budget(budget);
+        let mut svg = Svg::new(cfg);
+        for i in 0..10 {
+            let mut p = SvgPath::new_box(BBox::new(0., 0., 2., 2.));
+            p.add_attribute("fill", None, "red");
+            p.add_attribute("stroke", None, "black");
+            p.apply_transform(&Transform::of_translation([i as f64 * 3., 0.].into()));
+            svg.contents_add_element(p);
+        }
+        let pts: Vec<Point> = (0..=100)
+            .map(|i| [i as f64 * 0.3, 10. + 0.001 * (i % 2) as f64].into())
+            .collect();
+        let mut bp = crate::BezierPath::default();
+        for w in pts.windows(2) {
+            bp.add_bezier(crate::Bezier::line(&w[0], &w[1]));
+        }
+        svg.contents_add_element(SvgPath::new_path(bp, false));
+        svg.finalize().unwrap();
+        svg
+    }
+    #[test]
+    fn test_simplify() {
+        let pts: Vec<Point> = [[0., 0.], [1., 0.01], [2., 0.], [3., 1.]]
+            .into_iter()
+            .map(|p| p.into())
+            .collect();
+        assert_eq!(simplify(&pts, 0.1).len(), 3);
+        assert_eq!(simplify(&pts, 0.001).len(), 4);
+    }
+    #[test]
+    fn test_budget() {
+        let mut svg = build(SizeBudget::new(Some(1000), None));
+        svg.generate_diagram().unwrap();
+        assert!(svg.applied_degradations().is_empty());
+
+        let mut svg = build(SizeBudget::new(Some(5), None));
+        assert!(matches!(
+            svg.generate_diagram(),
+            Err(SvgError::OverBudget { .. })
+        ));
+
+        let budget = SizeBudget::new(None, Some(3000))
+            .add_degradation(Degradation::DropClasses(vec!["grid".into()]))
+            .add_degradation(Degradation::Simplify(0.01))
+            .add_degradation(Degradation::ShareGeometry);
+        let mut svg = build(budget);
+        svg.generate_diagram().unwrap();
+        assert!(!svg.applied_degradations().is_empty());
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        assert!(buf.len() <= 3000);
+    }
+}
@@ -0,0 +1,154 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_builder.rs
+@brief   Closure-based building of nested elements
+ */
+
+//a Imports
+use crate::{BezierPath, Point, Svg, SvgElement, SvgGroup, SvgPath, SvgText};
+
+//a SvgBuilder
+//tp SvgBuilder
+/// An [SvgBuilder] adds contents to an element (such as a group)
+/// whose nesting is expressed by closures, rather than by pushing to
+/// and popping from the stack of an [Svg]
+///
+/// A builder is created by [Svg::group] (or [SvgBuilder::group] for a
+/// nested group) and only borrows the element it builds, so the
+/// element is complete - and added to its parent - when the closure
+/// returns:
+///
+/// ```ignore
+/// svg.group(|g| {
+///     g.path(outline, true).add_attribute("fill", None, "red");
+///     g.group(|g2| {
+///         g2.text([0., 0.].into(), "label");
+///     });
+/// });
+/// ```
+pub struct SvgBuilder<'b, 'a> {
+    element: &'b mut SvgElement<'a>,
+}
+
+//ip SvgBuilder
+impl<'b, 'a> SvgBuilder<'b, 'a> {
+    //fp new
+    /// Create a builder that adds contents to an element
+    pub fn new(element: &'b mut SvgElement<'a>) -> Self {
+        Self { element }
+    }
+
+    //ap element
+    /// Get the element being built, to set its attributes or transform
+    pub fn element(&mut self) -> &mut SvgElement<'a> {
+        self.element
+    }
+
+    //mp add
+    /// Add an element to the contents, returning it so that its
+    /// attributes may be set
+    pub fn add(&mut self, e: SvgElement<'a>) -> &mut SvgElement<'a> {
+        self.element.push_content(e);
+        self.element.contents_mut().last_mut().unwrap()
+    }
+
+    //mp path
+    /// Add a path to the contents
+    pub fn path(&mut self, path: BezierPath, closed: bool) -> &mut SvgElement<'a> {
+        self.add(SvgPath::new_path(path, closed))
+    }
+
+    //mp text
+    /// Add a text element to the contents
+    pub fn text(&mut self, position: Point, text: &str) -> &mut SvgElement<'a> {
+        self.add(SvgText::new(position, text))
+    }
+
+    //mp group
+    /// Add a group to the contents, built by a closure
+    pub fn group<F>(&mut self, f: F) -> &mut SvgElement<'a>
+    where
+        F: FnOnce(&mut SvgBuilder<'_, 'a>),
+    {
+        let mut g = SvgGroup::new();
+        f(&mut SvgBuilder::new(&mut g));
+        self.add(g)
+    }
+}
+
+//a Svg
+//ip Svg
+impl<'a> Svg<'a> {
+    //mp group
+    /// Add a group built by a closure - to the contents of the
+    /// document, or (if the stack is in use) as a subelement of the
+    /// top of the stack
+    pub fn group<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut SvgBuilder<'_, 'a>),
+    {
+        let mut g = SvgGroup::new();
+        f(&mut SvgBuilder::new(&mut g));
+        self.add_built(g);
+    }
+
+    //mp add_built
+    /// Add an element - to the contents of the document, or (if the
+    /// stack is in use) as a subelement of the top of the stack
+    pub fn add_built(&mut self, e: SvgElement<'a>) {
+        match self.stack_top_mut() {
+            Some(top) => top.push_content(e),
+            None => self.contents_add_element(e),
+        }
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_builder {
+    use crate::{BBox, Polygon, Svg, SvgConfig, SvgGroup, SvgPath, XmlEvent};
+    #[test]
+    fn test_builder() {
+        let mut svg = Svg::new(SvgConfig::default());
+        let outline = Polygon::new_rect(2., 1.).as_paths();
+        svg.group(|g| {
+            g.element().add_attribute("id", None, "outer");
+            g.path(outline, true).add_attribute("fill", None, "red");
+            g.group(|g2| {
+                g2.text([0., 0.].into(), "label");
+            })
+            .add_attribute("class", None, "inner");
+        });
+        svg.stack_push(SvgGroup::new());
+        svg.group(|g| {
+            g.add(SvgPath::new_box(BBox::new(0., 0., 1., 1.)));
+        });
+        svg.contents_take_stack().unwrap();
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let names: Vec<String> = svg
+            .iter_events()
+            .filter_map(|e| match e {
+                XmlEvent::StartElement(e) => Some(e.ns_name().to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec!["svg", "g", "path", "g", "text", "g", "g", "path"]
+        );
+        assert!(svg.ids().contains("outer"));
+    }
+}
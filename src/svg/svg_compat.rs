@@ -0,0 +1,392 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_compat.rs
+@brief   Restriction of a document to static SVG 1.1 features
+ */
+
+//a Imports
+use std::collections::HashMap;
+
+use crate::{NumberFormat, SvgElement, SvgGroup};
+
+//a Constants
+/// Elements that are removed, as they are not static SVG 1.1 (or are
+/// not supported by very old renderers)
+const STRIPPED_ELEMENTS: &[&str] = &[
+    "style",
+    "script",
+    "filter",
+    "foreignObject",
+    "animate",
+    "animateTransform",
+    "animateMotion",
+    "set",
+];
+
+/// Attributes (and CSS properties) that are removed
+const STRIPPED_ATTRIBUTES: &[&str] = &["filter", "mix-blend-mode", "isolation"];
+
+/// Attributes whose value may be a color with transparency
+const COLOR_ATTRIBUTES: &[&str] = &["fill", "stroke", "stop-color", "flood-color"];
+
+/// Maximum depth of 'use' expansion, to prevent a 'use' of an
+/// ancestor recursing forever
+const MAX_USE_DEPTH: usize = 16;
+
+//a CompatChange
+//tp CompatChange
+/// A substitution made when applying the compatibility profile to a
+/// document with [Svg::apply_compat_profile](crate::Svg::apply_compat_profile)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompatChange {
+    /// The CSS rules for a class were applied as presentation attributes
+    ClassToAttributes { class: String },
+    /// An inline 'style' attribute was converted to presentation attributes
+    StyleToAttributes,
+    /// A color with transparency in an attribute was replaced by an
+    /// opaque color and an opacity attribute
+    RgbaToOpacity { attribute: String },
+    /// A 'use' of an element was replaced by a copy of it
+    UseExpanded { href: String },
+    /// A 'use' of an element that could not be found was removed
+    UseRemoved { href: String },
+    /// An element, attribute or CSS rule (by name or selector) was removed
+    Stripped { name: String },
+}
+
+//a Compat
+//ti Compat
+/// State used when applying the compatibility profile
+struct Compat<'a> {
+    /// CSS declarations for each class
+    rules: HashMap<String, Vec<(String, String)>>,
+    /// Copies of the elements with ids, for expanding 'use'
+    ids: HashMap<String, SvgElement<'a>>,
+    /// The substitutions made
+    report: Vec<CompatChange>,
+    /// Format of numbers in attributes that are rewritten
+    nf: NumberFormat,
+}
+
+//ii Compat
+impl<'a> Compat<'a> {
+    //mi note
+    fn note(&mut self, change: CompatChange) {
+        if !self.report.contains(&change) {
+            self.report.push(change);
+        }
+    }
+
+    //mi collect
+    /// Collect the CSS rules of 'style' elements and copies of the
+    /// elements with ids
+    fn collect(&mut self, e: &SvgElement<'a>) {
+        if e.ns_name().to_string() == "style" {
+            self.parse_css(e.characters());
+        }
+        if let Some(id) = e.get_attribute("id") {
+            self.ids.insert(id.to_string(), e.finalized_copy());
+        }
+        for c in e.contents() {
+            self.collect(c);
+        }
+    }
+
+    //mi parse_css
+    /// Parse CSS rules, keeping those with simple class selectors
+    fn parse_css(&mut self, css: &str) {
+        for rule in css.split('}') {
+            let Some((selectors, decls)) = rule.split_once('{') else {
+                continue;
+            };
+            let decls = parse_declarations(decls);
+            for selector in selectors.split(',').map(|s| s.trim()) {
+                let class = selector.strip_prefix('.').filter(|c| {
+                    !c.is_empty()
+                        && c.chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                });
+                match class {
+                    Some(class) => self
+                        .rules
+                        .entry(class.to_string())
+                        .or_default()
+                        .extend(decls.iter().cloned()),
+                    None => self.note(CompatChange::Stripped {
+                        name: selector.to_string(),
+                    }),
+                }
+            }
+        }
+    }
+
+    //mi set_property
+    /// Set a presentation attribute from a CSS property, unless it is
+    /// stripped
+    fn set_property(&mut self, e: &mut SvgElement<'a>, name: &str, value: &str) {
+        if STRIPPED_ATTRIBUTES.contains(&name) {
+            self.note(CompatChange::Stripped { name: name.into() });
+        } else {
+            e.set_local_attribute(name, value);
+        }
+    }
+
+    //mi convert_attributes
+    fn convert_attributes(&mut self, e: &mut SvgElement<'a>) {
+        let classes: Vec<String> = e
+            .get_attribute("class")
+            .map(|c| c.split_whitespace().map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+        for class in classes {
+            if let Some(decls) = self.rules.get(&class).cloned() {
+                for (n, v) in decls.iter() {
+                    self.set_property(e, n, v);
+                }
+                self.note(CompatChange::ClassToAttributes { class });
+            }
+        }
+        if let Some(style) = e.remove_attribute("style") {
+            for (n, v) in parse_declarations(&style) {
+                self.set_property(e, &n, &v);
+            }
+            self.note(CompatChange::StyleToAttributes);
+        }
+        for name in STRIPPED_ATTRIBUTES {
+            if e.remove_attribute(name).is_some() {
+                self.note(CompatChange::Stripped {
+                    name: name.to_string(),
+                });
+            }
+        }
+        for name in COLOR_ATTRIBUTES {
+            let Some((color, alpha)) = e.get_attribute(name).and_then(split_rgba) else {
+                continue;
+            };
+            let opacity_name = format!("{}-opacity", name.strip_suffix("-color").unwrap_or(name));
+            let opacity = e
+                .get_attribute(&opacity_name)
+                .and_then(|o| o.parse::<f64>().ok())
+                .unwrap_or(1.)
+                * alpha;
+            e.set_local_attribute(name, &color);
+            e.set_local_attribute(&opacity_name, &self.nf.number(opacity));
+            self.note(CompatChange::RgbaToOpacity {
+                attribute: name.to_string(),
+            });
+        }
+    }
+
+    //mi expand_use
+    /// Create the replacement for a 'use' element - a group
+    /// containing a copy of the referenced element - if the element
+    /// can be found
+    fn expand_use(&mut self, u: &SvgElement<'a>) -> Option<SvgElement<'a>> {
+        let href = u
+            .get_attribute("href")
+            .or_else(|| u.get_attribute("xlink:href"))
+            .unwrap_or("")
+            .to_string();
+        let Some(target) = href.strip_prefix('#').and_then(|id| self.ids.get(id)) else {
+            self.note(CompatChange::UseRemoved { href });
+            return None;
+        };
+        let mut copy = target.finalized_copy();
+        copy.remove_attribute("id");
+        let mut g = SvgGroup::new();
+        let mut translate = (0., 0.);
+        for (n, v) in u.attributes() {
+            match n.to_string().as_str() {
+                "href" | "xlink:href" | "width" | "height" => (),
+                "x" => translate.0 = v.parse().unwrap_or(0.),
+                "y" => translate.1 = v.parse().unwrap_or(0.),
+                n => g.set_local_attribute(n, v),
+            }
+        }
+        if translate != (0., 0.) {
+            let t = format!(
+                "{} translate({} {})",
+                g.get_attribute("transform").unwrap_or(""),
+                self.nf.number(translate.0),
+                self.nf.number(translate.1)
+            );
+            g.set_local_attribute("transform", t.trim());
+        }
+        g.push_content(copy);
+        self.note(CompatChange::UseExpanded { href });
+        Some(g)
+    }
+
+    //mi convert
+    /// Convert an element and its contents
+    fn convert(&mut self, e: &mut SvgElement<'a>, depth: usize) {
+        self.convert_attributes(e);
+        let mut stripped = vec![];
+        e.retain_contents(|c| {
+            let name = c.ns_name().to_string();
+            let strip = STRIPPED_ELEMENTS.contains(&name.as_str());
+            if strip {
+                stripped.push(name);
+            }
+            !strip
+        });
+        for name in stripped {
+            self.note(CompatChange::Stripped { name });
+        }
+        let mut unresolved = false;
+        for c in e.contents_mut() {
+            if c.ns_name().to_string() == "use" {
+                match self.expand_use(c) {
+                    Some(g) if depth < MAX_USE_DEPTH => *c = g,
+                    _ => {
+                        c.remove_attribute("href");
+                        unresolved = true;
+                    }
+                }
+            }
+            self.convert(c, depth + 1);
+        }
+        if unresolved {
+            e.retain_contents(|c| c.ns_name().to_string() != "use");
+        }
+    }
+}
+
+//a Functions
+//fi parse_declarations
+/// Parse CSS declarations 'name: value; ...'
+fn parse_declarations(decls: &str) -> Vec<(String, String)> {
+    decls
+        .split(';')
+        .filter_map(|d| d.split_once(':'))
+        .map(|(n, v)| {
+            let v = v.trim().trim_end_matches("!important").trim();
+            (n.trim().to_string(), v.to_string())
+        })
+        .filter(|(n, v)| !n.is_empty() && !v.is_empty())
+        .collect()
+}
+
+//fi split_rgba
+/// Split an 'rgba(...)' color into a hex color and an opacity; the
+/// alpha may be 0 to 1, or 0 to 255 as written for an [Rgba](crate::Rgba)
+fn split_rgba(value: &str) -> Option<(String, f64)> {
+    let args = value.trim().strip_prefix("rgba(")?.strip_suffix(')')?;
+    let v: Vec<f64> = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<f64>().ok())
+        .collect::<Option<_>>()?;
+    if v.len() != 4 {
+        return None;
+    }
+    let byte = |c: f64| c.clamp(0., 255.).round() as u8;
+    let alpha = if v[3] > 1. { v[3] / 255. } else { v[3] };
+    Some((
+        format!("#{:02x}{:02x}{:02x}", byte(v[0]), byte(v[1]), byte(v[2])),
+        alpha.clamp(0., 1.),
+    ))
+}
+
+//fp apply_compat
+/// Apply the compatibility profile to the root element of a
+/// generated document, returning the substitutions made
+pub(crate) fn apply_compat(root: &mut SvgElement, nf: &NumberFormat) -> Vec<CompatChange> {
+    let mut compat = Compat {
+        rules: HashMap::new(),
+        ids: HashMap::new(),
+        report: vec![],
+        nf: *nf,
+    };
+    compat.collect(root);
+    root.set_attribute("version", None, "1.1");
+    compat.convert(root, 0);
+    compat.report
+}
+
+//a Test
+#[cfg(test)]
+mod test_compat {
+    use super::*;
+    use crate::svg::number_str;
+    use crate::{BBox, MultiStroke, Svg, SvgConfig, SvgPath, SvgTag};
+    fn count(e: &SvgElement, name: &str) -> usize {
+        let n = if e.ns_name().to_string() == name {
+            1
+        } else {
+            0
+        };
+        n + e.contents().iter().map(|c| count(c, name)).sum::<usize>()
+    }
+    #[test]
+    fn test_split_rgba() {
+        assert_eq!(
+            split_rgba("rgba(255,0,0,51)"),
+            Some(("#ff0000".to_string(), 0.2))
+        );
+        assert_eq!(
+            split_rgba("rgba(0 128 255 / 0.5)"),
+            Some(("#0080ff".to_string(), 0.5))
+        );
+        assert_eq!(split_rgba("red"), None);
+    }
+    #[test]
+    fn test_compat() {
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut style = SvgTag::new("style", None);
+        style.push_string(
+            ".warn { fill: red; mix-blend-mode: multiply } svg:hover .x { fill: blue }",
+        );
+        svg.contents_add_element(style);
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        p.add_attribute("class", None, "warn");
+        p.add_attribute("style", None, "stroke: green");
+        svg.contents_add_element(p);
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        p.add_attribute("fill", None, "rgba(0,0,255,51)");
+        p.add_attribute("id", None, "road");
+        p.set_multi_stroke(MultiStroke::new().add_pass(1., "black").unwrap());
+        svg.contents_add_element(p);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+
+        let report = svg.apply_compat_profile().unwrap();
+        let root = svg.root_element().unwrap();
+        assert_eq!(root.get_attribute("version"), Some("1.1"));
+        assert_eq!(count(root, "style"), 0);
+        assert_eq!(count(root, "use"), 0);
+        let warn = &root.contents()[0];
+        assert_eq!(warn.get_attribute("fill"), Some("red"));
+        assert_eq!(warn.get_attribute("stroke"), Some("green"));
+        assert_eq!(warn.get_attribute("mix-blend-mode"), None);
+        // The fill of the multi-stroke is on the first (expanded) pass
+        let road = &root.contents()[1].contents()[1];
+        assert_eq!(road.ns_name().to_string(), "g");
+        assert_eq!(road.contents()[0].get_attribute("id"), None);
+        assert_eq!(road.get_attribute("fill"), Some("#0000ff"));
+        assert_eq!(
+            road.get_attribute("fill-opacity"),
+            Some(number_str(0.2).as_str())
+        );
+        assert!(report.contains(&CompatChange::ClassToAttributes {
+            class: "warn".into()
+        }));
+        assert!(report.contains(&CompatChange::Stripped {
+            name: "svg:hover .x".into()
+        }));
+        assert!(report.contains(&CompatChange::UseExpanded {
+            href: "#road-geometry".into()
+        }));
+    }
+}
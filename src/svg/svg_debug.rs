@@ -0,0 +1,142 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_debug.rs
+@brief   Structured JSON dumps of element trees for debug and tests
+ */
+
+//a Imports
+use std::fmt::Write;
+
+use crate::{BBox, SvgElement};
+
+//a Functions
+//fi json_str
+/// Format a string as a JSON string
+fn json_str(s: &str) -> String {
+    let mut r = String::with_capacity(s.len() + 2);
+    r.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => r.push_str("\\\""),
+            '\\' => r.push_str("\\\\"),
+            '\n' => r.push_str("\\n"),
+            '\r' => r.push_str("\\r"),
+            '\t' => r.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(r, "\\u{:04x}", c as u32);
+            }
+            c => r.push(c),
+        }
+    }
+    r.push('"');
+    r
+}
+
+//fi json_number
+/// Format a number as JSON; JSON has no infinities or NaN, so these
+/// are null
+fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        format!("{}", v)
+    } else {
+        "null".into()
+    }
+}
+
+//fp bbox_json
+/// Format a bbox as a JSON array '[x0, y0, x1, y1]', or null if it is
+/// none
+pub(crate) fn bbox_json(bbox: &BBox) -> String {
+    if bbox.is_none() {
+        "null".into()
+    } else {
+        format!(
+            "[{}, {}, {}, {}]",
+            json_number(bbox.x[0]),
+            json_number(bbox.y[0]),
+            json_number(bbox.x[1]),
+            json_number(bbox.y[1])
+        )
+    }
+}
+
+//fp element_json
+/// Append the JSON for an element and its contents, indented by two
+/// spaces per depth
+///
+/// The element is an object with its name, attributes (an object, in
+/// order), transform (as an SVG attribute value, if not the
+/// identity), bbox, characters (if any), and contents (if any)
+pub(crate) fn element_json(e: &SvgElement, r: &mut String, depth: usize) {
+    let pad = "  ".repeat(depth + 1);
+    r.push_str("{\n");
+    let _ = write!(r, "{}\"name\": {}", pad, json_str(&e.ns_name().to_string()));
+    let _ = write!(r, ",\n{}\"attributes\": {{", pad);
+    for (i, (n, v)) in e.attributes().iter().enumerate() {
+        let sep = if i == 0 { "" } else { ", " };
+        let _ = write!(r, "{}{}: {}", sep, json_str(&n.to_string()), json_str(v));
+    }
+    r.push('}');
+    let transform = e.transform().as_svg_attribute_string();
+    if !transform.is_empty() {
+        let _ = write!(r, ",\n{}\"transform\": {}", pad, json_str(transform.trim()));
+    }
+    let _ = write!(r, ",\n{}\"bbox\": {}", pad, bbox_json(&e.bbox()));
+    if !e.characters().is_empty() {
+        let _ = write!(r, ",\n{}\"characters\": {}", pad, json_str(e.characters()));
+    }
+    if !e.contents().is_empty() {
+        let _ = write!(r, ",\n{}\"contents\": [", pad);
+        for (i, c) in e.contents().iter().enumerate() {
+            let sep = if i == 0 { "\n" } else { ",\n" };
+            let _ = write!(r, "{}{}  ", sep, pad);
+            element_json(c, r, depth + 2);
+        }
+        let _ = write!(r, "\n{}]", pad);
+    }
+    let _ = write!(r, "\n{}}}", "  ".repeat(depth));
+}
+
+//a Test
+#[cfg(test)]
+mod test_debug {
+    use super::*;
+    use crate::{Svg, SvgConfig, SvgGroup, SvgPath, SvgText, Transform};
+    #[test]
+    fn test_json() {
+        assert_eq!(json_str("a\"b\\c\n\u{1}"), r#""a\"b\\c\n\u0001""#);
+        assert_eq!(bbox_json(&BBox::none()), "null");
+
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut g = SvgGroup::new();
+        g.apply_transform(&Transform::of_translation([1., 2.].into()));
+        let mut t = SvgText::new([0., 0.].into(), "label");
+        t.add_attribute("class", None, "x");
+        g.push_content(t);
+        g.push_content(SvgPath::new_box(BBox::new(0., 0., 4., 3.)));
+        svg.contents_add_element(g);
+        let json = svg.to_debug_json();
+        assert!(json.contains(r#""name": "g""#));
+        assert!(json.contains(r#""attributes": {"class": "x"}"#));
+        assert!(json.contains(r#""characters": "label""#));
+        assert!(json.contains(r#""transform": "translate("#));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let json = svg.to_debug_json();
+        // Text has no bbox, so the document bbox is that of the box
+        assert!(json.starts_with("{\n  \"bbox\": [1, 2, 5, 5]"));
+        assert!(json.contains(r#""name": "svg""#));
+    }
+}
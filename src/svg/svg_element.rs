@@ -18,18 +18,113 @@ limitations under the License.
 
 //a Imports
 use std::borrow::Cow;
+use std::f64::consts::{PI, TAU};
 
+use geo_nd::Vector;
 use indent_display::{IndentedDisplay, Indenter};
 
-use crate::IndentOpt;
-use crate::{BBox, BezierPath, Color, ColorDatabase, Point, Polygon, Transform};
-use crate::{SvgColorDatabase, SvgConfig};
+use super::svg_format::{NumberFormat, NumberText};
+use super::svg_ids::StableHasher;
+use crate::{BBox, Bezier, BezierPath, Color, ColorDatabase, Gear, GridLines, Point, Polygon};
+use crate::{Error, IndentOpt, NamespaceName};
+use crate::{MultiStroke, PresentationMode, SvgColorDatabase, SvgConfig};
+use crate::{Shape, Transform};
 
 //a Useful stuff
-fn pt_as_str(pt: &Point) -> String {
-    format!("{:.4},{:.4}", pt[0], pt[1])
+fn path_as_str(path: &BezierPath, closed: bool, nf: &NumberFormat) -> String {
+    let point_str = |pt: &Point| nf.point(pt);
+    let mut r = String::new();
+    r.push_str(&format!("M {}", point_str(&path.get_pt(0))));
+    for b in path.iter_beziers() {
+        if b.degree() == 1 {
+            r.push_str(&format!(" L {}", point_str(b.borrow_pt(1))))
+        } else if b.degree() == 2 {
+            r.push_str(&format!(
+                " Q {} {}",
+                point_str(b.borrow_pt(2)),
+                point_str(b.borrow_pt(1))
+            ));
+        } else {
+            r.push_str(&format!(
+                " C {} {} {}",
+                point_str(b.borrow_pt(2)),
+                point_str(b.borrow_pt(3)),
+                point_str(b.borrow_pt(1))
+            ));
+        }
+    }
+    if closed {
+        r.push_str(" z");
+    }
+    r
 }
-const INDENT_STRING: &str = "                                                            ";
+
+/// Attributes shown in the style summary of the indented display of
+/// an element
+const STYLE_SUMMARY_ATTRIBUTES: &[&str] = &[
+    "class",
+    "style",
+    "fill",
+    "stroke",
+    "stroke-width",
+    "opacity",
+    "font-size",
+];
+
+/// Presentation attributes that are folded into a 'style' attribute
+/// with [PresentationMode::StyleAttribute]
+const PRESENTATION_ATTRIBUTES: &[&str] = &[
+    "alignment-baseline",
+    "baseline-shift",
+    "clip-path",
+    "clip-rule",
+    "color",
+    "cursor",
+    "display",
+    "dominant-baseline",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "filter",
+    "flood-color",
+    "flood-opacity",
+    "font-family",
+    "font-size",
+    "font-stretch",
+    "font-style",
+    "font-variant",
+    "font-weight",
+    "image-rendering",
+    "isolation",
+    "letter-spacing",
+    "lighting-color",
+    "marker-end",
+    "marker-mid",
+    "marker-start",
+    "mask",
+    "mix-blend-mode",
+    "opacity",
+    "paint-order",
+    "pointer-events",
+    "shape-rendering",
+    "stop-color",
+    "stop-opacity",
+    "stroke",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-opacity",
+    "stroke-width",
+    "text-anchor",
+    "text-decoration",
+    "text-rendering",
+    "vector-effect",
+    "visibility",
+    "word-spacing",
+    "writing-mode",
+];
 
 //a SvgElementType
 pub trait SvgElementType<'a>: std::fmt::Debug {
@@ -47,8 +142,31 @@ pub trait SvgElementType<'a>: std::fmt::Debug {
         BBox::none()
     }
 
-    /// Push the attributes when ready for rendering as SVG (post-finalize)
-    fn push_attributes(&self, attrs: &mut Vec<(NamespaceName<'a>, String)>) {}
+    /// Clamp any open-ended geometry to the viewbox of the document,
+    /// given in the coordinates of the element
+    ///
+    /// This is invoked after finalize, and returns true if the
+    /// attributes must then be pushed (again)
+    fn clamp_to_viewbox(&mut self, _viewbox: &BBox) -> bool {
+        false
+    }
+
+    /// Push the attributes when ready for rendering as SVG
+    /// (post-finalize), with numbers in the [NumberFormat] of the
+    /// document
+    fn push_attributes(&self, _nf: &NumberFormat, _attrs: &mut Vec<(NamespaceName<'a>, String)>) {}
+
+    /// Get the closed paths that make up the fill of the element, if
+    /// it can be filled with hatch lines instead
+    fn closed_paths(&self) -> Vec<&BezierPath> {
+        vec![]
+    }
+
+    /// Get the geometry of the element (not its contents) flattened
+    /// to polylines, for output to plotters
+    fn polylines(&self, _tolerance: f64) -> Vec<Vec<Point>> {
+        vec![]
+    }
 }
 
 //a SvgElementTypes
@@ -58,6 +176,7 @@ pub struct SvgSvg();
 
 //ip SvgSvg
 impl SvgSvg {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new<'a>() -> SvgElement<'a> {
         let g = Self();
         SvgElement::new(g)
@@ -71,16 +190,120 @@ impl<'a> SvgElementType<'a> for SvgSvg {
     }
 }
 
+//tp SvgDefs
+#[derive(Debug)]
+pub struct SvgDefs();
+
+//ip SvgDefs
+impl SvgDefs {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>() -> SvgElement<'a> {
+        let g = Self();
+        SvgElement::new(g)
+    }
+}
+
+//ip SvgElementType for SvgDefs
+impl<'a> SvgElementType<'a> for SvgDefs {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::local("defs")
+    }
+}
+
+//tp SvgTag
+/// An [SvgTag] is a plain element with a given name, such as
+/// 'metadata', 'title' or 'desc'; any attributes and contents are
+/// added to the [SvgElement]
+#[derive(Debug)]
+pub struct SvgTag {
+    name: &'static str,
+    prefix: Option<&'static str>,
+}
+
+//ip SvgTag
+impl SvgTag {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>(name: &'static str, prefix: Option<&'static str>) -> SvgElement<'a> {
+        let t = Self { name, prefix };
+        SvgElement::new(t)
+    }
+}
+
+//ip SvgElementType for SvgTag
+impl<'a> SvgElementType<'a> for SvgTag {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::new(self.name, self.prefix)
+    }
+}
+
+//ti SvgCopy
+/// The type of a copy of a finalized element, which needs only its
+/// name as its attributes have already been pushed
+#[derive(Debug)]
+struct SvgCopy<'a> {
+    name: NamespaceName<'a>,
+}
+
+//ip SvgElementType for SvgCopy
+impl<'a> SvgElementType<'a> for SvgCopy<'a> {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        self.name.clone()
+    }
+}
+
+//tp SvgText
+/// An [SvgText] is a 'text' element at a position, whose text is the
+/// characters of the [SvgElement]
+#[derive(Debug)]
+pub struct SvgText {
+    position: Point,
+}
+
+//ip SvgText
+impl SvgText {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>(position: Point, text: &str) -> SvgElement<'a> {
+        let t = Self { position };
+        let mut e = SvgElement::new(t);
+        e.push_string(text);
+        e
+    }
+}
+
+//ip SvgElementType for SvgText
+impl<'a> SvgElementType<'a> for SvgText {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::local("text")
+    }
+    /// Push the attributes when ready for rendering as SVG (post-finalize)
+    fn push_attributes(&self, nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
+        attrs.push((NamespaceName::local("x"), nf.number(self.position[0])));
+        attrs.push((NamespaceName::local("y"), nf.number(self.position[1])));
+    }
+}
+
 //tp SvgGroup
 #[derive(Debug)]
 pub struct SvgGroup();
 
 //ip SvgGroup
 impl SvgGroup {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new<'a>() -> SvgElement<'a> {
         let g = Self();
         SvgElement::new(g)
     }
+
+    //fp with_opacity
+    /// Create a group that is translucent as one unit, with an
+    /// 'opacity' attribute rather than a 'fill-opacity' on each child,
+    /// so that overlapping children (such as a highlight overlay)
+    /// composite with each other before the opacity is applied
+    pub fn with_opacity<'a>(opacity: f64) -> SvgElement<'a> {
+        let mut g = Self::new();
+        g.add_numbers("opacity", opacity.clamp(0., 1.).into());
+        g
+    }
 }
 
 //ip SvgElementType for SvgGroup
@@ -124,43 +347,261 @@ impl<'a> SvgElementType<'a> for SvgPath {
         NamespaceName::local("path")
     }
     fn bbox(&self) -> BBox {
-        let mut bbox = BBox::none();
+        self.path
+            .iter_beziers()
+            .flat_map(|b| b.as_points(0.1))
+            .collect()
+    }
+    /// Push the attributes when ready for rendering as SVG (post-finalize)
+    fn push_attributes(&self, nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
+        attrs.push((
+            NamespaceName::local("d"),
+            path_as_str(&self.path, self.closed, nf),
+        ));
+    }
+    fn polylines(&self, tolerance: f64) -> Vec<Vec<Point>> {
+        let mut pts: Vec<Point> = vec![];
         for b in self.path.iter_beziers() {
-            for p in b.as_points(0.1) {
-                bbox = bbox.include(p);
+            for p in b.as_points(tolerance) {
+                if pts.last().is_none_or(|l| l[0] != p[0] || l[1] != p[1]) {
+                    pts.push(p);
+                }
+            }
+        }
+        if self.closed && pts.len() > 1 {
+            let (first, last) = (pts[0], pts[pts.len() - 1]);
+            if first[0] != last[0] || first[1] != last[1] {
+                pts.push(first);
             }
         }
-        bbox
+        vec![pts]
+    }
+    fn closed_paths(&self) -> Vec<&BezierPath> {
+        if self.closed {
+            vec![&self.path]
+        } else {
+            vec![]
+        }
+    }
+}
+
+//tp SvgCompoundPath
+/// An [SvgCompoundPath] is a 'path' element made up of a number of
+/// closed subpaths, filled with the even-odd rule so that inner
+/// subpaths are holes
+#[derive(Debug)]
+pub struct SvgCompoundPath {
+    paths: Vec<BezierPath>,
+}
+
+//ip SvgCompoundPath
+impl SvgCompoundPath {
+    //fp new
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>(paths: Vec<BezierPath>) -> SvgElement<'a> {
+        SvgElement::new(Self { paths })
+    }
+
+    //fp new_shape
+    /// Create a path for a [Shape], which records the kind of shape
+    /// and its dimensions as its primitive (see
+    /// [SvgElement::set_primitive])
+    pub fn new_shape<'a>(shape: &Shape) -> SvgElement<'a> {
+        let mut e = Self::new(shape.as_paths());
+        e.set_primitive(shape.kind().name(), &shape.kind().parameters());
+        e
+    }
+
+    //fp new_gear
+    /// Create a path for a [Gear], which records the kind of gear and
+    /// its tooth form as its primitive (see [SvgElement::set_primitive])
+    pub fn new_gear<'a>(gear: &Gear) -> SvgElement<'a> {
+        let mut e = Self::new(vec![gear.as_paths()]);
+        e.set_primitive(gear.name(), &gear.parameters());
+        e
+    }
+}
+
+//ip SvgElementType for SvgCompoundPath
+impl<'a> SvgElementType<'a> for SvgCompoundPath {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::local("path")
+    }
+    fn bbox(&self) -> BBox {
+        self.paths
+            .iter()
+            .flat_map(|p| p.iter_beziers())
+            .flat_map(|b| b.as_points(0.1))
+            .collect()
     }
     /// Push the attributes when ready for rendering as SVG (post-finalize)
-    fn push_attributes(&self, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
+    fn push_attributes(&self, nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
+        let d: Vec<String> = self
+            .paths
+            .iter()
+            .filter(|p| p.iter_beziers().next().is_some())
+            .map(|p| path_as_str(p, true, nf))
+            .collect();
+        attrs.push((NamespaceName::local("d"), d.join(" ")));
+        attrs.push((NamespaceName::local("fill-rule"), "evenodd".into()));
+    }
+    fn polylines(&self, tolerance: f64) -> Vec<Vec<Point>> {
+        let mut r = vec![];
+        for path in self.paths.iter() {
+            let mut pts: Vec<Point> = vec![];
+            for b in path.iter_beziers() {
+                for p in b.as_points(tolerance) {
+                    if pts.last().is_none_or(|l| l[0] != p[0] || l[1] != p[1]) {
+                        pts.push(p);
+                    }
+                }
+            }
+            if pts.len() > 1 {
+                let (first, last) = (pts[0], pts[pts.len() - 1]);
+                if first[0] != last[0] || first[1] != last[1] {
+                    pts.push(first);
+                }
+            }
+            r.push(pts);
+        }
+        r
+    }
+    fn closed_paths(&self) -> Vec<&BezierPath> {
+        self.paths.iter().collect()
+    }
+}
+
+//tp SvgLines
+/// An [SvgLines] is a 'path' element made up of a set of disjoint
+/// straight lines, such as tick marks
+#[derive(Debug)]
+pub struct SvgLines {
+    lines: Vec<(Point, Point)>,
+}
+
+//ip SvgLines
+impl SvgLines {
+    //fp new
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>(lines: Vec<(Point, Point)>) -> SvgElement<'a> {
+        SvgElement::new(Self { lines })
+    }
+}
+
+//ip SvgElementType for SvgLines
+impl<'a> SvgElementType<'a> for SvgLines {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::local("path")
+    }
+    fn bbox(&self) -> BBox {
+        self.lines.iter().flat_map(|(p0, p1)| [*p0, *p1]).collect()
+    }
+    /// Push the attributes when ready for rendering as SVG (post-finalize)
+    fn push_attributes(&self, nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
         let mut r = String::new();
-        r.push_str(&format!("M {}", pt_as_str(&self.path.get_pt(0))));
-        for b in self.path.iter_beziers() {
-            if b.degree() == 1 {
-                r.push_str(&format!(" L {}", pt_as_str(b.borrow_pt(1))))
-            } else if b.degree() == 2 {
-                r.push_str(&format!(
-                    " Q {} {}",
-                    pt_as_str(b.borrow_pt(2)),
-                    pt_as_str(b.borrow_pt(1))
-                ));
+        for (p0, p1) in self.lines.iter() {
+            if !r.is_empty() {
+                r.push(' ');
+            }
+            r.push_str(&format!("M {} L {}", nf.point(p0), nf.point(p1)));
+        }
+        attrs.push((NamespaceName::local("d"), r));
+    }
+    fn polylines(&self, _tolerance: f64) -> Vec<Vec<Point>> {
+        self.lines.iter().map(|(p0, p1)| vec![*p0, *p1]).collect()
+    }
+}
+
+//tp SvgRegion
+/// An [SvgRegion] is a rectangular region that may be open-ended, such
+/// as a shaded region 'y > 3'
+///
+/// An open-ended region does not contribute to the bbox of its
+/// parent; its unbounded sides are clamped to the viewbox of the
+/// document when that is finalized.
+#[derive(Debug)]
+pub struct SvgRegion {
+    /// Region, which may be unbounded
+    region: BBox,
+    /// Region clamped to the viewbox (if unbounded)
+    clamped: BBox,
+}
+
+//ip SvgRegion
+impl SvgRegion {
+    //fp new
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>(region: BBox) -> SvgElement<'a> {
+        let clamped = {
+            if region.is_bounded() {
+                region
             } else {
-                r.push_str(&format!(
-                    " C {} {} {}",
-                    pt_as_str(b.borrow_pt(2)),
-                    pt_as_str(b.borrow_pt(3)),
-                    pt_as_str(b.borrow_pt(1))
-                ));
+                BBox::none()
             }
+        };
+        let p = Self { region, clamped };
+        SvgElement::new(p)
+    }
+}
+
+//ip SvgElementType for SvgRegion
+impl<'a> SvgElementType<'a> for SvgRegion {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::local("path")
+    }
+    fn bbox(&self) -> BBox {
+        self.clamped
+    }
+    fn clamp_to_viewbox(&mut self, viewbox: &BBox) -> bool {
+        if self.region.is_bounded() {
+            false
+        } else {
+            self.clamped = self.region.clamp(viewbox);
+            true
         }
-        if self.closed {
-            r.push_str(" z");
+    }
+    /// Push the attributes when ready for rendering as SVG (post-finalize)
+    fn push_attributes(&self, nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
+        if self.clamped.is_none() {
+            return;
         }
-        attrs.push((NamespaceName::local("d"), r));
+        let (x, y, w, h) = self.clamped.get_bounds();
+        attrs.push((
+            NamespaceName::local("d"),
+            format!(
+                "M {} h {} v {} h {} z",
+                nf.point(&[x, y].into()),
+                nf.number(w),
+                nf.number(h),
+                nf.number(-w)
+            ),
+        ));
     }
 }
 
+//tp GridMode
+/// The style of grid drawn by an [SvgGrid]
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridMode {
+    /// Horizontal and vertical lines
+    #[default]
+    Cartesian,
+    /// Cartesian grid lines rotated by an angle in degrees around the
+    /// center of the grid
+    Rotated { degrees: f64 },
+    /// Concentric circles (or ellipses, for an eccentricity other than
+    /// 1) around a center, with a number of radial spokes
+    ///
+    /// The X spacing of the grid is used as the spacing of the circles
+    Polar {
+        #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_point"))]
+        center: Point,
+        eccentricity: f64,
+        spokes: usize,
+    },
+}
+
 //tp SvgGrid
 /// An [SvgGrid] is generally an artefact; it is created to show the
 /// grid of some SVG contents.
@@ -175,15 +616,168 @@ pub struct SvgGrid {
     spacings: (f64, f64),
     /// Bounding box of grid
     bbox: BBox,
+    /// Style of grid
+    mode: GridMode,
 }
 
 //ip SvgGrid
 impl SvgGrid {
     //fp new
+    #[allow(clippy::new_ret_no_self)]
     pub fn new<'a>(bbox: BBox, spacings: (f64, f64)) -> SvgElement<'a> {
-        let p = Self { spacings, bbox };
+        Self::new_of_mode(bbox, spacings, GridMode::Cartesian)
+    }
+
+    //fp new_of_mode
+    pub fn new_of_mode<'a>(bbox: BBox, spacings: (f64, f64), mode: GridMode) -> SvgElement<'a> {
+        let p = Self {
+            spacings,
+            bbox,
+            mode,
+        };
         SvgElement::new(p)
     }
+
+    //mi push_lines
+    fn push_lines(r: &mut String, lines: &[BezierPath], nf: &NumberFormat) {
+        for p in lines {
+            r.push_str(&format!(
+                "M {} L {} ",
+                nf.point(&p.get_pt(0)),
+                nf.point(&p.get_pt(1))
+            ));
+        }
+    }
+
+    //mi clip_line
+    /// Clip a line to the bounding box of the grid, returning None if
+    /// no part of it lies within the box
+    fn clip_line(&self, p0: Point, p1: Point) -> Option<(Point, Point)> {
+        let d = p1 - p0;
+        let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+        for (n, q) in [
+            (-d[0], p0[0] - self.bbox.x[0]),
+            (d[0], self.bbox.x[1] - p0[0]),
+            (-d[1], p0[1] - self.bbox.y[0]),
+            (d[1], self.bbox.y[1] - p0[1]),
+        ] {
+            if n == 0. {
+                if q < 0. {
+                    return None;
+                }
+            } else if n < 0. {
+                t0 = t0.max(q / n);
+            } else {
+                t1 = t1.min(q / n);
+            }
+        }
+        if t0 > t1 {
+            None
+        } else {
+            Some((p0 + d * t0, p0 + d * t1))
+        }
+    }
+
+    //mi ellipse_arcs_within
+    /// Get the arcs of an axis-aligned ellipse (of a radius in Y, with
+    /// the X radius scaled by the eccentricity) that lie within the
+    /// grid, as start angles and (positive) angles in radians; an
+    /// ellipse entirely within the grid is a single arc of 2.PI
+    fn ellipse_arcs_within(
+        &self,
+        center: Point,
+        radius: f64,
+        eccentricity: f64,
+    ) -> Vec<(f64, f64)> {
+        let rx = radius * eccentricity;
+        let mut angles = vec![0., TAU];
+        let mut add = |a: f64| angles.push(a.rem_euclid(TAU));
+        for x in [self.bbox.x[0], self.bbox.x[1]] {
+            let c = (x - center[0]) / rx;
+            if c.abs() <= 1. {
+                add(c.acos());
+                add(-c.acos());
+            }
+        }
+        for y in [self.bbox.y[0], self.bbox.y[1]] {
+            let s = (y - center[1]) / radius;
+            if s.abs() <= 1. {
+                add(s.asin());
+                add(PI - s.asin());
+            }
+        }
+        angles.sort_by(|a, b| a.total_cmp(b));
+        angles.dedup_by(|a, b| (*a - *b).abs() < 1E-12);
+        let mut arcs: Vec<(f64, f64)> = vec![];
+        for w in angles.windows(2) {
+            let mid = (w[0] + w[1]) / 2.;
+            let p = center + Point::from_array([mid.cos() * rx, mid.sin() * radius]);
+            let within = (self.bbox.x[0]..=self.bbox.x[1]).contains(&p[0])
+                && (self.bbox.y[0]..=self.bbox.y[1]).contains(&p[1]);
+            if !within {
+                continue;
+            }
+            match arcs.last_mut() {
+                Some((start, angle)) if (*start + *angle - w[0]).abs() < 1E-12 => {
+                    *angle = w[1] - *start;
+                }
+                _ => arcs.push((w[0], w[1] - w[0])),
+            }
+        }
+        // An arc ending at 2.PI continues into one starting at 0
+        if arcs.len() > 1 {
+            let (last_start, last_angle) = arcs[arcs.len() - 1];
+            if arcs[0].0 == 0. && last_start + last_angle >= TAU - 1E-12 {
+                arcs.pop();
+                arcs[0] = (last_start, last_angle + arcs[0].1);
+            }
+        }
+        arcs
+    }
+
+    //mi push_polar
+    fn push_polar(
+        &self,
+        r: &mut String,
+        center: Point,
+        eccentricity: f64,
+        spokes: usize,
+        nf: &NumberFormat,
+    ) {
+        let spacing = self.spacings.0;
+        if spacing <= 0. || eccentricity <= 0. {
+            return;
+        }
+        let mut rmax: f64 = 0.;
+        for c in self.bbox.add_as_points(false, vec![]) {
+            let d = c - center;
+            let d: Point = [d[0] / eccentricity, d[1]].into();
+            rmax = rmax.max(d.length());
+        }
+        let n = (rmax / spacing).ceil() as usize;
+        for i in 1..=n {
+            let radius = (i as f64) * spacing;
+            let arcs = self.ellipse_arcs_within(center, radius, eccentricity);
+            if arcs.len() == 1 && arcs[0].1 >= TAU {
+                let bp = BezierPath::of_ellipse(center, radius, eccentricity, 0.);
+                r.push_str(&path_as_str(&bp, true, nf));
+                r.push(' ');
+                continue;
+            }
+            for (start, angle) in arcs {
+                let bp = BezierPath::of_ellipse_arc(center, radius, eccentricity, start, angle);
+                r.push_str(&path_as_str(&bp, false, nf));
+                r.push(' ');
+            }
+        }
+        for i in 0..spokes {
+            let angle = (360.0 * (i as f64) / (spokes as f64)).to_radians();
+            let end = center + Point::from_array([angle.cos() * eccentricity, angle.sin()]) * rmax;
+            if let Some((p0, p1)) = self.clip_line(center, end) {
+                r.push_str(&format!("M {} L {} ", nf.point(&p0), nf.point(&p1)));
+            }
+        }
+    }
 }
 
 //ip SvgElementType for SvgGrid
@@ -195,39 +789,45 @@ impl<'a> SvgElementType<'a> for SvgGrid {
         self.bbox
     }
     /// Push the attributes when ready for rendering as SVG (post-finalize)
-    fn push_attributes(&self, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
-        let xmin = ((self.bbox.x[0] / self.spacings.0) + 0.).floor() as isize;
-        let xmax = ((self.bbox.x[1] / self.spacings.0) + 1.).floor() as isize;
-        let xlen = xmax - xmin;
-
-        let ymin = ((self.bbox.y[0] / self.spacings.1) + 0.).floor() as isize;
-        let ymax = ((self.bbox.y[1] / self.spacings.1) + 1.).floor() as isize;
-        let ylen = ymax - ymin;
-
+    fn push_attributes(&self, nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
         let mut r = String::new();
-        if xlen >= 2 {
-            for i in xmin..=xmax {
-                let coord = (i as f64) * self.spacings.0;
-                r.push_str(&format!(
-                    "M {},{} v {} ",
-                    coord,
-                    self.bbox.y[0],
-                    self.bbox.y[1] - self.bbox.y[0]
-                ));
+        match self.mode {
+            GridMode::Cartesian => {
+                Self::push_lines(
+                    &mut r,
+                    &GridLines::new(self.bbox, self.spacings).as_paths(),
+                    nf,
+                );
             }
-        }
-        if ylen >= 2 {
-            for i in ymin..ymax + 1 {
-                let coord = (i as f64) * self.spacings.1;
-                r.push_str(&format!(
-                    "M {},{} h {} ",
-                    self.bbox.x[0],
-                    coord,
-                    self.bbox.x[1] - self.bbox.x[0]
-                ));
+            GridMode::Rotated { degrees } => {
+                let center = self.bbox.center();
+                let radians = degrees.to_radians();
+                let region = self.bbox.new_rotated_around(&center, -degrees);
+                // The lines cover the rotated region, so clip them to
+                // the grid once they are rotated back
+                let mut lines = vec![];
+                for l in GridLines::new(region, self.spacings)
+                    .set_origin(center)
+                    .as_paths()
+                {
+                    let p0 = l.get_pt(0).rotate_around(&center, radians, 0, 1);
+                    let p1 = l.get_pt(1).rotate_around(&center, radians, 0, 1);
+                    if let Some((p0, p1)) = self.clip_line(p0, p1) {
+                        let mut l = BezierPath::default();
+                        l.add_bezier(Bezier::line(&p0, &p1));
+                        lines.push(l);
+                    }
+                }
+                Self::push_lines(&mut r, &lines, nf);
+            }
+            GridMode::Polar {
+                center,
+                eccentricity,
+                spokes,
+            } => {
+                self.push_polar(&mut r, center, eccentricity, spokes, nf);
             }
         }
-
         attrs.push((NamespaceName::local("d"), r));
     }
 }
@@ -242,17 +842,20 @@ pub struct SvgElement<'a> {
     contents: Vec<SvgElement<'a>>,
     characters: String,
     bbox: BBox,
+    casing: Option<(f64, Color)>,
+    multi_stroke: Option<MultiStroke>,
+    translucent: Vec<(&'a str, Color)>,
+    primitive: Option<(String, Vec<(String, f64)>)>,
+    numbers: Vec<(String, NumberText)>,
+    fold_presentation: bool,
 }
 
 //ip IndentedDisplay for SvgElement
 impl<'a, 'i> IndentedDisplay<'i, IndentOpt> for SvgElement<'a> {
     fn indent(&self, f: &mut Indenter<'i, IndentOpt>) -> Result<(), std::fmt::Error> {
         use std::fmt::Write;
-        write!(f, "{}", self.ele_type.ns_name())?;
-        if !self.transform.is_identity() {
-            write!(f, " {}", self.transform)?;
-        }
-        writeln!(f)?;
+        let line = self.indent_line(f.options());
+        writeln!(f, "{}", line)?;
         {
             let mut sub = f.push("...");
             for c in self.contents.iter() {
@@ -276,6 +879,12 @@ impl<'a> SvgElement<'a> {
             contents: Vec::new(),
             characters: String::new(),
             bbox: BBox::default(),
+            casing: None,
+            multi_stroke: None,
+            translucent: Vec::new(),
+            primitive: None,
+            numbers: Vec::new(),
+            fold_presentation: false,
         }
     }
 
@@ -285,10 +894,54 @@ impl<'a> SvgElement<'a> {
     }
 
     //ap attributes
-    pub fn attributes(&self) -> &[(NamespaceName, String)] {
+    pub fn attributes(&self) -> &[(NamespaceName<'a>, String)] {
         &self.attributes
     }
 
+    //ap written_attributes
+    /// Get the attributes as they are written - with the presentation
+    /// attributes folded into a 'style' attribute if the element was
+    /// finalized with [PresentationMode::StyleAttribute]
+    ///
+    /// The style declarations of the presentation attributes precede
+    /// those of any style attribute already present (which take
+    /// precedence in CSS, as they would over the attributes)
+    pub fn written_attributes(&self) -> Cow<'_, [(NamespaceName<'a>, String)]> {
+        if !self.fold_presentation {
+            return Cow::Borrowed(&self.attributes);
+        }
+        let mut declarations = vec![];
+        let mut style = None;
+        let mut attributes = vec![];
+        for (n, v) in self.attributes.iter() {
+            let name = n.to_string();
+            if PRESENTATION_ATTRIBUTES.contains(&name.as_str()) {
+                declarations.push(format!("{}:{}", name, v));
+            } else if name == "style" {
+                style = Some(v.trim().trim_end_matches(';'));
+            } else {
+                attributes.push((n.clone(), v.clone()));
+            }
+        }
+        if let Some(style) = style.filter(|s| !s.is_empty()) {
+            declarations.push(style.to_string());
+        }
+        if !declarations.is_empty() {
+            attributes.push((NamespaceName::local("style"), declarations.join(";")));
+        }
+        Cow::Owned(attributes)
+    }
+
+    //ap get_attribute
+    /// Get the value of an attribute given its name (including any
+    /// namespace prefix, e.g. 'xlink:href')
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n.to_string() == name)
+            .map(|(_, v)| v.as_str())
+    }
+
     //ap characters
     pub fn characters(&self) -> &str {
         &self.characters
@@ -299,6 +952,11 @@ impl<'a> SvgElement<'a> {
         &self.contents
     }
 
+    //ap contents_mut
+    pub fn contents_mut(&mut self) -> &mut [Self] {
+        &mut self.contents
+    }
+
     //ap transform
     pub fn transform(&self) -> &Transform {
         &self.transform
@@ -315,16 +973,126 @@ impl<'a> SvgElement<'a> {
         self.attributes.push((ns_name, value.into()));
     }
 
+    //fp set_attribute
+    /// Set an attribute, replacing any existing value of it
+    pub fn set_attribute(&mut self, name: &'a str, prefix: Option<&'a str>, value: &str) {
+        let ns_name = NamespaceName::new(name, prefix);
+        let full_name = ns_name.to_string();
+        self.numbers.retain(|(n, _)| *n != full_name);
+        self.attributes.retain(|(n, _)| *n != ns_name);
+        self.attributes.push((ns_name, value.into()));
+    }
+
+    //fp set_local_attribute
+    /// Set an attribute without a namespace prefix, whose name need
+    /// not be static, replacing any existing value of it
+    pub fn set_local_attribute(&mut self, name: &str, value: &str) {
+        let ns_name = NamespaceName::local(name.to_string());
+        self.numbers.retain(|(n, _)| n != name);
+        self.attributes.retain(|(n, _)| *n != ns_name);
+        self.attributes.push((ns_name, value.into()));
+    }
+
+    //fp add_numbers
+    /// Add an attribute whose value contains numbers, which are
+    /// formatted with the [NumberFormat] of the configuration when the
+    /// element is finalized
+    pub(crate) fn add_numbers(&mut self, name: &'a str, value: NumberText) {
+        self.add_attribute(name, None, &value.format(&NumberFormat::default()));
+        self.numbers.push((name.to_string(), value));
+    }
+
+    //fp set_local_numbers
+    /// Set an attribute without a namespace prefix whose value contains
+    /// numbers, as for [Self::add_numbers], replacing any existing
+    /// value of it
+    pub(crate) fn set_local_numbers(&mut self, name: &str, value: NumberText) {
+        self.set_local_attribute(name, &value.format(&NumberFormat::default()));
+        self.numbers.push((name.to_string(), value));
+    }
+
+    //mi take_numbers
+    /// Format the attributes that contain numbers with a number format
+    fn take_numbers(&mut self, nf: &NumberFormat) {
+        for (name, value) in std::mem::take(&mut self.numbers) {
+            if let Some(a) = self
+                .attributes
+                .iter_mut()
+                .rev()
+                .find(|(n, _)| n.to_string() == name)
+            {
+                a.1 = value.format(nf);
+            }
+        }
+    }
+
+    //fp remove_attribute
+    /// Remove an attribute given its name (including any namespace
+    /// prefix), returning its value if it was present
+    pub fn remove_attribute(&mut self, name: &str) -> Option<String> {
+        let n = self
+            .attributes
+            .iter()
+            .position(|(n, _)| n.to_string() == name)?;
+        self.numbers.retain(|(n, _)| n != name);
+        Some(self.attributes.remove(n).1)
+    }
+
+    //fp set_id
+    /// Set the 'id' of the element, replacing any existing id
+    ///
+    /// The id is reserved when the element is added to the contents of
+    /// a document, so that generated ids do not clash with it
+    pub fn set_id(&mut self, id: &str) {
+        self.set_local_attribute("id", id);
+    }
+
+    //ap id
+    /// Get the 'id' of the element, if any
+    pub fn id(&self) -> Option<&str> {
+        self.get_attribute("id")
+    }
+
+    //mp find_by_id
+    /// Find the element (this or a descendant) with an id
+    pub fn find_by_id(&self, id: &str) -> Option<&Self> {
+        if self.id() == Some(id) {
+            return Some(self);
+        }
+        self.contents.iter().find_map(|c| c.find_by_id(id))
+    }
+
+    //mp find_by_id_mut
+    /// Find the element (this or a descendant) with an id, mutably
+    pub fn find_by_id_mut(&mut self, id: &str) -> Option<&mut Self> {
+        if self.id() == Some(id) {
+            return Some(self);
+        }
+        self.contents.iter_mut().find_map(|c| c.find_by_id_mut(id))
+    }
+
     //fp push_string
     pub fn push_string(&mut self, s: &str) {
         self.characters.push_str(s);
     }
 
+    //fp set_characters
+    /// Replace the characters of the element
+    pub fn set_characters(&mut self, s: &str) {
+        self.characters = s.into();
+    }
+
     //fp apply_transform
     pub fn apply_transform(&mut self, transform: &Transform) {
         self.transform = transform.apply_to_transform(&self.transform);
     }
 
+    //fp set_transform
+    /// Replace the transform of the element
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
     //fp transform_inner
     pub fn transform_inner(&mut self, transform: &Transform) {
         self.transform = self.transform.apply_to_transform(transform);
@@ -332,17 +1100,97 @@ impl<'a> SvgElement<'a> {
 
     //fp add_size
     pub fn add_size(&mut self, name: &'a str, value: f64) {
-        self.add_attribute(name, None, &format!("{:.4}", value));
+        self.add_numbers(name, value.into());
     }
 
     //fp add_color
-    pub fn add_color<'c, T>(&mut self, attr_name: &'a str, color: T)
+    /// Add a color attribute
+    ///
+    /// An error is returned (and no attribute added) if a color name
+    /// is not known
+    pub fn add_color<'c, T>(&mut self, attr_name: &'a str, color: T) -> Result<(), Error>
     where
-        (T, &'c ColorDatabase<'c>): Into<Color>,
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
     {
-        let color: Color = (color, &SvgColorDatabase).into();
-        let color = color.as_str();
-        self.add_attribute(attr_name, None, &color);
+        let color: Color = (color, &SvgColorDatabase).try_into()?;
+        self.add_color_value(attr_name, color);
+        Ok(())
+    }
+
+    //fp add_color_value
+    /// Add a color attribute of a color that has already been found
+    pub(crate) fn add_color_value(&mut self, attr_name: &'a str, color: Color) {
+        self.add_attribute(attr_name, None, &color.as_str());
+        self.note_translucent(attr_name, color);
+    }
+
+    //fi note_translucent
+    /// Record a color attribute that has transparency, so that it may
+    /// be split into a color and an opacity when finalized
+    fn note_translucent(&mut self, attr_name: &'a str, color: Color) {
+        self.translucent.retain(|(n, _)| *n != attr_name);
+        if color.as_tuple_rgba().3 != 255 {
+            self.translucent.push((attr_name, color));
+        }
+    }
+
+    //mi take_translucent
+    /// If required by the configuration, replace color attributes with
+    /// transparency by an opaque color and an opacity attribute
+    ///
+    /// The opacity attribute for 'fill' is 'fill-opacity', and for
+    /// 'stop-color' is 'stop-opacity'
+    fn take_translucent(&mut self, svg_cfg: &SvgConfig) {
+        if !svg_cfg.opacity_attributes {
+            return;
+        }
+        for (name, color) in std::mem::take(&mut self.translucent) {
+            let opacity = (color.as_tuple_rgba().3 as f64) / 255.;
+            self.set_attribute(name, None, &color.set_alpha(255).as_str());
+            let opacity_name = format!("{}-opacity", name.strip_suffix("-color").unwrap_or(name));
+            self.attributes.push((
+                NamespaceName::local(opacity_name),
+                svg_cfg.number_format.number(opacity),
+            ));
+        }
+    }
+
+    //fp set_primitive
+    /// Record the primitive that the element draws (such as a
+    /// 'chevron' or a 'gear') with its parameters
+    ///
+    /// If the configuration has semantic classes enabled, then when
+    /// the element is finalized the name of the primitive is added to
+    /// its classes, and each parameter becomes a `data-<name>`
+    /// attribute - so that CSS, scripts and tests may find (say) all
+    /// of the arrows of a document
+    pub fn set_primitive(&mut self, name: &str, parameters: &[(&str, f64)]) {
+        let parameters = parameters
+            .iter()
+            .map(|(n, v)| (n.to_string(), *v))
+            .collect();
+        self.primitive = Some((name.into(), parameters));
+    }
+
+    //mi take_primitive
+    /// If required by the configuration, add the class and data
+    /// attributes of the primitive the element draws
+    fn take_primitive(&mut self, svg_cfg: &SvgConfig) {
+        if !svg_cfg.semantic_classes {
+            return;
+        }
+        let Some((name, parameters)) = self.primitive.take() else {
+            return;
+        };
+        let class = match self.get_attribute("class") {
+            Some(c) if c.split_whitespace().any(|c| c == name) => c.to_string(),
+            Some(c) => format!("{} {}", c, name),
+            None => name,
+        };
+        self.set_local_attribute("class", &class);
+        for (n, v) in parameters {
+            self.set_local_attribute(&format!("data-{}", n), &svg_cfg.number_format.number(v));
+        }
     }
 
     //fp add_markers
@@ -358,26 +1206,332 @@ impl<'a> SvgElement<'a> {
         }
     }
 
+    //fp set_casing
+    /// Case the element when it is finalized, as is done for roads on
+    /// maps: the element is stroked underneath with a stroke wider by
+    /// `width` on each side in the given color, so that a thin stroke
+    /// stands out on a busy background
+    ///
+    /// This is a [MultiStroke] of the casing and the stroke of the
+    /// element (from its 'stroke' and 'stroke-width' attributes)
+    ///
+    /// An error is returned if a color name is not known
+    pub fn set_casing<'c, T>(&mut self, width: f64, color: T) -> Result<(), Error>
+    where
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        self.casing = Some((width, (color, &SvgColorDatabase).try_into()?));
+        Ok(())
+    }
+
+    //fp set_multi_stroke
+    /// Render the element as a [MultiStroke] when it is finalized
+    ///
+    /// The stroke attributes of the element are replaced by those of
+    /// the passes
+    pub fn set_multi_stroke(&mut self, multi_stroke: MultiStroke) {
+        self.multi_stroke = Some(multi_stroke);
+    }
+
+    //fi take_multi_stroke
+    /// If the element has a multi-stroke (or casing) then replace it
+    /// with a group of a 'defs' holding the geometry and a 'use' of
+    /// that for each pass
+    fn take_multi_stroke(&mut self) {
+        let multi_stroke = match (self.multi_stroke.take(), self.casing.take()) {
+            (Some(multi_stroke), _) => multi_stroke,
+            (None, Some((width, color))) => {
+                let stroke_width = self
+                    .get_attribute("stroke-width")
+                    .and_then(|s| s.parse::<f64>().ok())
+                    .unwrap_or(1.);
+                let stroke = self.get_attribute("stroke").unwrap_or("black").to_string();
+                let dash = self
+                    .get_attribute("stroke-dasharray")
+                    .map(|d| {
+                        d.split(|c: char| c == ',' || c.is_whitespace())
+                            .filter_map(|v| v.parse::<f64>().ok())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let mut multi_stroke = MultiStroke::new();
+                multi_stroke.push_pass(
+                    stroke_width + 2. * width,
+                    color.as_str().to_string(),
+                    vec![],
+                );
+                multi_stroke.push_pass(stroke_width, stroke, dash);
+                multi_stroke
+            }
+            _ => {
+                return;
+            }
+        };
+        if multi_stroke.is_empty() {
+            return;
+        }
+
+        let id = match self.get_attribute("id") {
+            Some(id) => format!("{}-geometry", id),
+            None => format!("geometry-{:016x}", self.stable_hash()),
+        };
+
+        let mut geometry = std::mem::replace(self, SvgGroup::new());
+        let mut fill = None;
+        for (n, v) in std::mem::take(&mut geometry.attributes) {
+            match n.to_string().as_str() {
+                "id" | "class" => self.attributes.push((n, v)),
+                "fill" => fill = Some(v),
+                "stroke" | "stroke-width" | "stroke-dasharray" => (),
+                _ => geometry.attributes.push((n, v)),
+            }
+        }
+        geometry.add_attribute("id", None, &id);
+        let mut defs = SvgDefs::new();
+        defs.push_content(geometry);
+        self.push_content(defs);
+
+        for (i, pass) in multi_stroke.passes().into_iter().enumerate() {
+            let mut u = SvgTag::new("use", None);
+            u.add_attribute("href", None, &format!("#{}", id));
+            match (i, &fill) {
+                (0, Some(fill)) => u.add_attribute("fill", None, fill),
+                _ => u.add_attribute("fill", None, "none"),
+            }
+            u.add_attribute("stroke", None, &pass.color);
+            u.add_size("stroke-width", pass.width);
+            if let Some(dash) = pass.dash_text() {
+                u.add_numbers("stroke-dasharray", dash);
+            }
+            self.push_content(u);
+        }
+    }
+
+    //fi take_hatch_fill
+    /// If the configuration requires hatched fills and the element is
+    /// a closed path with a fill color, then replace it with a group of
+    /// hatch lines in that color and the element without a fill
+    fn take_hatch_fill(&mut self, svg_cfg: &SvgConfig) {
+        let Some(hatch) = &svg_cfg.hatch_fill else {
+            return;
+        };
+        let paths = self.ele_type.closed_paths();
+        if paths.is_empty() {
+            return;
+        }
+        let fill = match self.get_attribute("fill") {
+            None => return,
+            Some(fill) if fill.eq_ignore_ascii_case("none") || fill.starts_with("url(") => {
+                return;
+            }
+            Some(fill) => fill.to_string(),
+        };
+        let mut lines = SvgLines::new(hatch.lines_of_paths(&paths));
+        lines.add_attribute("class", None, "hatch");
+        lines.add_attribute("fill", None, "none");
+        lines.add_attribute("stroke", None, &fill);
+        lines.add_size("stroke-width", hatch.line_width);
+        lines.transform = self.transform;
+
+        let mut element = std::mem::replace(self, SvgGroup::new());
+        element.attributes.retain(|(n, _)| n.to_string() != "fill");
+        element.add_attribute("fill", None, "none");
+        self.push_content(lines);
+        self.push_content(element);
+    }
+
+    //mp shape_polylines
+    /// Get the geometry of the element itself (not its contents)
+    /// flattened to polylines, in the coordinates of the element (i.e.
+    /// prior to its transform), for output to plotters
+    pub fn shape_polylines(&self, tolerance: f64) -> Vec<Vec<Point>> {
+        self.ele_type.polylines(tolerance)
+    }
+
+    //mp path_length
+    /// Get the length of the geometry of the element itself (not its
+    /// contents), prior to its transform
+    pub fn path_length(&self, tolerance: f64) -> f64 {
+        let mut length = 0.;
+        for l in self.shape_polylines(tolerance) {
+            for p in l.windows(2) {
+                length += (p[1] - p[0]).length();
+            }
+        }
+        length
+    }
+
+    //mp animate_draw
+    /// Animate the element being drawn over a duration (in seconds),
+    /// by dashing its stroke with a single dash of its length and
+    /// animating the dash offset from the length down to zero
+    ///
+    /// The length of the path is returned; an element without
+    /// geometry (such as a group) is not animated, and zero is
+    /// returned
+    pub fn animate_draw(&mut self, duration: f64) -> f64 {
+        let length = self.path_length(0.05);
+        if length <= 0. {
+            return 0.;
+        }
+        self.set_local_numbers("stroke-dasharray", length.into());
+        self.set_local_numbers("stroke-dashoffset", length.into());
+        let mut animate = SvgTag::new("animate", None);
+        animate.add_attribute("attributeName", None, "stroke-dashoffset");
+        animate.add_numbers("from", length.into());
+        animate.add_attribute("to", None, "0");
+        animate.add_numbers("dur", NumberText::of_seconds(duration));
+        animate.add_attribute("fill", None, "freeze");
+        self.push_content(animate);
+        length
+    }
+
     //fp push_content
     pub fn push_content(&mut self, e: Self) {
         self.contents.push(e);
     }
 
-    //mp finalize
-    pub fn finalize(&mut self, svg_cfg: &SvgConfig) -> Vec<Self> {
+    //fp insert_content
+    /// Insert an element into the contents at an index
+    pub fn insert_content(&mut self, n: usize, e: Self) {
+        self.contents.insert(n, e);
+    }
+
+    //fp retain_contents
+    /// Retain only the contents for which a function returns true
+    pub fn retain_contents<F: FnMut(&Self) -> bool>(&mut self, f: F) {
+        self.contents.retain(f);
+    }
+
+    //mp stable_hash
+    /// Get a hash of the element and its contents that is stable
+    /// across runs and Rust releases, for generating ids of
+    /// definitions
+    ///
+    /// The hash covers the name, the attributes (in order of name,
+    /// including those of the element type and the transform), the
+    /// characters and the contents; it is the same whether or not the
+    /// element has been finalized, and excludes any 'id'
+    pub(crate) fn stable_hash(&self) -> u64 {
+        let mut hasher = StableHasher::default();
+        self.add_to_hasher(&mut hasher);
+        hasher.finish()
+    }
+
+    //mi add_to_hasher
+    fn add_to_hasher(&self, hasher: &mut StableHasher) {
+        hasher.add(b"<");
+        hasher.add(self.ele_type.ns_name().to_string().as_bytes());
+        let mut type_attrs = vec![];
+        self.ele_type
+            .push_attributes(&NumberFormat::default(), &mut type_attrs);
         let transform = self.transform.as_svg_attribute_string();
+        let mut attrs: Vec<(String, &str)> = type_attrs
+            .iter()
+            .chain(self.attributes.iter())
+            .map(|(n, v)| (n.to_string(), v.as_str()))
+            .filter(|(n, _)| n != "id")
+            .collect();
+        if !transform.is_empty() {
+            attrs.push(("transform".into(), &transform));
+        }
+        attrs.sort();
+        attrs.dedup();
+        for (n, v) in attrs {
+            hasher.add(n.as_bytes());
+            hasher.add(v.as_bytes());
+        }
+        hasher.add(b"\"");
+        hasher.add(self.characters.as_bytes());
+        for c in self.contents.iter() {
+            c.add_to_hasher(hasher);
+        }
+        hasher.add(b">");
+    }
+
+    //mp finalized_copy
+    /// Create a deep copy of a finalized element and its contents
+    ///
+    /// The copy has the name, attributes, characters and bbox of the
+    /// element; it cannot be finalized again
+    pub(crate) fn finalized_copy(&self) -> Self {
+        let mut e = Self::new_finalized(
+            self.ele_type.ns_name(),
+            self.attributes.clone(),
+            self.characters.clone(),
+            self.bbox,
+            self.contents.iter().map(|c| c.finalized_copy()).collect(),
+        );
+        e.fold_presentation = self.fold_presentation;
+        e
+    }
+
+    //fp new_finalized
+    /// Create an element that is already finalized, from its parts
+    pub(crate) fn new_finalized(
+        name: NamespaceName<'a>,
+        attributes: Vec<(NamespaceName<'a>, String)>,
+        characters: String,
+        bbox: BBox,
+        contents: Vec<Self>,
+    ) -> Self {
+        let mut e = Self::new(SvgCopy { name });
+        e.attributes = attributes;
+        e.characters = characters;
+        e.bbox = bbox;
+        e.contents = contents;
+        e
+    }
+
+    //fp truncate_contents
+    /// Remove all but the first `n` contents of the element
+    pub fn truncate_contents(&mut self, n: usize) {
+        self.contents.truncate(n);
+    }
+
+    //mp finalize
+    /// Finalize the element and its contents, generating their
+    /// attributes and bounding boxes, and returning any extra elements
+    /// that must follow the element in its parent
+    ///
+    /// An error is returned if the geometry of the element or its
+    /// contents is not a number
+    pub fn finalize(&mut self, svg_cfg: &SvgConfig) -> Result<Vec<Self>, Error> {
+        let nf = &svg_cfg.number_format;
+        let fold_presentation = svg_cfg.presentation_mode == PresentationMode::StyleAttribute;
+        self.take_numbers(nf);
+        self.take_translucent(svg_cfg);
+        self.take_primitive(svg_cfg);
+        self.take_multi_stroke();
+        self.take_hatch_fill(svg_cfg);
+        let transform = self.transform.svg_attribute_string(nf);
 
-        let mut bbox = BBox::none();
         let mut child_extra = vec![];
         for c in self.contents.iter_mut() {
-            child_extra.append(&mut c.finalize(svg_cfg));
-            bbox = bbox.union(c.bbox());
+            child_extra.append(&mut c.finalize(svg_cfg)?);
         }
+        let bbox: BBox = self.contents.iter().map(|c| c.bbox()).collect();
 
         // Children are finalized now
         self.ele_type
             .finalize(svg_cfg, &self.contents, &self.characters);
-        self.bbox = bbox.union(self.ele_type.bbox());
+        let own_bbox = self.ele_type.bbox();
+        if [&own_bbox.x, &own_bbox.y]
+            .iter()
+            .any(|r| r[0].is_nan() || r[1].is_nan())
+        {
+            let e = Error::BadConstruction {
+                reason: format!(
+                    "the geometry of a '{}' element is not a number",
+                    self.ele_type.ns_name()
+                ),
+            };
+            return Err(match self.id() {
+                Some(id) => e.context(format!("finalizing '{}'", id)),
+                None => e,
+            });
+        }
+        self.bbox = bbox.union(own_bbox);
 
         for c in child_extra {
             self.contents.push(c);
@@ -389,24 +1543,96 @@ impl<'a> SvgElement<'a> {
             if !transform.is_empty() {
                 e.add_attribute("transform", None, &transform);
             }
-            e.ele_type.push_attributes(&mut e.attributes);
+            e.take_numbers(nf);
+            e.ele_type.push_attributes(nf, &mut e.attributes);
+            e.fold_presentation = fold_presentation;
             extra.push(e);
         }
         self.bbox = self.bbox.transform(&self.transform);
         if !transform.is_empty() {
             self.add_attribute("transform", None, &transform);
         }
-        self.ele_type.push_attributes(&mut self.attributes);
-        extra
+        self.ele_type.push_attributes(nf, &mut self.attributes);
+        self.fold_presentation = fold_presentation;
+        Ok(extra)
+    }
+
+    //mi indent_line
+    /// Get the line for the element in its indented display: its name
+    /// and transform, and as required by the options its bbox, a
+    /// summary of its style, and its attributes
+    fn indent_line(&self, opts: &IndentOpt) -> String {
+        let mut r = self.ele_type.ns_name().to_string();
+        if !self.transform.is_identity() {
+            r.push_str(&format!(" {}", self.transform));
+        }
+        if opts.bboxes {
+            r.push_str(&format!(" bbox {}", self.bbox));
+        }
+        if opts.styles {
+            let style: Vec<String> = STYLE_SUMMARY_ATTRIBUTES
+                .iter()
+                .filter_map(|n| self.get_attribute(n).map(|v| format!("{}:{}", n, v)))
+                .collect();
+            if !style.is_empty() {
+                r.push_str(&format!(" {{{}}}", style.join(";")));
+            }
+        }
+        if opts.attributes {
+            for (n, v) in self.attributes.iter() {
+                r.push_str(&format!(" {}={:?}", n, v));
+            }
+        }
+        r
+    }
+
+    //mp measure
+    /// Determine the bounding box of the element and its contents (in
+    /// the coordinates of its parent) as [SvgElement::finalize] does,
+    /// but without generating its attributes, so that the geometry of
+    /// a document may be found quickly
+    ///
+    /// The element may still be finalized afterwards
+    pub fn measure(&mut self, svg_cfg: &SvgConfig) -> BBox {
+        for c in self.contents.iter_mut() {
+            c.measure(svg_cfg);
+        }
+        self.ele_type
+            .finalize(svg_cfg, &self.contents, &self.characters);
+        let bbox: BBox = self.contents.iter().map(|c| c.bbox()).collect();
+        self.bbox = bbox.union(self.ele_type.bbox()).transform(&self.transform);
+        self.bbox
+    }
+
+    //mp clamp_to_viewbox
+    /// Clamp any open-ended contents to the viewbox of the document,
+    /// given in the coordinates of the parent of this element
+    pub fn clamp_to_viewbox(&mut self, viewbox: &BBox, svg_cfg: &SvgConfig) {
+        let viewbox = viewbox.transform(&self.transform.inverse());
+        for c in self.contents.iter_mut() {
+            c.clamp_to_viewbox(&viewbox, svg_cfg);
+        }
+        if self.ele_type.clamp_to_viewbox(&viewbox) {
+            // The element has already been finalized, so replace the
+            // attributes it pushed then rather than duplicating them
+            let mut attrs = vec![];
+            self.ele_type
+                .push_attributes(&svg_cfg.number_format, &mut attrs);
+            for (n, v) in attrs {
+                let name = n.to_string();
+                self.attributes.retain(|(a, _)| a.to_string() != name);
+                self.attributes.push((n, v));
+            }
+        }
     }
 
     //cp new_box
     /// Create a box for a BBox
     pub fn new_box(bbox: BBox, line_width: f64, color: &Color) -> Self {
         let mut e = SvgPath::new_box(bbox);
-        e.add_color("fill", "none");
-        e.add_color("stroke", color);
-        e.add_attribute("stroke-width", None, &format!("{:.4}", line_width));
+        e.add_color_value("fill", Color::none());
+        e.add_color_value("stroke", color.clone());
+        e.add_numbers("stroke-width", line_width.into());
         e
     }
 
@@ -414,24 +1640,282 @@ impl<'a> SvgElement<'a> {
     /// Create a grid element with given region, spacing, line
     /// width and color
     pub fn new_grid(bbox: BBox, spacing: f64, line_width: f64, color: &str) -> Self {
-        let mut grid = SvgGrid::new(bbox, (spacing, spacing));
+        Self::new_grid_of_mode(bbox, GridMode::Cartesian, spacing, line_width, color)
+    }
+
+    //cp new_grid_of_mode
+    /// Create a grid element of a given mode with given region,
+    /// spacing, line width and color
+    pub fn new_grid_of_mode(
+        bbox: BBox,
+        mode: GridMode,
+        spacing: f64,
+        line_width: f64,
+        color: &str,
+    ) -> Self {
+        let mut grid = SvgGrid::new_of_mode(bbox, (spacing, spacing), mode);
         grid.add_attribute("fill", None, "None");
         grid.add_attribute("stroke", None, color);
-        grid.add_attribute("stroke-width", None, &format!("{}", line_width));
+        grid.add_numbers("stroke-width", line_width.into());
         grid
     }
 
-    //fp display
-    pub fn display(&self, indent: usize) {
-        let indent_str = &INDENT_STRING[0..indent];
-        println!("{}{}", indent_str, self.ele_type.ns_name());
-        for (n, v) in &self.attributes {
-            println!("{}      {}={}", indent_str, n, v);
+    //zz All done
+}
+
+//a Test
+#[cfg(test)]
+mod test_element {
+    use super::*;
+    use crate::svg::number_str;
+    fn grid_path(mode: GridMode) -> String {
+        let bbox = BBox::new(0., 0., 10., 6.);
+        let mut e = SvgElement::new_grid_of_mode(bbox, mode, 2., 0.1, "grey");
+        e.finalize(&SvgConfig::default()).unwrap();
+        e.get_attribute("d").unwrap().to_string()
+    }
+    fn line_ends(d: &str) -> Vec<(f64, f64)> {
+        d.split_whitespace()
+            .filter_map(|t| t.split_once(','))
+            .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
+            .collect()
+    }
+    fn assert_within(pts: &[(f64, f64)]) {
+        for (x, y) in pts {
+            assert!(
+                (-1E-6..=10. + 1E-6).contains(x) && (-1E-6..=6. + 1E-6).contains(y),
+                "Point {x},{y} outside the grid"
+            );
         }
-        for e in &self.contents {
-            e.display(indent + 2);
+    }
+    #[test]
+    fn test_grid_cartesian() {
+        let d = grid_path(GridMode::Cartesian);
+        let pts = line_ends(&d);
+        assert_within(&pts);
+        // Six vertical and four horizontal lines
+        assert_eq!(d.matches('M').count(), 10);
+        assert!(pts.contains(&(0., 0.)) && pts.contains(&(10., 6.)));
+    }
+    #[test]
+    fn test_grid_rotated() {
+        let d = grid_path(GridMode::Rotated { degrees: 30. });
+        let pts = line_ends(&d);
+        assert!(!pts.is_empty());
+        assert_within(&pts);
+        // Every line is clipped to end on the boundary of the grid
+        for (x, y) in &pts {
+            let on_edge =
+                x.abs() < 1E-3 || (x - 10.).abs() < 1E-3 || y.abs() < 1E-3 || (y - 6.).abs() < 1E-3;
+            assert!(on_edge, "Line end {x},{y} is not on the boundary");
         }
     }
+    #[test]
+    fn test_grid_polar() {
+        let center = [5., 3.].into();
+        let d = grid_path(GridMode::Polar {
+            center,
+            eccentricity: 1.,
+            spokes: 8,
+        });
+        // Circles out to the corners, and 8 clipped spokes
+        let circles = d.matches('C').count();
+        assert!(circles > 0);
+        let spokes: Vec<&str> = d
+            .split('M')
+            .filter(|s| s.contains('L') && !s.contains('C'))
+            .collect();
+        assert_eq!(spokes.len(), 8);
+        for s in spokes {
+            assert_within(&line_ends(s));
+        }
 
-    //zz All done
+        // Rings about a center away from the middle are clipped to
+        // arcs that end on the boundary of the grid
+        let d = grid_path(GridMode::Polar {
+            center: [1., 1.].into(),
+            eccentricity: 1.,
+            spokes: 0,
+        });
+        let arcs: Vec<&str> = d.split('M').filter(|s| s.contains('C')).collect();
+        assert!(!arcs.is_empty());
+        for a in arcs {
+            assert!(!a.contains('z'), "Ring {a} is not clipped");
+            let pts = line_ends(a);
+            let ends = [pts[0], pts[pts.len() - 1]];
+            assert_within(&ends);
+            for (x, y) in ends {
+                let on_edge = x.abs() < 1E-3
+                    || (x - 10.).abs() < 1E-3
+                    || y.abs() < 1E-3
+                    || (y - 6.).abs() < 1E-3;
+                assert!(on_edge, "Arc end {x},{y} is not on the boundary");
+            }
+        }
+    }
+    #[test]
+    fn test_animate_draw() {
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 10., 5.));
+        let length = e.animate_draw(2.);
+        assert!((length - 30.).abs() < 1E-6);
+        assert_eq!(
+            e.get_attribute("stroke-dasharray"),
+            Some(number_str(30.).as_str())
+        );
+        assert_eq!(
+            e.contents()[0].get_attribute("dur"),
+            Some(format!("{}s", number_str(2.)).as_str())
+        );
+        assert_eq!(SvgGroup::new().animate_draw(2.), 0.);
+    }
+    #[test]
+    fn test_casing() {
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        e.add_attribute("id", None, "road");
+        e.add_color("stroke", "red").unwrap();
+        e.add_attribute("stroke-width", None, "1");
+        e.set_casing(0.5, "white").unwrap();
+        e.finalize(&SvgConfig::default()).unwrap();
+        let xml: String = crate::ElementIter::new(&e).map(|ev| ev.as_xml()).collect();
+        assert!(xml.contains(r#"<g id="road"><defs>"#), "{xml}");
+        // The wider casing is drawn first, underneath the stroke
+        let casing = xml.find(r#"stroke="white""#).unwrap();
+        let stroke = xml.find(r#"stroke="red""#).unwrap();
+        assert!(casing < stroke, "{xml}");
+        assert_eq!(
+            e.contents()[1].get_attribute("stroke-width"),
+            Some(number_str(2.).as_str())
+        );
+        assert_eq!(
+            e.contents()[2].get_attribute("stroke-width"),
+            Some(number_str(1.).as_str())
+        );
+    }
+    #[test]
+    fn test_opacity_attributes() {
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+        e.add_color("fill", (255, 0, 0, 128)).unwrap();
+        e.add_color("stroke", "blue").unwrap();
+        e.finalize(&SvgConfig::default()).unwrap();
+        assert_eq!(e.get_attribute("fill"), Some("rgba(255,0,0,128)"));
+
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+        e.add_color("fill", (255, 0, 0, 128)).unwrap();
+        e.add_color("stroke", "blue").unwrap();
+        e.finalize(&SvgConfig::default().set_opacity_attributes(true))
+            .unwrap();
+        assert_eq!(e.get_attribute("fill"), Some("#ff0000"));
+        assert_eq!(
+            e.get_attribute("fill-opacity"),
+            Some(number_str(128. / 255.).as_str())
+        );
+        assert_eq!(e.get_attribute("stroke"), Some("blue"));
+        assert_eq!(e.get_attribute("stroke-opacity"), None);
+    }
+    #[test]
+    fn test_presentation_mode() {
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+        e.add_color("fill", "red").unwrap();
+        e.add_attribute("stroke-width", None, "2");
+        e.add_attribute("style", None, "stroke:blue;");
+        e.add_attribute("class", None, "box");
+        let cfg = SvgConfig::default().set_presentation_mode(PresentationMode::StyleAttribute);
+        e.finalize(&cfg).unwrap();
+        // The attributes are folded only when written
+        assert_eq!(e.get_attribute("fill"), Some("red"));
+        let written = e.written_attributes();
+        let get = |name: &str| {
+            written
+                .iter()
+                .find(|(n, _)| n.to_string() == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("fill"), None);
+        assert_eq!(get("stroke-width"), None);
+        assert_eq!(get("style"), Some("fill:red;stroke-width:2;stroke:blue"));
+        assert_eq!(get("class"), Some("box"));
+        assert!(get("d").is_some());
+
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+        e.add_color("fill", "red").unwrap();
+        e.finalize(&SvgConfig::default()).unwrap();
+        assert_eq!(e.get_attribute("fill"), Some("red"));
+        assert_eq!(e.get_attribute("style"), None);
+        assert_eq!(e.written_attributes().len(), e.attributes().len());
+    }
+    #[test]
+    fn test_semantic_classes() {
+        let shape = Shape::new_chevron(4., 2., 1.);
+        let cfg = SvgConfig::default().set_semantic_classes(true);
+        let mut e = SvgCompoundPath::new_shape(&shape);
+        e.add_attribute("class", None, "flow");
+        e.finalize(&cfg).unwrap();
+        assert_eq!(e.get_attribute("class"), Some("flow chevron"));
+        assert_eq!(e.get_attribute("data-width"), Some(number_str(4.).as_str()));
+        assert_eq!(e.get_attribute("data-depth"), Some(number_str(1.).as_str()));
+
+        let mut e = SvgCompoundPath::new_gear(&Gear::new_involute(12, 2., 20.));
+        e.finalize(&cfg).unwrap();
+        assert_eq!(e.get_attribute("class"), Some("gear"));
+        assert_eq!(
+            e.get_attribute("data-teeth"),
+            Some(number_str(12.).as_str())
+        );
+
+        let mut e = SvgCompoundPath::new_shape(&shape);
+        e.finalize(&SvgConfig::default()).unwrap();
+        assert_eq!(e.get_attribute("class"), None);
+        assert_eq!(e.get_attribute("data-width"), None);
+    }
+    #[test]
+    fn test_indent_line() {
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 1., 2.));
+        e.add_attribute("class", None, "box");
+        e.add_attribute("fill", None, "red");
+        e.finalize(&SvgConfig::default()).unwrap();
+        assert_eq!(e.indent_line(&IndentOpt::new()), "path");
+        let line = e.indent_line(&IndentOpt::new().set_bboxes(true).set_styles(true));
+        assert_eq!(
+            line,
+            format!("path bbox {} {{class:box;fill:red}}", e.bbox())
+        );
+        let line = e.indent_line(&IndentOpt::new().set_attributes(true));
+        assert!(
+            line.starts_with(r#"path class="box" fill="red" d="M"#),
+            "{line}"
+        );
+    }
+    #[test]
+    fn test_post_processor() {
+        use crate::Svg;
+        let mut svg = Svg::new(SvgConfig::default());
+        svg.add_post_processor(|e| {
+            e.remove_attribute("class");
+        });
+        svg.add_post_processor(|e| {
+            if e.ns_name().to_string() == "path" {
+                e.set_local_attribute("data-track", "plot");
+            }
+        });
+        let mut g = SvgGroup::new();
+        g.add_attribute("class", None, "outer");
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 10., 5.));
+        p.add_attribute("class", None, "inner");
+        g.push_content(p);
+        svg.contents_add_element(g);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let g = &svg.root_element().unwrap().contents()[0];
+        assert_eq!(g.get_attribute("class"), None);
+        assert_eq!(g.get_attribute("data-track"), None);
+        assert_eq!(g.contents()[0].get_attribute("class"), None);
+        assert_eq!(g.contents()[0].get_attribute("data-track"), Some("plot"));
+    }
+    #[test]
+    fn test_group_opacity() {
+        let g = SvgGroup::with_opacity(1.5);
+        assert_eq!(g.get_attribute("opacity"), Some(number_str(1.).as_str()));
+        let g = SvgGroup::with_opacity(0.25);
+        assert_eq!(g.get_attribute("opacity"), Some(number_str(0.25).as_str()));
+    }
 }
@@ -0,0 +1,107 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_element_data.rs
+@brief   Owned, serializable representation of a finalized element tree
+ */
+
+//a Imports
+use crate::{BBox, NamespaceName, SvgElement};
+
+//a SvgElementData
+//tp SvgElementData
+/// An [SvgElementData] is an owned representation of a finalized
+/// [SvgElement] and its contents, which may be serialized (for example
+/// to JSON) and later turned back into an element to be re-emitted or
+/// patched
+///
+/// Only the output of the element is represented - its name,
+/// attributes, characters and bbox - and not how it was created, so
+/// the element it becomes cannot be finalized again
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SvgElementData {
+    /// Qualified name of the element, such as 'path'
+    pub name: String,
+    /// Qualified names and values of the attributes, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<(String, String)>,
+    /// Characters of the element
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub characters: String,
+    /// Bounding box of the element in the coordinates of its parent
+    pub bbox: BBox,
+    /// Contents of the element
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contents: Vec<SvgElementData>,
+}
+
+//ip From<&SvgElement> for SvgElementData
+impl<'a> From<&SvgElement<'a>> for SvgElementData {
+    fn from(e: &SvgElement<'a>) -> Self {
+        Self {
+            name: e.ns_name().to_string(),
+            attributes: e
+                .attributes()
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.clone()))
+                .collect(),
+            characters: e.characters().to_string(),
+            bbox: e.bbox(),
+            contents: e.contents().iter().map(|c| c.into()).collect(),
+        }
+    }
+}
+
+//ip SvgElementData
+impl SvgElementData {
+    //mp to_element
+    /// Create a finalized element from the data
+    pub fn to_element<'a>(&self) -> SvgElement<'a> {
+        SvgElement::new_finalized(
+            NamespaceName::of_qualified(&self.name),
+            self.attributes
+                .iter()
+                .map(|(n, v)| (NamespaceName::of_qualified(n), v.clone()))
+                .collect(),
+            self.characters.clone(),
+            self.bbox,
+            self.contents.iter().map(|c| c.to_element()).collect(),
+        )
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_element_data {
+    use super::*;
+    use crate::{Svg, SvgConfig, SvgText};
+    #[test]
+    fn test_round_trip() {
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut t = SvgText::new([0., 0.].into(), "label");
+        t.add_attribute("href", Some("xlink"), "#a");
+        svg.contents_add_element(t);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let root = svg.root_element().unwrap();
+        let data: SvgElementData = root.into();
+        let json = serde_json::to_string(&data).unwrap();
+        let data2: SvgElementData = serde_json::from_str(&json).unwrap();
+        assert_eq!(data, data2);
+        let e = data2.to_element();
+        assert_eq!(e.contents()[0].get_attribute("xlink:href"), Some("#a"));
+        assert_eq!(e.contents()[0].characters(), "label");
+        assert_eq!(SvgElementData::from(&e), data);
+    }
+}
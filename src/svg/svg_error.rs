@@ -25,6 +25,82 @@ use thiserror::Error;
 pub enum SvgError {
     #[error("bad configuration")]
     BadConfiguration,
-    #[error("Invalid transformation matrix, {reason}")]
-    InvalidTransformationMatrix { reason: String },
+    #[error("stack savepoint is no longer valid")]
+    InvalidSavepoint,
+    #[error("the stack must be empty, but has {depth} elements")]
+    StackInUse { depth: usize },
+    #[error("{operation} requires at least {required} elements on the stack, but it has {depth}")]
+    StackUnderflow {
+        operation: &'static str,
+        required: usize,
+        depth: usize,
+    },
+    #[error("{operation} requires just one element on the stack, but it has {depth}")]
+    StackNotSingle {
+        operation: &'static str,
+        depth: usize,
+    },
+    #[error("the diagram has not been generated")]
+    NotGenerated,
+    #[error("ink '{0}' is not used in the document")]
+    UnknownInk(String),
+    #[error("the shared definitions have not been finalized")]
+    NotFinalized,
+    #[error("no element or layer '{0}' in the document")]
+    UnknownSubtree(String),
+    #[error("references to ids that are not in the document, {0:?}")]
+    DanglingReferences(Vec<String>),
+    #[error("document exceeds its size budget with {elements} elements and {bytes} bytes")]
+    OverBudget { elements: usize, bytes: usize },
+    #[error("the document is written as UTF-8, so cannot declare encoding '{0}'")]
+    UnsupportedEncoding(String),
+    #[error("IO error, {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Construction(#[from] crate::Error),
+    #[cfg(feature = "xml-rs")]
+    #[error("XML writer error, {0}")]
+    XmlWriter(#[from] ::xml::writer::Error),
+    #[cfg(feature = "quick-xml")]
+    #[error("quick-xml error, {0}")]
+    QuickXml(#[from] ::quick_xml::Error),
+}
+
+//a Test
+#[cfg(test)]
+mod test_svg_error {
+    use super::*;
+    use crate::{Svg, SvgConfig, SvgGroup};
+    #[test]
+    fn test_stack() {
+        let mut svg = Svg::new(SvgConfig::default());
+        assert!(matches!(
+            svg.stack_pop(),
+            Err(SvgError::StackUnderflow {
+                required: 1,
+                depth: 0,
+                ..
+            })
+        ));
+        assert!(svg.stack_add_subelement(SvgGroup::new()).is_err());
+        assert!(svg.contents_take_stack().is_err());
+
+        svg.stack_push(SvgGroup::new());
+        let e = svg.stack_pop_to_child().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "stack_pop_to_child requires at least 2 elements on the stack, but it has 1"
+        );
+        svg.stack_push(SvgGroup::new());
+        svg.stack_add_subelement(SvgGroup::new()).unwrap();
+        assert!(matches!(
+            svg.definitions_take_stack(),
+            Err(SvgError::StackNotSingle { depth: 2, .. })
+        ));
+        svg.stack_pop_to_child().unwrap();
+        svg.contents_take_stack().unwrap();
+        assert_eq!(svg.stack_depth(), 0);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+    }
 }
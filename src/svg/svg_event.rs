@@ -17,8 +17,184 @@ limitations under the License.
  */
 
 //a Imports
+use std::borrow::Cow;
+use std::io::Write;
+
+use super::svg_ids::StableHasher;
+use super::XmlProlog;
 use crate::SvgElement;
 
+//a Functions
+//fp escape_xml
+/// Escape a string for use as XML character data or (if `attribute`
+/// is true) as a double-quoted attribute value
+pub(crate) fn escape_xml(s: &str, attribute: bool) -> Cow<'_, str> {
+    let needs_escape = |c: char| matches!(c, '&' | '<' | '>') || (attribute && c == '"');
+    if !s.contains(needs_escape) {
+        return Cow::Borrowed(s);
+    }
+    let mut r = String::with_capacity(s.len() + 16);
+    for c in s.chars() {
+        match c {
+            '&' => r.push_str("&amp;"),
+            '<' => r.push_str("&lt;"),
+            '>' => r.push_str("&gt;"),
+            '"' if attribute => r.push_str("&quot;"),
+            _ => r.push(c),
+        }
+    }
+    Cow::Owned(r)
+}
+
+//fp is_self_closing
+/// Return true if an element is written as a single self-closing tag
+/// (such as '<path d="..."/>'), as it has no contents or characters
+pub(crate) fn is_self_closing(e: &SvgElement) -> bool {
+    e.contents().is_empty() && e.characters().is_empty()
+}
+
+//a Output format
+//tp Indent
+/// The indentation of nested elements when writing a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Indent {
+    /// No indentation
+    #[default]
+    None,
+    /// A number of spaces per level of nesting
+    Spaces(usize),
+    /// A tab per level of nesting
+    Tabs,
+}
+
+//tp Newline
+/// The newline policy when writing a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Newline {
+    /// The document is written as a single line (after the prolog)
+    #[default]
+    None,
+    /// Each element starts on a new line, with '\n' line endings
+    Lf,
+    /// Each element starts on a new line, with '\r\n' line endings
+    CrLf,
+}
+
+//fi write_line_start
+/// Start a new line with the indentation for a depth
+pub(crate) fn write_line_start<W: Write>(
+    w: &mut W,
+    indent: Indent,
+    newline: Newline,
+    depth: usize,
+) -> std::io::Result<()> {
+    match newline {
+        Newline::None => return Ok(()),
+        Newline::Lf => writeln!(w)?,
+        Newline::CrLf => write!(w, "\r\n")?,
+    }
+    match indent {
+        Indent::None => Ok(()),
+        Indent::Spaces(n) => write!(w, "{:1$}", "", n * depth),
+        Indent::Tabs => write!(w, "{}", "\t".repeat(depth)),
+    }
+}
+
+//fp content_hash
+/// Generate a stable hash of the events of a document
+///
+/// This is the [StableHasher] hash of the element names, attributes
+/// (in order of name) and characters, so it does not depend on the
+/// order in which attributes were added, nor on the Rust release (as
+/// the standard library hashers may)
+pub(crate) fn content_hash<'a: 'x, 'x, I: Iterator<Item = XmlEvent<'a, 'x>>>(events: I) -> u64 {
+    let mut hasher = StableHasher::default();
+    let mut add = |bytes: &[u8]| hasher.add(bytes);
+    for event in events {
+        match event {
+            XmlEvent::StartElement(e) => {
+                add(b"<");
+                add(e.ns_name().to_string().as_bytes());
+                let written = e.written_attributes();
+                let mut attrs: Vec<(String, &str)> = written
+                    .iter()
+                    .map(|(n, v)| (n.to_string(), v.as_str()))
+                    .collect();
+                attrs.sort();
+                for (n, v) in attrs {
+                    add(n.as_bytes());
+                    add(v.as_bytes());
+                }
+            }
+            XmlEvent::Characters(e) => {
+                add(b"\"");
+                add(e.characters().as_bytes());
+            }
+            XmlEvent::EndElement(_) => add(b">"),
+            _ => (),
+        }
+    }
+    hasher.finish()
+}
+
+//fp write_events
+/// Write the events of a document, escaped, with newlines and
+/// indentation between elements
+///
+/// The document starts with the prolog given. No whitespace is added
+/// within an element that has characters (such as a 'text'), as it
+/// would change its content
+pub(crate) fn write_events<'a: 'x, 'x, W: Write, I: Iterator<Item = XmlEvent<'a, 'x>>>(
+    events: I,
+    w: &mut W,
+    prolog: &XmlProlog,
+    indent: Indent,
+    newline: Newline,
+) -> std::io::Result<()> {
+    write_events_at(events, w, prolog, indent, newline, 0)
+}
+
+//fp write_events_at
+/// Write the events of an element (or document) that is at a depth
+/// within a document, as for [write_events]
+///
+/// An element at a depth greater than zero is preceded by a line
+/// start, as it is within an element that is already written
+pub(crate) fn write_events_at<'a: 'x, 'x, W: Write, I: Iterator<Item = XmlEvent<'a, 'x>>>(
+    events: I,
+    w: &mut W,
+    prolog: &XmlProlog,
+    indent: Indent,
+    newline: Newline,
+    depth: usize,
+) -> std::io::Result<()> {
+    let mut preserve = vec![false];
+    for event in events {
+        match &event {
+            XmlEvent::StartElement(e) => {
+                let p = *preserve.last().unwrap();
+                if !p && (depth > 0 || preserve.len() > 1) {
+                    write_line_start(w, indent, newline, depth + preserve.len() - 1)?;
+                }
+                preserve.push(p || !e.characters().is_empty());
+                event.write_xml(w)?;
+            }
+            XmlEvent::EndElement(e) => {
+                let p = preserve.pop().unwrap_or(false);
+                if !p && !e.contents().is_empty() {
+                    write_line_start(w, indent, newline, depth + preserve.len() - 1)?;
+                }
+                event.write_xml(w)?;
+            }
+            XmlEvent::StartDocument => prolog.write(w)?,
+            _ => event.write_xml(w)?,
+        }
+    }
+    Ok(())
+}
+
 //a XmlEvent
 //tp XmlEvent
 #[derive(Debug)]
@@ -32,23 +208,64 @@ pub enum XmlEvent<'a, 'x> {
 
 //ip XmlEvent
 impl<'a, 'x> XmlEvent<'a, 'x> {
+    //mp as_xml
+    /// Get the XML for the event, escaping attribute values and
+    /// character data
+    ///
+    /// An element with no contents or characters is a single
+    /// self-closing tag, written for its start event; its end event is
+    /// then empty
     pub fn as_xml(&self) -> String {
         use XmlEvent::*;
         match self {
-            StartDocument => r#"<?xml version="1.0" encoding="utf8"?>"#.into(),
+            StartDocument => XmlProlog::default().declaration_xml().unwrap_or_default(),
             EndDocument => "".into(),
             StartElement(e) => {
                 let mut r = format!("<{}", e.ns_name());
-                for (n, v) in e.attributes() {
-                    r.push_str(&format!(r#" {}="{}""#, n, v));
+                for (n, v) in e.written_attributes().iter() {
+                    r.push_str(&format!(r#" {}="{}""#, n, escape_xml(v, true)));
+                }
+                if is_self_closing(e) {
+                    r.push_str("/>");
+                } else {
+                    r.push('>');
                 }
-                r.push('>');
                 r
             }
-            Characters(e) => e.characters().into(),
+            Characters(e) => escape_xml(e.characters(), false).into_owned(),
             EndElement(e) => {
-                format!("</{}>", e.ns_name())
+                if is_self_closing(e) {
+                    String::new()
+                } else {
+                    format!("</{}>", e.ns_name())
+                }
+            }
+        }
+    }
+
+    //mp write_xml
+    /// Write the XML for the event, escaping attribute values and
+    /// character data; empty elements are self-closing, as for
+    /// [XmlEvent::as_xml]
+    pub fn write_xml<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        use XmlEvent::*;
+        match self {
+            StartDocument => XmlProlog::default().write(w),
+            EndDocument => writeln!(w),
+            StartElement(e) => {
+                write!(w, "<{}", e.ns_name())?;
+                for (n, v) in e.written_attributes().iter() {
+                    write!(w, r#" {}="{}""#, n, escape_xml(v, true))?;
+                }
+                if is_self_closing(e) {
+                    write!(w, "/>")
+                } else {
+                    write!(w, ">")
+                }
             }
+            Characters(e) => write!(w, "{}", escape_xml(e.characters(), false)),
+            EndElement(e) if is_self_closing(e) => Ok(()),
+            EndElement(e) => write!(w, "</{}>", e.ns_name()),
         }
     }
 }
@@ -158,3 +375,166 @@ impl<'a, 'i> Iterator for ElementIter<'a, 'i> {
         }
     }
 }
+
+//a Test
+#[cfg(test)]
+mod test_event {
+    use super::*;
+    use crate::{Svg, SvgConfig, SvgError, SvgText};
+    #[test]
+    fn test_write_svg() {
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut buf = vec![];
+        assert!(matches!(
+            svg.write_svg(&mut buf),
+            Err(SvgError::NotGenerated)
+        ));
+        let mut t = SvgText::new([0., 0.].into(), "Fish & <Chips>");
+        t.add_attribute("class", None, "a\"b");
+        svg.contents_add_element(t);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        svg.write_svg(&mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("Fish &amp; &lt;Chips&gt;"));
+        assert!(xml.contains(r#"class="a&quot;b""#));
+        assert!(xml.trim_end().ends_with("</svg>"));
+    }
+    #[test]
+    fn test_pretty() {
+        let cfg = SvgConfig::default().set_output_format(Indent::Spaces(2), Newline::Lf);
+        let mut svg = Svg::new(cfg);
+        let mut g = crate::SvgGroup::new();
+        let mut t = SvgText::new([0., 0.].into(), "a");
+        t.push_content(crate::SvgTag::new("tspan", None));
+        g.push_content(t);
+        svg.contents_add_element(g);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = xml.lines().collect();
+        assert!(lines[1].starts_with("<svg"));
+        assert!(lines.iter().any(|l| l.starts_with("  <g>")));
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("    <text") && l.ends_with("a<tspan/></text>")));
+        assert!(lines.contains(&"  </g>"));
+        assert!(lines.contains(&"</svg>"));
+    }
+    #[test]
+    fn test_escaping() {
+        let adversarial = [
+            "Fish & Chips",
+            "a<b>c",
+            r#"say "hello""#,
+            "it's",
+            "&amp; already",
+            "]]>",
+            "</text><script>alert(1)</script>",
+        ];
+        for s in adversarial {
+            let mut t = SvgText::new([0., 0.].into(), s);
+            t.add_attribute("aria-label", None, s);
+            t.finalize(&SvgConfig::default()).unwrap();
+            let xml: String = ElementIter::new(&t).map(|e| e.as_xml()).collect();
+            let mut buf = vec![];
+            write_events(
+                ElementIter::new(&t),
+                &mut buf,
+                &XmlProlog::default(),
+                Indent::None,
+                Newline::None,
+            )
+            .unwrap();
+            let written = String::from_utf8(buf).unwrap();
+            let body = xml.trim_start_matches(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+            assert!(written.trim_end().ends_with(body), "{written} vs {body}");
+            // Only the markup of the one element remains once escaped
+            assert_eq!(body.matches('<').count(), 2, "{body}");
+            assert_eq!(body.matches('>').count(), 2, "{body}");
+            let label = body.split(r#"aria-label=""#).nth(1).unwrap();
+            let label = &label[..label.find('"').unwrap()];
+            assert_eq!(
+                label
+                    .replace("&quot;", "\"")
+                    .replace("&lt;", "<")
+                    .replace("&gt;", ">")
+                    .replace("&amp;", "&"),
+                s
+            );
+        }
+    }
+    #[test]
+    fn test_self_closing() {
+        let mut g = crate::SvgGroup::new();
+        let mut p = crate::SvgPath::new_box(crate::BBox::new(0., 0., 1., 1.));
+        p.add_attribute("id", None, "box");
+        g.push_content(p);
+        g.push_content(SvgText::new([0., 0.].into(), "a"));
+        g.finalize(&SvgConfig::default()).unwrap();
+        let xml: String = ElementIter::new(&g).map(|e| e.as_xml()).collect();
+        // The path data is pushed after the id when finalized
+        assert!(xml.contains(r#"<path id="box" d="M "#));
+        assert!(xml.contains(r#" z"/><text"#));
+        assert!(xml.ends_with("a</text></g>"));
+        let mut buf = vec![];
+        write_events(
+            ElementIter::new(&g),
+            &mut buf,
+            &XmlProlog::default(),
+            Indent::None,
+            Newline::None,
+        )
+        .unwrap();
+        let written = String::from_utf8(buf).unwrap();
+        assert!(written.contains(r#" z"/><text"#));
+        assert!(!written.contains("</path>"));
+    }
+    #[test]
+    fn test_content_hash() {
+        let build = |class_first: bool, text: &str| {
+            let mut svg = Svg::new(SvgConfig::default());
+            let mut t = SvgText::new([0., 0.].into(), text);
+            if class_first {
+                t.add_attribute("class", None, "label");
+                t.add_attribute("fill", None, "red");
+            } else {
+                t.add_attribute("fill", None, "red");
+                t.add_attribute("class", None, "label");
+            }
+            svg.contents_add_element(t);
+            svg.finalize().unwrap();
+            svg.generate_diagram().unwrap();
+            svg.content_hash().unwrap()
+        };
+        assert_eq!(build(true, "a"), build(true, "a"));
+        assert_eq!(build(true, "a"), build(false, "a"));
+        assert_ne!(build(true, "a"), build(true, "b"));
+        assert!(matches!(
+            Svg::new(SvgConfig::default()).content_hash(),
+            Err(SvgError::NotGenerated)
+        ));
+    }
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn test_write_svgz() {
+        use std::io::Read;
+        let mut svg = Svg::new(SvgConfig::default());
+        svg.contents_add_element(SvgText::new([0., 0.].into(), "compressed"));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        svg.write_svgz(&mut buf).unwrap();
+        assert_eq!(&buf[0..2], &[0x1f, 0x8b]);
+        let mut xml = String::new();
+        flate2::read::GzDecoder::new(&buf[..])
+            .read_to_string(&mut xml)
+            .unwrap();
+        let mut plain = vec![];
+        svg.write_svg(&mut plain).unwrap();
+        assert_eq!(xml.as_bytes(), &plain[..]);
+    }
+}
@@ -0,0 +1,310 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_format.rs
+@brief   Formatting of numbers for SVG output and for labels
+ */
+
+//a Imports
+use crate::Point;
+
+//a NumberFormat
+//tp NumberFormat
+/// The formatting of numbers emitted in the SVG itself (attribute
+/// values, path data, and so on)
+///
+/// This always uses '.' as the decimal separator, and never uses
+/// thousands separators, so that any SVG consumer can parse it; a
+/// negative number that rounds to zero is emitted as zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumberFormat {
+    /// Number of decimal places
+    pub decimal_places: usize,
+    /// If asserted then trailing zeros (and a trailing decimal point)
+    /// are removed
+    pub trim_zeros: bool,
+}
+
+//ip Default for NumberFormat
+impl std::default::Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_places: 4,
+            trim_zeros: false,
+        }
+    }
+}
+
+//ip NumberFormat
+impl NumberFormat {
+    //bp set_decimal_places
+    pub fn set_decimal_places(mut self, decimal_places: usize) -> Self {
+        self.decimal_places = decimal_places;
+        self
+    }
+
+    //bp set_trim_zeros
+    pub fn set_trim_zeros(mut self, trim_zeros: bool) -> Self {
+        self.trim_zeros = trim_zeros;
+        self
+    }
+
+    //mp number
+    /// Format a number
+    pub fn number(&self, v: f64) -> String {
+        let mut s = format!("{:.*}", self.decimal_places, v);
+        if self.trim_zeros && s.contains('.') {
+            let n = s.trim_end_matches('0').trim_end_matches('.').len();
+            s.truncate(n);
+        }
+        if s.starts_with('-') && s[1..].chars().all(|c| c == '0' || c == '.') {
+            s.remove(0);
+        }
+        s
+    }
+
+    //mp point
+    /// Format a point as 'x,y'
+    pub fn point(&self, pt: &Point) -> String {
+        format!("{},{}", self.number(pt[0]), self.number(pt[1]))
+    }
+
+    //zz All done
+}
+
+//fp number_str
+/// Format a number with the default [NumberFormat] - four decimal
+/// places, untrimmed - as the tests expect of unconfigured documents
+#[cfg(test)]
+pub fn number_str(v: f64) -> String {
+    NumberFormat::default().number(v)
+}
+
+//fp display_number_format
+/// Get the [NumberFormat] for the display of values with a formatter,
+/// with the precision of the formatter (such as '{:.1}') if it has one
+pub(crate) fn display_number_format(f: &std::fmt::Formatter) -> NumberFormat {
+    match f.precision() {
+        Some(p) => NumberFormat::default().set_decimal_places(p),
+        None => NumberFormat::default(),
+    }
+}
+
+//a NumberText
+//ti NumberPart
+/// A part of a [NumberText]
+#[derive(Debug, Clone, PartialEq)]
+enum NumberPart {
+    Number(f64),
+    Text(String),
+}
+
+//tp NumberText
+/// Text containing numbers, such as an attribute value, whose numbers
+/// are formatted once the [NumberFormat] of the configuration is
+/// known (when its element is finalized)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct NumberText {
+    parts: Vec<NumberPart>,
+}
+
+//ip NumberText
+impl NumberText {
+    //fp of_numbers
+    /// Create the text of a list of numbers with a separator
+    pub fn of_numbers<I: IntoIterator<Item = f64>>(values: I, separator: &str) -> Self {
+        let mut r = Self::default();
+        for (i, v) in values.into_iter().enumerate() {
+            if i > 0 {
+                r = r.text(separator);
+            }
+            r = r.number(v);
+        }
+        r
+    }
+
+    //fp of_seconds
+    /// Create the text of a duration in seconds, such as '0.5s'
+    pub fn of_seconds(v: f64) -> Self {
+        Self::default().number(v).text("s")
+    }
+
+    //cp number
+    /// Append a number
+    pub fn number(mut self, v: f64) -> Self {
+        self.parts.push(NumberPart::Number(v));
+        self
+    }
+
+    //cp text
+    /// Append some text
+    pub fn text(mut self, s: &str) -> Self {
+        self.parts.push(NumberPart::Text(s.into()));
+        self
+    }
+
+    //mp format
+    /// Format the text with a number format
+    pub fn format(&self, number_format: &NumberFormat) -> String {
+        let mut r = String::new();
+        for p in self.parts.iter() {
+            match p {
+                NumberPart::Number(v) => r.push_str(&number_format.number(*v)),
+                NumberPart::Text(s) => r.push_str(s),
+            }
+        }
+        r
+    }
+}
+
+//ip From<f64> for NumberText
+impl From<f64> for NumberText {
+    fn from(v: f64) -> Self {
+        Self::default().number(v)
+    }
+}
+
+//a LabelFormat
+//tp LabelFormat
+/// The formatting of numbers for visible labels (such as tick labels
+/// on rulers and axes), which may follow the conventions of a locale
+///
+/// This is independent of the formatting of numbers in the SVG
+/// itself, which is always '.'-separated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LabelFormat {
+    /// Character used to separate the integer and fractional parts
+    pub decimal_separator: char,
+    /// Character used to separate groups of three digits in the
+    /// integer part, if any (e.g. ',' or a thin space '\u{2009}')
+    pub thousands_separator: Option<char>,
+    /// Number of decimal places to show
+    pub decimal_places: usize,
+}
+
+//ip Default for LabelFormat
+impl std::default::Default for LabelFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            thousands_separator: None,
+            decimal_places: 2,
+        }
+    }
+}
+
+//ip LabelFormat
+impl LabelFormat {
+    //bp set_decimal_separator
+    pub fn set_decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    //bp set_thousands_separator
+    pub fn set_thousands_separator(mut self, thousands_separator: Option<char>) -> Self {
+        self.thousands_separator = thousands_separator;
+        self
+    }
+
+    //bp set_decimal_places
+    pub fn set_decimal_places(mut self, decimal_places: usize) -> Self {
+        self.decimal_places = decimal_places;
+        self
+    }
+
+    //mp format
+    /// Format a number for a visible label
+    pub fn format(&self, v: f64) -> String {
+        let s = format!("{:.*}", self.decimal_places, v.abs());
+        let (int, frac) = s.split_once('.').unwrap_or((s.as_str(), ""));
+        let mut r = String::new();
+        if v < 0. && s.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            r.push('-');
+        }
+        let n = int.len();
+        for (i, c) in int.chars().enumerate() {
+            if i > 0 && (n - i) % 3 == 0 {
+                if let Some(sep) = self.thousands_separator {
+                    r.push(sep);
+                }
+            }
+            r.push(c);
+        }
+        if !frac.is_empty() {
+            r.push(self.decimal_separator);
+            r.push_str(frac);
+        }
+        r
+    }
+
+    //zz All done
+}
+
+//a Test
+#[cfg(test)]
+mod test_format {
+    use super::*;
+    #[test]
+    fn test_number() {
+        assert_eq!(number_str(1234.5), "1234.5000");
+        assert_eq!(
+            NumberFormat::default().point(&[1., -2.].into()),
+            "1.0000,-2.0000"
+        );
+    }
+    #[test]
+    fn test_number_format() {
+        let f = NumberFormat::default().set_decimal_places(6);
+        assert_eq!(f.number(1.5), "1.500000");
+        let f = f.set_decimal_places(2).set_trim_zeros(true);
+        assert_eq!(f.number(1.5), "1.5");
+        assert_eq!(f.number(2.), "2");
+        assert_eq!(f.number(-0.001), "0");
+        assert_eq!(f.number(1234.567), "1234.57");
+        assert_eq!(f.point(&[1., -2.5].into()), "1,-2.5");
+        let f = NumberFormat::default().set_decimal_places(0);
+        assert_eq!(f.number(2.6), "3");
+        assert_eq!(number_str(1.5), "1.5000");
+    }
+    #[test]
+    fn test_number_text() {
+        let t = NumberText::of_numbers([0., 0.25], ";")
+            .text(";")
+            .number(1.5)
+            .text("s");
+        assert_eq!(t.format(&NumberFormat::default()), "0.0000;0.2500;1.5000s");
+        let f = NumberFormat::default()
+            .set_decimal_places(1)
+            .set_trim_zeros(true);
+        assert_eq!(t.format(&f), "0;0.2;1.5s");
+        assert_eq!(NumberText::from(2.).format(&f), "2");
+    }
+    #[test]
+    fn test_label() {
+        let f = LabelFormat::default();
+        assert_eq!(f.format(1234567.891), "1234567.89");
+        let f = f
+            .set_decimal_separator(',')
+            .set_thousands_separator(Some('\u{2009}'));
+        assert_eq!(f.format(1234567.891), "1\u{2009}234\u{2009}567,89");
+        assert_eq!(f.format(-123.4), "-123,40");
+        assert_eq!(f.format(-0.001), "0,00");
+        let f = f.set_decimal_places(0).set_thousands_separator(Some('.'));
+        assert_eq!(f.format(1000.), "1.000");
+        assert_eq!(f.format(999.), "999");
+    }
+}
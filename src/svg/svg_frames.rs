@@ -0,0 +1,170 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_frames.rs
+@brief   Named coordinate frames
+ */
+
+//a Imports
+use std::collections::HashMap;
+
+use crate::{Error, Point, SvgElement, Transform};
+
+//a FrameRegistry
+//tp FrameRegistry
+/// A [FrameRegistry] holds a set of named coordinate frames, each of
+/// which is a [Transform] from the frame to the document coordinates
+///
+/// The document frame itself is always present, with the name
+/// [FrameRegistry::DOCUMENT]
+///
+/// Points can be converted between frames, and elements can be
+/// placed 'in' a frame (i.e. given the transform of the frame)
+#[derive(Debug, Clone, Default)]
+pub struct FrameRegistry {
+    /// Map from frame name to the transform from that frame to the document
+    frames: HashMap<String, Transform>,
+}
+
+//ip FrameRegistry
+impl FrameRegistry {
+    /// Name of the document frame
+    pub const DOCUMENT: &'static str = "document";
+
+    //fp new
+    /// Create a new registry containing just the document frame
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //mp add_frame
+    /// Add (or replace) a frame given its transform to the document
+    /// coordinates
+    ///
+    /// The document frame cannot be replaced, so adding a frame with
+    /// that name is an error
+    pub fn add_frame(&mut self, name: &str, transform: Transform) -> Result<(), Error> {
+        if name == Self::DOCUMENT {
+            return Err(Error::ReservedFrame { name: name.into() });
+        }
+        self.frames.insert(name.into(), transform);
+        Ok(())
+    }
+
+    //mp add_frame_in
+    /// Add (or replace) a frame given its transform to the coordinates
+    /// of another (parent) frame
+    pub fn add_frame_in(
+        &mut self,
+        name: &str,
+        parent: &str,
+        transform: Transform,
+    ) -> Result<(), Error> {
+        let parent = self.transform_of(parent)?;
+        self.add_frame(name, parent.apply_to_transform(&transform))
+    }
+
+    //mp has_frame
+    /// Return true if the frame is known
+    pub fn has_frame(&self, name: &str) -> bool {
+        name == Self::DOCUMENT || self.frames.contains_key(name)
+    }
+
+    //mp transform_of
+    /// Get the transform from a frame to the document coordinates
+    pub fn transform_of(&self, name: &str) -> Result<Transform, Error> {
+        if name == Self::DOCUMENT {
+            Ok(Transform::default())
+        } else {
+            self.frames
+                .get(name)
+                .copied()
+                .ok_or_else(|| Error::UnknownFrame { name: name.into() })
+        }
+    }
+
+    //mp transform_between
+    /// Get the transform that converts coordinates in frame `from` to
+    /// coordinates in frame `to`
+    pub fn transform_between(&self, from: &str, to: &str) -> Result<Transform, Error> {
+        let from = self.transform_of(from)?;
+        let to = self.transform_of(to)?;
+        Ok(to.inverse().apply_to_transform(&from))
+    }
+
+    //mp convert
+    /// Convert a point in frame `from` to its coordinates in frame `to`
+    pub fn convert(&self, pt: Point, from: &str, to: &str) -> Result<Point, Error> {
+        Ok(self.transform_between(from, to)?.apply(pt))
+    }
+
+    //mp place
+    /// Place an element, whose contents are in the coordinates of
+    /// frame `name`, into the document coordinates
+    pub fn place(&self, name: &str, element: &mut SvgElement) -> Result<(), Error> {
+        element.apply_transform(&self.transform_of(name)?);
+        Ok(())
+    }
+
+    //zz All done
+}
+
+//a Test
+#[cfg(test)]
+mod test_frames {
+    use super::*;
+    fn pt_eq(pt: &Point, x: f64, y: f64) {
+        assert!(
+            (pt[0] - x).abs() < 1E-8 && (pt[1] - y).abs() < 1E-8,
+            "mismatch {:?} {} {}",
+            pt,
+            x,
+            y
+        );
+    }
+    #[test]
+    fn test_convert() {
+        let mut frames = FrameRegistry::new();
+        frames
+            .add_frame("a", Transform::of_translation([10., 0.].into()))
+            .unwrap();
+        frames
+            .add_frame_in("b", "a", Transform::of_trs([0., 5.].into(), 90., 2.))
+            .unwrap();
+        assert!(frames.has_frame("document"));
+        assert!(frames.has_frame("b"));
+        assert!(!frames.has_frame("c"));
+        let p = frames.convert([1., 0.].into(), "b", "document").unwrap();
+        pt_eq(&p, 10., 7.);
+        let p = frames.convert([1., 0.].into(), "b", "a").unwrap();
+        pt_eq(&p, 0., 7.);
+        let p = frames.convert([10., 7.].into(), "document", "b").unwrap();
+        pt_eq(&p, 1., 0.);
+        assert!(frames.convert([0., 0.].into(), "c", "a").is_err());
+    }
+    #[test]
+    fn test_reserved() {
+        let mut frames = FrameRegistry::new();
+        let t = Transform::of_translation([10., 0.].into());
+        assert!(matches!(
+            frames.add_frame("document", t),
+            Err(Error::ReservedFrame { .. })
+        ));
+        assert!(frames.add_frame_in("document", "document", t).is_err());
+        let p = frames
+            .convert([1., 0.].into(), "document", "document")
+            .unwrap();
+        pt_eq(&p, 1., 0.);
+    }
+}
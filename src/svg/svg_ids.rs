@@ -0,0 +1,273 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_ids.rs
+@brief   Deterministic allocation of element ids
+ */
+
+//a Imports
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::SvgElement;
+
+//a StableHasher
+//tp StableHasher
+/// A 64-bit FNV-1a hasher, whose output does not depend on the Rust
+/// release (as the standard library hashers may)
+///
+/// Each item added is terminated by a zero byte, so that the
+/// boundaries between items are part of the hash
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StableHasher(u64);
+
+//ip Default for StableHasher
+impl std::default::Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+//ip StableHasher
+impl StableHasher {
+    //mp add
+    pub(crate) fn add(&mut self, bytes: &[u8]) {
+        for b in bytes.iter().chain(std::iter::once(&0)) {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    //ap finish
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+//a IdAllocator
+//tp IdAllocator
+/// An [IdAllocator] generates the ids of elements of a document (such
+/// as gradients, markers and clip paths in its definitions) so that
+/// they are stable from one build to the next and do not collide
+///
+/// Ids are allocated for keys - the same key always yields the same
+/// id - and are derived from the key (reduced to characters valid in
+/// an id), with a numeric suffix if that is already taken. Ids may
+/// also be derived from a hash of the content of an element, so that
+/// identical definitions share an id; and ids chosen elsewhere may be
+/// reserved so that they are not allocated
+#[derive(Debug, Clone, Default)]
+pub struct IdAllocator {
+    /// Ids allocated, by key
+    ids: BTreeMap<String, String>,
+    /// All ids allocated or reserved
+    used: BTreeSet<String>,
+}
+
+//ip IdAllocator
+impl IdAllocator {
+    //fp new
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //fi sanitize
+    /// Reduce a key to a valid id: ASCII alphanumerics, '-' and '_',
+    /// starting with a letter or '_'
+    fn sanitize(key: &str) -> String {
+        let mut id: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        if !id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            id.insert(0, '_');
+        }
+        id
+    }
+
+    //mp reserve
+    /// Reserve an id (such as one given explicitly to an element) so
+    /// that it is not allocated; returns false if it was already
+    /// allocated or reserved
+    pub fn reserve(&mut self, id: &str) -> bool {
+        self.used.insert(id.into())
+    }
+
+    //mp reserve_element_ids
+    /// Reserve the ids of an element and its contents
+    pub fn reserve_element_ids(&mut self, element: &SvgElement) {
+        element.walk(&mut |e, _| {
+            if let Some(id) = e.id() {
+                self.reserve(id);
+            }
+        });
+    }
+
+    //mp allocate
+    /// Get the id for a key, allocating it if the key is new
+    ///
+    /// The id is the key made valid, followed by '-2', '-3' and so on
+    /// if that is already in use
+    pub fn allocate(&mut self, key: &str) -> String {
+        if let Some(id) = self.ids.get(key) {
+            return id.clone();
+        }
+        let base = Self::sanitize(key);
+        let mut id = base.clone();
+        let mut n = 1;
+        while self.used.contains(&id) {
+            n += 1;
+            id = format!("{}-{}", base, n);
+        }
+        self.used.insert(id.clone());
+        self.ids.insert(key.into(), id.clone());
+        id
+    }
+
+    //mp allocate_hashed
+    /// Get the id for an element from a hash of its content, as
+    /// `<prefix>-<hash>`; the element's own 'id' is not part of the
+    /// hash
+    ///
+    /// Returns the id and true if this is the first time that content
+    /// has been allocated an id - hence if the element needs to be
+    /// added to the definitions - or false if an identical element
+    /// has already been allocated it
+    pub fn allocate_hashed(&mut self, prefix: &str, element: &SvgElement) -> (String, bool) {
+        let key = format!("{}-{:016x}", prefix, element.stable_hash());
+        let is_new = !self.ids.contains_key(&key);
+        (self.allocate(&key), is_new)
+    }
+
+    //ap get
+    /// Get the id allocated for a key, if any
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.ids.get(key).map(|s| s.as_str())
+    }
+
+    //ap contains
+    /// Return true if an id has been allocated or reserved
+    pub fn contains(&self, id: &str) -> bool {
+        self.used.contains(id)
+    }
+
+    //ap iter
+    /// Iterate over the keys and their allocated ids, in order of key
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.ids.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_ids {
+    use super::*;
+    use crate::{BBox, SvgPath, SvgTag};
+    #[test]
+    fn test_allocate() {
+        let mut ids = IdAllocator::new();
+        assert!(ids.reserve("arrow"));
+        assert!(!ids.reserve("arrow"));
+        assert_eq!(ids.allocate("arrow"), "arrow-2");
+        assert_eq!(ids.allocate("arrow"), "arrow-2");
+        assert_eq!(ids.allocate("sea level"), "sea-level");
+        assert_eq!(ids.allocate("sea:level"), "sea-level-2");
+        assert_eq!(ids.allocate("3d"), "_3d");
+        assert_eq!(ids.get("sea:level"), Some("sea-level-2"));
+        assert_eq!(ids.get("missing"), None);
+        assert_eq!(ids.iter().count(), 4);
+    }
+    fn gradient<'a>(color: &str) -> SvgElement<'a> {
+        let mut g = SvgTag::new("linearGradient", None);
+        let mut stop = SvgTag::new("stop", None);
+        stop.add_attribute("stop-color", None, color);
+        g.push_content(stop);
+        g
+    }
+    #[test]
+    fn test_hashed() {
+        let mut ids = IdAllocator::new();
+        let (id0, new0) = ids.allocate_hashed("gradient", &gradient("red"));
+        let (id1, new1) = ids.allocate_hashed("gradient", &gradient("red"));
+        let (id2, new2) = ids.allocate_hashed("gradient", &gradient("blue"));
+        assert!(new0 && !new1 && new2);
+        assert_eq!(id0, id1);
+        assert_ne!(id0, id2);
+        assert!(id0.starts_with("gradient-"));
+
+        // The same in a new allocator, and whether finalized or not
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+        let (id, _) = IdAllocator::new().allocate_hashed("clip", &p);
+        p.finalize(&crate::SvgConfig::default()).unwrap();
+        p.add_attribute("id", None, &id);
+        assert_eq!(IdAllocator::new().allocate_hashed("clip", &p).0, id);
+    }
+    #[test]
+    fn test_svg() {
+        use crate::{Svg, SvgConfig};
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut marker = SvgTag::new("marker", None);
+        marker.add_attribute("id", None, "arrow");
+        svg.definitions_add_element(marker);
+        assert_eq!(svg.ids_mut().allocate("arrow"), "arrow-2");
+        let id0 = svg.definitions_add_hashed("gradient", gradient("red"));
+        let id1 = svg.definitions_add_hashed("gradient", gradient("red"));
+        assert_eq!(id0, id1);
+        assert!(svg.ids().contains(&id0));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let ids: Vec<&str> = svg
+            .iter_events()
+            .filter_map(|e| match e {
+                crate::XmlEvent::StartElement(e) => e.get_attribute("id"),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids, vec!["arrow", id0.as_str()]);
+    }
+    #[test]
+    fn test_element_by_id() {
+        use crate::{Svg, SvgConfig, SvgGroup};
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut g = SvgGroup::new();
+        g.set_id("panel");
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+        p.set_id("hit");
+        p.set_id("target");
+        g.push_content(p);
+        svg.contents_add_element(g);
+        assert!(svg.ids().contains("target"));
+        assert!(svg.element_by_id("hit").is_none());
+        assert_eq!(svg.element_by_id("panel").unwrap().contents().len(), 1);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+
+        // Late passes may change the generated elements
+        svg.element_by_id_mut("target")
+            .unwrap()
+            .set_attribute("fill", None, "red");
+        assert_eq!(
+            svg.element_by_id("target").unwrap().get_attribute("fill"),
+            Some("red")
+        );
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        assert!(String::from_utf8(buf).unwrap().contains(r#"fill="red""#));
+    }
+}
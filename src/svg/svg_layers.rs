@@ -0,0 +1,226 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_layers.rs
+@brief   Named layers with visibility toggles
+ */
+
+//a Imports
+use crate::{
+    BBox, Error, NamespaceName, NumberFormat, SvgConfig, SvgElement, SvgElementType, SvgGroup,
+    SvgTag, SvgText,
+};
+
+//a Constants
+/// Name of the attribute that identifies a layer
+pub(crate) const LAYER_ATTR: &str = "data-layer";
+
+/// Name of the script function used to toggle a layer
+const TOGGLE_FN: &str = "svgNdToggleLayer";
+
+/// Script defining the function used to toggle a layer
+const TOGGLE_SCRIPT: &str = "function svgNdToggleLayer(name) {
+  for (const e of document.querySelectorAll('.layer-' + name)) {
+    e.style.display = (e.style.display == 'none') ? '' : 'none';
+  }
+}
+";
+
+//a LayerToggles
+//tp LayerToggles
+/// The manner in which controls to toggle the visibility of the named
+/// layers of a document are emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayerToggles {
+    /// HTML checkboxes in a 'foreignObject' with CSS rules using
+    /// ':has()' to hide unchecked layers; this requires no script
+    Css,
+    /// Clickable labels with a small script to hide and show layers
+    Script,
+}
+
+//a SvgLayer
+//tp SvgLayer
+/// An [SvgLayer] is a 'g' element for a named layer of a document,
+/// which is given the class `layer-<name>` so that its visibility can
+/// be toggled
+///
+/// The name is reduced to ASCII alphanumerics, '-' and '_' so that it
+/// is usable as part of a CSS class
+#[derive(Debug)]
+pub struct SvgLayer {
+    name: String,
+}
+
+//ip SvgLayer
+impl SvgLayer {
+    //fp new
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>(name: &str) -> SvgElement<'a> {
+        let name = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        SvgElement::new(Self { name })
+    }
+}
+
+//ip SvgElementType for SvgLayer
+impl<'a> SvgElementType<'a> for SvgLayer {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::local("g")
+    }
+    fn push_attributes(&self, _nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
+        attrs.push((
+            NamespaceName::local("class"),
+            format!("layer-{}", self.name),
+        ));
+        attrs.push((NamespaceName::local(LAYER_ATTR), self.name.clone()));
+    }
+}
+
+//a Functions
+//fp layer_names
+/// Find the names of the layers that are (finalized) elements of the
+/// contents, in order
+pub fn layer_names(contents: &[SvgElement]) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for c in contents {
+        if let Some(name) = c.get_attribute(LAYER_ATTR) {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.into());
+            }
+        }
+    }
+    names
+}
+
+//fp layer_controls
+/// Create the elements that provide controls to toggle the layers,
+/// placed in the top left corner of the viewBox
+pub fn layer_controls<'a>(
+    names: &[String],
+    toggles: LayerToggles,
+    bbox: &BBox,
+    svg_cfg: &SvgConfig,
+) -> Result<Vec<SvgElement<'a>>, Error> {
+    let font_size = 3.;
+    let nf = &svg_cfg.number_format;
+    let (x, y) = (bbox.x[0], bbox.y[0]);
+    let mut controls = SvgGroup::new();
+    controls.add_attribute("class", None, "layer-controls");
+    let mut r = vec![];
+    match toggles {
+        LayerToggles::Css => {
+            let mut css = String::new();
+            for n in names {
+                css.push_str(&format!(
+                    "svg:has(#layer-toggle-{n}:not(:checked)) .layer-{n} {{ display: none; }}\n"
+                ));
+            }
+            let mut style = SvgTag::new("style", None);
+            style.push_string(&css);
+            r.push(style);
+            let mut fo = SvgTag::new("foreignObject", None);
+            fo.add_attribute("x", None, &nf.number(x));
+            fo.add_attribute("y", None, &nf.number(y));
+            fo.add_attribute("width", None, &nf.number(font_size * 15.));
+            fo.add_attribute(
+                "height",
+                None,
+                &nf.number(font_size * 1.5 * (names.len() as f64)),
+            );
+            let mut div = SvgTag::new("div", None);
+            div.add_attribute("xmlns", None, "http://www.w3.org/1999/xhtml");
+            div.add_attribute(
+                "style",
+                None,
+                &format!("font-size:{}px", nf.number(font_size)),
+            );
+            for n in names {
+                let mut label = SvgTag::new("label", None);
+                label.add_attribute("style", None, "display:block");
+                let mut input = SvgTag::new("input", None);
+                input.add_attribute("type", None, "checkbox");
+                input.add_attribute("id", None, &format!("layer-toggle-{}", n));
+                input.add_attribute("checked", None, "checked");
+                label.push_content(input);
+                label.push_string(n);
+                div.push_content(label);
+            }
+            fo.push_content(div);
+            controls.push_content(fo);
+        }
+        LayerToggles::Script => {
+            let mut script = SvgTag::new("script", None);
+            script.push_string(TOGGLE_SCRIPT);
+            r.push(script);
+            for (i, n) in names.iter().enumerate() {
+                let pos = [x + font_size / 2., y + font_size * 1.5 * ((i + 1) as f64)].into();
+                let mut t = SvgText::new(pos, n);
+                t.add_size("font-size", font_size);
+                t.add_attribute("style", None, "cursor:pointer");
+                t.add_attribute("onclick", None, &format!("{}('{}')", TOGGLE_FN, n));
+                controls.push_content(t);
+            }
+        }
+    }
+    r.push(controls);
+    for e in r.iter_mut() {
+        e.finalize(svg_cfg)?;
+    }
+    Ok(r)
+}
+
+//a Test
+#[cfg(test)]
+mod test_layers {
+    use super::*;
+    #[test]
+    fn test_layers() {
+        let cfg = SvgConfig::default();
+        let mut contents = vec![
+            SvgLayer::new("base"),
+            SvgGroup::new(),
+            SvgLayer::new("notes 2"),
+            SvgLayer::new("base"),
+        ];
+        for c in contents.iter_mut() {
+            c.finalize(&cfg).unwrap();
+        }
+        let names = layer_names(&contents);
+        assert_eq!(names, vec!["base".to_string(), "notes-2".to_string()]);
+        assert_eq!(contents[2].get_attribute("class"), Some("layer-notes-2"));
+
+        let bbox = BBox::new(0., 0., 100., 100.);
+        let c = layer_controls(&names, LayerToggles::Css, &bbox, &cfg).unwrap();
+        assert_eq!(c.len(), 2);
+        assert!(c[0]
+            .characters()
+            .contains("#layer-toggle-notes-2:not(:checked)"));
+        let c = layer_controls(&names, LayerToggles::Script, &bbox, &cfg).unwrap();
+        assert_eq!(c[1].contents().len(), 2);
+        assert_eq!(
+            c[1].contents()[1].get_attribute("onclick"),
+            Some("svgNdToggleLayer('notes-2')")
+        );
+    }
+}
@@ -0,0 +1,104 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_measure.rs
+@brief   Manifest of the geometry of measured elements
+ */
+
+//a Imports
+use crate::{BBox, SvgElement, Transform};
+
+//a Measurement
+//tp Measurement
+/// The geometry of an element of a document measured with
+/// [crate::Svg::measure] - its bounding box and transform in the
+/// coordinates of the document
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    /// Name of the element, such as 'path'
+    pub name: String,
+    /// The 'id' of the element, if any
+    pub id: Option<String>,
+    /// Depth of the element, with the contents of the document at zero
+    pub depth: usize,
+    /// Transform from the coordinates of the element to those of the
+    /// document
+    pub transform: Transform,
+    /// Bounding box of the element and its contents in the coordinates
+    /// of the document
+    pub bbox: BBox,
+}
+
+//a Functions
+//fp add_measurements
+/// Add the measurements of a measured (or finalized) element and its
+/// contents, in document order, given the transform of its parent to
+/// the document
+pub(crate) fn add_measurements(
+    e: &SvgElement,
+    parent: &Transform,
+    depth: usize,
+    manifest: &mut Vec<Measurement>,
+) {
+    let transform = parent.apply_to_transform(e.transform());
+    manifest.push(Measurement {
+        name: e.ns_name().to_string(),
+        id: e.get_attribute("id").map(|s| s.to_string()),
+        depth,
+        transform,
+        bbox: parent.apply_bbox(&e.bbox()),
+    });
+    for c in e.contents() {
+        add_measurements(c, &transform, depth + 1, manifest);
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_measure {
+    use crate::{BBox, Svg, SvgConfig, SvgGroup, SvgPath, Transform};
+    #[test]
+    fn test_measure() {
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut g = SvgGroup::new();
+        g.add_attribute("id", None, "panel");
+        g.apply_transform(&Transform::of_translation([10., 20.].into()));
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 4., 2.));
+        p.add_attribute("id", None, "hit");
+        g.push_content(p);
+        svg.contents_add_element(g);
+
+        let manifest = svg.measure();
+        assert_eq!(manifest.len(), 2);
+        assert_eq!(manifest[0].id.as_deref(), Some("panel"));
+        assert_eq!(manifest[1].name, "path");
+        assert_eq!(manifest[1].depth, 1);
+        let bbox = manifest[1].bbox;
+        assert!((bbox.x[0] - 10.).abs() < 1E-9 && (bbox.x[1] - 14.).abs() < 1E-9);
+        assert!((bbox.y[0] - 20.).abs() < 1E-9 && (bbox.y[1] - 22.).abs() < 1E-9);
+        let origin = manifest[1].transform.apply([0., 0.].into());
+        assert!((origin[0] - 10.).abs() < 1E-9 && (origin[1] - 20.).abs() < 1E-9);
+        assert!((svg.bbox().x[1] - 14.).abs() < 1E-9);
+
+        // No attribute strings are generated, and the document may
+        // still be finalized and generated afterwards
+        assert!(!svg.to_debug_json().contains("\"d\""));
+        assert_eq!(svg.measure().len(), 2);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        assert!(String::from_utf8(buf).unwrap().contains(" d=\""));
+    }
+}
@@ -0,0 +1,402 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_morph.rs
+@brief   Animated transitions between two generated documents
+ */
+
+//a Imports
+use std::collections::HashMap;
+
+use super::svg_event::write_events;
+use super::svg_format::NumberFormat;
+use crate::{ElementIter, Svg, SvgColorDatabase, SvgElement, SvgError, SvgTag};
+
+//a Constants
+/// Attributes whose values are interpolated by SVG user agents when
+/// their structure matches (and are otherwise switched)
+const INTERPOLATED_ATTRIBUTES: &[&str] = &["d", "points", "viewBox"];
+
+/// Attributes whose values are colors, which are interpolated if both
+/// values are colors (a class such as 'red' is not)
+const COLOR_ATTRIBUTES: &[&str] = &[
+    "fill",
+    "stroke",
+    "stop-color",
+    "flood-color",
+    "lighting-color",
+];
+
+//a Useful functions
+//fi is_color
+fn is_color(value: &str) -> bool {
+    value != "none"
+        && SvgColorDatabase
+            .find_color(value)
+            .is_some_and(|c| c.as_tuple_rgba().3 > 0)
+}
+
+//fi translation
+/// Get the translation of a transform attribute that is just a
+/// translation (or empty)
+fn translation(value: &str) -> Option<(f64, f64)> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some((0., 0.));
+    }
+    let args = value.strip_prefix("translate(")?.strip_suffix(')')?;
+    let mut xy = args
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<f64>());
+    let x = xy.next()?.ok()?;
+    let y = xy.next().unwrap_or(Ok(0.)).ok()?;
+    Some((x, y))
+}
+
+//fi add_ids
+fn add_ids<'x, 'a>(e: &'x SvgElement<'a>, ids: &mut HashMap<String, &'x SvgElement<'a>>) {
+    e.walk(&mut |e, _| {
+        if let Some(id) = e.id() {
+            ids.insert(id.into(), e);
+        }
+    });
+}
+
+//a Morph
+//tp Morph
+/// A [Morph] creates an animated transition between two generated
+/// versions of a document (such as two states of a chart), as a
+/// single SVG document using SMIL animations
+///
+/// Elements are matched by their 'id'. Matched elements (with the
+/// same element name) tween their numeric attributes (positions and
+/// sizes), colors (fills and strokes), path data and translations;
+/// other changed attributes switch at the end of the transition.
+/// Elements only in the first document fade out, and those only in
+/// the second fade in (placed within their nearest ancestor with an
+/// id in both, or at the end of the document). Elements without an
+/// id are shown as they are in the first document.
+#[derive(Debug, Clone)]
+pub struct Morph {
+    duration: f64,
+    repeat: bool,
+}
+
+//ip Morph
+impl Morph {
+    //fp new
+    /// Create a morph of a duration in seconds
+    pub fn new(duration: f64) -> Self {
+        Self {
+            duration,
+            repeat: false,
+        }
+    }
+
+    //bp set_repeat
+    /// Set whether the transition repeats indefinitely
+    pub fn set_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    //mi smil
+    /// Create an SMIL animation element of an attribute between two
+    /// values
+    fn smil<'a>(
+        &self,
+        nf: &NumberFormat,
+        name: &'static str,
+        attr: &str,
+        from: &str,
+        to: &str,
+    ) -> SvgElement<'a> {
+        let mut a = SvgTag::new(name, None);
+        a.add_attribute("attributeName", None, attr);
+        a.add_attribute("dur", None, &format!("{}s", nf.number(self.duration)));
+        a.add_attribute("values", None, &format!("{};{}", from, to));
+        if self.repeat {
+            a.add_attribute("repeatCount", None, "indefinite");
+        } else {
+            a.add_attribute("fill", None, "freeze");
+        }
+        a
+    }
+
+    //mi switch
+    /// Create an SMIL 'set' element that switches an attribute to a
+    /// value at the end of the transition
+    fn switch<'a>(&self, nf: &NumberFormat, attr: &str, to: &str) -> SvgElement<'a> {
+        let mut a = SvgTag::new("set", None);
+        a.add_attribute("attributeName", None, attr);
+        a.add_attribute("to", None, to);
+        a.add_attribute("begin", None, &format!("{}s", nf.number(self.duration)));
+        a.add_attribute("fill", None, "freeze");
+        a
+    }
+
+    //mi fade
+    fn fade<'a>(&self, nf: &NumberFormat, e: &SvgElement, fade_in: bool) -> SvgElement<'a> {
+        let opacity = e.get_attribute("opacity").unwrap_or("1");
+        if fade_in {
+            self.smil(nf, "animate", "opacity", "0", opacity)
+        } else {
+            self.smil(nf, "animate", "opacity", opacity, "0")
+        }
+    }
+
+    //mi tweens
+    /// Get the animations from one element to another
+    fn tweens<'a>(
+        &self,
+        nf: &NumberFormat,
+        from: &SvgElement,
+        to: &SvgElement,
+    ) -> Vec<SvgElement<'a>> {
+        let mut r = vec![];
+        for (n, to_value) in to.attributes() {
+            let attr = n.to_string();
+            if attr == "id" {
+                continue;
+            }
+            let from_value = from.get_attribute(&attr).unwrap_or("");
+            if from_value == to_value {
+                continue;
+            }
+            if attr == "transform" {
+                if let (Some(f), Some(t)) = (translation(from_value), translation(to_value)) {
+                    let values = |(x, y): (f64, f64)| format!("{} {}", nf.number(x), nf.number(y));
+                    let mut a = self.smil(nf, "animateTransform", &attr, &values(f), &values(t));
+                    a.add_attribute("type", None, "translate");
+                    r.push(a);
+                    continue;
+                }
+            }
+            let numeric = from_value.parse::<f64>().is_ok() && to_value.parse::<f64>().is_ok();
+            let colors = COLOR_ATTRIBUTES.contains(&attr.as_str())
+                && is_color(from_value)
+                && is_color(to_value);
+            if numeric || colors || INTERPOLATED_ATTRIBUTES.contains(&attr.as_str()) {
+                r.push(self.smil(nf, "animate", &attr, from_value, to_value));
+            } else {
+                r.push(self.switch(nf, &attr, to_value));
+            }
+        }
+        r
+    }
+
+    //mi morph_element
+    /// Add the animations to a copy of an element of the first
+    /// document, and its contents
+    fn morph_element(
+        &self,
+        nf: &NumberFormat,
+        e: &mut SvgElement,
+        to_ids: &HashMap<String, &SvgElement>,
+    ) {
+        if let Some(id) = e.get_attribute("id") {
+            let animations = match to_ids.get(id) {
+                Some(to) if to.ns_name() == e.ns_name() => self.tweens(nf, e, to),
+                Some(_) => vec![],
+                None => vec![self.fade(nf, e, false)],
+            };
+            for c in e.contents_mut() {
+                self.morph_element(nf, c, to_ids);
+            }
+            for a in animations {
+                e.push_content(a);
+            }
+        } else {
+            for c in e.contents_mut() {
+                self.morph_element(nf, c, to_ids);
+            }
+        }
+    }
+
+    //mi add_new_elements
+    /// Find the elements of the second document whose ids are not in
+    /// the first, with the id of their nearest ancestor that is in both
+    fn add_new_elements<'a>(
+        &self,
+        nf: &NumberFormat,
+        e: &SvgElement<'a>,
+        parent: Option<&str>,
+        from_ids: &HashMap<String, &SvgElement>,
+        new_elements: &mut Vec<(Option<String>, SvgElement<'a>)>,
+    ) {
+        let mut parent = parent.map(|s| s.to_string());
+        if let Some(id) = e.get_attribute("id") {
+            if !from_ids.contains_key(id) {
+                let mut copy = e.finalized_copy();
+                copy.push_content(self.fade(nf, e, true));
+                new_elements.push((parent, copy));
+                return;
+            }
+            parent = Some(id.into());
+        }
+        for c in e.contents() {
+            self.add_new_elements(nf, c, parent.as_deref(), from_ids, new_elements);
+        }
+    }
+
+    //mp document
+    /// Create the document of the transition from one generated
+    /// document's root element to another's, with the numbers of the
+    /// animations in a number format
+    pub fn document<'a>(
+        &self,
+        from: &SvgElement<'a>,
+        to: &SvgElement<'a>,
+        number_format: &NumberFormat,
+    ) -> SvgElement<'a> {
+        let nf = number_format;
+        let mut from_ids = HashMap::new();
+        add_ids(from, &mut from_ids);
+        let mut to_ids = HashMap::new();
+        add_ids(to, &mut to_ids);
+
+        let mut doc = from.finalized_copy();
+        let root_tweens: Vec<SvgElement> = self
+            .tweens(nf, from, to)
+            .into_iter()
+            .filter(|a| a.get_attribute("attributeName") == Some("viewBox"))
+            .collect();
+        self.morph_element(nf, &mut doc, &to_ids);
+        for a in root_tweens {
+            doc.push_content(a);
+        }
+
+        let mut new_elements = vec![];
+        for c in to.contents() {
+            self.add_new_elements(nf, c, None, &from_ids, &mut new_elements);
+        }
+        for (parent, e) in new_elements {
+            match parent.as_deref().and_then(|p| doc.find_by_id_mut(p)) {
+                Some(p) => p.push_content(e),
+                None => doc.push_content(e),
+            }
+        }
+        doc
+    }
+
+    //mp write
+    /// Write the transition between two generated documents, with the
+    /// indentation, newlines and number format of the first
+    pub fn write<'a, W: std::io::Write>(
+        &self,
+        from: &Svg<'a>,
+        to: &Svg<'a>,
+        mut writer: W,
+    ) -> Result<(), SvgError> {
+        let from_root = from.root_element().ok_or(SvgError::NotGenerated)?;
+        let to_root = to.root_element().ok_or(SvgError::NotGenerated)?;
+        let doc = self.document(from_root, to_root, &from.config.number_format);
+        write_events(
+            ElementIter::new(&doc),
+            &mut writer,
+            &from.config.written_prolog(),
+            from.config.indent,
+            from.config.newline,
+        )?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_morph {
+    use super::*;
+    use crate::{BBox, SvgConfig, SvgGroup, SvgPath};
+    fn document<'a>(x: f64, color: &str, extra: &str) -> Svg<'a> {
+        let mut g = SvgGroup::new();
+        g.add_attribute("id", None, "chart");
+        let mut p = SvgPath::new_box(BBox::new(x, 0., x + 10., 10.));
+        p.add_attribute("id", None, "bar");
+        p.add_attribute("fill", None, color);
+        p.add_attribute("class", None, color);
+        g.push_content(p);
+        let mut p = SvgPath::new_box(BBox::new(0., 20., 5., 25.));
+        p.add_attribute("id", None, extra);
+        g.push_content(p);
+        let mut svg = Svg::new(SvgConfig::default());
+        svg.contents_add_element(g);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        svg
+    }
+    #[test]
+    fn test_translation() {
+        assert_eq!(translation(""), Some((0., 0.)));
+        assert_eq!(translation("translate(1 2)"), Some((1., 2.)));
+        assert_eq!(translation("translate(3)"), Some((3., 0.)));
+        assert_eq!(translation("rotate(30)"), None);
+    }
+    #[test]
+    fn test_morph() {
+        let from = document(0., "red", "old");
+        let to = document(5., "blue", "new");
+        let morph = Morph::new(2.);
+        let doc = morph.document(
+            from.root_element().unwrap(),
+            to.root_element().unwrap(),
+            &NumberFormat::default(),
+        );
+        let mut ids = HashMap::new();
+        add_ids(&doc, &mut ids);
+        let bar = ids["bar"];
+        let animated: Vec<String> = bar
+            .contents()
+            .iter()
+            .map(|a| {
+                format!(
+                    "{} {}",
+                    a.ns_name(),
+                    a.get_attribute("attributeName").unwrap()
+                )
+            })
+            .collect();
+        assert!(animated.contains(&"animate fill".to_string()));
+        assert!(animated.contains(&"animate d".to_string()));
+        assert!(animated.contains(&"set class".to_string()));
+        assert_eq!(
+            ids["old"].contents()[0].get_attribute("values"),
+            Some("1;0")
+        );
+        let new = ids["new"];
+        assert_eq!(new.contents()[0].get_attribute("values"), Some("0;1"));
+        assert_eq!(ids["chart"].contents().len(), 3);
+        assert_eq!(
+            bar.contents()[0].get_attribute("dur"),
+            Some(format!("{}s", NumberFormat::default().number(2.)).as_str())
+        );
+        let doc = morph.document(
+            from.root_element().unwrap(),
+            to.root_element().unwrap(),
+            &NumberFormat::default()
+                .set_decimal_places(1)
+                .set_trim_zeros(true),
+        );
+        assert_eq!(
+            doc.find_by_id("old").unwrap().contents()[0].get_attribute("dur"),
+            Some("2s")
+        );
+
+        let mut buf = vec![];
+        morph.write(&from, &to, &mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.contains("attributeName=\"fill\""));
+    }
+}
@@ -0,0 +1,86 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_preview.rs
+@brief   Artefacts added to SVG output for preview and review
+ */
+
+//a Imports
+use crate::{BBox, Error, SvgConfig, SvgElement, SvgPath, SvgTag};
+
+//a Constants
+/// Id of the checkerboard pattern definition
+const CHECKERBOARD_ID: &str = "svg-nd-checkerboard";
+
+//a Functions
+//fp checkerboard
+/// Create a checkerboard pattern definition, with squares of a given
+/// size, and a backdrop rectangle covering a region that is filled
+/// with it
+///
+/// The backdrop should be placed behind all of the contents, so that
+/// any transparency in the contents shows the checkerboard
+pub fn checkerboard<'a>(
+    size: f64,
+    bbox: &BBox,
+    svg_cfg: &SvgConfig,
+) -> Result<(SvgElement<'a>, SvgElement<'a>), Error> {
+    let mut pattern = SvgTag::new("pattern", None);
+    pattern.add_attribute("id", None, CHECKERBOARD_ID);
+    pattern.add_attribute("patternUnits", None, "userSpaceOnUse");
+    let nf = &svg_cfg.number_format;
+    pattern.add_attribute("width", None, &nf.number(size * 2.));
+    pattern.add_attribute("height", None, &nf.number(size * 2.));
+    for (x, y, color) in [
+        (0., 0., "white"),
+        (size, size, "white"),
+        (size, 0., "lightgrey"),
+        (0., size, "lightgrey"),
+    ] {
+        let mut square = SvgPath::new_box(BBox::new(x, y, x + size, y + size));
+        square.add_attribute("fill", None, color);
+        square.add_attribute("stroke", None, "none");
+        pattern.push_content(square);
+    }
+    pattern.finalize(svg_cfg)?;
+
+    let mut backdrop = SvgPath::new_box(*bbox);
+    backdrop.add_attribute("class", None, "checkerboard");
+    backdrop.add_attribute("fill", None, &format!("url(#{})", CHECKERBOARD_ID));
+    backdrop.add_attribute("stroke", None, "none");
+    backdrop.finalize(svg_cfg)?;
+    Ok((pattern, backdrop))
+}
+
+//a Test
+#[cfg(test)]
+mod test_preview {
+    use super::*;
+    use crate::svg::number_str;
+    #[test]
+    fn test_checkerboard() {
+        let cfg = SvgConfig::default();
+        let (pattern, backdrop) = checkerboard(2., &BBox::new(0., 0., 10., 10.), &cfg).unwrap();
+        assert_eq!(pattern.get_attribute("id"), Some(CHECKERBOARD_ID));
+        assert_eq!(
+            pattern.get_attribute("width"),
+            Some(number_str(4.).as_str())
+        );
+        assert_eq!(pattern.contents().len(), 4);
+        assert_eq!(
+            backdrop.get_attribute("fill"),
+            Some("url(#svg-nd-checkerboard)")
+        );
+    }
+}
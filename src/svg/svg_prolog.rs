@@ -0,0 +1,246 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_prolog.rs
+@brief   The XML declaration and document type of a written document
+ */
+
+//a Imports
+use std::io::Write;
+
+use crate::SvgError;
+
+//a Constants
+/// The document type declaration of SVG 1.1
+pub const SVG11_DOCTYPE: &str =
+    r#"svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd""#;
+
+//a XmlProlog
+//tp XmlProlog
+/// The prolog of a written document - the XML declaration (with its
+/// version, encoding and standalone declaration) and an optional
+/// document type declaration
+///
+/// By default the declaration is `<?xml version="1.0"
+/// encoding="UTF-8"?>` with no document type; some legacy consumers
+/// require a particular spelling of the encoding name, or the SVG 1.1
+/// document type.
+///
+/// The document is always written as UTF-8, so only names of that
+/// encoding may be declared
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct XmlProlog {
+    /// if asserted then the XML declaration is written
+    pub declaration: bool,
+    /// the XML version of the declaration
+    pub version: String,
+    /// the encoding of the declaration, if any
+    pub encoding: Option<String>,
+    /// the standalone declaration, if any
+    pub standalone: Option<bool>,
+    /// the document type declaration (the text within '<!DOCTYPE' and
+    /// '>'), if any
+    pub doctype: Option<String>,
+}
+
+//ip Default for XmlProlog
+impl std::default::Default for XmlProlog {
+    fn default() -> Self {
+        Self {
+            declaration: true,
+            version: "1.0".into(),
+            encoding: Some("UTF-8".into()),
+            standalone: None,
+            doctype: None,
+        }
+    }
+}
+
+//ip XmlProlog
+impl XmlProlog {
+    //fp new
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //bp set_declaration
+    /// Set whether the XML declaration is written at all
+    pub fn set_declaration(mut self, declaration: bool) -> Self {
+        self.declaration = declaration;
+        self
+    }
+
+    //bp set_version
+    /// Set the XML version of the declaration, such as '1.0' or '1.1'
+    pub fn set_version(mut self, version: &str) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    //bp set_encoding
+    /// Set the encoding of the declaration, such as 'UTF-8' or
+    /// 'utf8', or None to omit it
+    ///
+    /// This fails for any encoding other than UTF-8, as that is how the
+    /// document is written
+    pub fn set_encoding(mut self, encoding: Option<&str>) -> Result<Self, SvgError> {
+        if let Some(encoding) = encoding {
+            if !matches!(encoding.to_ascii_lowercase().as_str(), "utf-8" | "utf8") {
+                return Err(SvgError::UnsupportedEncoding(encoding.into()));
+            }
+        }
+        self.encoding = encoding.map(|s| s.into());
+        Ok(self)
+    }
+
+    //bp set_standalone
+    /// Set the standalone declaration, or None to omit it
+    pub fn set_standalone(mut self, standalone: Option<bool>) -> Self {
+        self.standalone = standalone;
+        self
+    }
+
+    //bp set_doctype
+    /// Set the document type declaration, as the text within
+    /// '<!DOCTYPE' and '>' (such as [SVG11_DOCTYPE]), or None to omit
+    /// it
+    pub fn set_doctype(mut self, doctype: Option<&str>) -> Self {
+        self.doctype = doctype.map(|s| s.into());
+        self
+    }
+
+    //bp set_svg11_doctype
+    /// Set the document type declaration to that of SVG 1.1
+    pub fn set_svg11_doctype(self) -> Self {
+        self.set_doctype(Some(SVG11_DOCTYPE))
+    }
+
+    //ap declaration_xml
+    /// Get the XML declaration, if it is written
+    pub fn declaration_xml(&self) -> Option<String> {
+        if !self.declaration {
+            return None;
+        }
+        let mut r = format!(r#"<?xml version="{}""#, self.version);
+        if let Some(encoding) = &self.encoding {
+            r.push_str(&format!(r#" encoding="{}""#, encoding));
+        }
+        if let Some(standalone) = self.standalone {
+            let yes_no = if standalone { "yes" } else { "no" };
+            r.push_str(&format!(r#" standalone="{}""#, yes_no));
+        }
+        r.push_str("?>");
+        Some(r)
+    }
+
+    //ap doctype_xml
+    /// Get the document type declaration, if any
+    pub fn doctype_xml(&self) -> Option<String> {
+        self.doctype.as_ref().map(|d| format!("<!DOCTYPE {}>", d))
+    }
+
+    //ap as_xml
+    /// Get the prolog as XML, with each declaration on its own line
+    pub fn as_xml(&self) -> String {
+        let mut r = String::new();
+        for l in [self.declaration_xml(), self.doctype_xml()]
+            .into_iter()
+            .flatten()
+        {
+            r.push_str(&l);
+            r.push('\n');
+        }
+        r
+    }
+
+    //mp write
+    /// Write the prolog, with each declaration on its own line
+    pub(crate) fn write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        write!(w, "{}", self.as_xml())
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_prolog {
+    use super::*;
+    use crate::{Svg, SvgConfig, SvgText};
+    #[test]
+    fn test_prolog() {
+        assert_eq!(
+            XmlProlog::default().as_xml(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"
+        );
+        let p = XmlProlog::new()
+            .set_encoding(Some("utf-8"))
+            .unwrap()
+            .set_standalone(Some(false))
+            .set_svg11_doctype();
+        assert_eq!(
+            p.declaration_xml().unwrap(),
+            r#"<?xml version="1.0" encoding="utf-8" standalone="no"?>"#
+        );
+        assert!(p.doctype_xml().unwrap().starts_with("<!DOCTYPE svg PUBLIC"));
+        let p = XmlProlog::new().set_declaration(false);
+        assert_eq!(p.as_xml(), "");
+        assert!(matches!(
+            XmlProlog::new().set_encoding(Some("ISO-8859-1")),
+            Err(SvgError::UnsupportedEncoding(e)) if e == "ISO-8859-1"
+        ));
+    }
+    #[test]
+    fn test_svg() {
+        let prolog = XmlProlog::new()
+            .set_encoding(None)
+            .unwrap()
+            .set_standalone(Some(true))
+            .set_svg11_doctype();
+        let mut svg = Svg::new(SvgConfig::default().set_prolog(prolog));
+        svg.contents_add_element(SvgText::new([0., 0.].into(), "a"));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        let mut lines = xml.lines();
+        assert_eq!(
+            lines.next(),
+            Some(r#"<?xml version="1.0" standalone="yes"?>"#)
+        );
+        assert_eq!(
+            lines.next(),
+            Some(format!("<!DOCTYPE {}>", SVG11_DOCTYPE).as_str())
+        );
+        assert!(lines.next().unwrap().starts_with("<svg"));
+    }
+    #[test]
+    fn test_html_fragment() {
+        let cfg = SvgConfig::default()
+            .set_prolog(XmlProlog::new().set_svg11_doctype())
+            .set_html_fragment(true);
+        let mut svg = Svg::new(cfg);
+        svg.contents_add_element(SvgText::new([0., 0.].into(), "a"));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.starts_with("<svg width="), "{xml}");
+        assert!(!xml.contains("xmlns"));
+        assert!(!xml.contains("version="));
+        assert!(!xml.contains("<!DOCTYPE"));
+    }
+}
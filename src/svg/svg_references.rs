@@ -0,0 +1,106 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_references.rs
+@brief   References between elements by id, and their integrity
+ */
+
+//a Imports
+use std::collections::HashSet;
+
+use crate::SvgElement;
+
+//a Functions
+//fp add_references
+/// Add the ids referenced by an element and its contents - through
+/// 'url(#id)' values and '#id' hrefs - to a list
+pub(crate) fn add_references(e: &SvgElement, ids: &mut Vec<String>) {
+    for (n, v) in e.attributes() {
+        let mut refs = vec![];
+        if n.name() == "href" {
+            refs.extend(v.strip_prefix('#'));
+        }
+        let mut rest = v.as_str();
+        while let Some(n) = rest.find("url(#") {
+            rest = &rest[n + 5..];
+            if let Some(end) = rest.find(')') {
+                refs.push(&rest[..end]);
+            }
+        }
+        for r in refs {
+            if !ids.iter().any(|i| i == r) {
+                ids.push(r.to_string());
+            }
+        }
+    }
+    for c in e.contents() {
+        add_references(c, ids);
+    }
+}
+
+//fi add_ids
+/// Add the ids of an element and its contents to a set
+fn add_ids<'x>(e: &'x SvgElement, ids: &mut HashSet<&'x str>) {
+    if let Some(id) = e.get_attribute("id") {
+        ids.insert(id);
+    }
+    for c in e.contents() {
+        add_ids(c, ids);
+    }
+}
+
+//fp dangling_references
+/// Find the ids referenced within an element (such as the root of a
+/// document) that are not the id of any element within it, in the
+/// order they are first referenced
+pub(crate) fn dangling_references(root: &SvgElement) -> Vec<String> {
+    let mut refs = vec![];
+    add_references(root, &mut refs);
+    let mut ids = HashSet::new();
+    add_ids(root, &mut ids);
+    refs.retain(|r| !ids.contains(r.as_str()));
+    refs
+}
+
+//a Test
+#[cfg(test)]
+mod test_references {
+    use crate::{BBox, Svg, SvgConfig, SvgError, SvgPath, SvgTag};
+    fn build(cfg: SvgConfig) -> Svg<'static> {
+        let mut svg = Svg::new(cfg);
+        let mut marker = SvgTag::new("marker", None);
+        marker.add_attribute("id", None, "arrow");
+        svg.definitions_add_element(marker);
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        p.add_markers(&(Some("arrow".into()), None, Some("dot".into())));
+        p.add_attribute("fill", None, "url(#shade)");
+        svg.contents_add_element(p);
+        svg.finalize().unwrap();
+        svg
+    }
+    #[test]
+    fn test_dangling() {
+        let mut svg = build(SvgConfig::default());
+        svg.generate_diagram().unwrap();
+        assert_eq!(
+            svg.dangling_references(),
+            &["dot".to_string(), "shade".to_string()]
+        );
+        let mut svg = build(SvgConfig::default().set_strict_references(true));
+        assert!(matches!(
+            svg.generate_diagram(),
+            Err(SvgError::DanglingReferences(r)) if r.len() == 2
+        ));
+    }
+}
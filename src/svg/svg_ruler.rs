@@ -0,0 +1,171 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_ruler.rs
+@brief   Rulers along the edges of a document
+ */
+
+//a Imports
+use geo_nd::Vector;
+
+use crate::{BBox, GridLines, LabelFormat, Point, SvgElement, SvgGroup, SvgLines, SvgText};
+
+//a Ruler
+//tp Ruler
+/// A [Ruler] describes tick marks and coordinate labels drawn along
+/// the inside edges of the final viewBox of a document, like the
+/// rulers of a CAD package, to aid review of printed output
+///
+/// Ticks are placed at multiples of the spacing, with every
+/// `major_every`th tick being longer and labelled (along the top and
+/// left edges only)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ruler {
+    /// Spacing of the minor ticks, in document units (mm)
+    pub spacing: f64,
+    /// Number of minor ticks per major (labelled) tick
+    pub major_every: usize,
+    /// Length of a major tick; minor ticks are half of this
+    pub size: f64,
+    /// Font size of the labels
+    pub font_size: f64,
+    /// Stroke width of the ticks
+    pub line_width: f64,
+    /// Suffix for the labels, such as 'mm'
+    pub unit: String,
+}
+
+//ip Default for Ruler
+impl std::default::Default for Ruler {
+    fn default() -> Self {
+        Self {
+            spacing: 1.,
+            major_every: 10,
+            size: 3.,
+            font_size: 2.,
+            line_width: 0.1,
+            unit: String::new(),
+        }
+    }
+}
+
+//ip Ruler
+impl Ruler {
+    //fp new
+    /// Create a new ruler with minor ticks at a spacing, and labelled
+    /// major ticks every so many minor ticks
+    pub fn new(spacing: f64, major_every: usize) -> Self {
+        Self {
+            spacing,
+            major_every,
+            ..Default::default()
+        }
+    }
+
+    //bp set_size
+    /// Set the length of the major ticks and the font size of labels
+    pub fn set_size(mut self, size: f64, font_size: f64) -> Self {
+        self.size = size;
+        self.font_size = font_size;
+        self
+    }
+
+    //bp set_unit
+    /// Set the suffix for labels, such as 'mm'
+    pub fn set_unit(mut self, unit: &str) -> Self {
+        self.unit = unit.into();
+        self
+    }
+
+    //fi is_major
+    /// Return true if a coordinate is at a major tick
+    fn is_major(&self, v: f64) -> bool {
+        let n = (v / self.spacing).round() as i64;
+        self.major_every > 0 && n % (self.major_every as i64) == 0
+    }
+
+    //mp element
+    /// Create the group element of the ruler for a viewBox
+    pub fn element<'a>(&self, bbox: &BBox, label_format: &LabelFormat) -> SvgElement<'a> {
+        let mut group = SvgGroup::new();
+        group.add_attribute("class", None, "ruler");
+        if bbox.is_none() || !bbox.is_bounded() || self.spacing <= 0. {
+            return group;
+        }
+        let (x0, y0, x1, y1) = (bbox.x[0], bbox.y[0], bbox.x[1], bbox.y[1]);
+        let grid = GridLines::new(*bbox, (self.spacing, self.spacing));
+        let mut ticks = vec![];
+        let mut labels = vec![];
+        for x in grid.x_coords() {
+            let major = self.is_major(x);
+            let l = if major { self.size } else { self.size / 2. };
+            ticks.push((Point::from_array([x, y0]), Point::from_array([x, y0 + l])));
+            ticks.push((Point::from_array([x, y1]), Point::from_array([x, y1 - l])));
+            if major {
+                let pos = [x, y0 + self.size + self.font_size].into();
+                labels.push((pos, label_format.format(x), "middle"));
+            }
+        }
+        for y in grid.y_coords() {
+            let major = self.is_major(y);
+            let l = if major { self.size } else { self.size / 2. };
+            ticks.push((Point::from_array([x0, y]), Point::from_array([x0 + l, y])));
+            ticks.push((Point::from_array([x1, y]), Point::from_array([x1 - l, y])));
+            if major {
+                let pos = [
+                    x0 + self.size + self.font_size / 4.,
+                    y + self.font_size / 3.,
+                ]
+                .into();
+                labels.push((pos, label_format.format(y), "start"));
+            }
+        }
+        let mut lines = SvgLines::new(ticks);
+        lines.add_attribute("fill", None, "none");
+        lines.add_attribute("stroke", None, "black");
+        lines.add_size("stroke-width", self.line_width);
+        group.push_content(lines);
+        for (pos, text, anchor) in labels {
+            let mut e = SvgText::new(pos, &format!("{}{}", text, self.unit));
+            e.add_size("font-size", self.font_size);
+            e.add_attribute("text-anchor", None, anchor);
+            group.push_content(e);
+        }
+        group
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_ruler {
+    use super::*;
+    #[test]
+    fn test_ruler() {
+        let bbox = BBox::new(0., 0., 25., 10.);
+        let ruler = Ruler::new(1., 10).set_unit("mm");
+        let e = ruler.element(&bbox, &LabelFormat::default().set_decimal_places(0));
+        // One path of ticks, then labels at x=0,10,20 and y=0,10
+        assert_eq!(e.contents().len(), 6);
+        assert_eq!(e.contents()[2].characters(), "10mm");
+        assert_eq!(e.get_attribute("class"), Some("ruler"));
+        assert_eq!(
+            ruler
+                .element(&BBox::none(), &LabelFormat::default())
+                .contents()
+                .len(),
+            0
+        );
+    }
+}
@@ -0,0 +1,295 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_separation.rs
+@brief   Separation of a document into layers of a single color
+ */
+
+//a Imports
+use super::svg_layers::LAYER_ATTR;
+use crate::{NamespaceName, SvgColorDatabase, SvgElement};
+
+//a Constants
+/// Names of elements that are not separated, but kept whole in every
+/// separation
+const KEPT_ELEMENTS: &[&str] = &[
+    "defs", "style", "script", "metadata", "title", "desc", "view",
+];
+
+//a Functions
+//fp ink
+/// Get the ink of a 'fill' or 'stroke' value - a color as '#rrggbb'
+/// (ignoring any opacity), other paints (such as 'url(#gradient)')
+/// as given, or None for 'none'
+pub(crate) fn ink(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value == "none" || value == "transparent" {
+        return None;
+    }
+    if ["currentcolor", "inherit"].contains(&value.to_ascii_lowercase().as_str()) {
+        return Some(value.into());
+    }
+    match SvgColorDatabase.find_color(value) {
+        Some(c) => {
+            let (r, g, b, _) = c.as_tuple_rgba();
+            Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+        }
+        None => Some(value.into()),
+    }
+}
+
+//fi is_leaf
+/// Return true if an element is drawn as a whole, rather than being a
+/// container of elements that are drawn
+fn is_leaf(e: &SvgElement) -> bool {
+    e.contents().is_empty() || e.ns_name().to_string() == "text"
+}
+
+//fi inks
+/// Get the fill and stroke inks of an element, given those it
+/// inherits
+fn inks(
+    e: &SvgElement,
+    fill: &Option<String>,
+    stroke: &Option<String>,
+) -> (Option<String>, Option<String>) {
+    let fill = match e.get_attribute("fill") {
+        Some(f) => ink(f),
+        None => fill.clone(),
+    };
+    let stroke = match e.get_attribute("stroke") {
+        Some(s) => ink(s),
+        None => stroke.clone(),
+    };
+    (fill, stroke)
+}
+
+//fi add_inks
+fn add_inks(
+    e: &SvgElement,
+    fill: Option<String>,
+    stroke: Option<String>,
+    inks_used: &mut Vec<String>,
+) {
+    if KEPT_ELEMENTS.contains(&e.ns_name().to_string().as_str()) {
+        return;
+    }
+    let (fill, stroke) = inks(e, &fill, &stroke);
+    if is_leaf(e) {
+        for i in [fill, stroke].into_iter().flatten() {
+            if !inks_used.contains(&i) {
+                inks_used.push(i);
+            }
+        }
+    } else {
+        for c in e.contents() {
+            add_inks(c, fill.clone(), stroke.clone(), inks_used);
+        }
+    }
+}
+
+//fp separation_inks
+/// Find the inks used by the drawn elements within an element (such
+/// as the root of a document), in order of first use
+///
+/// Elements that do not specify a fill are filled black, as in SVG
+pub(crate) fn separation_inks(root: &SvgElement) -> Vec<String> {
+    let mut inks_used = vec![];
+    for c in root.contents() {
+        add_inks(c, Some("#000000".into()), None, &mut inks_used);
+    }
+    inks_used
+}
+
+//fi separate_element
+/// Create a copy of an element with only the parts drawn in an ink,
+/// or None if none of it is drawn in the ink
+///
+/// Drawn elements whose fill (or stroke) is in the ink have their
+/// stroke (or fill) removed if that is of another ink
+fn separate_element<'a>(
+    e: &SvgElement<'a>,
+    ink: &str,
+    fill: Option<String>,
+    stroke: Option<String>,
+) -> Option<SvgElement<'a>> {
+    if KEPT_ELEMENTS.contains(&e.ns_name().to_string().as_str()) {
+        return None;
+    }
+    let (fill, stroke) = inks(e, &fill, &stroke);
+    if is_leaf(e) {
+        let fill_in_ink = fill.as_deref() == Some(ink);
+        let stroke_in_ink = stroke.as_deref() == Some(ink);
+        if !fill_in_ink && !stroke_in_ink {
+            return None;
+        }
+        let mut copy = e.finalized_copy();
+        if !fill_in_ink {
+            copy.set_local_attribute("fill", "none");
+        }
+        if !stroke_in_ink && stroke.is_some() {
+            copy.set_local_attribute("stroke", "none");
+        }
+        Some(copy)
+    } else {
+        let contents: Vec<SvgElement<'a>> = e
+            .contents()
+            .iter()
+            .filter_map(|c| separate_element(c, ink, fill.clone(), stroke.clone()))
+            .collect();
+        if contents.is_empty() {
+            return None;
+        }
+        let mut copy = e.finalized_copy();
+        copy.retain_contents(|_| false);
+        for c in contents {
+            copy.push_content(c);
+        }
+        Some(copy)
+    }
+}
+
+//fi kept_contents
+/// Get copies of the contents of the root that are kept whole in
+/// every separation
+fn kept_contents<'a>(root: &SvgElement<'a>) -> Vec<SvgElement<'a>> {
+    root.contents()
+        .iter()
+        .filter(|c| KEPT_ELEMENTS.contains(&c.ns_name().to_string().as_str()))
+        .map(|c| c.finalized_copy())
+        .collect()
+}
+
+//fp separation_document
+/// Create a standalone document with just the parts of a (generated)
+/// document drawn in an ink, preserving their geometry and order
+///
+/// The document has the root attributes and the definitions (and
+/// styles, etc) of the original
+pub(crate) fn separation_document<'a>(root: &SvgElement<'a>, ink: &str) -> SvgElement<'a> {
+    let mut doc = root.finalized_copy();
+    doc.retain_contents(|_| false);
+    for c in kept_contents(root) {
+        doc.push_content(c);
+    }
+    for c in root.contents() {
+        if let Some(c) = separate_element(c, ink, Some("#000000".into()), None) {
+            doc.push_content(c);
+        }
+    }
+    doc
+}
+
+//fp layer_name
+/// Get the name of the layer for an ink
+pub(crate) fn layer_name(ink: &str) -> String {
+    let name: String = ink
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("ink-{}", name.trim_matches('-'))
+}
+
+//fp separate_into_layers
+/// Replace the drawn contents of the root of a (generated) document
+/// by a layer per ink (in order of first use), each of which has the
+/// parts of the contents drawn in that ink; returns the inks
+pub(crate) fn separate_into_layers<'a>(root: &mut SvgElement<'a>) -> Vec<String> {
+    let inks_used = separation_inks(root);
+    let mut layers = vec![];
+    for i in inks_used.iter() {
+        let name = layer_name(i);
+        let mut layer = SvgElement::new_finalized(
+            NamespaceName::local("g"),
+            vec![
+                (NamespaceName::local("class"), format!("layer-{}", name)),
+                (NamespaceName::local(LAYER_ATTR), name),
+            ],
+            String::new(),
+            root.bbox(),
+            vec![],
+        );
+        for c in root.contents() {
+            if let Some(c) = separate_element(c, i, Some("#000000".into()), None) {
+                layer.push_content(c);
+            }
+        }
+        layers.push(layer);
+    }
+    root.retain_contents(|c| KEPT_ELEMENTS.contains(&c.ns_name().to_string().as_str()));
+    for l in layers {
+        root.push_content(l);
+    }
+    inks_used
+}
+
+//a Test
+#[cfg(test)]
+mod test_separation {
+    use super::*;
+    use crate::{BBox, SvgConfig, SvgDefs, SvgGroup, SvgPath, SvgTag};
+    fn document<'a>() -> SvgElement<'a> {
+        let mut root = SvgTag::new("svg", None);
+        root.push_content(SvgDefs::new());
+        let mut g = SvgGroup::new();
+        g.add_attribute("stroke", None, "red");
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        p.add_attribute("fill", None, "blue");
+        g.push_content(p);
+        let mut p = SvgPath::new_box(BBox::new(5., 5., 10., 10.));
+        p.add_attribute("fill", None, "none");
+        g.push_content(p);
+        root.push_content(g);
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+        p.add_attribute("fill", None, "#ff0000");
+        root.push_content(p);
+        root.finalize(&SvgConfig::default()).unwrap();
+        root
+    }
+    #[test]
+    fn test_inks() {
+        assert_eq!(ink("none"), None);
+        assert_eq!(ink("red"), Some("#ff0000".into()));
+        assert_eq!(ink("rgba(255,0,0,0.5)"), Some("#ff0000".into()));
+        assert_eq!(ink("url(#shade)"), Some("url(#shade)".into()));
+        assert_eq!(layer_name("#ff0000"), "ink-ff0000");
+        let root = document();
+        assert_eq!(separation_inks(&root), vec!["#0000ff", "#ff0000"]);
+    }
+    #[test]
+    fn test_separate() {
+        let root = document();
+        let red = separation_document(&root, "#ff0000");
+        assert_eq!(red.contents().len(), 3);
+        assert_eq!(red.contents()[0].ns_name().to_string(), "defs");
+        let g = &red.contents()[1];
+        assert_eq!(g.contents().len(), 2);
+        assert_eq!(g.contents()[0].get_attribute("fill"), Some("none"));
+        let blue = separation_document(&root, "#0000ff");
+        assert_eq!(blue.contents().len(), 2);
+        let p = &blue.contents()[1].contents()[0];
+        assert_eq!(p.get_attribute("fill"), Some("blue"));
+        assert_eq!(p.get_attribute("stroke"), Some("none"));
+
+        let mut root = document();
+        let inks = separate_into_layers(&mut root);
+        assert_eq!(inks.len(), 2);
+        assert_eq!(root.contents().len(), 3);
+        assert_eq!(
+            root.contents()[2].get_attribute(LAYER_ATTR),
+            Some("ink-ff0000")
+        );
+        assert_eq!(root.contents()[2].contents().len(), 2);
+    }
+}
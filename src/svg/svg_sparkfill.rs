@@ -0,0 +1,187 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_sparkfill.rs
+@brief   Pattern fills that encode a small data series
+ */
+
+//a Imports
+use crate::{BBox, BezierPath, Color, ColorDatabase, Error, Point, SvgColorDatabase};
+use crate::{SvgConfig, SvgElement, SvgPath, SvgTag};
+
+//a SparkfillKind
+//tp SparkfillKind
+/// The marks used to show the values of a [Sparkfill]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SparkfillKind {
+    /// A mini bar chart, with bars rising from the bottom of the tile
+    Bars,
+    /// A mini dot plot, with a dot at the height of each value
+    Dots,
+}
+
+//a Sparkfill
+//tp Sparkfill
+/// A [Sparkfill] converts a small data series into a 'pattern' whose
+/// tile is a mini chart of the values, so that a bar or box filled
+/// with it carries a secondary data texture
+///
+/// Values are scaled so that the largest fills the height of the
+/// tile; negative values are shown as zero
+#[derive(Debug, Clone)]
+pub struct Sparkfill {
+    kind: SparkfillKind,
+    width: f64,
+    height: f64,
+    color: Color,
+    background: Option<Color>,
+}
+
+//ip Sparkfill
+impl Sparkfill {
+    //fp new
+    /// Create a sparkfill of a kind with a tile size
+    pub fn new(kind: SparkfillKind, width: f64, height: f64) -> Self {
+        Self {
+            kind,
+            width,
+            height,
+            color: Color::black(),
+            background: None,
+        }
+    }
+
+    //bp set_color
+    /// Set the color of the marks
+    pub fn set_color<'c, T>(mut self, color: T) -> Result<Self, Error>
+    where
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        self.color = (color, &SvgColorDatabase).try_into()?;
+        Ok(self)
+    }
+
+    //bp set_background
+    /// Set the color of the background of the tile; by default it is
+    /// transparent
+    pub fn set_background<'c, T>(mut self, color: T) -> Result<Self, Error>
+    where
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        self.background = Some((color, &SvgColorDatabase).try_into()?);
+        Ok(self)
+    }
+
+    //fp fill_url
+    /// Get the value of a 'fill' attribute that uses the pattern with
+    /// an id
+    pub fn fill_url(id: &str) -> String {
+        format!("url(#{})", id)
+    }
+
+    //mp pattern
+    /// Create the 'pattern' definition with an id for a data series;
+    /// this should be placed in the definitions of the document
+    pub fn pattern<'a>(
+        &self,
+        id: &str,
+        values: &[f64],
+        svg_cfg: &SvgConfig,
+    ) -> Result<SvgElement<'a>, Error> {
+        let (w, h) = (self.width, self.height);
+        let mut pattern = SvgTag::new("pattern", None);
+        pattern.add_attribute("id", None, id);
+        pattern.add_attribute("patternUnits", None, "userSpaceOnUse");
+        let nf = &svg_cfg.number_format;
+        pattern.add_attribute("width", None, &nf.number(w));
+        pattern.add_attribute("height", None, &nf.number(h));
+        if let Some(background) = &self.background {
+            let mut back = SvgPath::new_box(BBox::new(0., 0., w, h));
+            back.add_attribute("fill", None, &background.as_str());
+            back.add_attribute("stroke", None, "none");
+            pattern.push_content(back);
+        }
+        let max = values.iter().copied().fold(0., f64::max);
+        let n = values.len() as f64;
+        for (i, v) in values.iter().enumerate() {
+            let v = if max > 0. { v.max(0.) / max } else { 0. };
+            let x = w * (i as f64 + 0.5) / n;
+            let mut mark = match self.kind {
+                SparkfillKind::Bars => {
+                    // Built from its corners, as a zero-height bar has
+                    // no well-defined rectangle polygon
+                    let half_width = 0.4 * w / n;
+                    let (x0, x1, y0) = (x - half_width, x + half_width, h * (1. - v));
+                    let corners: [Point; 4] = [
+                        [x0, y0].into(),
+                        [x1, y0].into(),
+                        [x1, h].into(),
+                        [x0, h].into(),
+                    ];
+                    SvgPath::new_path(BezierPath::of_points(&corners, 0.), true)
+                }
+                SparkfillKind::Dots => {
+                    let r = 0.3 * (w / n).min(h);
+                    let y = r + (h - 2. * r) * (1. - v);
+                    SvgPath::new_path(BezierPath::of_ellipse([x, y].into(), r, 1., 0.), true)
+                }
+            };
+            mark.add_attribute("fill", None, &self.color.as_str());
+            mark.add_attribute("stroke", None, "none");
+            pattern.push_content(mark);
+        }
+        pattern.finalize(svg_cfg)?;
+        Ok(pattern)
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_sparkfill {
+    use super::*;
+    use crate::Range;
+    #[test]
+    fn test_pattern() {
+        let cfg = SvgConfig::default();
+        let s = Sparkfill::new(SparkfillKind::Bars, 4., 2.)
+            .set_color("red")
+            .unwrap()
+            .set_background("white")
+            .unwrap();
+        let p = s.pattern("spark", &[1., 2., -1., 4.], &cfg).unwrap();
+        assert_eq!(p.get_attribute("id"), Some("spark"));
+        assert_eq!(p.contents().len(), 5);
+        // The largest value fills the tile; the negative value is empty
+        let range_eq = |r: Range, min: f64, max: f64| {
+            assert!(
+                (r[0] - min).abs() < 1E-8 && (r[1] - max).abs() < 1E-8,
+                "Range mismatch {r:?} [{min}, {max}]"
+            );
+        };
+        range_eq(p.contents()[4].bbox().y, 0., 2.);
+        range_eq(p.contents()[3].bbox().y, 2., 2.);
+        assert_eq!(Sparkfill::fill_url("spark"), "url(#spark)");
+
+        let s = Sparkfill::new(SparkfillKind::Dots, 4., 2.);
+        let p = s.pattern("dots", &[1., 2.], &cfg).unwrap();
+        assert_eq!(p.contents().len(), 2);
+
+        let s = Sparkfill::new(SparkfillKind::Bars, f64::NAN, 2.);
+        assert!(matches!(
+            s.pattern("nan", &[1.], &cfg).unwrap_err().root_cause(),
+            Error::BadConstruction { .. }
+        ));
+    }
+}
@@ -0,0 +1,263 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_stream.rs
+@brief   Streaming generation of a document, one element at a time
+ */
+
+//a Imports
+use std::io::{Seek, SeekFrom, Write};
+
+use super::svg_event::{escape_xml, write_events_at, write_line_start};
+use crate::XmlEvent;
+use crate::{BBox, ElementIter, Length, SvgConfig, SvgElement, SvgError, SvgVersion};
+
+//a Constants
+/// Number of bytes reserved for the size attributes of the root
+/// element when the viewBox is deferred until the end of the stream
+const RESERVED_SIZE: usize = 160;
+
+//a Types
+/// Function that patches the reserved size attributes of the root
+/// element, at a position in the output
+type PatchFn<W> = fn(&mut W, u64, &str) -> std::io::Result<()>;
+
+//a Functions
+//fi size_attributes
+/// The width, height and viewBox attributes of the root for a bbox
+fn size_attributes(bbox: &BBox, svg_cfg: &SvgConfig) -> String {
+    let (x, y, w, h) = bbox.get_bounds();
+    let nf = &svg_cfg.number_format;
+    format!(
+        r#" width="{}" height="{}" viewBox="{} {} {} {}""#,
+        Length::of_document(w, svg_cfg.units).svg_string(nf),
+        Length::of_document(h, svg_cfg.units).svg_string(nf),
+        nf.number(x),
+        nf.number(y),
+        nf.number(w),
+        nf.number(h)
+    )
+}
+
+//fi patch_reserved
+/// Overwrite the reserved size attributes of the root element
+fn patch_reserved<W: Write + Seek>(w: &mut W, pos: u64, attrs: &str) -> std::io::Result<()> {
+    let end = w.stream_position()?;
+    w.seek(SeekFrom::Start(pos))?;
+    write!(w, "{:1$}", attrs, RESERVED_SIZE)?;
+    w.seek(SeekFrom::Start(end))?;
+    Ok(())
+}
+
+//a SvgStreamWriter
+//tp SvgStreamWriter
+/// An [SvgStreamWriter] generates a document one element at a time,
+/// writing each element as it is added, so that very large plots need
+/// not be held in memory as a tree
+///
+/// Each element is finalized when it is added; the bbox of the
+/// document is accumulated as elements are added. The viewBox is
+/// either given explicitly (in which case open-ended elements are
+/// clamped to it), or deferred until [SvgStreamWriter::finish] for a
+/// writer that can seek, when space is reserved for it in the root
+/// element and it is filled in with the accumulated bbox.
+///
+/// Unlike [Svg](crate::Svg) there are no definitions, layers, views or
+/// other document-level additions.
+pub struct SvgStreamWriter<W: Write> {
+    /// Writer that the document is written to
+    writer: W,
+    /// Configuration used to finalize the elements
+    config: SvgConfig,
+    /// Version of SVG for the root element
+    version: SvgVersion,
+    /// Explicit viewBox, if any
+    viewbox: Option<BBox>,
+    /// Accumulated bbox of the elements
+    bbox: BBox,
+    /// Position of the reserved size attributes, and the means to
+    /// patch them, once the document has started (if deferred)
+    reserved: Option<(u64, PatchFn<W>)>,
+    /// Asserted once the prolog and root element have been written
+    started: bool,
+}
+
+//ip SvgStreamWriter
+impl<W: Write> SvgStreamWriter<W> {
+    //fp new
+    /// Create a new stream writer with an explicit viewBox
+    pub fn new(writer: W, config: SvgConfig, viewbox: BBox) -> Self {
+        Self {
+            writer,
+            config,
+            version: SvgVersion::Version2_0,
+            viewbox: Some(viewbox),
+            bbox: BBox::none(),
+            reserved: None,
+            started: false,
+        }
+    }
+
+    //bp set_version
+    /// Set the version of SVG of the document
+    pub fn set_version<I: Into<SvgVersion>>(mut self, version: I) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    //ap bbox
+    /// Get the bbox of the elements added so far
+    pub fn bbox(&self) -> BBox {
+        self.bbox
+    }
+
+    //mi start
+    /// Write the prolog and the start of the root element, if not
+    /// already written
+    fn start(&mut self) -> Result<(), SvgError> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        self.config.written_prolog().write(&mut self.writer)?;
+        if self.config.html_fragment {
+            write!(self.writer, "<svg")?;
+        } else {
+            let version: &str = self.version.into();
+            write!(
+                self.writer,
+                r#"<svg xmlns:svg="http://www.w3.org/2000/svg" xmlns="http://www.w3.org/2000/svg" version="{}""#,
+                escape_xml(version, true)
+            )?;
+        }
+        match (&self.viewbox, &self.reserved) {
+            (Some(viewbox), _) => {
+                write!(self.writer, "{}", size_attributes(viewbox, &self.config))?
+            }
+            (None, Some(_)) => write!(self.writer, "{:1$}", "", RESERVED_SIZE)?,
+            _ => (),
+        }
+        write!(self.writer, ">")?;
+        Ok(())
+    }
+
+    //mp add_element
+    /// Finalize an element and write it to the stream
+    pub fn add_element(&mut self, mut e: SvgElement) -> Result<(), SvgError> {
+        self.start()?;
+        let extra = e.finalize(&self.config)?;
+        for mut e in std::iter::once(e).chain(extra) {
+            if let Some(viewbox) = &self.viewbox {
+                e.clamp_to_viewbox(viewbox, &self.config);
+            }
+            self.bbox = self.bbox.union(e.bbox());
+            let events = ElementIter::new(&e)
+                .filter(|ev| !matches!(ev, XmlEvent::StartDocument | XmlEvent::EndDocument));
+            write_events_at(
+                events,
+                &mut self.writer,
+                &self.config.written_prolog(),
+                self.config.indent,
+                self.config.newline,
+                1,
+            )?;
+        }
+        Ok(())
+    }
+
+    //mp finish
+    /// Complete the document, filling in a deferred viewBox, and
+    /// return the writer
+    pub fn finish(mut self) -> Result<W, SvgError> {
+        self.start()?;
+        write_line_start(&mut self.writer, self.config.indent, self.config.newline, 0)?;
+        write!(self.writer, "</svg>")?;
+        XmlEvent::EndDocument.write_xml(&mut self.writer)?;
+        if let Some((pos, patch)) = self.reserved {
+            let mut attrs = String::new();
+            if !self.bbox.is_none() {
+                attrs = size_attributes(&self.bbox, &self.config);
+            }
+            if attrs.len() > RESERVED_SIZE {
+                return Err(SvgError::BadConfiguration);
+            }
+            patch(&mut self.writer, pos, &attrs)?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+//ip SvgStreamWriter for seekable writers
+impl<W: Write + Seek> SvgStreamWriter<W> {
+    //fp new_deferred
+    /// Create a new stream writer whose viewBox is the bbox of all of
+    /// its elements, filled in when the stream is finished
+    pub fn new_deferred(writer: W, config: SvgConfig) -> Result<Self, SvgError> {
+        let mut s = Self {
+            writer,
+            config,
+            version: SvgVersion::Version2_0,
+            viewbox: None,
+            bbox: BBox::none(),
+            reserved: Some((0, patch_reserved::<W>)),
+            started: false,
+        };
+        s.start()?;
+        // The reserved space is just before the closing '>' of the root
+        let end = s.writer.stream_position()?;
+        s.reserved = Some((end - 1 - RESERVED_SIZE as u64, patch_reserved::<W>));
+        Ok(s)
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_stream {
+    use super::*;
+    use crate::svg::number_str;
+    use crate::{Indent, Newline, SvgGroup, SvgPath};
+    #[test]
+    fn test_stream() {
+        let cfg = SvgConfig::default().set_output_format(Indent::Spaces(1), Newline::Lf);
+        let mut s = SvgStreamWriter::new(vec![], cfg.clone(), BBox::new(0., 0., 20., 20.));
+        let mut g = SvgGroup::new();
+        g.push_content(SvgPath::new_box(BBox::new(0., 0., 10., 10.)));
+        s.add_element(g).unwrap();
+        s.add_element(SvgPath::new_box(BBox::new(10., 0., 20., 5.)))
+            .unwrap();
+        let b = s.bbox();
+        for (v, expected) in [(b.x[0], 0.), (b.y[0], 0.), (b.x[1], 20.), (b.y[1], 10.)] {
+            assert!((v - expected).abs() < 1E-8, "{b}");
+        }
+        let xml = String::from_utf8(s.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = xml.lines().collect();
+        assert!(lines[0].starts_with("<?xml"));
+        assert!(lines[1].contains(&format!(r#"viewBox="{}"#, number_str(0.))));
+        assert_eq!(lines[2], " <g>");
+        assert!(lines[3].starts_with("  <path"));
+        assert!(lines[5].starts_with(" <path"));
+        assert_eq!(lines[6], "</svg>");
+
+        let cfg = cfg.set_decimal_places(Some(0), false);
+        let mut s = SvgStreamWriter::new_deferred(std::io::Cursor::new(vec![]), cfg).unwrap();
+        s.add_element(SvgPath::new_box(BBox::new(0., 0., 10., 10.)))
+            .unwrap();
+        s.add_element(SvgPath::new_box(BBox::new(10., 0., 20., 5.)))
+            .unwrap();
+        let xml = String::from_utf8(s.finish().unwrap().into_inner()).unwrap();
+        assert!(xml.contains(r#"viewBox="0 0 20 10""#));
+        assert!(xml.contains(r#"width="20mm""#));
+    }
+}
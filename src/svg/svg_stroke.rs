@@ -0,0 +1,213 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_stroke.rs
+@brief   Multi-pass strokes of a single geometry
+ */
+
+//a Imports
+use super::svg_format::{NumberFormat, NumberText};
+use crate::{Color, ColorDatabase, Error, SvgColorDatabase};
+
+//a StrokePass
+//tp StrokePass
+/// A single pass of a [MultiStroke]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrokePass {
+    /// Stroke width of the pass
+    pub width: f64,
+    /// Stroke color of the pass, as used in the SVG
+    pub color: String,
+    /// Dash array of the pass; if empty then the stroke is solid
+    pub dash: Vec<f64>,
+}
+
+//ip StrokePass
+impl StrokePass {
+    //ap dash_str
+    /// Get the 'stroke-dasharray' value for the pass with a number
+    /// format, if it is dashed
+    pub fn dash_str(&self, number_format: &NumberFormat) -> Option<String> {
+        self.dash_text().map(|t| t.format(number_format))
+    }
+
+    //ap dash_text
+    /// Get the 'stroke-dasharray' value for the pass, if it is dashed,
+    /// to be formatted when its element is finalized
+    pub(crate) fn dash_text(&self) -> Option<NumberText> {
+        if self.dash.is_empty() {
+            None
+        } else {
+            Some(NumberText::of_numbers(self.dash.iter().copied(), " "))
+        }
+    }
+}
+
+//a MultiStroke
+//tp MultiStroke
+/// A [MultiStroke] is a style in which a single path is stroked a
+/// number of times, widest first, as is done for roads on maps (a
+/// dark wide casing, a lighter fill, and perhaps a dashed center
+/// line)
+///
+/// When an element with a multi-stroke is finalized its geometry is
+/// placed once in a 'defs', and each pass is a 'use' of it
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiStroke {
+    passes: Vec<StrokePass>,
+}
+
+//ip MultiStroke
+impl MultiStroke {
+    //fp new
+    /// Create a new empty multi-stroke
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //fp of_casing
+    /// Create a multi-stroke for a stroke of a width and color with a
+    /// casing wider by `casing_width` on each side
+    ///
+    /// An error is returned if a color name is not known
+    pub fn of_casing<'c, T, U>(
+        width: f64,
+        color: T,
+        casing_width: f64,
+        casing_color: U,
+    ) -> Result<Self, Error>
+    where
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+        (U, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        Self::new()
+            .add_pass(width + 2. * casing_width, casing_color)?
+            .add_pass(width, color)
+    }
+
+    //bp add_pass
+    /// Add a solid pass
+    ///
+    /// An error is returned if a color name is not known
+    pub fn add_pass<'c, T>(self, width: f64, color: T) -> Result<Self, Error>
+    where
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        self.add_dashed_pass(width, color, &[])
+    }
+
+    //bp add_dashed_pass
+    /// Add a dashed pass
+    ///
+    /// An error is returned if a color name is not known
+    pub fn add_dashed_pass<'c, T>(
+        mut self,
+        width: f64,
+        color: T,
+        dash: &[f64],
+    ) -> Result<Self, Error>
+    where
+        (T, &'c ColorDatabase<'c>): TryInto<Color, Error = Error>,
+    {
+        let color: Color = (color, &SvgColorDatabase).try_into()?;
+        self.push_pass(width, color.as_str().to_string(), dash.to_vec());
+        Ok(self)
+    }
+
+    //mp push_pass
+    /// Add a pass with a color given as an SVG paint string (such as
+    /// 'url(#gradient)')
+    pub fn push_pass(&mut self, width: f64, color: String, dash: Vec<f64>) {
+        self.passes.push(StrokePass { width, color, dash });
+    }
+
+    //ap is_empty
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    //ap passes
+    /// Get the passes in the order they are rendered - widest first,
+    /// and otherwise in the order they were added
+    pub fn passes(&self) -> Vec<&StrokePass> {
+        let mut passes: Vec<&StrokePass> = self.passes.iter().collect();
+        passes.sort_by(|a, b| b.width.total_cmp(&a.width));
+        passes
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_stroke {
+    use super::*;
+    #[test]
+    fn test_passes() {
+        let ms = MultiStroke::new()
+            .add_dashed_pass(0.2, "white", &[1., 0.5])
+            .and_then(|ms| ms.add_pass(2., "black"))
+            .and_then(|ms| ms.add_pass(1.5, "#ffcc00"))
+            .unwrap();
+        let passes = ms.passes();
+        assert_eq!(passes.len(), 3);
+        assert_eq!(passes[0].width, 2.);
+        assert_eq!(passes[1].color, "#ffcc00");
+        assert_eq!(
+            passes[2].dash_str(&NumberFormat::default().set_trim_zeros(true)),
+            Some("1 0.5".into())
+        );
+        let ms = MultiStroke::of_casing(1., "red", 0.5, "white").unwrap();
+        assert!(MultiStroke::of_casing(1., "red", 0.5, "blurple").is_err());
+        assert_eq!(ms.passes()[0].width, 2.);
+        assert_eq!(ms.passes()[1].color, "red");
+    }
+    #[test]
+    fn test_element() {
+        use crate::svg::number_str;
+        use crate::{BBox, SvgConfig, SvgPath};
+        let cfg = SvgConfig::default();
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        e.add_attribute("id", None, "road");
+        e.add_attribute("stroke", None, "red");
+        e.set_multi_stroke(MultiStroke::of_casing(1., "yellow", 0.5, "black").unwrap());
+        e.finalize(&cfg).unwrap();
+        assert_eq!(e.get_attribute("id"), Some("road"));
+        assert_eq!(e.contents().len(), 3);
+        let geometry = &e.contents()[0].contents()[0];
+        assert_eq!(geometry.get_attribute("id"), Some("road-geometry"));
+        assert_eq!(geometry.get_attribute("stroke"), None);
+        assert_eq!(e.contents()[1].get_attribute("stroke"), Some("black"));
+        assert_eq!(
+            e.contents()[2].get_attribute("href"),
+            Some("#road-geometry")
+        );
+        let b = e.bbox();
+        for (v, expected) in [(b.x[0], 0.), (b.y[0], 0.), (b.x[1], 10.), (b.y[1], 10.)] {
+            assert!((v - expected).abs() < 1E-8, "{b}");
+        }
+
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 10., 10.));
+        e.add_attribute("stroke", None, "red");
+        e.add_attribute("stroke-width", None, "2");
+        e.set_casing(1., "white").unwrap();
+        e.finalize(&cfg).unwrap();
+        assert_eq!(e.contents().len(), 3);
+        assert_eq!(
+            e.contents()[1].get_attribute("stroke-width"),
+            Some(number_str(4.).as_str())
+        );
+        assert_eq!(e.contents()[2].get_attribute("stroke"), Some("red"));
+    }
+}
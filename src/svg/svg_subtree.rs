@@ -0,0 +1,158 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_subtree.rs
+@brief   Standalone documents of a subtree of a generated document
+ */
+
+//a Imports
+use super::svg_layers::LAYER_ATTR;
+use super::svg_references::add_references;
+use crate::{Length, SvgConfig, SvgDefs, SvgElement, SvgGroup};
+
+//a Functions
+//fi find_path
+/// Find the path from (but excluding) an element to the first
+/// descendant with a given id or layer name
+fn find_path<'x, 'a>(e: &'x SvgElement<'a>, name: &str) -> Option<Vec<&'x SvgElement<'a>>> {
+    for c in e.contents() {
+        if c.get_attribute("id") == Some(name) || c.get_attribute(LAYER_ATTR) == Some(name) {
+            return Some(vec![c]);
+        }
+        if let Some(mut path) = find_path(c, name) {
+            path.insert(0, c);
+            return Some(path);
+        }
+    }
+    None
+}
+
+//fp subtree_document
+/// Create a standalone document of the element (or layer) of a
+/// generated document with a given id (or layer name)
+///
+/// The document has a copy of the root 'svg' element, with its
+/// viewBox (and size) recomputed for the bounds of the subtree; the
+/// definitions referenced by the subtree that lie outside it; and a
+/// copy of the subtree, within groups that have the transforms of its
+/// ancestors
+pub(crate) fn subtree_document<'a>(
+    root: &SvgElement<'a>,
+    name: &str,
+    svg_cfg: &SvgConfig,
+) -> Option<SvgElement<'a>> {
+    let path = find_path(root, name)?;
+    let (target, ancestors) = path.split_last()?;
+
+    let mut element = target.finalized_copy();
+    let mut bbox = target.bbox();
+    for a in ancestors.iter().rev() {
+        bbox = bbox.transform(a.transform());
+        if let Some(transform) = a.get_attribute("transform") {
+            let mut g = SvgGroup::new();
+            g.set_local_attribute("transform", transform);
+            g.push_content(element);
+            element = g;
+        }
+    }
+
+    let mut ids = vec![];
+    add_references(&element, &mut ids);
+    let mut defs = SvgDefs::new();
+    let mut n = 0;
+    while n < ids.len() {
+        if element.find_by_id(&ids[n]).is_none() {
+            if let Some(d) = root.find_by_id(&ids[n]) {
+                add_references(d, &mut ids);
+                defs.push_content(d.finalized_copy());
+            }
+        }
+        n += 1;
+    }
+
+    let nf = &svg_cfg.number_format;
+    let mut doc = root.finalized_copy();
+    doc.retain_contents(|_| false);
+    if !bbox.is_none() && bbox.is_bounded() {
+        let (x, y, w, h) = bbox.get_bounds();
+        let units = svg_cfg.units;
+        doc.set_local_attribute("width", &Length::of_document(w, units).svg_string(nf));
+        doc.set_local_attribute("height", &Length::of_document(h, units).svg_string(nf));
+        doc.set_local_attribute(
+            "viewBox",
+            &format!(
+                "{} {} {} {}",
+                nf.number(x),
+                nf.number(y),
+                nf.number(w),
+                nf.number(h)
+            ),
+        );
+    }
+    if !defs.contents().is_empty() {
+        doc.push_content(defs);
+    }
+    doc.push_content(element);
+    Some(doc)
+}
+
+//a Test
+#[cfg(test)]
+mod test_subtree {
+    use crate::svg::number_str;
+    use crate::{BBox, Svg, SvgConfig, SvgError, SvgGroup, SvgLayer, SvgPath, SvgTag};
+    #[test]
+    fn test_subtree() {
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut gradient = SvgTag::new("linearGradient", None);
+        gradient.add_attribute("id", None, "shade");
+        let mut defs = crate::SvgDefs::new();
+        defs.push_content(gradient);
+        svg.contents_add_element(defs);
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 100., 100.));
+        p.add_attribute("id", None, "big");
+        svg.contents_add_element(p);
+        let mut layer = SvgLayer::new("panel");
+        let mut g = SvgGroup::new();
+        g.apply_transform(&crate::Transform::of_translation([10., 20.].into()));
+        let mut p = SvgPath::new_box(BBox::new(0., 0., 5., 5.));
+        p.add_attribute("id", None, "small");
+        p.add_attribute("fill", None, "url(#shade)");
+        g.push_content(p);
+        layer.push_content(g);
+        svg.contents_add_element(layer);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+
+        let mut buf = vec![];
+        svg.write_subtree("small", &mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        let view_box = [10., 20., 5., 5.].map(number_str).join(" ");
+        assert!(xml.contains(&format!(r#"viewBox="{view_box}""#)));
+        assert!(xml.contains(r#"id="shade""#));
+        assert!(xml.contains(r#"id="small""#));
+        assert!(!xml.contains(r#"id="big""#));
+
+        let mut buf = vec![];
+        svg.write_subtree("panel", &mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.contains(r#"data-layer="panel""#));
+        assert!(!xml.contains(r#"id="big""#));
+
+        assert!(matches!(
+            svg.write_subtree("missing", vec![]),
+            Err(SvgError::UnknownSubtree(_))
+        ));
+    }
+}
@@ -0,0 +1,197 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_text_animation.rs
+@brief   Per-character animation of text
+ */
+
+//a Imports
+use super::svg_format::NumberText;
+use crate::{Point, SvgElement, SvgTag, SvgText};
+
+//a TextAnimationKind
+//tp TextAnimationKind
+/// The manner in which each character of a [TextAnimation] appears
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextAnimationKind {
+    /// Fade in from transparent
+    Fade,
+    /// Fade in while sliding vertically into place from an offset
+    Slide(f64),
+}
+
+//a TextAnimation
+//tp TextAnimation
+/// A [TextAnimation] splits a text string into a 'tspan' per
+/// character, positioned using the advances of the characters, and
+/// animates each in turn with SMIL animations staggered in time, for
+/// title cards and animated explainers
+///
+/// The animations are SMIL 'animate' elements, so they play in a
+/// standalone SVG file without any script or stylesheet
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextAnimation {
+    /// How each character appears
+    pub kind: TextAnimationKind,
+    /// Time (in seconds) for each character to appear
+    pub duration: f64,
+    /// Time (in seconds) between the start of each character
+    pub stagger: f64,
+    /// Time (in seconds) before the first character starts
+    pub delay: f64,
+}
+
+//ip Default for TextAnimation
+impl std::default::Default for TextAnimation {
+    fn default() -> Self {
+        Self {
+            kind: TextAnimationKind::Fade,
+            duration: 0.5,
+            stagger: 0.05,
+            delay: 0.,
+        }
+    }
+}
+
+//ip TextAnimation
+impl TextAnimation {
+    //fp new
+    pub fn new(kind: TextAnimationKind, duration: f64, stagger: f64) -> Self {
+        Self {
+            kind,
+            duration,
+            stagger,
+            ..Default::default()
+        }
+    }
+
+    //bp set_delay
+    pub fn set_delay(mut self, delay: f64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    //fp estimated_advance
+    /// An estimate of the advance of a character in a proportional
+    /// font of a size, for use when the font metrics are not known
+    pub fn estimated_advance(font_size: f64) -> impl Fn(char) -> f64 {
+        move |c: char| {
+            let em = match c {
+                'i' | 'j' | 'l' | '!' | '.' | ',' | '\'' | '|' | ':' | ';' => 0.3,
+                'f' | 't' | 'r' | ' ' | '(' | ')' | '[' | ']' | '-' => 0.4,
+                'm' | 'w' | 'M' | 'W' => 0.9,
+                c if c.is_uppercase() => 0.7,
+                _ => 0.55,
+            };
+            em * font_size
+        }
+    }
+
+    //fi animate
+    /// Create an SMIL 'animate' element for an attribute
+    fn animate<'a>(&self, attr: &str, from: f64, to: f64, begin: f64) -> SvgElement<'a> {
+        let mut a = SvgTag::new("animate", None);
+        a.add_attribute("attributeName", None, attr);
+        a.add_size("from", from);
+        a.add_size("to", to);
+        a.add_numbers("begin", NumberText::of_seconds(begin));
+        a.add_numbers("dur", NumberText::of_seconds(self.duration));
+        a.add_attribute("fill", None, "freeze");
+        a
+    }
+
+    //mp element
+    /// Create the 'text' element for a string at a position, with the
+    /// advance of each character given by a function (such as
+    /// [TextAnimation::estimated_advance])
+    ///
+    /// Whitespace advances the position but is not animated
+    pub fn element<'a, F: Fn(char) -> f64>(
+        &self,
+        position: Point,
+        text: &str,
+        advance: F,
+    ) -> SvgElement<'a> {
+        let mut e = SvgText::new(position, "");
+        let mut x = position[0];
+        let mut n = 0;
+        for c in text.chars() {
+            if !c.is_whitespace() {
+                let begin = self.delay + self.stagger * (n as f64);
+                let mut tspan = SvgTag::new("tspan", None);
+                tspan.add_size("x", x);
+                tspan.add_attribute("opacity", None, "0");
+                tspan.push_string(&c.to_string());
+                tspan.push_content(self.animate("opacity", 0., 1., begin));
+                if let TextAnimationKind::Slide(dy) = self.kind {
+                    let y = position[1];
+                    tspan.add_size("y", y + dy);
+                    tspan.push_content(self.animate("y", y + dy, y, begin));
+                }
+                e.push_content(tspan);
+                n += 1;
+            }
+            x += advance(c);
+        }
+        e
+    }
+
+    //mp total_duration
+    /// Get the time (in seconds) for a string with `n` characters to
+    /// be fully shown
+    pub fn total_duration(&self, n: usize) -> f64 {
+        self.delay + self.stagger * (n.max(1) - 1) as f64 + self.duration
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_text_animation {
+    use super::*;
+    use crate::svg::number_str;
+    #[test]
+    fn test_element() {
+        let anim = TextAnimation::new(TextAnimationKind::Slide(5.), 1., 0.25);
+        let e = anim.element([10., 20.].into(), "Hi there", |_| 2.);
+        assert_eq!(e.contents().len(), 7);
+        let t = &e.contents()[2];
+        assert_eq!(t.characters(), "t");
+        assert_eq!(t.get_attribute("x"), Some(number_str(16.).as_str()));
+        assert_eq!(t.contents().len(), 2);
+        assert_eq!(
+            t.contents()[0].get_attribute("begin"),
+            Some(format!("{}s", number_str(0.5)).as_str())
+        );
+        assert_eq!(t.contents()[1].get_attribute("attributeName"), Some("y"));
+        assert_eq!(anim.total_duration(7), 2.5);
+
+        // The numbers follow the configuration once finalized
+        let mut e = e;
+        e.finalize(&crate::SvgConfig::default().set_decimal_places(Some(1), true))
+            .unwrap();
+        let t = &e.contents()[2];
+        assert_eq!(t.get_attribute("x"), Some("16"));
+        assert_eq!(t.contents()[0].get_attribute("begin"), Some("0.5s"));
+        assert_eq!(t.contents()[0].get_attribute("dur"), Some("1s"));
+
+        let e = TextAnimation::default().element(
+            [0., 0.].into(),
+            "ab",
+            TextAnimation::estimated_advance(10.),
+        );
+        assert_eq!(e.contents()[1].contents().len(), 1);
+    }
+}
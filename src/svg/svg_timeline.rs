@@ -0,0 +1,450 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_timeline.rs
+@brief   Attributes and transforms of elements keyed at times
+ */
+
+//a Imports
+use super::svg_format::NumberText;
+use crate::{Error, Svg, SvgConfig, SvgElement, SvgError, SvgTag, Transform};
+
+//a TrackKind
+//ti TrackKind
+/// What a track of a [Timeline] animates
+#[derive(Debug, Clone, PartialEq)]
+enum TrackKind {
+    /// A numeric attribute
+    Attribute(String),
+    /// The transform, keyed as translation x and y, rotation and scale
+    Transform,
+}
+
+//a Track
+//ti Track
+/// The keys of one attribute (or the transform) of one element
+#[derive(Debug, Clone)]
+struct Track {
+    id: String,
+    kind: TrackKind,
+    /// Keys in time order; each is a time and values
+    keys: Vec<(f64, Vec<f64>)>,
+}
+
+//ii Track
+impl Track {
+    //mi add_key
+    fn add_key(&mut self, time: f64, values: Vec<f64>) {
+        self.keys.retain(|(t, _)| *t != time);
+        let n = self.keys.partition_point(|(t, _)| *t < time);
+        self.keys.insert(n, (time, values));
+    }
+
+    //mi value_at
+    /// Interpolate the values linearly at a time, holding the first
+    /// and last keys before and after them
+    fn value_at(&self, time: f64) -> Vec<f64> {
+        let n = self.keys.partition_point(|(t, _)| *t <= time);
+        if n == 0 {
+            return self.keys[0].1.clone();
+        }
+        if n == self.keys.len() {
+            return self.keys[n - 1].1.clone();
+        }
+        let (t0, v0) = &self.keys[n - 1];
+        let (t1, v1) = &self.keys[n];
+        let f = (time - t0) / (t1 - t0);
+        v0.iter()
+            .zip(v1.iter())
+            .map(|(a, b)| a + (b - a) * f)
+            .collect()
+    }
+
+    //mi keys_of_component
+    /// Get the times and values of the keys for component `i`, with
+    /// keys added at the start and end of the timeline if required
+    fn keys_of_component(&self, duration: f64, i: usize) -> Vec<(f64, f64)> {
+        let mut keys: Vec<(f64, f64)> = self
+            .keys
+            .iter()
+            .filter(|(t, _)| *t >= 0. && *t <= duration)
+            .map(|(t, v)| (*t, v[i]))
+            .collect();
+        if keys.first().map(|(t, _)| *t) != Some(0.) {
+            keys.insert(0, (0., self.value_at(0.)[i]));
+        }
+        if keys.last().map(|(t, _)| *t) != Some(duration) {
+            keys.push((duration, self.value_at(duration)[i]));
+        }
+        keys
+    }
+
+    //mi key_times_and_values
+    /// Get the SMIL 'keyTimes' and the values of the keys for
+    /// component `i`, as for [Self::keys_of_component]
+    fn key_times_and_values(&self, duration: f64, i: usize) -> (NumberText, NumberText) {
+        let keys = self.keys_of_component(duration, i);
+        (
+            NumberText::of_numbers(keys.iter().map(|(t, _)| t / duration), ";"),
+            NumberText::of_numbers(keys.iter().map(|(_, v)| *v), ";"),
+        )
+    }
+}
+
+//a Timeline
+//tp Timeline
+/// A [Timeline] holds numeric attributes and transforms of elements
+/// (identified by their 'id') keyed at times, with linear
+/// interpolation between keys
+///
+/// It may be exported as SMIL animations added to the elements, so
+/// that one SVG file plays the animation, or as a sequence of static
+/// frames (for assembly into a video), in which each element has the
+/// values interpolated for the time of the frame
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    duration: f64,
+    repeat: bool,
+    tracks: Vec<Track>,
+}
+
+//ip Timeline
+impl Timeline {
+    //fp new
+    /// Create a timeline of a duration in seconds
+    pub fn new(duration: f64) -> Self {
+        Self {
+            duration,
+            repeat: false,
+            tracks: vec![],
+        }
+    }
+
+    //bp set_repeat
+    /// Set whether the SMIL animation repeats indefinitely
+    pub fn set_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    //ap duration
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    //mi track_mut
+    fn track_mut(&mut self, id: &str, kind: TrackKind) -> &mut Track {
+        let n = match self
+            .tracks
+            .iter()
+            .position(|t| t.id == id && t.kind == kind)
+        {
+            Some(n) => n,
+            None => {
+                self.tracks.push(Track {
+                    id: id.into(),
+                    kind,
+                    keys: vec![],
+                });
+                self.tracks.len() - 1
+            }
+        };
+        &mut self.tracks[n]
+    }
+
+    //mp key_attribute
+    /// Key a numeric attribute (such as 'opacity' or 'r') of the
+    /// element with an id at a time (in seconds)
+    pub fn key_attribute(&mut self, id: &str, attr: &str, time: f64, value: f64) {
+        self.track_mut(id, TrackKind::Attribute(attr.into()))
+            .add_key(time, vec![value]);
+    }
+
+    //mp key_transform
+    /// Key the transform of the element with an id at a time (in
+    /// seconds); the translation, rotation and scale are interpolated
+    /// separately, and any flip is ignored
+    pub fn key_transform(&mut self, id: &str, time: f64, transform: &Transform) {
+        let t = transform.translation();
+        self.track_mut(id, TrackKind::Transform).add_key(
+            time,
+            vec![t[0], t[1], transform.rotation(), transform.scale()],
+        );
+    }
+
+    //mp attribute_at
+    /// Get the value of a keyed attribute of an element at a time
+    pub fn attribute_at(&self, id: &str, attr: &str, time: f64) -> Option<f64> {
+        let kind = TrackKind::Attribute(attr.into());
+        self.tracks
+            .iter()
+            .find(|t| t.id == id && t.kind == kind)
+            .map(|t| t.value_at(time)[0])
+    }
+
+    //mp transform_at
+    /// Get the keyed transform of an element at a time
+    pub fn transform_at(&self, id: &str, time: f64) -> Option<Transform> {
+        self.tracks
+            .iter()
+            .find(|t| t.id == id && t.kind == TrackKind::Transform)
+            .map(|t| {
+                let v = t.value_at(time);
+                Transform::of_trs([v[0], v[1]].into(), v[2], v[3])
+            })
+    }
+
+    //mi smil
+    /// Create an SMIL animation element
+    fn smil<'a>(
+        &self,
+        name: &'static str,
+        key_times: NumberText,
+        values: NumberText,
+    ) -> SvgElement<'a> {
+        let mut a = SvgTag::new(name, None);
+        a.add_numbers("dur", NumberText::of_seconds(self.duration));
+        a.add_numbers("keyTimes", key_times);
+        a.add_numbers("values", values);
+        a.add_attribute("calcMode", None, "linear");
+        if self.repeat {
+            a.add_attribute("repeatCount", None, "indefinite");
+        } else {
+            a.add_attribute("fill", None, "freeze");
+        }
+        a
+    }
+
+    //mp animate
+    /// Add SMIL animations to the elements of a tree (prior to
+    /// finalizing) for all of the keyed attributes and transforms
+    ///
+    /// Transforms are animated as a translation, then a rotation, then
+    /// a scale, replacing any transform of the element
+    ///
+    /// An error is returned (and nothing animated) if a track is keyed
+    /// for an id that is not in the tree
+    pub fn animate(&self, element: &mut SvgElement) -> Result<(), Error> {
+        self.check_ids(element)?;
+        self.animate_tree(element);
+        Ok(())
+    }
+
+    //mi check_ids
+    /// Check that every track is for an id in a tree
+    fn check_ids(&self, element: &SvgElement) -> Result<(), Error> {
+        for track in self.tracks.iter() {
+            if element.find_by_id(&track.id).is_none() {
+                return Err(Error::UnknownReference {
+                    id: track.id.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    //mi animate_tree
+    fn animate_tree(&self, element: &mut SvgElement) {
+        if let Some(id) = element.get_attribute("id").map(|s| s.to_string()) {
+            for track in self.tracks.iter().filter(|t| t.id == id) {
+                if track.keys.is_empty() {
+                    continue;
+                }
+                match &track.kind {
+                    TrackKind::Attribute(attr) => {
+                        let (times, values) = track.key_times_and_values(self.duration, 0);
+                        let mut a = self.smil("animate", times, values);
+                        a.add_attribute("attributeName", None, attr);
+                        element.push_content(a);
+                    }
+                    TrackKind::Transform => {
+                        let (times, _) = track.key_times_and_values(self.duration, 0);
+                        let (_, rotate) = track.key_times_and_values(self.duration, 2);
+                        let (_, scale) = track.key_times_and_values(self.duration, 3);
+                        let x = track.keys_of_component(self.duration, 0);
+                        let y = track.keys_of_component(self.duration, 1);
+                        let mut translate = NumberText::default();
+                        for (j, ((_, x), (_, y))) in x.iter().zip(y.iter()).enumerate() {
+                            if j > 0 {
+                                translate = translate.text(";");
+                            }
+                            translate = translate.number(*x).text(" ").number(*y);
+                        }
+                        for (kind, values) in [
+                            ("translate", translate),
+                            ("rotate", rotate),
+                            ("scale", scale),
+                        ] {
+                            let mut a = self.smil("animateTransform", times.clone(), values);
+                            a.add_attribute("attributeName", None, "transform");
+                            a.add_attribute("type", None, kind);
+                            if kind != "translate" {
+                                a.add_attribute("additive", None, "sum");
+                            }
+                            element.push_content(a);
+                        }
+                    }
+                }
+            }
+        }
+        for c in element.contents_mut() {
+            self.animate_tree(c);
+        }
+    }
+
+    //mp apply_frame
+    /// Set the keyed attributes and transforms of the elements of a
+    /// tree (prior to finalizing) to their values at a time, for a
+    /// static frame
+    ///
+    /// An error is returned (and nothing set) if a track is keyed for
+    /// an id that is not in the tree
+    pub fn apply_frame(&self, element: &mut SvgElement, time: f64) -> Result<(), Error> {
+        self.check_ids(element)?;
+        self.apply_frame_tree(element, time);
+        Ok(())
+    }
+
+    //mi apply_frame_tree
+    fn apply_frame_tree(&self, element: &mut SvgElement, time: f64) {
+        if let Some(id) = element.get_attribute("id").map(|s| s.to_string()) {
+            for track in self.tracks.iter().filter(|t| t.id == id) {
+                if track.keys.is_empty() {
+                    continue;
+                }
+                let v = track.value_at(time);
+                match &track.kind {
+                    TrackKind::Attribute(attr) => {
+                        element.set_local_numbers(attr, v[0].into());
+                    }
+                    TrackKind::Transform => {
+                        element.set_transform(Transform::of_trs([v[0], v[1]].into(), v[2], v[3]));
+                    }
+                }
+            }
+        }
+        for c in element.contents_mut() {
+            self.apply_frame_tree(c, time);
+        }
+    }
+
+    //mp frame_times
+    /// Get the times of the frames at a frame rate, from zero to the
+    /// duration inclusive
+    pub fn frame_times(&self, fps: f64) -> Vec<f64> {
+        if fps <= 0. {
+            return vec![0.];
+        }
+        let n = (self.duration * fps).round() as usize;
+        (0..=n).map(|i| (i as f64) / fps).collect()
+    }
+
+    //mp frames_as_xml
+    /// Render the frames at a frame rate as separate SVG documents;
+    /// `build` is invoked for each frame to create the (unfinalized)
+    /// contents, to which the values of the frame are applied
+    pub fn frames_as_xml<'a, F>(
+        &self,
+        config: &SvgConfig,
+        fps: f64,
+        mut build: F,
+    ) -> Result<Vec<String>, SvgError>
+    where
+        F: FnMut() -> SvgElement<'a>,
+    {
+        let mut frames = vec![];
+        for t in self.frame_times(fps) {
+            let mut e = build();
+            self.apply_frame(&mut e, t)?;
+            let mut svg = Svg::new(config.clone());
+            svg.contents_add_element(e);
+            svg.finalize()?;
+            svg.generate_diagram()?;
+            let xml: String = svg.iter_events().map(|e| e.as_xml()).collect();
+            frames.push(xml);
+        }
+        Ok(frames)
+    }
+
+    //mp write_frames
+    /// Write the frames at a frame rate as numbered SVG files in a
+    /// directory, named `<prefix><nnnn>.svg`
+    pub fn write_frames<'a, F>(
+        &self,
+        dir: &std::path::Path,
+        prefix: &str,
+        config: &SvgConfig,
+        fps: f64,
+        build: F,
+    ) -> Result<usize, SvgError>
+    where
+        F: FnMut() -> SvgElement<'a>,
+    {
+        let frames = self.frames_as_xml(config, fps, build)?;
+        for (i, xml) in frames.iter().enumerate() {
+            let path = dir.join(format!("{}{:04}.svg", prefix, i));
+            std::fs::write(path, xml)?;
+        }
+        Ok(frames.len())
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_timeline {
+    use super::*;
+    use crate::svg::number_str;
+    use crate::{BBox, SvgPath};
+    fn square<'a>() -> SvgElement<'a> {
+        let mut e = SvgPath::new_box(BBox::new(0., 0., 1., 1.));
+        e.add_attribute("id", None, "sq");
+        e.add_attribute("opacity", None, "1");
+        e
+    }
+    #[test]
+    fn test_values() {
+        let mut tl = Timeline::new(2.);
+        tl.key_attribute("sq", "opacity", 0.5, 0.);
+        tl.key_attribute("sq", "opacity", 1.5, 1.);
+        tl.key_transform("sq", 1., &Transform::of_translation([10., 0.].into()));
+        assert_eq!(tl.attribute_at("sq", "opacity", 0.), Some(0.));
+        assert_eq!(tl.attribute_at("sq", "opacity", 1.), Some(0.5));
+        assert_eq!(tl.attribute_at("sq", "opacity", 2.), Some(1.));
+        assert_eq!(tl.attribute_at("sq", "r", 2.), None);
+        assert_eq!(tl.transform_at("sq", 0.).unwrap().translation()[0], 10.);
+
+        let mut e = square();
+        tl.animate(&mut e).unwrap();
+        assert_eq!(e.contents().len(), 4);
+        assert_eq!(
+            e.contents()[0].get_attribute("keyTimes"),
+            Some([0., 0.25, 0.75, 1.].map(number_str).join(";").as_str())
+        );
+        assert_eq!(e.contents()[2].get_attribute("type"), Some("rotate"));
+
+        let mut e = square();
+        tl.apply_frame(&mut e, 1.).unwrap();
+        assert_eq!(e.get_attribute("opacity"), Some(number_str(0.5).as_str()));
+
+        tl.key_attribute("circle", "r", 0., 1.);
+        let mut e = square();
+        assert!(matches!(
+            tl.apply_frame(&mut e, 1.),
+            Err(Error::UnknownReference { id }) if id == "circle"
+        ));
+        assert_eq!(e.get_attribute("opacity"), Some("1"));
+        assert!(tl.frames_as_xml(&SvgConfig::default(), 1., square).is_err());
+        assert_eq!(tl.frame_times(2.).len(), 5);
+    }
+}
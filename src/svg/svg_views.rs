@@ -0,0 +1,198 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_views.rs
+@brief   Named views of regions of a document, for deep-linking
+ */
+
+//a Imports
+
+use crate::{BBox, Error, NamespaceName, NumberFormat, SvgConfig, SvgElement, SvgElementType};
+
+//a SvgView
+//tp SvgView
+/// A named region of a document (such as a panel of a multi-panel
+/// figure), which is emitted as a 'view' element so that a URL with
+/// the fragment `#<name>` displays just that region
+///
+/// An [SvgView] is also an element type, so a view may be placed
+/// anywhere in the contents of a document
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SvgView {
+    /// Name of the view, used as its id
+    pub name: String,
+    /// Title of the view, for a table of contents
+    pub title: String,
+    /// Region of the document shown by the view
+    pub region: BBox,
+    /// The 'preserveAspectRatio' of the view, if not the default
+    /// ('xMidYMid meet')
+    pub preserve_aspect_ratio: Option<String>,
+}
+
+//ip SvgView
+impl SvgView {
+    //fp new
+    /// Create a new view of a region; the name is reduced to ASCII
+    /// alphanumerics, '-' and '_' so that it is usable as an id, and the
+    /// title is the name as given
+    pub fn new(name: &str, region: BBox) -> Self {
+        let title = name.to_string();
+        let name = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        Self {
+            name,
+            title,
+            region,
+            preserve_aspect_ratio: None,
+        }
+    }
+
+    //bp set_preserve_aspect_ratio
+    /// Set the 'preserveAspectRatio' of the view, such as 'xMinYMin
+    /// slice'
+    pub fn set_preserve_aspect_ratio(mut self, preserve_aspect_ratio: &str) -> Self {
+        self.preserve_aspect_ratio = Some(preserve_aspect_ratio.into());
+        self
+    }
+
+    //ap view_box
+    /// Get the 'viewBox' value of the view, with numbers in a
+    /// [NumberFormat]
+    pub fn view_box(&self, nf: &NumberFormat) -> String {
+        let (x, y, w, h) = self.region.get_bounds();
+        format!(
+            "{} {} {} {}",
+            nf.number(x),
+            nf.number(y),
+            nf.number(w),
+            nf.number(h)
+        )
+    }
+
+    //ap url_fragment
+    /// Get the URL fragment that selects the view
+    pub fn url_fragment(&self) -> String {
+        format!("#{}", self.name)
+    }
+
+    //ap view_spec_fragment
+    /// Get the URL fragment that selects the region of the view
+    /// directly, with an SVG view specification, which does not require
+    /// the 'view' element to be present in the document
+    pub fn view_spec_fragment(&self, nf: &NumberFormat) -> String {
+        let mut r = format!("#svgView(viewBox({})", self.view_box(nf).replace(' ', ","));
+        if let Some(par) = &self.preserve_aspect_ratio {
+            r.push_str(&format!(";preserveAspectRatio({})", par));
+        }
+        r.push(')');
+        r
+    }
+
+    //mp element
+    /// Create the (finalized) 'view' element for the view
+    pub fn element<'a>(&self, svg_cfg: &SvgConfig) -> Result<SvgElement<'a>, Error> {
+        let mut e = SvgElement::new(self.clone());
+        e.finalize(svg_cfg)?;
+        Ok(e)
+    }
+}
+
+//ip SvgElementType for SvgView
+impl<'a> SvgElementType<'a> for SvgView {
+    fn ns_name(&self) -> NamespaceName<'a> {
+        NamespaceName::local("view")
+    }
+    fn push_attributes(&self, nf: &NumberFormat, attrs: &mut Vec<(NamespaceName<'a>, String)>) {
+        attrs.push((NamespaceName::local("id"), self.name.clone()));
+        attrs.push((NamespaceName::local("viewBox"), self.view_box(nf)));
+        if let Some(par) = &self.preserve_aspect_ratio {
+            attrs.push((NamespaceName::local("preserveAspectRatio"), par.clone()));
+        }
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_views {
+    use super::*;
+    use crate::Svg;
+    #[test]
+    fn test_views() {
+        let view = SvgView::new("panel A", BBox::new(10., 0., 60., 50.));
+        assert_eq!(view.name, "panel-A");
+        assert_eq!(view.title, "panel A");
+        assert_eq!(view.url_fragment(), "#panel-A");
+        let cfg = SvgConfig::default().set_decimal_places(Some(0), false);
+        let e = view.element(&cfg).unwrap();
+        assert_eq!(e.get_attribute("viewBox"), Some("10 0 50 50"));
+        let view = view.set_preserve_aspect_ratio("xMinYMin slice");
+        let e = view.element(&cfg).unwrap();
+        assert_eq!(
+            e.get_attribute("preserveAspectRatio"),
+            Some("xMinYMin slice")
+        );
+        assert_eq!(
+            view.view_spec_fragment(&cfg.number_format),
+            "#svgView(viewBox(10,0,50,50);preserveAspectRatio(xMinYMin slice))"
+        );
+
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut g = crate::SvgGroup::new();
+        g.push_content(SvgElement::new(SvgView::new(
+            "C",
+            BBox::new(0., 0., 5., 5.),
+        )));
+        svg.contents_add_element(g);
+        svg.add_view("A", BBox::new(0., 0., 10., 10.));
+        svg.add_view("B", BBox::new(10., 0., 20., 10.));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        assert_eq!(svg.views().len(), 2);
+        let root = svg.root_element().unwrap();
+        let views: Vec<_> = root
+            .contents()
+            .iter()
+            .filter_map(|c| c.get_attribute("id"))
+            .collect();
+        assert_eq!(views, vec!["A", "B"]);
+    }
+    #[test]
+    fn test_write() {
+        let cfg = SvgConfig::default().set_decimal_places(Some(1), true);
+        let nf = cfg.number_format;
+        let mut svg = Svg::new(cfg);
+        svg.contents_add_element(crate::SvgPath::new_box(BBox::new(0., 0., 20., 10.)));
+        svg.add_view("left half", BBox::new(0., 0., 10., 10.));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.contains(r#"<view id="left-half" viewBox="0 0 10 10"/>"#));
+        // The fragment of the view names the id in the document
+        let view = &svg.views()[0];
+        assert!(xml.contains(&format!(r#"id="{}""#, &view.url_fragment()[1..])));
+        assert_eq!(view.view_spec_fragment(&nf), "#svgView(viewBox(0,0,10,10))");
+    }
+}
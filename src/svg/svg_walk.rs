@@ -0,0 +1,167 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    svg_walk.rs
+@brief   Walking and visiting the tree of an element
+ */
+
+//a Imports
+use crate::SvgElement;
+
+//a Visitor
+//tt Visitor
+/// A [Visitor] is invoked on entering and leaving each element of a
+/// tree visited with [SvgElement::visit], with the depth of the
+/// element (zero for the element the visit started at)
+///
+/// This permits passes that need to know where the contents of an
+/// element end (such as tracking the transforms or styles in force)
+/// without reimplementing the recursion
+pub trait Visitor<'a> {
+    /// Invoked on entering an element, before its contents; return
+    /// false to skip the contents (leave is still invoked)
+    fn enter(&mut self, _e: &SvgElement<'a>, _depth: usize) -> bool {
+        true
+    }
+    /// Invoked on leaving an element, after its contents
+    fn leave(&mut self, _e: &SvgElement<'a>, _depth: usize) {}
+}
+
+//a SvgElement
+//ip SvgElement
+impl<'a> SvgElement<'a> {
+    //mp walk
+    /// Invoke a function on the element and every descendant, in
+    /// document order, with its depth (zero for this element)
+    pub fn walk<'x, F>(&'x self, f: &mut F)
+    where
+        F: FnMut(&'x Self, usize),
+    {
+        self.walk_at(f, 0);
+    }
+
+    //mi walk_at
+    fn walk_at<'x, F>(&'x self, f: &mut F, depth: usize)
+    where
+        F: FnMut(&'x Self, usize),
+    {
+        f(self, depth);
+        for c in self.contents() {
+            c.walk_at(f, depth + 1);
+        }
+    }
+
+    //mp walk_mut
+    /// Invoke a function on the element and every descendant, in
+    /// document order, with its depth (zero for this element),
+    /// permitting it to be changed
+    ///
+    /// An element is changed before its contents are walked, so any
+    /// contents added to it are also walked
+    pub fn walk_mut<F>(&mut self, f: &mut F)
+    where
+        F: FnMut(&mut Self, usize),
+    {
+        self.walk_mut_at(f, 0);
+    }
+
+    //mi walk_mut_at
+    fn walk_mut_at<F>(&mut self, f: &mut F, depth: usize)
+    where
+        F: FnMut(&mut Self, usize),
+    {
+        f(self, depth);
+        for c in self.contents_mut() {
+            c.walk_mut_at(f, depth + 1);
+        }
+    }
+
+    //mp visit
+    /// Visit the element and its descendants with a [Visitor]
+    pub fn visit<V: Visitor<'a>>(&self, visitor: &mut V) {
+        self.visit_at(visitor, 0);
+    }
+
+    //mi visit_at
+    fn visit_at<V: Visitor<'a>>(&self, visitor: &mut V, depth: usize) {
+        if visitor.enter(self, depth) {
+            for c in self.contents() {
+                c.visit_at(visitor, depth + 1);
+            }
+        }
+        visitor.leave(self, depth);
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_walk {
+    use super::*;
+    use crate::{BBox, SvgGroup, SvgPath, SvgText};
+    fn tree<'a>() -> SvgElement<'a> {
+        let mut g = SvgGroup::new();
+        let mut inner = SvgGroup::new();
+        inner.push_content(SvgPath::new_box(BBox::new(0., 0., 1., 1.)));
+        inner.push_content(SvgText::new([0., 0.].into(), "a"));
+        g.push_content(inner);
+        g.push_content(SvgPath::new_box(BBox::new(0., 0., 2., 2.)));
+        g
+    }
+    struct Outline(String);
+    impl<'a> Visitor<'a> for Outline {
+        fn enter(&mut self, e: &SvgElement<'a>, depth: usize) -> bool {
+            self.0.push_str(&format!("<{}", e.ns_name()));
+            depth == 0
+        }
+        fn leave(&mut self, _e: &SvgElement<'a>, _depth: usize) {
+            self.0.push('>');
+        }
+    }
+    #[test]
+    fn test_walk() {
+        let mut g = tree();
+        let mut seen = vec![];
+        g.walk(&mut |e, depth| seen.push((e.ns_name().to_string(), depth)));
+        let seen: Vec<(&str, usize)> = seen.iter().map(|(n, d)| (n.as_str(), *d)).collect();
+        assert_eq!(
+            seen,
+            vec![("g", 0), ("g", 1), ("path", 2), ("text", 2), ("path", 1)]
+        );
+
+        let mut paths = vec![];
+        g.walk(&mut |e, _| {
+            if e.ns_name().to_string() == "path" {
+                paths.push(e);
+            }
+        });
+        assert_eq!(paths.len(), 2);
+
+        g.walk_mut(&mut |e, depth| {
+            if depth > 0 {
+                e.set_attribute("stroke", None, "red");
+            }
+        });
+        let mut n = 0;
+        g.walk(&mut |e, _| {
+            if e.get_attribute("stroke") == Some("red") {
+                n += 1;
+            }
+        });
+        assert_eq!(n, 4);
+
+        let mut outline = Outline(String::new());
+        g.visit(&mut outline);
+        assert_eq!(outline.0, "<g<g><path>>");
+    }
+}
@@ -52,20 +52,20 @@ impl std::fmt::Display for CharIndices {
 // hmtx has lsb (left side bearing) and rsb (right side bearing) and width
 impl CharIndices {
     //fi of_indices
+    /// Pack the indices, or return None if any is too large for its
+    /// field
     fn of_indices(
         width: usize,
         height: usize,
         depth: usize,
         italic: usize,
         options: usize,
-    ) -> Self {
-        assert!(width < 256);
-        assert!(height < 16);
-        assert!(depth < 16);
-        assert!(italic < 64);
-        assert!(options < 1024);
+    ) -> Option<Self> {
+        if width >= 256 || height >= 16 || depth >= 16 || italic >= 64 || options >= 1024 {
+            return None;
+        }
         let v = (options << 22) | (italic << 16) | (depth << 12) | (height << 8) | (width << 0);
-        Self(v as u32)
+        Some(Self(v as u32))
     }
 
     //fp width_index
@@ -89,16 +89,16 @@ impl CharIndices {
         ((self.0 >> 22) & 0x3ff) as usize
     }
     //fp width
-    pub fn width<V: Value>(&self, metrics: &Metrics) -> f32 {
+    pub fn width<V: Value>(&self, metrics: &Metrics) -> Option<f32> {
         metrics.get_width(self.width_index())
     }
-    pub fn height<V: Value>(&self, metrics: &Metrics) -> f32 {
+    pub fn height<V: Value>(&self, metrics: &Metrics) -> Option<f32> {
         metrics.get_height(self.height_index())
     }
-    pub fn depth<V: Value>(&self, metrics: &Metrics) -> f32 {
+    pub fn depth<V: Value>(&self, metrics: &Metrics) -> Option<f32> {
         metrics.get_depth(self.depth_index())
     }
-    pub fn italic<V: Value>(&self, metrics: &Metrics) -> f32 {
+    pub fn italic<V: Value>(&self, metrics: &Metrics) -> Option<f32> {
         metrics.get_italic(self.italic_index())
     }
 }
@@ -156,7 +156,8 @@ impl CharRangeMetrics {
         let num_heights = 1;
         let num_depths = 1;
         let num_italics = 1;
-        let char_metrics = vec![CharIndices::of_indices(1, 1, 1, 1, 0)];
+        // The indices are all in range, so this is a single entry
+        let char_metrics = CharIndices::of_indices(1, 1, 1, 1, 0).into_iter().collect();
         let parameters = Vec::new();
         let exceptions = Vec::new();
         Self {
@@ -172,50 +173,45 @@ impl CharRangeMetrics {
             exceptions,
         }
     }
+    //mi get_value
+    /// Get the value at an index (from 1) of one of the tables in
+    /// the data, which starts at an offset and has a length; index 0
+    /// is always 0.0, and None is returned if the index is beyond the
+    /// table
     #[inline]
-    pub fn get_width(&self, index: usize) -> f32 {
-        assert!(index <= self.num_widths);
-        if index == 0 {
-            0.0
-        } else {
-            self.data[index - 1]
+    fn get_value(&self, offset: usize, length: usize, index: usize) -> Option<f32> {
+        match index {
+            0 => Some(0.0),
+            i if i <= length => self.data.get(offset + i - 1).copied(),
+            _ => None,
         }
     }
     #[inline]
-    pub fn get_height(&self, index: usize) -> f32 {
-        assert!(index <= self.num_heights);
-        if index == 0 {
-            0.0
-        } else {
-            self.data[self.num_widths + index - 1]
-        }
+    pub fn get_width(&self, index: usize) -> Option<f32> {
+        self.get_value(0, self.num_widths, index)
     }
     #[inline]
-    pub fn get_depth(&self, index: usize) -> f32 {
-        assert!(index <= self.num_depths);
-        if index == 0 {
-            0.0
-        } else {
-            self.data[self.num_widths + self.num_heights + index - 1]
-        }
+    pub fn get_height(&self, index: usize) -> Option<f32> {
+        self.get_value(self.num_widths, self.num_heights, index)
     }
     #[inline]
-    pub fn get_italic(&self, index: usize) -> f32 {
-        assert!(index <= self.num_italics);
-        if index == 0 {
-            0.0
-        } else {
-            self.data[self.num_widths + self.num_heights + self.num_depths + index - 1]
-        }
+    pub fn get_depth(&self, index: usize) -> Option<f32> {
+        let offset = self.num_widths + self.num_heights;
+        self.get_value(offset, self.num_depths, index)
     }
-    pub fn get_glyph_metrics(&self, index: usize) -> GlyphMetrics {
-        let ci = self.char_metrics[index];
-        let width = ci.width(self);
-        let height = ci.height(self);
-        let depth = ci.depth(self);
-        let italic = ci.italic(self);
+    #[inline]
+    pub fn get_italic(&self, index: usize) -> Option<f32> {
+        let offset = self.num_widths + self.num_heights + self.num_depths;
+        self.get_value(offset, self.num_italics, index)
+    }
+    pub fn get_glyph_metrics(&self, index: usize) -> Option<GlyphMetrics> {
+        let ci = self.char_metrics.get(index)?;
+        let width = ci.width(self)?;
+        let height = ci.height(self)?;
+        let depth = ci.depth(self)?;
+        let italic = ci.italic(self)?;
         let options = ci.options();
-        GlyphMetrics {
+        Some(GlyphMetrics {
             width,
             ascent: height,
             descent: depth,
@@ -223,7 +219,7 @@ impl CharRangeMetrics {
             rsb: 0.,
             italic,
             options,
-        }
+        })
     }
     /// Get the [CharRangeMetrics] for a Unicode codepoint and the
     /// index into it for it
@@ -243,7 +239,8 @@ impl CharRangeMetrics {
     }
     /// Get the glyph metrics for a Unicode codepoint
     pub fn glyph_metrics(&self, c: char) -> Option<GlyphMetrics> {
-        self.metrics_of_char(c).map(|(m, i)| m.get_glyph_metrics(i))
+        self.metrics_of_char(c)
+            .and_then(|(m, i)| m.get_glyph_metrics(i))
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::{Attributes, BBox, LayoutElement, Svg, SvgElement, Transform};
+use crate::{Attributes, LayoutElement, Svg, SvgElement};
 
 pub trait SvgAttributes: Attributes {
     fn add_attributes(&self, svg: &mut Svg, element: &mut SvgElement);
@@ -18,29 +18,75 @@ limitations under the License.
 
 //a Imports
 use indent_display::IndentedOptions;
-pub struct IndentOpt();
+
+/// Options for the indented display (such as of an [crate::Svg] or
+/// [crate::SvgElement]) used when debugging layout; by default just
+/// the names and transforms of the elements are shown
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IndentOpt {
+    /// if asserted then the bounding box of each element is shown
+    pub bboxes: bool,
+    /// if asserted then all of the attributes of each element are shown
+    pub attributes: bool,
+    /// if asserted then a summary of the style of each element (its
+    /// class, fill, stroke and so on) is shown
+    pub styles: bool,
+}
 impl<'a> IndentedOptions<'a> for IndentOpt {}
+impl IndentOpt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn set_bboxes(mut self, bboxes: bool) -> Self {
+        self.bboxes = bboxes;
+        self
+    }
+    pub fn set_attributes(mut self, attributes: bool) -> Self {
+        self.attributes = attributes;
+        self
+    }
+    pub fn set_styles(mut self, styles: bool) -> Self {
+        self.styles = styles;
+        self
+    }
+}
 
 /// The [Point] type is a 2D point of f64's
 pub type Point = geo_nd::FArray<f64, 2>;
 
+/// The [Point3D] type is a 3D point of f64's, used for wireframes
+pub type Point3D = geo_nd::FArray<f64, 3>;
+
 /// The [Bezier] type is a Bezier curve of [Point]s
 pub type Bezier = bezier_nd::Bezier<f64, Point, 2>;
 
+mod affine;
 mod bbox;
 mod error;
+mod length;
+mod margins;
+mod polar;
 mod range;
+#[cfg(feature = "serde")]
+pub(crate) mod serde_point;
+mod snap;
 mod transform;
 use crate::Attributes;
-pub use bbox::BBox;
-pub use error::Error;
+pub use affine::Affine;
+pub use bbox::{BBox, FitMode};
+pub use error::{Error, ErrorContext};
+pub use length::{Length, LengthUnit};
+pub use margins::Margins;
+pub use polar::PolarPoint;
 pub use range::Range;
+pub use snap::Snap;
 pub use transform::Transform;
 
 #[derive(Debug, Default)]
 pub struct Attr {
     bbox: BBox,
     transform: Transform,
+    margins: Margins,
 }
 impl Attributes for Attr {
     fn bbox(&self) -> BBox {
@@ -55,4 +101,10 @@ impl Attributes for Attr {
     fn set_transform(&mut self, transform: Transform) {
         self.transform = transform;
     }
+    fn margins(&self) -> Margins {
+        self.margins
+    }
+    fn set_margins(&mut self, margins: Margins) {
+        self.margins = margins;
+    }
 }
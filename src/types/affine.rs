@@ -0,0 +1,280 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    affine.rs
+@brief   Full affine transformation class
+ */
+
+//a Imports
+use crate::svg::display_number_format;
+use crate::{Error, NumberFormat, Point, Transform};
+
+//a Affine type
+//tp Affine
+/// An [Affine] is an arbitrary 2D affine transformation, including
+/// skew and non-uniform scaling, which a [Transform] cannot represent
+///
+/// It is held as the six values of an SVG 'matrix(a b c d e f)',
+/// i.e. the 3x3 matrix
+///
+///   a c e; b d f; 0 0 1
+///
+/// which maps (x,y) to (a*x + c*y + e, b*x + d*y + f)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    /// The values a, b, c, d, e, f
+    m: [f64; 6],
+}
+
+//ip Default for Affine
+impl std::default::Default for Affine {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+//ip Affine
+impl Affine {
+    //cp identity
+    /// Create an identity affine transformation
+    #[inline]
+    #[must_use]
+    pub const fn identity() -> Self {
+        Self {
+            m: [1., 0., 0., 1., 0., 0.],
+        }
+    }
+
+    //cp of_abcdef
+    /// Create an affine transformation from the six values of an SVG
+    /// 'matrix(a b c d e f)'
+    #[inline]
+    #[must_use]
+    pub const fn of_abcdef(m: [f64; 6]) -> Self {
+        Self { m }
+    }
+
+    //fp of_matrix
+    /// Set to be whatever a 3x3 matrix indicates
+    ///
+    /// The matrix must be a c e; b d f; 0 0 1
+    pub fn of_matrix(matrix: &[f64]) -> Result<Self, Error> {
+        if matrix.len() != 9 {
+            Err(Error::InvalidTransformationMatrix {
+                reason: "matrix was not 3-by-3".into(),
+            })?
+        }
+        if !(matrix[8] == 1. && matrix[7] == 0. && matrix[6] == 0.) {
+            Err(Error::InvalidTransformationMatrix {
+                reason: "bottom row must be 0, 0, 1".into(),
+            })?
+        }
+        Ok(Self::of_abcdef([
+            matrix[0], matrix[3], matrix[1], matrix[4], matrix[2], matrix[5],
+        ]))
+    }
+
+    //ap abcdef
+    /// Get the six values of the SVG 'matrix(a b c d e f)'
+    #[inline]
+    pub fn abcdef(&self) -> [f64; 6] {
+        self.m
+    }
+
+    //mp to_matrix
+    /// Returns a 3x3 matrix that can be applied to points (x,y,1) or vectors (dx,dy,0)
+    pub fn to_matrix(&self) -> [f64; 9] {
+        let [a, b, c, d, e, f] = self.m;
+        [a, c, e, b, d, f, 0., 0., 1.]
+    }
+
+    //mp is_identity
+    /// Return true if this is an identity transformation
+    pub fn is_identity(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    //mp determinant
+    /// Return the determinant of the (top left 2x2 of the) matrix
+    pub fn determinant(&self) -> f64 {
+        self.m[0] * self.m[3] - self.m[1] * self.m[2]
+    }
+
+    //mp apply
+    /// Apply this transformation to a point
+    pub fn apply(&self, pt: Point) -> Point {
+        let [a, b, c, d, e, f] = self.m;
+        let x = pt[0];
+        let y = pt[1];
+        [a * x + c * y + e, b * x + d * y + f].into()
+    }
+
+    //mp apply_to_affine
+    /// Apply this transformation to another, returning a new
+    /// transformation that is the other followed by this
+    pub fn apply_to_affine(&self, other: &Self) -> Self {
+        let [a0, b0, c0, d0, e0, f0] = self.m;
+        let [a1, b1, c1, d1, e1, f1] = other.m;
+        Self::of_abcdef([
+            a0 * a1 + c0 * b1,
+            b0 * a1 + d0 * b1,
+            a0 * c1 + c0 * d1,
+            b0 * c1 + d0 * d1,
+            a0 * e1 + c0 * f1 + e0,
+            b0 * e1 + d0 * f1 + f0,
+        ])
+    }
+
+    //mp inverse
+    /// Return the inverse of this transformation, if it is not singular
+    pub fn inverse(&self) -> Result<Self, Error> {
+        let det = self.determinant();
+        if det.abs() < 1.0E-12 {
+            Err(Error::InvalidTransformationMatrix {
+                reason: "matrix is singular and cannot be inverted".into(),
+            })?
+        }
+        let [a, b, c, d, e, f] = self.m;
+        Ok(Self::of_abcdef([
+            d / det,
+            -b / det,
+            -c / det,
+            a / det,
+            (c * f - d * e) / det,
+            (b * e - a * f) / det,
+        ]))
+    }
+
+    //mp as_transform
+    /// Convert to a [Transform], if the affine transformation is
+    /// simply a translation, rotation and uniform scale
+    pub fn as_transform(&self) -> Result<Transform, Error> {
+        Transform::of_matrix(&self.to_matrix())
+    }
+
+    //fp as_svg_attribute_string
+    /// Generate the string for an SVG 'transform' attribute, with
+    /// numbers in the default [NumberFormat]
+    pub fn as_svg_attribute_string(&self) -> String {
+        self.svg_attribute_string(&NumberFormat::default())
+    }
+
+    //fp svg_attribute_string
+    /// Generate the string for an SVG 'transform' attribute, with
+    /// numbers in a [NumberFormat]
+    pub fn svg_attribute_string(&self, number_format: &NumberFormat) -> String {
+        if self.is_identity() {
+            String::new()
+        } else {
+            let m: Vec<String> = self.m.iter().map(|v| number_format.number(*v)).collect();
+            format!("matrix({})", m.join(" "))
+        }
+    }
+
+    //zz All done
+}
+
+//ip From<Transform> for Affine
+impl From<Transform> for Affine {
+    fn from(t: Transform) -> Self {
+        let m = t.to_matrix();
+        Self::of_abcdef([m[0], m[3], m[1], m[4], m[2], m[5]])
+    }
+}
+
+//ip TryFrom<Affine> for Transform
+impl TryFrom<Affine> for Transform {
+    type Error = Error;
+    fn try_from(a: Affine) -> Result<Self, Error> {
+        a.as_transform()
+    }
+}
+
+//ip std::fmt::Display for Affine
+impl std::fmt::Display for Affine {
+    //mp fmt - format an `Affine` for display
+    /// Display the `Affine` in a human-readable form
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_identity() {
+            write!(f, "<identity>")
+        } else {
+            let nf = display_number_format(f);
+            write!(
+                f,
+                "<[{} {} {}; {} {} {}]>",
+                nf.number(self.m[0]),
+                nf.number(self.m[2]),
+                nf.number(self.m[4]),
+                nf.number(self.m[1]),
+                nf.number(self.m[3]),
+                nf.number(self.m[5])
+            )
+        }
+    }
+}
+
+//mt Test for Affine
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn approx_eq(a: f64, b: f64) -> bool {
+        let diff = a - b;
+        diff > -1.0E-6 && diff < 1.0E-6
+    }
+    fn check_affine(a: &Affine, e: &[f64; 6]) {
+        let okay = a
+            .abcdef()
+            .iter()
+            .zip(e.iter())
+            .fold(true, |acc, (m, e)| acc && approx_eq(*m, *e));
+        assert!(okay, "Affine {} expected {:?}", a, e);
+    }
+    #[test]
+    fn test_transform() {
+        let t = Transform::of_trs([4., 5.].into(), 90., 7.);
+        let a: Affine = t.into();
+        check_affine(&a, &[0., 7., -7., 0., 4., 5.]);
+        let t1: Transform = a.try_into().unwrap();
+        let p = t1.apply([1., 2.].into());
+        let q = a.apply([1., 2.].into());
+        assert!(approx_eq(p[0], q[0]) && approx_eq(p[1], q[1]));
+        let skew = Affine::of_abcdef([1., 0., 0.5, 1., 0., 0.]);
+        assert!(skew.as_transform().is_err());
+    }
+    #[test]
+    fn test_compose() {
+        let skew = Affine::of_abcdef([1., 0., 0.5, 1., 0., 0.]);
+        let t: Affine = Transform::of_trs([4., 5.].into(), 30., 2.).into();
+        let c = t.apply_to_affine(&skew);
+        for p in [[1., 2.], [-3., 0.5], [0., 0.]] {
+            let p: Point = p.into();
+            let p0 = c.apply(p);
+            let p1 = t.apply(skew.apply(p));
+            assert!(approx_eq(p0[0], p1[0]) && approx_eq(p0[1], p1[1]));
+        }
+        let ci = c.inverse().unwrap();
+        check_affine(&ci.apply_to_affine(&c), &[1., 0., 0., 1., 0., 0.]);
+        assert!(Affine::of_abcdef([1., 2., 2., 4., 0., 0.])
+            .inverse()
+            .is_err());
+    }
+    #[test]
+    fn test_svg() {
+        assert_eq!(Affine::identity().as_svg_attribute_string(), "");
+        assert_eq!(
+            Affine::of_abcdef([1., 0., 0.5, 1., 2., 3.]).as_svg_attribute_string(),
+            "matrix(1.0000 0.0000 0.5000 1.0000 2.0000 3.0000)"
+        );
+    }
+}
@@ -18,14 +18,36 @@ limitations under the License.
 //a Imports
 use geo_nd::vector;
 
-use crate::{Point, Range, Transform};
+use crate::{Affine, Error, Margins, Point, PolarPoint, Range, Transform};
+
+//a FitMode
+//tp FitMode
+/// The manner in which one [BBox] is fitted within another
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FitMode {
+    /// Scale uniformly so that the whole of the bbox is within the
+    /// target, leaving space on two sides if the aspect ratios differ
+    #[default]
+    Contain,
+    /// Scale uniformly so that the bbox covers the whole of the
+    /// target, overflowing on two sides if the aspect ratios differ
+    Cover,
+    /// Scale X and Y independently so that the bbox exactly matches
+    /// the target
+    Stretch,
+}
 
 //a BBox
 //tp BBox
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// [BBox] describes a region bounded by (x0,y0) and (x1,y1) It
 /// requires x0 <= x1 and y0 <= y1, and if either are equal then the
 /// region is deemed to be *none*
+///
+/// Either range may be open-ended (see [Range]), in which case the
+/// [BBox] is not *bounded*; it can be clamped to a bounded region
+/// (such as the final viewbox of a diagram) using [BBox::clamp]
 pub struct BBox {
     /// X range
     pub x: Range,
@@ -65,6 +87,12 @@ impl BBox {
         self.x.is_none() || self.y.is_none()
     }
 
+    //mp is_bounded
+    /// Return `true` if the rectangle is bounded in both X and Y
+    pub fn is_bounded(&self) -> bool {
+        self.x.is_bounded() && self.y.is_bounded()
+    }
+
     //fp new
     /// Make a rectangle using the coordinates supplied, ensuring that
     /// the rectangle is correctly defined
@@ -91,12 +119,7 @@ impl BBox {
     //cp of_points
     /// Make a new rectangle that is the bounding box of a vec of points
     pub fn of_points(pts: &[Point]) -> Self {
-        let mut s = Self::none();
-        for p in pts.iter() {
-            s.x = s.x.include(p[0]);
-            s.y = s.y.include(p[1]);
-        }
-        s
+        pts.iter().copied().collect()
     }
 
     //fp of_cwh
@@ -199,22 +222,44 @@ impl BBox {
     }
 
     //cp expand
-    /// exand in-place by expansion scaled by 'scale'
+    /// Expand outwards by margins scaled by 'scale'
     ///
-    /// Was Float4 x0, x1, y0,, x1 now [x0, y0, x1, y1]
+    /// The margins may be given as [Margins] or as an array of
+    /// [left, top, right, bottom] (i.e. in the order [x0, y0, x1, y1])
     #[must_use]
-    pub fn expand(mut self, other: &[f64; 4], scale: f64) -> Self {
-        self.x = Range::new(self.x[0] - scale * other[0], self.x[1] + scale * other[2]);
-        self.y = Range::new(self.y[0] - scale * other[1], self.y[1] + scale * other[3]);
+    pub fn expand<M: Into<Margins>>(mut self, margins: M, scale: f64) -> Self {
+        let m: Margins = margins.into();
+        self.x = Range::new(self.x[0] - scale * m.left, self.x[1] + scale * m.right);
+        self.y = Range::new(self.y[0] - scale * m.top, self.y[1] + scale * m.bottom);
         self
     }
 
     //cp shrink
-    /// shrink in-place by expansion scaled by 'scale'
+    /// Shrink inwards by margins scaled by 'scale'
     #[must_use]
     #[inline]
-    pub fn shrink(self, other: &[f64; 4], scale: f64) -> Self {
-        self.expand(other, -scale)
+    pub fn shrink<M: Into<Margins>>(self, margins: M, scale: f64) -> Self {
+        self.expand(margins, -scale)
+    }
+
+    //cp expand_to_aspect
+    /// Grow the rectangle symmetrically in one dimension so that its
+    /// width / height ratio is as given
+    ///
+    /// A none rectangle, or a ratio that is not positive, leaves the
+    /// rectangle unchanged
+    #[must_use]
+    pub fn expand_to_aspect(mut self, ratio: f64) -> Self {
+        if self.is_none() || ratio <= 0. || !ratio.is_finite() {
+            return self;
+        }
+        let (w, h) = self.get_wh();
+        if w < h * ratio {
+            self.x = self.x.enlarge((h * ratio - w) / 2.);
+        } else if w > h * ratio {
+            self.y = self.y.enlarge((w / ratio - h) / 2.);
+        }
+        self
     }
 
     //cp include
@@ -259,6 +304,54 @@ impl BBox {
         }
     }
 
+    //cp clamp
+    /// Replace any unbounded sides of the rectangle with the
+    /// corresponding sides of `within`
+    #[must_use]
+    #[inline]
+    pub fn clamp(mut self, within: &Self) -> Self {
+        self.x = self.x.clamp(&within.x);
+        self.y = self.y.clamp(&within.y);
+        self
+    }
+
+    //cp of_polar_sector
+    /// Create the bounding box of a sector of an annulus around a
+    /// center, between two radii, sweeping anticlockwise from one
+    /// angle to another (in degrees)
+    ///
+    /// An inner radius of 0 gives a pie slice; a sweep of 360 degrees
+    /// or more is a full annulus
+    pub fn of_polar_sector(
+        center: &Point,
+        r0: f64,
+        r1: f64,
+        start_degrees: f64,
+        end_degrees: f64,
+    ) -> Self {
+        let mut sweep = end_degrees - start_degrees;
+        if sweep < 0. {
+            sweep = sweep.rem_euclid(360.);
+        }
+        let mut bbox = Self::none();
+        for r in [r0, r1] {
+            bbox = bbox
+                .include(Point::from_polar_around(center, r, start_degrees))
+                .include(Point::from_polar_around(
+                    center,
+                    r,
+                    start_degrees + sweep.min(360.),
+                ));
+        }
+        let first_axis = (start_degrees / 90.).ceil() * 90.;
+        let mut a = first_axis;
+        while a <= start_degrees + sweep.min(360.) {
+            bbox = bbox.include(Point::from_polar_around(center, r0.max(r1), a));
+            a += 90.;
+        }
+        bbox
+    }
+
     //cp new_rotated_around
     /// Rotate the rectangle around a point by an angle,
     /// generating a new rectangle that is the bounding box of that rotated rectangle
@@ -284,21 +377,106 @@ impl BBox {
         Self { x, y }
     }
 
+    //mp split_rows
+    /// Split the rectangle into `n` rows of equal height, in order of
+    /// increasing Y
+    pub fn split_rows(&self, n: usize) -> impl Iterator<Item = BBox> {
+        self.grid(1, n, 0.)
+    }
+
+    //mp split_cols
+    /// Split the rectangle into `n` columns of equal width, in order of
+    /// increasing X
+    pub fn split_cols(&self, n: usize) -> impl Iterator<Item = BBox> {
+        self.grid(n, 1, 0.)
+    }
+
+    //mp grid
+    /// Split the rectangle into `nx` by `ny` cells of equal size, with
+    /// a gutter between adjacent cells
+    ///
+    /// The cells are returned a row at a time (in order of increasing
+    /// Y), with each row in order of increasing X; a none rectangle
+    /// has no cells, and if the gutters are too large the cells are
+    /// none
+    pub fn grid(&self, nx: usize, ny: usize, gutter: f64) -> impl Iterator<Item = BBox> {
+        let (nx, ny) = if self.is_none() { (0, 0) } else { (nx, ny) };
+        let (w, h) = self.get_wh();
+        let cell_w = ((w - gutter * (nx.max(1) - 1) as f64) / nx.max(1) as f64).max(0.);
+        let cell_h = ((h - gutter * (ny.max(1) - 1) as f64) / ny.max(1) as f64).max(0.);
+        let (x0, y0) = (self.x[0], self.y[0]);
+        (0..ny).flat_map(move |j| {
+            (0..nx).map(move |i| {
+                let x = x0 + (cell_w + gutter) * (i as f64);
+                let y = y0 + (cell_h + gutter) * (j as f64);
+                BBox::new(x, y, x + cell_w, y + cell_h)
+            })
+        })
+    }
+
+    //fi fit_scales
+    /// Get the X and Y scale factors required to fit this rectangle
+    /// in the target
+    fn fit_scales(&self, target: &BBox, mode: FitMode) -> (f64, f64) {
+        if self.is_none() || target.is_none() {
+            return (1., 1.);
+        }
+        let (w, h) = self.get_wh();
+        let (tw, th) = target.get_wh();
+        let (sx, sy) = match (w > 0., h > 0.) {
+            (true, true) => (tw / w, th / h),
+            (true, false) => (tw / w, tw / w),
+            (false, true) => (th / h, th / h),
+            _ => (1., 1.),
+        };
+        match mode {
+            FitMode::Contain => (sx.min(sy), sx.min(sy)),
+            FitMode::Cover => (sx.max(sy), sx.max(sy)),
+            FitMode::Stretch => (sx, sy),
+        }
+    }
+
+    //mp fit_within
+    /// Get the affine transformation that scales and translates this
+    /// rectangle to fit the target, keeping their centers aligned
+    ///
+    /// This is an [Affine] as [FitMode::Stretch] generally requires
+    /// non-uniform scaling; use [BBox::fit_transform] to get a
+    /// [Transform] for the other modes
+    #[must_use]
+    pub fn fit_within(&self, target: &BBox, mode: FitMode) -> Affine {
+        let (sx, sy) = self.fit_scales(target, mode);
+        let c = self.center();
+        let tc = target.center();
+        Affine::of_abcdef([sx, 0., 0., sy, tc[0] - sx * c[0], tc[1] - sy * c[1]])
+    }
+
+    //mp fit_transform
+    /// Get the [Transform] that scales and translates this rectangle
+    /// to fit the target, keeping their centers aligned
+    ///
+    /// This fails for [FitMode::Stretch] unless the two rectangles
+    /// have the same aspect ratio
+    pub fn fit_transform(&self, target: &BBox, mode: FitMode) -> Result<Transform, Error> {
+        let (sx, sy) = self.fit_scales(target, mode);
+        if (sx - sy).abs() > 1.0E-9 * sx.abs().max(sy.abs()) {
+            Err(Error::InvalidTransformationMatrix {
+                reason: "fitting requires a non-uniform scale".into(),
+            })?
+        }
+        let c = self.center();
+        let tc = target.center();
+        let translation: Point = [tc[0] - sx * c[0], tc[1] - sx * c[1]].into();
+        Ok(Transform::of_trsf(translation, 0., sx, false))
+    }
+
     //mp transform
+    /// Transform the rectangle, returning the bounding box of the
+    /// transformed corners
     #[must_use]
     #[inline]
-    pub fn transform(mut self, transform: &Transform) -> Self {
-        let corners: [Point; 4] = [
-            [self.x[0], self.y[0]].into(),
-            [self.x[1], self.y[0]].into(),
-            [self.x[0], self.y[1]].into(),
-            [self.x[1], self.y[1]].into(),
-        ];
-        self = Self::none();
-        for c in corners {
-            self = self.include(transform.apply(c));
-        }
-        self
+    pub fn transform(self, transform: &Transform) -> Self {
+        transform.apply_bbox(&self)
     }
 
     //zz All done
@@ -374,6 +552,49 @@ impl std::ops::DivAssign<f64> for BBox {
     }
 }
 
+//ip Extend<Point> for BBox
+impl Extend<Point> for BBox {
+    /// Include all of the points in the BBox
+    fn extend<I: IntoIterator<Item = Point>>(&mut self, iter: I) {
+        for p in iter {
+            self.x = self.x.include(p[0]);
+            self.y = self.y.include(p[1]);
+        }
+    }
+}
+
+//ip Extend<BBox> for BBox
+impl Extend<BBox> for BBox {
+    /// Union all of the BBoxes with the BBox
+    fn extend<I: IntoIterator<Item = BBox>>(&mut self, iter: I) {
+        for b in iter {
+            *self = self.union(b);
+        }
+    }
+}
+
+//ip FromIterator<Point> for BBox
+impl std::iter::FromIterator<Point> for BBox {
+    /// Create the smallest BBox that includes all of the points; this
+    /// is none if there are no points
+    fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+        let mut s = Self::none();
+        s.extend(iter);
+        s
+    }
+}
+
+//ip FromIterator<BBox> for BBox
+impl std::iter::FromIterator<BBox> for BBox {
+    /// Create the union of all of the BBoxes; this is none if there
+    /// are none
+    fn from_iter<I: IntoIterator<Item = BBox>>(iter: I) -> Self {
+        let mut s = Self::none();
+        s.extend(iter);
+        s
+    }
+}
+
 //a Test
 #[cfg(test)]
 mod tests_polygon {
@@ -452,10 +673,10 @@ mod tests_polygon {
         let x = BBox::new(2., 1., 5., 7.);
         let y = BBox::new(4., 0., 6., 3.);
         let z = BBox::new(5., 1., 7., 4.);
-        let x_and_y = x.clone().intersect(y);
-        let x_or_y = x.clone().union(y);
-        let x_and_z = x.clone().intersect(z);
-        let x_or_z = x.clone().union(z);
+        let x_and_y = x.intersect(y);
+        let x_or_y = x.union(y);
+        let x_and_z = x.intersect(z);
+        let x_or_z = x.union(z);
         println!("x_and_y:{}", x_and_y);
         println!("x_or_y:{}", x_or_y);
         println!("x_and_z:{}", x_and_z);
@@ -479,8 +700,8 @@ mod tests_polygon {
     fn test_ops_1() {
         let x = BBox::new(2., 1., 5., 7.);
         let y = [0.1, 0.2, 0.3, 0.5];
-        let x_p_y = x.clone().expand(&y, 1.);
-        let x_p_2y = x.clone().expand(&y, 2.);
+        let x_p_y = x.expand(y, 1.);
+        let x_p_2y = x.expand(y, 2.);
         println!("x_p_y:{}", x_p_y);
         println!("x_p_2y:{}", x_p_2y);
         range_eq(&x_p_y.x, 1.9, 5.3);
@@ -492,8 +713,8 @@ mod tests_polygon {
     fn test_ops_2() {
         let x = BBox::new(2., 1., 5., 7.);
         let y = [0.1, 0.2, 0.3, 0.5];
-        let x_m_y = x.clone().shrink(&y, 1.);
-        let x_m_2y = x.clone().shrink(&y, 2.);
+        let x_m_y = x.shrink(y, 1.);
+        let x_m_2y = x.shrink(y, 2.);
         println!("x_m_y:{}", x_m_y);
         println!("x_m_2y:{}", x_m_2y);
         range_eq(&x_m_y.x, 2.1, 4.7);
@@ -501,4 +722,117 @@ mod tests_polygon {
         range_eq(&x_m_2y.x, 2.2, 4.4);
         range_eq(&x_m_2y.y, 1.4, 6.);
     }
+    #[test]
+    fn test_aspect() {
+        let b = BBox::new(0., 0., 10., 10.).expand_to_aspect(16. / 9.);
+        range_eq(&b.y, 0., 10.);
+        assert!((b.width() / b.height() - 16. / 9.).abs() < 1E-8);
+        assert!((b.center()[0] - 5.).abs() < 1E-8);
+        let b = BBox::new(0., 0., 40., 10.).expand_to_aspect(2.);
+        range_eq(&b.x, 0., 40.);
+        range_eq(&b.y, -5., 15.);
+        assert!(BBox::none().expand_to_aspect(2.).is_none());
+    }
+    #[test]
+    fn test_collect() {
+        let pts: Vec<Point> = vec![[1., 2.].into(), [-1., 5.].into(), [3., 0.].into()];
+        let b: BBox = pts.iter().copied().collect();
+        range_eq(&b.x, -1., 3.);
+        range_eq(&b.y, 0., 5.);
+        let b: BBox = vec![
+            BBox::new(0., 0., 1., 1.),
+            BBox::none(),
+            BBox::new(2., 3., 4., 5.),
+        ]
+        .into_iter()
+        .collect();
+        range_eq(&b.x, 0., 4.);
+        range_eq(&b.y, 0., 5.);
+        let mut b = BBox::none();
+        b.extend(pts);
+        range_eq(&b.x, -1., 3.);
+        assert!(std::iter::empty::<Point>().collect::<BBox>().is_none());
+    }
+    #[test]
+    fn test_polar() {
+        let c: Point = [1., 1.].into();
+        let b = BBox::of_polar_sector(&c, 0., 2., 0., 90.);
+        range_eq(&b.x, 1., 3.);
+        range_eq(&b.y, 1., 3.);
+        let b = BBox::of_polar_sector(&c, 1., 2., 45., 135.);
+        range_eq(&b.y, 1. + 0.5_f64.sqrt(), 3.);
+        let b = BBox::of_polar_sector(&c, 1., 2., 270., 90.);
+        range_eq(&b.x, 1., 3.);
+        range_eq(&b.y, -1., 3.);
+        let b = BBox::of_polar_sector(&c, 0., 1., 0., 360.);
+        range_eq(&b.x, 0., 2.);
+        let (r, a) = Point::from_polar(2., 120.).to_polar();
+        assert!((r - 2.).abs() < 1E-8 && (a - 120.).abs() < 1E-8);
+    }
+    #[test]
+    fn test_margins() {
+        let x = BBox::new(0., 0., 10., 20.);
+        let m = Margins::new(1., 2., 3., 4.);
+        let b = x.expand(m, 1.);
+        range_eq(&b.x, -1., 13.);
+        range_eq(&b.y, -2., 24.);
+        let b = x.expand([1., 2., 3., 4.], 2.);
+        range_eq(&b.x, -2., 16.);
+        range_eq(&b.y, -4., 28.);
+        let b = x.shrink(Margins::symmetric(1., 2.), 1.);
+        range_eq(&b.x, 1., 9.);
+        range_eq(&b.y, 2., 18.);
+        assert_eq!(Margins::uniform(2.).horizontal(), 4.);
+    }
+    #[test]
+    fn test_grid() {
+        let x = BBox::new(0., 0., 10., 20.);
+        let rows: Vec<BBox> = x.split_rows(4).collect();
+        assert_eq!(rows.len(), 4);
+        range_eq(&rows[1].x, 0., 10.);
+        range_eq(&rows[1].y, 5., 10.);
+        let cols: Vec<BBox> = x.split_cols(2).collect();
+        range_eq(&cols[1].x, 5., 10.);
+        range_eq(&cols[1].y, 0., 20.);
+        let cells: Vec<BBox> = x.grid(2, 3, 1.).collect();
+        assert_eq!(cells.len(), 6);
+        range_eq(&cells[0].x, 0., 4.5);
+        range_eq(&cells[3].x, 5.5, 10.);
+        range_eq(&cells[3].y, 7., 13.);
+        assert_eq!(BBox::none().grid(2, 2, 0.).count(), 0);
+        assert_eq!(x.split_rows(0).count(), 0);
+    }
+    #[test]
+    fn test_fit() {
+        let x = BBox::new(0., 0., 10., 5.);
+        let target = BBox::new(100., 100., 120., 140.);
+        let t = x.fit_transform(&target, FitMode::Contain).unwrap();
+        let b = x.transform(&t);
+        range_eq(&b.x, 100., 120.);
+        range_eq(&b.y, 115., 125.);
+        let t = x.fit_transform(&target, FitMode::Cover).unwrap();
+        let b = x.transform(&t);
+        range_eq(&b.x, 70., 150.);
+        range_eq(&b.y, 100., 140.);
+        let a = x.fit_within(&target, FitMode::Stretch);
+        pt_eq(&a.apply([0., 0.].into()), 100., 100.);
+        pt_eq(&a.apply([10., 5.].into()), 120., 140.);
+        assert!(x.fit_transform(&target, FitMode::Stretch).is_err());
+        let a = x.fit_within(&target, FitMode::Contain);
+        pt_eq(&a.apply([10., 5.].into()), 120., 125.);
+    }
+    #[test]
+    fn test_clamp() {
+        let view = BBox::new(-5., -5., 10., 20.);
+        let above = BBox::of_ranges(Range::unbounded(), Range::at_least(3.));
+        assert!(!above.is_bounded());
+        assert!(view.is_bounded());
+        let x = above.clamp(&view);
+        assert!(x.is_bounded());
+        range_eq(&x.x, -5., 10.);
+        range_eq(&x.y, 3., 20.);
+        let x = BBox::new(1., 2., 3., 4.).clamp(&view);
+        range_eq(&x.x, 1., 3.);
+        range_eq(&x.y, 2., 4.);
+    }
 }
@@ -12,8 +12,8 @@ WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 See the License for the specific language governing permissions and
 limitations under the License.
 
-@file    svg_error.rs
-@brief   Errors when generating SVG output
+@file    error.rs
+@brief   Errors in the construction of colors, geometry and documents
  */
 
 //a Imports
@@ -21,8 +21,175 @@ use thiserror::Error;
 
 //a Error
 //tp Error
+/// Errors in the construction of colors, geometry, text and documents
+///
+/// An error may be given context with [Error::context] (or
+/// [ErrorContext::context] on a result); the original error is then
+/// its [std::error::Error::source]
+///
+/// Reading an input (such as an image to embed) may fail with
+/// [Error::Io]; failures writing a document are
+/// [SvgError::Io](crate::SvgError::Io)
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Invalid transformation matrix, {reason}")]
     InvalidTransformationMatrix { reason: String },
+    #[error("Unknown coordinate frame '{name}'")]
+    UnknownFrame { name: String },
+    #[error("Coordinate frame '{name}' is reserved")]
+    ReservedFrame { name: String },
+    #[error("Bad path description at '{token}', {reason}")]
+    BadPathDescription { token: String, reason: String },
+    #[error("Bad color '{color}', {reason}")]
+    BadColor { color: String, reason: String },
+    #[error("Unknown color '{name}'")]
+    UnknownColor { name: String },
+    #[error("Unknown font '{name}'")]
+    UnknownFont { name: String },
+    #[error("Bad layout, {reason}")]
+    BadLayout { reason: String },
+    #[error("Reference to unknown id '{id}'")]
+    UnknownReference { id: String },
+    #[error("Unsupported SVG version '{version}', versions supported are 1.0, 1.1 and 2.0")]
+    BadVersion { version: String },
+    #[error("Bad route, {reason}")]
+    BadRoute { reason: String },
+    #[error("Bad length '{length}', {reason}")]
+    BadLength { length: String, reason: String },
+    #[error("Bad geometric construction, {reason}")]
+    BadConstruction { reason: String },
+    #[error("IO error, {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+//ip Error
+impl Error {
+    //mp context
+    /// Wrap the error with a description of what was being done, such
+    /// as the name of the element or file
+    pub fn context<S: Into<String>>(self, context: S) -> Self {
+        Self::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    //ap root_cause
+    /// Get the original error, without any context
+    pub fn root_cause(&self) -> &Self {
+        match self {
+            Self::Context { source, .. } => source.root_cause(),
+            e => e,
+        }
+    }
+}
+
+//a ErrorContext
+//tt ErrorContext
+/// Trait for adding context to the error of a result
+pub trait ErrorContext<T> {
+    /// Wrap any error with a description of what was being done
+    fn context<S: Into<String>>(self, context: S) -> Result<T, Error>;
+    /// Wrap any error with a description generated only on failure
+    fn with_context<S: Into<String>, F: FnOnce() -> S>(self, f: F) -> Result<T, Error>;
+}
+
+//ip ErrorContext for Result
+impl<T, E: Into<Error>> ErrorContext<T> for Result<T, E> {
+    fn context<S: Into<String>>(self, context: S) -> Result<T, Error> {
+        self.map_err(|e| e.into().context(context))
+    }
+    fn with_context<S: Into<String>, F: FnOnce() -> S>(self, f: F) -> Result<T, Error> {
+        self.map_err(|e| e.into().context(f()))
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_error {
+    use super::*;
+    use std::error::Error as StdError;
+    #[test]
+    fn test_context() {
+        let r: Result<(), Error> = Err(Error::UnknownColor {
+            name: "blurple".into(),
+        });
+        let e = r.context("filling 'logo'").unwrap_err();
+        assert_eq!(e.to_string(), "filling 'logo'");
+        assert_eq!(e.source().unwrap().to_string(), "Unknown color 'blurple'");
+        assert!(matches!(e.root_cause(), Error::UnknownColor { .. }));
+
+        let r = "#12".parse::<crate::Rgba>();
+        let e = r
+            .with_context(|| format!("reading {}", "palette.txt"))
+            .unwrap_err();
+        assert_eq!(e.to_string(), "reading palette.txt");
+        assert!(matches!(e.root_cause(), Error::BadColor { .. }));
+    }
+    #[test]
+    fn test_fallible() {
+        use crate::{
+            BBox, Color, ColorDatabaseOwned, Polygon, Svg, SvgColorDatabase, SvgConfig, SvgError,
+            SvgGroup, SvgPath, SvgVersion, Wireframe,
+        };
+        assert!(matches!(
+            "3.0".parse::<SvgVersion>(),
+            Err(Error::BadVersion { .. })
+        ));
+        assert_eq!("1.1".parse::<SvgVersion>().unwrap(), SvgVersion::Version1_1);
+        let db = ColorDatabaseOwned::new();
+        assert!(matches!(
+            db.try_find_color("blurple"),
+            Err(Error::UnknownColor { .. })
+        ));
+        assert!(db.try_find_color("#102030").is_ok());
+        assert!(matches!(
+            Color::try_from_name("blurple", &SvgColorDatabase),
+            Err(Error::UnknownColor { .. })
+        ));
+        assert!(Color::try_from(("blurple", &db)).is_err());
+        assert!(Color::try_from(("#102030", &SvgColorDatabase)).is_ok());
+        let mut e = SvgGroup::new();
+        assert!(e.add_color("fill", "blurple").is_err());
+        assert_eq!(e.get_attribute("fill"), None);
+        assert!(e.add_color("fill", "red").is_ok());
+        assert!(matches!(
+            Polygon::new_polygon(4, f64::NAN, 0., 0.).try_as_paths(),
+            Err(Error::BadConstruction { .. })
+        ));
+        assert!(Polygon::new_polygon(4, 1., 0., 0.).try_as_paths().is_ok());
+        let mut w = Wireframe::new();
+        w.add_point([0., 0., 0.].into());
+        assert!(w.add_edge(0, 1).is_err());
+        let mut svg = Svg::new(SvgConfig::default());
+        svg.stack_push(SvgGroup::new());
+        assert!(matches!(
+            svg.finalize(),
+            Err(SvgError::StackInUse { depth: 1 })
+        ));
+
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut p = SvgPath::new_box(BBox::new(0., 0., f64::NAN, 1.));
+        p.add_attribute("id", None, "bar");
+        let mut g = SvgGroup::new();
+        g.push_content(p);
+        svg.contents_add_element(g);
+        let SvgError::Construction(e) = svg.finalize().unwrap_err() else {
+            panic!("expected a construction error");
+        };
+        assert_eq!(e.to_string(), "finalizing 'bar'");
+        assert!(matches!(e.root_cause(), Error::BadConstruction { .. }));
+
+        let e: SvgError = Error::BadLayout {
+            reason: "no room".into(),
+        }
+        .into();
+        assert_eq!(e.to_string(), "Bad layout, no room");
+    }
 }
@@ -0,0 +1,221 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    length.rs
+@brief   Lengths with units, such as the size of a document
+ */
+
+//a Imports
+use crate::svg::display_number_format;
+use crate::{Error, NumberFormat};
+
+//a LengthUnit
+//tp LengthUnit
+/// The unit of a [Length]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LengthUnit {
+    /// CSS pixels, of which there are 96 to the inch
+    Px,
+    /// Millimeters
+    #[default]
+    Mm,
+    /// Centimeters
+    Cm,
+    /// Inches
+    In,
+    /// Points, of which there are 72 to the inch
+    Pt,
+    /// A percentage of the size of the containing viewport
+    Percent,
+    /// User units, with no unit given
+    User,
+}
+
+//ip LengthUnit
+impl LengthUnit {
+    //ap suffix
+    /// Get the suffix of a length in the unit, such as 'mm'
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Self::Px => "px",
+            Self::Mm => "mm",
+            Self::Cm => "cm",
+            Self::In => "in",
+            Self::Pt => "pt",
+            Self::Percent => "%",
+            Self::User => "",
+        }
+    }
+
+    //ap per_inch
+    /// Get the number of the unit in an inch, or None if it is not an
+    /// absolute unit (a percentage or user units)
+    pub fn per_inch(&self) -> Option<f64> {
+        match self {
+            Self::Px => Some(96.),
+            Self::Mm => Some(25.4),
+            Self::Cm => Some(2.54),
+            Self::In => Some(1.),
+            Self::Pt => Some(72.),
+            Self::Percent | Self::User => None,
+        }
+    }
+}
+
+//a Length
+//tp Length
+/// A [Length] is a value with a unit, as used for the width and height
+/// of a document
+///
+/// A length is written as its value (with the precision of the
+/// configuration) followed by the suffix of its unit, such as '210mm'
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Length {
+    /// The value of the length, in its unit
+    pub value: f64,
+    /// The unit of the length
+    pub unit: LengthUnit,
+}
+
+//ip Length
+impl Length {
+    //fp new
+    /// Create a new length of a value in a unit
+    pub fn new(value: f64, unit: LengthUnit) -> Self {
+        Self { value, unit }
+    }
+
+    //mp convert
+    /// Convert the length to another unit, or None if either unit is
+    /// not absolute (and they differ)
+    pub fn convert(&self, unit: LengthUnit) -> Option<Self> {
+        if unit == self.unit {
+            return Some(*self);
+        }
+        let scale = unit.per_inch()? / self.unit.per_inch()?;
+        Some(Self::new(self.value * scale, unit))
+    }
+
+    //fp of_document
+    /// Get the length in a unit of a size in the coordinates of a
+    /// document, which are millimeters
+    ///
+    /// In user units the size is unchanged (so each user unit is a CSS
+    /// pixel); as a percentage it is the whole of the viewport
+    pub fn of_document(size: f64, unit: LengthUnit) -> Self {
+        match unit {
+            LengthUnit::Percent => Self::new(100., unit),
+            LengthUnit::User => Self::new(size, unit),
+            _ => Self::new(size, LengthUnit::Mm)
+                .convert(unit)
+                .unwrap_or(Self::new(size, unit)),
+        }
+    }
+
+    //mp svg_string
+    /// Format the length for an SVG attribute with a [NumberFormat]
+    pub fn svg_string(&self, number_format: &NumberFormat) -> String {
+        format!("{}{}", number_format.number(self.value), self.unit.suffix())
+    }
+}
+
+//ip Display for Length
+impl std::fmt::Display for Length {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let nf = display_number_format(fmt);
+        write!(fmt, "{}{}", nf.number(self.value), self.unit.suffix())
+    }
+}
+
+//ip FromStr for Length
+impl std::str::FromStr for Length {
+    type Err = Error;
+    /// Parse a length such as '210mm', '50%' or '12' (in user units)
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let s = s.trim();
+        let bad = |reason: &str| Error::BadLength {
+            length: s.into(),
+            reason: reason.into(),
+        };
+        let split = s
+            .trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%')
+            .len();
+        let (value, suffix) = s.split_at(split);
+        let unit = match suffix {
+            "px" => LengthUnit::Px,
+            "mm" => LengthUnit::Mm,
+            "cm" => LengthUnit::Cm,
+            "in" => LengthUnit::In,
+            "pt" => LengthUnit::Pt,
+            "%" => LengthUnit::Percent,
+            "" => LengthUnit::User,
+            _ => return Err(bad("unknown unit")),
+        };
+        let value = value
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| bad("expected a number"))?;
+        Ok(Self::new(value, unit))
+    }
+}
+
+//a Test
+#[cfg(test)]
+mod test_length {
+    use super::*;
+    #[test]
+    fn test_length() {
+        let l: Length = "25.4mm".parse().unwrap();
+        assert_eq!(l, Length::new(25.4, LengthUnit::Mm));
+        let inch = l.convert(LengthUnit::In).unwrap();
+        assert!((inch.value - 1.).abs() < 1E-12);
+        let px = l.convert(LengthUnit::Px).unwrap();
+        assert!((px.value - 96.).abs() < 1E-9);
+        assert!(l.convert(LengthUnit::Percent).is_none());
+        assert_eq!("50%".parse::<Length>().unwrap().unit, LengthUnit::Percent);
+        assert_eq!("12".parse::<Length>().unwrap().unit, LengthUnit::User);
+        assert!("12furlongs".parse::<Length>().is_err());
+        assert!("mm".parse::<Length>().is_err());
+        assert_eq!("1e1in".parse::<Length>().unwrap().value, 10.);
+        assert_eq!(
+            Length::new(210., LengthUnit::Mm).to_string(),
+            format!("{}mm", crate::svg::number_str(210.))
+        );
+        assert_eq!(
+            format!("{:.1}", Length::new(210.25, LengthUnit::Mm)),
+            "210.2mm"
+        );
+        let cm = Length::of_document(10., LengthUnit::Cm);
+        assert_eq!(cm.unit, LengthUnit::Cm);
+        assert!((cm.value - 1.).abs() < 1E-12);
+        assert_eq!(Length::of_document(10., LengthUnit::Percent).value, 100.);
+    }
+    #[test]
+    fn test_document_units() {
+        use crate::{BBox, Svg, SvgConfig, SvgPath};
+        let cfg = SvgConfig::default()
+            .set_decimal_places(Some(2), true)
+            .set_units(LengthUnit::In);
+        let mut svg = Svg::new(cfg);
+        svg.contents_add_element(SvgPath::new_box(BBox::new(0., 0., 50.8, 25.4)));
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        svg.write_svg(&mut buf).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.contains(r#"width="2in" height="1in" viewBox="0 0 50.8 25.4""#));
+    }
+}
@@ -0,0 +1,119 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    margins.rs
+@brief   Per-side margins and padding
+ */
+
+//a Margins
+//tp Margins
+/// [Margins] describe a distance for each side of a rectangle, for
+/// use as margins or padding (see [crate::BBox::expand])
+///
+/// The 'top' is the side with the smaller Y coordinate, as in SVG
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Margins {
+    /// Margin at the minimum X side
+    pub left: f64,
+    /// Margin at the minimum Y side
+    pub top: f64,
+    /// Margin at the maximum X side
+    pub right: f64,
+    /// Margin at the maximum Y side
+    pub bottom: f64,
+}
+
+//ip Margins
+impl Margins {
+    //fp new
+    /// Create new margins with each side given
+    #[must_use]
+    pub const fn new(left: f64, top: f64, right: f64, bottom: f64) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    //fp uniform
+    /// Create new margins that are the same on all sides
+    #[must_use]
+    pub const fn uniform(value: f64) -> Self {
+        Self::new(value, value, value, value)
+    }
+
+    //fp symmetric
+    /// Create new margins with one value for the left and right and
+    /// another for the top and bottom
+    #[must_use]
+    pub const fn symmetric(horizontal: f64, vertical: f64) -> Self {
+        Self::new(horizontal, vertical, horizontal, vertical)
+    }
+
+    //mp horizontal
+    /// Get the total of the left and right margins
+    #[must_use]
+    pub fn horizontal(&self) -> f64 {
+        self.left + self.right
+    }
+
+    //mp vertical
+    /// Get the total of the top and bottom margins
+    #[must_use]
+    pub fn vertical(&self) -> f64 {
+        self.top + self.bottom
+    }
+
+    //zz All done
+}
+
+//ip From<[f64; 4]> for Margins
+impl From<[f64; 4]> for Margins {
+    /// Convert from [left, top, right, bottom] - the same order as
+    /// [x0, y0, x1, y1] of a bounding box
+    fn from(m: [f64; 4]) -> Self {
+        Self::new(m[0], m[1], m[2], m[3])
+    }
+}
+
+//ip From<&[f64; 4]> for Margins
+impl From<&[f64; 4]> for Margins {
+    fn from(m: &[f64; 4]) -> Self {
+        (*m).into()
+    }
+}
+
+//ip From<&Margins> for Margins
+impl From<&Margins> for Margins {
+    fn from(m: &Margins) -> Self {
+        *m
+    }
+}
+
+//ip std::ops::Mul<f64> for Margins
+impl std::ops::Mul<f64> for Margins {
+    type Output = Self;
+    #[inline]
+    fn mul(self, scale: f64) -> Self {
+        Self::new(
+            self.left * scale,
+            self.top * scale,
+            self.right * scale,
+            self.bottom * scale,
+        )
+    }
+}
@@ -0,0 +1,58 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    polar.rs
+@brief   Polar coordinate helpers for points
+ */
+
+//a Imports
+use crate::Point;
+
+//a PolarPoint
+//tt PolarPoint
+/// Conversion of points to and from polar coordinates, with angles in
+/// degrees anticlockwise from the X axis
+///
+/// As [Point] is a type alias this is a trait, which must be in scope
+/// to use (e.g. 'Point::from_polar(r, degrees)')
+pub trait PolarPoint: Sized {
+    /// Create a point at a radius and angle from the origin
+    fn from_polar(r: f64, degrees: f64) -> Self;
+
+    /// Create a point at a radius and angle from a center
+    fn from_polar_around(center: &Self, r: f64, degrees: f64) -> Self;
+
+    /// Get the radius and angle (in the range -180 to 180) of the point
+    /// relative to the origin
+    fn to_polar(&self) -> (f64, f64);
+}
+
+//ip PolarPoint for Point
+impl PolarPoint for Point {
+    fn from_polar(r: f64, degrees: f64) -> Self {
+        let a = degrees.to_radians();
+        [r * a.cos(), r * a.sin()].into()
+    }
+
+    fn from_polar_around(center: &Self, r: f64, degrees: f64) -> Self {
+        *center + Self::from_polar(r, degrees)
+    }
+
+    fn to_polar(&self) -> (f64, f64) {
+        (
+            (self[0] * self[0] + self[1] * self[1]).sqrt(),
+            self[1].atan2(self[0]).to_degrees(),
+        )
+    }
+}
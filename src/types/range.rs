@@ -19,9 +19,14 @@ limitations under the License.
 //a Range
 //tp Range
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// This is a simple 'range' class for a single dimension
 ///
 /// min <= max for a valid range; min > max indicates an empty range
+///
+/// A range may be open-ended, with a min of -infinity and/or a max of
+/// +infinity; such ranges are useful for regions such as 'y > 3', and
+/// they can be clamped to a bounded range when that is known
 pub struct Range {
     /// Minimum coordinate of the range
     min: f64,
@@ -62,6 +67,41 @@ impl Range {
         Self { min: 0., max: -1. }
     }
 
+    //fp unbounded
+    /// Create a new range that is unbounded in both directions
+    #[must_use]
+    #[inline]
+    pub const fn unbounded() -> Self {
+        Self {
+            min: f64::NEG_INFINITY,
+            max: f64::INFINITY,
+        }
+    }
+
+    //fp at_least
+    /// Create a new range that includes all values greater than or
+    /// equal to `min`
+    #[must_use]
+    #[inline]
+    pub const fn at_least(min: f64) -> Self {
+        Self {
+            min,
+            max: f64::INFINITY,
+        }
+    }
+
+    //fp at_most
+    /// Create a new range that includes all values less than or equal
+    /// to `max`
+    #[must_use]
+    #[inline]
+    pub const fn at_most(max: f64) -> Self {
+        Self {
+            min: f64::NEG_INFINITY,
+            max,
+        }
+    }
+
     //fp is_none
     /// Return true if the range is empty
     #[inline]
@@ -69,6 +109,13 @@ impl Range {
         self.min > self.max
     }
 
+    //fp is_bounded
+    /// Return true if the range is bounded at both ends
+    #[inline]
+    pub fn is_bounded(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite()
+    }
+
     //fp of_pts
     #[must_use]
     #[inline]
@@ -117,6 +164,24 @@ impl Range {
         self
     }
 
+    //cp clamp
+    /// Replace any unbounded end of the range with the corresponding
+    /// end of `within`
+    ///
+    /// Bounded ends are not changed, so a range lying outside `within`
+    /// may become a none range
+    #[must_use]
+    #[inline]
+    pub fn clamp(mut self, within: &Range) -> Self {
+        if self.min == f64::NEG_INFINITY {
+            self.min = within.min;
+        }
+        if self.max == f64::INFINITY {
+            self.max = within.max;
+        }
+        self
+    }
+
     //cp enlarge
     /// Enlarge by an amount
     #[must_use]
@@ -186,6 +251,65 @@ impl Range {
             self
         }
     }
+
+    //mp nice_step
+    /// Get a 'nice' step (1, 2 or 5 times a power of ten) that divides
+    /// the range into approximately a given number of intervals
+    ///
+    /// Returns 0. if the range is none, unbounded, or has zero size
+    pub fn nice_step(&self, approx_count: usize) -> f64 {
+        let size = self.size();
+        if approx_count == 0 || !self.is_bounded() || size <= 0. {
+            return 0.;
+        }
+        let raw = size / (approx_count as f64);
+        let magnitude = 10.0_f64.powf(raw.log10().floor());
+        let norm = raw / magnitude;
+        let nice = if norm < 1.5 {
+            1.
+        } else if norm < 3. {
+            2.
+        } else if norm < 7. {
+            5.
+        } else {
+            10.
+        };
+        nice * magnitude
+    }
+
+    //mp ticks
+    /// Get 'nice' tick values (multiples of 1, 2 or 5 times a power of
+    /// ten) within the range, with approximately a given number of
+    /// intervals between them
+    pub fn ticks(&self, approx_count: usize) -> Vec<f64> {
+        self.ticks_step(self.nice_step(approx_count))
+    }
+
+    //mp ticks_step
+    /// Get the tick values within the range at integer multiples of a
+    /// step
+    pub fn ticks_step(&self, step: f64) -> Vec<f64> {
+        self.ticks_step_from(0., step)
+    }
+
+    //mp ticks_step_from
+    /// Get the tick values within the range at integer multiples of a
+    /// step from an origin
+    ///
+    /// There are no ticks if the range is none or unbounded, or if the
+    /// step is not positive
+    pub fn ticks_step_from(&self, origin: f64, step: f64) -> Vec<f64> {
+        let mut ticks = vec![];
+        if self.is_none() || !self.is_bounded() || step <= 0. {
+            return ticks;
+        }
+        let min = ((self.min - origin) / step).ceil() as isize;
+        let max = ((self.max - origin) / step).floor() as isize;
+        for i in min..=max {
+            ticks.push(origin + (i as f64) * step);
+        }
+        ticks
+    }
 }
 
 //ip std::ops::Add<f64> for Range
@@ -282,12 +406,7 @@ impl std::ops::Index<usize> for Range {
 
     #[inline]
     fn index(&self, index: usize) -> &Self::Output {
-        assert!(index < 2);
-        if index == 0 {
-            &self.min
-        } else {
-            &self.max
-        }
+        [&self.min, &self.max][index]
     }
 }
 
@@ -345,4 +464,38 @@ mod test_range {
         rng_eq(&Range::new(0., 4.).intersect(&Range::new(-1., 3.)), 0., 3.);
         rng_eq(&Range::new(0., 4.).intersect(&Range::new(-1., 5.)), 0., 4.);
     }
+    #[test]
+    fn test_ticks() {
+        assert_eq!(Range::new(0., 10.).nice_step(5), 2.);
+        assert_eq!(Range::new(0., 10.).nice_step(10), 1.);
+        assert_eq!(Range::new(0., 1000.).nice_step(3), 500.);
+        assert!((Range::new(0., 0.7).nice_step(4) - 0.2).abs() < 1E-12);
+        assert_eq!(
+            Range::new(-3., 11.).ticks(7),
+            vec![-2., 0., 2., 4., 6., 8., 10.]
+        );
+        assert_eq!(Range::new(0.5, 3.).ticks_step(1.), vec![1., 2., 3.]);
+        assert_eq!(
+            Range::new(0., 3.).ticks_step_from(0.5, 1.),
+            vec![0.5, 1.5, 2.5]
+        );
+        assert!(Range::none().ticks(5).is_empty());
+        assert!(Range::at_least(1.).ticks(5).is_empty());
+        assert!(Range::new(0., 1.).ticks_step(0.).is_empty());
+    }
+    #[test]
+    fn test_unbounded() {
+        assert!(Range::new(1., 2.).is_bounded());
+        assert!(!Range::at_least(3.).is_bounded());
+        assert!(!Range::at_most(3.).is_bounded());
+        assert!(!Range::unbounded().is_bounded());
+        assert!(!Range::at_least(3.).is_none());
+        let within = Range::new(0., 10.);
+        rng_eq(&Range::at_least(3.).clamp(&within), 3., 10.);
+        rng_eq(&Range::at_most(3.).clamp(&within), 0., 3.);
+        rng_eq(&Range::unbounded().clamp(&within), 0., 10.);
+        rng_eq(&Range::new(1., 2.).clamp(&within), 1., 2.);
+        assert!(Range::at_least(12.).clamp(&within).is_none());
+        rng_eq(&Range::at_least(3.).intersect(&within), 3., 10.);
+    }
 }
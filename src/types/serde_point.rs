@@ -0,0 +1,71 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    serde_point.rs
+@brief   Serialization of points for the 'serde' feature
+ */
+
+//a Imports
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Point;
+
+//a Serde functions
+//fp serialize
+/// Serialize a [Point] as a pair of numbers, for use with
+/// '#[serde(with = "crate::types::serde_point")]'
+pub fn serialize<S: Serializer>(pt: &Point, serializer: S) -> Result<S::Ok, S::Error> {
+    [pt[0], pt[1]].serialize(serializer)
+}
+
+//fp deserialize
+/// Deserialize a [Point] from a pair of numbers
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Point, D::Error> {
+    let xy = <[f64; 2]>::deserialize(deserializer)?;
+    Ok(xy.into())
+}
+
+//a Test
+#[cfg(test)]
+mod test_serde {
+    use crate::{BBox, Color, Point, Polygon, Transform};
+    #[test]
+    fn test_point() {
+        let t = Transform::of_trs([1.5, -2.].into(), 30., 2.);
+        let json = serde_json::to_string(&t).unwrap();
+        assert!(json.contains("[1.5,-2.0]"), "{json}");
+        let t: Transform = serde_json::from_str(&json).unwrap();
+        assert_eq!(t.translation()[0], 1.5);
+        assert_eq!(t.translation()[1], -2.);
+        assert_eq!(t.rotation(), 30.);
+        assert_eq!(t.scale(), 2.);
+    }
+    #[test]
+    fn test_round_trip() {
+        let bbox = BBox::new(1., 2., 3., 4.);
+        let json = serde_json::to_string(&bbox).unwrap();
+        assert_eq!(serde_json::from_str::<BBox>(&json).unwrap(), bbox);
+
+        let p = Polygon::new_polygon(5, 2., 10., 0.5) + Point::from([3., 4.]);
+        let json = serde_json::to_string(&p).unwrap();
+        let q: Polygon = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{}", p), format!("{}", q));
+
+        let c = Color::of_rgb((10_u8, 20_u8, 30_u8, 40_u8));
+        let json = serde_json::to_string(&c).unwrap();
+        let d: Color = serde_json::from_str(&json).unwrap();
+        assert_eq!(d.as_tuple_rgba(), (10, 20, 30, 40));
+        assert_eq!(*d.as_str(), *c.as_str());
+    }
+}
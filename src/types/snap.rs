@@ -0,0 +1,71 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    snap.rs
+@brief   Snapping of coordinates to a grid
+ */
+
+//a Imports
+use crate::{BBox, Point, Range};
+
+//a Snap
+//fi snap_value
+/// Snap a value to the nearest multiple of the grid; infinite values
+/// and grids of zero or less leave the value unchanged
+fn snap_value(v: f64, grid: f64) -> f64 {
+    if grid <= 0. || !v.is_finite() {
+        v
+    } else {
+        (v / grid).round() * grid
+    }
+}
+
+//tt Snap
+/// Quantization of coordinates to the nearest multiple of a grid
+/// spacing, so that (for example) hairlines land on device pixels
+///
+/// As [Point] is a type alias this is a trait, which must be in scope
+/// to use (e.g. 'pt.snap(0.5)')
+pub trait Snap {
+    /// Snap all of the coordinates to the nearest multiple of the grid
+    #[must_use]
+    fn snap(&self, grid: f64) -> Self;
+}
+
+//ip Snap for Point
+impl Snap for Point {
+    fn snap(&self, grid: f64) -> Self {
+        [snap_value(self[0], grid), snap_value(self[1], grid)].into()
+    }
+}
+
+//ip Snap for Range
+impl Snap for Range {
+    /// Snap both ends of the range; a none range remains none
+    fn snap(&self, grid: f64) -> Self {
+        if self.is_none() {
+            *self
+        } else {
+            Range::new(snap_value(self[0], grid), snap_value(self[1], grid))
+        }
+    }
+}
+
+//ip Snap for BBox
+impl Snap for BBox {
+    /// Snap all of the edges of the bbox; a none bbox remains none
+    fn snap(&self, grid: f64) -> Self {
+        BBox::of_ranges(self.x.snap(grid), self.y.snap(grid))
+    }
+}
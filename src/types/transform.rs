@@ -19,23 +19,31 @@ limitations under the License.
 //a Imports
 use geo_nd::Vector;
 
-use crate::{Error, Point};
+use crate::svg::display_number_format;
+use crate::{BBox, Error, NumberFormat, Point, Snap};
 
 //a Transform type
 //tp Transform
 /// A Transfom is a transformation applied to something - for example,
 /// applied to content to present it in its parent coordinates.
 ///
-/// The transformation is translate(rotate(scale(pt)))
+/// The transformation is translate(rotate(scale(flip(pt)))), where
+/// flip is optional and negates the Y coordinate; hence mirror images
+/// (reflections in any line) may be represented
 ///
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transform {
     /// Translation - applied last
+    #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_point"))]
     translation: Point,
     /// Rotation around the origin in *degrees*
     rotation: f64,
     /// Scale factor
     scale: f64,
+    /// If true, negate the Y coordinate (a reflection in the X axis)
+    /// before scaling - applied first
+    flip: bool,
 }
 
 //ip Default for Transform
@@ -45,6 +53,7 @@ impl std::default::Default for Transform {
             translation: Point::default(),
             rotation: 0.,
             scale: 1.,
+            flip: false,
         }
     }
 }
@@ -69,6 +78,13 @@ impl Transform {
         self.scale
     }
 
+    //ap is_flipped
+    /// Return true if the transform includes a reflection
+    #[inline]
+    pub fn is_flipped(&self) -> bool {
+        self.flip
+    }
+
     //cp of_trs
     /// Create a transform from a translation, rotation and scale
     #[inline]
@@ -78,9 +94,53 @@ impl Transform {
             translation,
             rotation,
             scale,
+            flip: false,
         }
     }
 
+    //cp of_trsf
+    /// Create a transform from a translation, rotation, scale and
+    /// optional flip of the Y coordinate
+    #[inline]
+    #[must_use]
+    pub fn of_trsf(translation: Point, rotation: f64, scale: f64, flip: bool) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+            flip,
+        }
+    }
+
+    //cp of_flip_x
+    /// Create a transform that mirrors horizontally, i.e. negates the
+    /// X coordinate (a reflection in the Y axis)
+    #[inline]
+    #[must_use]
+    pub fn of_flip_x() -> Self {
+        Self::of_trsf(Point::zero(), 180., 1., true)
+    }
+
+    //cp of_flip_y
+    /// Create a transform that mirrors vertically, i.e. negates the
+    /// Y coordinate (a reflection in the X axis)
+    #[inline]
+    #[must_use]
+    pub fn of_flip_y() -> Self {
+        Self::of_trsf(Point::zero(), 0., 1., true)
+    }
+
+    //cp of_reflection
+    /// Create a transform that is a reflection in the line through
+    /// `pt` at an angle of `degrees` to the X axis
+    #[must_use]
+    pub fn of_reflection(pt: Point, degrees: f64) -> Self {
+        // Reflection in a line through the origin at angle a is
+        // rotate(2a) of flip; then conjugate by the translation to pt
+        let linear = Self::of_trsf(Point::zero(), 2. * degrees, 1., true);
+        Self::of_trsf(pt - linear.apply(pt), 2. * degrees, 1., true)
+    }
+
     //fp of_rotation
     /// Create a transform from a rotation
     #[inline]
@@ -89,6 +149,16 @@ impl Transform {
         Self::of_trs(Point::zero(), rotation, 1.)
     }
 
+    //cp of_rotation_about
+    /// Create a transform that is a rotation by `degrees` about a
+    /// `center` point
+    #[inline]
+    #[must_use]
+    pub fn of_rotation_about(degrees: f64, center: Point) -> Self {
+        let rotation = Self::of_rotation(degrees);
+        Self::of_trs(center - rotation.apply(center), degrees, 1.)
+    }
+
     //cp of_translation
     /// Create a transform from a translation
     #[inline]
@@ -109,7 +179,9 @@ impl Transform {
     ///
     /// Also the determinant of this is cs*cs + ss*ss = scale^2 * (cos^2+sin^2)
     ///
-    /// Hence the determinant must be >0 and its square root is the scale
+    /// Hence the determinant must be >0 and its square root is the
+    /// scale; if the determinant is negative then the matrix includes
+    /// a flip, which is (cs ss) (ss -cs) and has determinant -scale^2
     ///
     pub fn of_matrix(matrix: &[f64]) -> Result<Self, Error> {
         if matrix.len() != 9 {
@@ -132,27 +204,36 @@ impl Transform {
             })?
         }
         let sc2 = matrix[0] * matrix[4] - matrix[1] * matrix[3];
-        if sc2 < -1.0E-9 {
-            Err(Error::InvalidTransformationMatrix {
-                reason: "determinant (scale squared) is negative".into(),
-            })?
-        }
-        let sc = {
-            if sc2 < 0. {
-                0.
-            } else {
-                sc2.sqrt()
-            }
-        };
-        let angle = matrix[3].atan2(matrix[4]).to_degrees();
+        let flip = sc2 < -1.0E-9;
+        let sc = sc2.abs().sqrt();
+        // Remove any flip by negating the second column
+        let m4 = if flip { -matrix[4] } else { matrix[4] };
+        let angle = matrix[3].atan2(m4).to_degrees();
 
-        Ok(Self::of_trs([dx, dy].into(), angle, sc))
+        Ok(Self::of_trsf([dx, dy].into(), angle, sc, flip))
     }
 
     //mp is_identity
     /// Return true if this is an identity transform
     pub fn is_identity(&self) -> bool {
-        self.rotation == 0. && self.scale == 1. && self.translation.is_zero()
+        self.rotation == 0. && self.scale == 1. && !self.flip && self.translation.is_zero()
+    }
+
+    //mp inverse
+    /// Return the inverse of this transform
+    ///
+    /// The transform must not have a scale of zero
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        // flip.rotate(-r) = rotate(r).flip
+        let rotation = if self.flip {
+            self.rotation
+        } else {
+            -self.rotation
+        };
+        let scale = 1. / self.scale;
+        let dxy = Self::of_trsf(Point::zero(), rotation, scale, self.flip).apply(self.translation);
+        Self::of_trsf(-dxy, rotation, scale, self.flip)
     }
 
     //mp to_matrix
@@ -171,11 +252,15 @@ impl Transform {
         // i.e.
         // R.S    =   c*sc -s*sc  0;  s*sc  c*sc  0;  0  0  1
         // T.R.S  =   c*sc -s*sc  dx;  s*sc  c*sc  dy;  0  0  1
+        //
+        // and if flipped the flip matrix (1 0 0; 0 -1 0; 0 0 1)
+        // negates the second column
+        let f = if self.flip { -1. } else { 1. };
         result[0] = sc * c;
-        result[1] = -sc * s;
+        result[1] = -sc * s * f;
         result[2] = dx;
         result[3] = sc * s;
-        result[4] = sc * c;
+        result[4] = sc * c * f;
         result[5] = dy;
         result[8] = 1.;
         result
@@ -190,6 +275,31 @@ impl Transform {
         [m[0] * x + m[1] * y + m[2], m[3] * x + m[4] * y + m[5]].into()
     }
 
+    //mp apply_slice
+    /// Apply this transform to a slice of points in place
+    pub fn apply_slice(&self, pts: &mut [Point]) {
+        let m = self.to_matrix();
+        for pt in pts.iter_mut() {
+            let x = pt[0];
+            let y = pt[1];
+            *pt = [m[0] * x + m[1] * y + m[2], m[3] * x + m[4] * y + m[5]].into();
+        }
+    }
+
+    //mp apply_bbox
+    /// Apply this transform to a bounding box, returning the bounding
+    /// box of the four transformed corners
+    ///
+    /// A none bounding box remains none
+    pub fn apply_bbox(&self, bbox: &BBox) -> BBox {
+        if bbox.is_none() {
+            return BBox::none();
+        }
+        let mut corners = bbox.add_as_points(false, Vec::with_capacity(4));
+        self.apply_slice(&mut corners);
+        BBox::of_points(&corners)
+    }
+
     //mp apply_to_transform
     /// Apply this transform to another transform, returning a new
     /// transform
@@ -201,30 +311,121 @@ impl Transform {
     // Combine we get _ _ cs.DX-ss.DY+dx ; _ _ ss.DX+cs.DY+dy; 0 0 1
     // i.e. the resultant translation is:
     // self.rotate_scale(other.translate)+self.translate
+    //
+    // If self is flipped then its flip reverses the direction of the
+    // rotation of other, as flip.rotate(r) = rotate(-r).flip
     pub fn apply_to_transform(&self, other: &Self) -> Self {
-        let mut dxy = other.translation;
-        dxy.rotate_around(&Point::zero(), self.rotation, 0, 1);
-        dxy = dxy * self.scale + self.translation;
-        Self::of_trs(
+        let dxy = self.apply(other.translation);
+        let rotation = if self.flip {
+            self.rotation - other.rotation
+        } else {
+            self.rotation + other.rotation
+        };
+        Self::of_trsf(
             dxy,
-            self.rotation + other.rotation,
+            rotation,
             self.scale * other.scale,
+            self.flip != other.flip,
         )
     }
 
+    //mp rotation_center
+    /// If the transform has a rotation, return the center about which
+    /// the rotation (and translation) is performed
+    ///
+    /// translate(t).rotate(R) is rotation about c iff t = c - R.c,
+    /// i.e. c = inverse(I-R).t
+    pub fn rotation_center(&self) -> Option<Point> {
+        let s = self.rotation.to_radians().sin();
+        let c = self.rotation.to_radians().cos();
+        let det = (1. - c) * (1. - c) + s * s;
+        if det < 1.0E-9 {
+            None
+        } else {
+            let (tx, ty) = (self.translation[0], self.translation[1]);
+            Some(
+                [
+                    ((1. - c) * tx - s * ty) / det,
+                    (s * tx + (1. - c) * ty) / det,
+                ]
+                .into(),
+            )
+        }
+    }
+
+    //cp align_for_stroke
+    /// Adjust the translation of a transform that maps to device
+    /// pixels of a given size, so that lines with a given stroke width
+    /// at integer coordinates are drawn crisply on device pixels
+    ///
+    /// A stroke that covers an odd number of device pixels is
+    /// centered on the middle of a pixel, and one that covers an even
+    /// number is centered on a pixel boundary; this can only be
+    /// effective for rotations that are multiples of 90 degrees
+    #[must_use]
+    pub fn align_for_stroke(mut self, stroke_width: f64, pixel_size: f64) -> Self {
+        if pixel_size <= 0. {
+            return self;
+        }
+        let pixels = (stroke_width * self.scale / pixel_size).round() as i64;
+        let offset = if pixels % 2 == 1 { pixel_size / 2. } else { 0. };
+        let offset = Point::from_array([offset, offset]);
+        self.translation = (self.translation - offset).snap(pixel_size) + offset;
+        self
+    }
+
     //fp as_svg_attribute_string
+    /// Generate the string for an SVG 'transform' attribute
+    ///
+    /// If there is both a translation and a rotation then this uses
+    /// the compact 'rotate(a cx cy)' form; for rotations of less than a
+    /// degree the center is so far away that rounding the angle would
+    /// move the element, so then the translation is kept separate
+    ///
+    /// Numbers use the default [NumberFormat]
     pub fn as_svg_attribute_string(&self) -> String {
+        self.svg_attribute_string(&NumberFormat::default())
+    }
+
+    //fp svg_attribute_string
+    /// Generate the string for an SVG 'transform' attribute, with
+    /// numbers in a [NumberFormat]
+    pub fn svg_attribute_string(&self, number_format: &NumberFormat) -> String {
+        let number_str = |v| number_format.number(v);
         let mut r = String::new();
         let dxy = self.translation;
-        if dxy[0] != 0. || dxy[1] != 0. {
-            r.push_str(&format!("translate({:.4} {:.4}) ", dxy[0], dxy[1]));
-        }
-        if self.rotation != 0. {
-            r.push_str(&format!("rotate({:.4}) ", self.rotation));
-            dbg!(&r);
+        let center = {
+            let angle = (self.rotation + 180.).rem_euclid(360.) - 180.;
+            if dxy.is_zero() || angle.abs() < 1. {
+                None
+            } else {
+                self.rotation_center()
+            }
+        };
+        if let Some(center) = center {
+            r.push_str(&format!(
+                "rotate({} {} {}) ",
+                number_str(self.rotation),
+                number_str(center[0]),
+                number_str(center[1])
+            ));
+        } else {
+            if dxy[0] != 0. || dxy[1] != 0. {
+                r.push_str(&format!(
+                    "translate({} {}) ",
+                    number_str(dxy[0]),
+                    number_str(dxy[1])
+                ));
+            }
+            if self.rotation != 0. {
+                r.push_str(&format!("rotate({}) ", number_str(self.rotation)));
+            }
         }
         if self.scale != 1. {
-            r.push_str(&format!("scale({:.4}) ", self.scale));
+            r.push_str(&format!("scale({}) ", number_str(self.scale)));
+        }
+        if self.flip {
+            r.push_str("scale(1 -1) ");
         }
         r
     }
@@ -263,6 +464,55 @@ impl std::ops::Mul<f64> for Transform {
     }
 }
 
+//ip std::ops::Mul<Transform> for Transform
+impl std::ops::Mul<Transform> for Transform {
+    type Output = Self;
+    /// Compose two transforms; `parent * child` is the transform that
+    /// applies `child` and then `parent`
+    #[inline]
+    fn mul(self, other: Transform) -> Self {
+        self.apply_to_transform(&other)
+    }
+}
+
+//ip std::ops::Mul<&Transform> for &Transform
+impl std::ops::Mul<&Transform> for &Transform {
+    type Output = Transform;
+    #[inline]
+    fn mul(self, other: &Transform) -> Transform {
+        self.apply_to_transform(other)
+    }
+}
+
+//ip std::ops::MulAssign<Transform> for Transform
+impl std::ops::MulAssign<Transform> for Transform {
+    /// Apply another transform *inside* this one (i.e. `t *= child`)
+    #[inline]
+    fn mul_assign(&mut self, other: Transform) {
+        *self = self.apply_to_transform(&other);
+    }
+}
+
+//ip std::ops::Mul<Point> for Transform
+impl std::ops::Mul<Point> for Transform {
+    type Output = Point;
+    /// Apply the transform to a point
+    #[inline]
+    fn mul(self, pt: Point) -> Point {
+        self.apply(pt)
+    }
+}
+
+//ip std::ops::Mul<Point> for &Transform
+impl std::ops::Mul<Point> for &Transform {
+    type Output = Point;
+    /// Apply the transform to a point
+    #[inline]
+    fn mul(self, pt: Point) -> Point {
+        self.apply(pt)
+    }
+}
+
 //ip std::ops::Div<f64> for Transform
 impl std::ops::Div<f64> for Transform {
     type Output = Self;
@@ -279,20 +529,23 @@ impl std::fmt::Display for Transform {
     //mp fmt - format a `Transform` for display
     /// Display the `Transform` in a human-readable form
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        if self.translation.is_zero() && self.rotation == 0. && self.scale == 1. {
+        let nf = display_number_format(f);
+        if self.is_identity() {
             write!(f, "<identity>")
-        } else if self.rotation == 0. && self.scale == 1. {
+        } else if self.rotation == 0. && self.scale == 1. && !self.flip {
             write!(
                 f,
-                "<+({:.4}, {:.4})>",
-                self.translation[0], self.translation[1]
+                "<+({}, {})>",
+                nf.number(self.translation[0]),
+                nf.number(self.translation[1])
             )
         } else {
             if !self.translation.is_zero() {
                 write!(
                     f,
-                    "<+({:.4}, {:.4})>",
-                    self.translation[0], self.translation[1]
+                    "<+({}, {})>",
+                    nf.number(self.translation[0]),
+                    nf.number(self.translation[1])
                 )?
             };
             if self.rotation != 0. {
@@ -301,6 +554,9 @@ impl std::fmt::Display for Transform {
             if self.scale != 1. {
                 write!(f, "<*{}>", self.scale)?
             };
+            if self.flip {
+                write!(f, "<flip>")?
+            };
             Ok(())
         }
     }
@@ -356,7 +612,7 @@ mod tests {
         let okay = m
             .iter()
             .zip(e.iter())
-            .fold(true, |acc, (m, e)| (acc && approx_eq(*m, *e)));
+            .fold(true, |acc, (m, e)| acc && approx_eq(*m, *e));
         assert!(okay, "Matrix {:?} expected {:?}", m, e);
     }
     #[test]
@@ -396,7 +652,7 @@ mod tests {
     #[test]
     fn test_2() {
         // Note matrix of 0. always produces a transform of 0.0., 0., 0.
-        for (x, y) in vec![
+        for (x, y) in [
             (0., 0.),
             (0., 1.),
             (1., 0.),
@@ -404,8 +660,8 @@ mod tests {
             (-1., 0.),
             (-1., -1.),
         ] {
-            for r in vec![0., 45., 90., 135.] {
-                for s in vec![1., 5., 0.1] {
+            for r in [0., 45., 90., 135.] {
+                for s in [1., 5., 0.1] {
                     // cannot use 0.
                     let t = Transform::of_trs(Point::from_array([x, y]), r, s);
                     let m = t.to_matrix();
@@ -415,4 +671,113 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn test_flip() {
+        let p = Point::from_array([3., 2.]);
+        let q = Transform::of_flip_x().apply(p);
+        assert!(approx_eq(q[0], -3.) && approx_eq(q[1], 2.), "{:?}", q);
+        let q = Transform::of_flip_y().apply(p);
+        assert!(approx_eq(q[0], 3.) && approx_eq(q[1], -2.), "{:?}", q);
+        // Reflect in the line y=x+1
+        let t = Transform::of_reflection([0., 1.].into(), 45.);
+        let q = t.apply(p);
+        assert!(approx_eq(q[0], 1.) && approx_eq(q[1], 4.), "{:?}", q);
+        let q = t.apply(q);
+        assert!(approx_eq(q[0], 3.) && approx_eq(q[1], 2.), "{:?}", q);
+        // Round trip through a matrix
+        let t = Transform::of_trsf([1., 2.].into(), 30., 2., true);
+        let t1 = Transform::of_matrix(&t.to_matrix()).unwrap();
+        assert!(t1.is_flipped());
+        check_transform(&t1, 1., 2., 30., 2.);
+        // Composition and inverse
+        let t0 = Transform::of_trs([-1., 4.].into(), 70., 0.5);
+        for (a, b) in [(t, t0), (t0, t), (t, t)] {
+            let c = a.apply_to_transform(&b);
+            let q0 = c.apply(p);
+            let q1 = a.apply(b.apply(p));
+            assert!(approx_eq(q0[0], q1[0]) && approx_eq(q0[1], q1[1]));
+            let q2 = c.inverse().apply(q0);
+            assert!(approx_eq(q2[0], p[0]) && approx_eq(q2[1], p[1]));
+        }
+    }
+    #[test]
+    fn test_rotation_about() {
+        let t = Transform::of_rotation_about(90., [10., 5.].into());
+        let p = t.apply([12., 5.].into());
+        assert!(approx_eq(p[0], 10.) && approx_eq(p[1], 7.), "{:?}", p);
+        let c = t.rotation_center().unwrap();
+        assert!(approx_eq(c[0], 10.) && approx_eq(c[1], 5.), "{:?}", c);
+        assert_eq!(
+            t.as_svg_attribute_string(),
+            "rotate(90.0000 10.0000 5.0000) "
+        );
+        assert_eq!(
+            Transform::of_rotation(30.).as_svg_attribute_string(),
+            "rotate(30.0000) "
+        );
+        assert_eq!(
+            Transform::of_translation([1., 2.].into()).as_svg_attribute_string(),
+            "translate(1.0000 2.0000) "
+        );
+        // Near-zero rotations do not use a distant rotation center
+        let t = Transform::of_trs([1., 2.].into(), 0.00001, 1.);
+        assert_eq!(
+            t.as_svg_attribute_string(),
+            "translate(1.0000 2.0000) rotate(0.0000) "
+        );
+        let t = Transform::of_trs([1., 2.].into(), 359.5, 1.);
+        assert!(t.as_svg_attribute_string().starts_with("translate("));
+    }
+    #[test]
+    fn test_mul() {
+        let parent = Transform::of_trs([4., 5.].into(), 90., 2.);
+        let child = Transform::of_trs([1., 0.].into(), 45., 0.5);
+        let p = Point::from_array([1., 2.]);
+        let q0 = (parent * child) * p;
+        let q1 = parent * (child * p);
+        assert!(approx_eq(q0[0], q1[0]) && approx_eq(q0[1], q1[1]));
+        check_transform(&(parent * child), 4., 7., 135., 1.);
+        let mut t = parent;
+        t *= child;
+        check_transform(&t, 4., 7., 135., 1.);
+        // Multiplying references is supported too
+        #[allow(clippy::op_ref)]
+        let t = &parent * &parent.inverse();
+        check_transform(&t, 0., 0., 0., 1.);
+    }
+    #[test]
+    fn test_apply_bbox() {
+        let t = Transform::of_rotation_about(90., [1., 1.].into());
+        let b = t.apply_bbox(&BBox::new(1., 1., 3., 2.));
+        assert!(approx_eq(b.x[0], 0.) && approx_eq(b.x[1], 1.), "{}", b);
+        assert!(approx_eq(b.y[0], 1.) && approx_eq(b.y[1], 3.), "{}", b);
+        assert!(t.apply_bbox(&BBox::none()).is_none());
+        let mut pts: Vec<Point> = vec![[1., 1.].into(), [2., 1.].into()];
+        t.apply_slice(&mut pts);
+        assert!(approx_eq(pts[0][0], 1.) && approx_eq(pts[0][1], 1.));
+        assert!(approx_eq(pts[1][0], 1.) && approx_eq(pts[1][1], 2.));
+    }
+    #[test]
+    fn test_snap() {
+        let t = Transform::of_trs([10.3, 4.9].into(), 0., 2.);
+        let a = t.align_for_stroke(0.5, 1.);
+        assert!(approx_eq(a.translation()[0], 10.5) && approx_eq(a.translation()[1], 4.5));
+        let a = t.align_for_stroke(1., 1.);
+        assert!(approx_eq(a.translation()[0], 10.) && approx_eq(a.translation()[1], 5.));
+        let p: Point = [1.26, -0.74].into();
+        let p = p.snap(0.5);
+        assert!(approx_eq(p[0], 1.5) && approx_eq(p[1], -0.5));
+        let b = BBox::new(0.2, 0.8, 2.6, 3.1).snap(1.);
+        assert!(approx_eq(b.x[0], 0.) && approx_eq(b.x[1], 3.));
+        assert!(approx_eq(b.y[0], 1.) && approx_eq(b.y[1], 3.));
+        assert!(BBox::none().snap(1.).is_none());
+    }
+    #[test]
+    fn test_inverse() {
+        let t = Transform::of_trs(Point::from_array([3., -2.]), 30., 2.);
+        let ti = t.inverse();
+        let p = ti.apply(t.apply(Point::from_array([1., 5.])));
+        assert!(approx_eq(p[0], 1.), "Inverse {} of {} failed", ti, t);
+        assert!(approx_eq(p[1], 5.), "Inverse {} of {} failed", ti, t);
+    }
 }
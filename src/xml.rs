@@ -1,50 +1,140 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    xml.rs
+@brief   Output of the events of a document through the xml-rs writer
+ */
+
 //a Imports
-use xml::common::XmlVersion as XmlWriteVersion;
-use xml::namespace::Namespace as XmlWriteNamespace;
-use xml::write::events::XmlEvent as XmlWriteEvent;
-
-//a Event
-pub struct NamespaceName<'a> {
-    name: &'a str,
-    prefix: Option<&'a str>,
+use std::borrow::Cow;
+use std::io::Write;
+
+use ::xml::attribute::Attribute;
+use ::xml::common::XmlVersion;
+use ::xml::name::Name;
+use ::xml::namespace::{Namespace, NS_NO_PREFIX};
+use ::xml::writer::{EventWriter, XmlEvent as XmlWriteEvent};
+
+use crate::{NamespaceName, SvgError, XmlEvent, XmlProlog};
+
+//a Functions
+//fi xml_name
+/// Get the xml-rs name of a [NamespaceName]; the namespace URI is left
+/// for xml-rs to resolve from the prefix
+fn xml_name<'x>(name: &'x NamespaceName) -> Name<'x> {
+    Name {
+        local_name: name.name(),
+        namespace: None,
+        prefix: name.prefix(),
+    }
 }
 
-impl<'a> From<(XmlEvent<'a>, Cow<'a, XmlWriteNamespace>)> for xml_write::XmlEvent<'a> {
-    fn from(xml_event_ns: (XmlEvent<'a>, XmlWriteNamespace)) -> Option<xml_write::XmlEvent<'a>> {
-        let (xml_event, namespace) = xml_event_ns;
-        match xml_event {
-            XmlEvent::StartDocument => Some(XmlWriteEvent::StartDocument {
-                version: XmlWriteVersion::Version11,
-                encoding: Some("utf8"),
-                standalone: None,
-            }),
-            XmlEvent::EndDocument => None,
-            XmlEvent::StartElement { name, attributes } => Some(XmlWriteEvent::StartElement {
-                name: name.into_xml_name(),
-                attributes: attributes.into_xml_attributes(),
-                namespace: namespace,
-            }),
-            XmlEvent::EndElement => Some(XmlWriteEvent::EndElement { None }),
-            XmlEvent::Characters(s) => Some(XmlWriteEvent::Characters(s)),
+//fp write_events
+/// Write the events of a document through an xml-rs event writer
+///
+/// The 'xmlns' and 'xmlns:<prefix>' attributes of an element become
+/// the namespace mappings of its start event, so that xml-rs tracks
+/// and declares them; all other attributes are passed through with
+/// their prefixes. Escaping of attribute values and characters is
+/// performed by xml-rs.
+///
+/// The document starts with the prolog given; if it has no XML
+/// declaration then whether xml-rs writes one is determined by the
+/// configuration of the writer.
+pub(crate) fn write_events<'a: 'x, 'x, W: Write, I: Iterator<Item = XmlEvent<'a, 'x>>>(
+    events: I,
+    writer: &mut EventWriter<W>,
+    prolog: &XmlProlog,
+) -> Result<(), SvgError> {
+    for event in events {
+        match event {
+            XmlEvent::StartDocument => {
+                if prolog.declaration {
+                    let version = if prolog.version == "1.1" {
+                        XmlVersion::Version11
+                    } else {
+                        XmlVersion::Version10
+                    };
+                    writer.write(XmlWriteEvent::StartDocument {
+                        version,
+                        encoding: prolog.encoding.as_deref(),
+                        standalone: prolog.standalone,
+                    })?;
+                }
+                // xml-rs has no document type event, so it is written
+                // directly
+                if let Some(doctype) = prolog.doctype_xml() {
+                    writeln!(writer.inner_mut(), "{}", doctype)?;
+                }
+            }
+            XmlEvent::EndDocument => (),
+            XmlEvent::StartElement(e) => {
+                let name = e.ns_name();
+                let mut namespace = Namespace::empty();
+                let mut attributes = vec![];
+                let written = e.written_attributes();
+                for (n, v) in written.iter() {
+                    match (n.prefix(), n.name()) {
+                        (None, "xmlns") => {
+                            namespace.put(NS_NO_PREFIX, v.as_str());
+                        }
+                        (Some("xmlns"), prefix) => {
+                            namespace.put(prefix, v.as_str());
+                        }
+                        _ => attributes.push(Attribute {
+                            name: xml_name(n),
+                            value: v,
+                        }),
+                    }
+                }
+                writer.write(XmlWriteEvent::StartElement {
+                    name: xml_name(&name),
+                    attributes: Cow::Owned(attributes),
+                    namespace: Cow::Owned(namespace),
+                })?;
+            }
+            XmlEvent::Characters(e) => writer.write(XmlWriteEvent::Characters(e.characters()))?,
+            XmlEvent::EndElement(_) => writer.write(XmlWriteEvent::EndElement { name: None })?,
         }
     }
+    Ok(())
+}
+
+//a Test
+#[cfg(test)]
+mod test_xml {
+    use ::xml::writer::EmitterConfig;
+
+    use crate::{Svg, SvgConfig, SvgText};
+    #[test]
+    fn test_write() {
+        let mut svg = Svg::new(SvgConfig::default());
+        let mut t = SvgText::new([0., 0.].into(), "Fish & Chips");
+        t.add_attribute("href", Some("xlink"), "#a");
+        svg.contents_add_element(t);
+        svg.finalize().unwrap();
+        svg.generate_diagram().unwrap();
+        let mut buf = vec![];
+        let mut writer = EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buf);
+        svg.write_xml_rs(&mut writer).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(r#"xmlns="http://www.w3.org/2000/svg""#));
+        assert!(xml.contains(r##"xlink:href="#a""##));
+        assert!(xml.contains("Fish &amp; Chips"));
+        assert!(xml.trim_end().ends_with("</svg>"));
+    }
 }
-// NamespaceName
-//
-// into_xml_name( &'a self) -> xml_writer::Name<'a>
-// into_xml_namespace( &'a self) -> Cow<'a, xml_writer::Namespace>
-// into_xml_attributes( &'a Vec<NamespaceName<'a>, &'a str> ) -> Cow<'a, [xml_writer::Attribute<'a>]>>
-//
-// pub struct Name<'a> {
-//     pub local_name: &'a str,
-//     pub namespace: Option<&'a str>, = None
-//     pub prefix: Option<&'a str>,
-// }
-
-// pub struct Namespace(pub BTreeMap<String, String>);
-// pub struct Attribute<'a> {
-//     pub name: Name<'a>,
-//     pub value: &'a str,
-// }
-//
-// StartElement : Contents of the namespace mapping at this point of the document.
@@ -14,19 +14,19 @@ fn test_0() {
     let mut e = SvgPath::new_path(b, true);
     e.add_color("fill", "red");
     svg.stack_push(e);
-    svg.contents_take_stack();
+    svg.contents_take_stack().unwrap();
 
     let b = BezierPath::of_ellipse([0., 0.].into(), 10., 1.40, 35.0);
     let mut e = SvgPath::new_path(b, true);
     e.add_color("fill", (0., 1.0, 0.));
     e.apply_transform(&(Transform::of_rotation(30.0) + [20., 20.].into()));
     svg.stack_push(e);
-    svg.contents_take_stack();
+    svg.contents_take_stack().unwrap();
 
     let mut stdout = std::io::stdout();
     use indent_display::IndentedDisplay;
     let mut ind = indent_display::Indenter::new(&mut stdout, "  ", &svg_nd::IndentOpt());
-    svg.finalize();
+    svg.finalize().unwrap();
     svg.indent(&mut ind);
     svg.generate_diagram();
     for e in svg.iter_events() {
@@ -47,19 +47,19 @@ fn test_0() {
     let mut e = SvgPath::new_path(b, true);
     e.add_color("fill", "red");
     svg.stack_push(e);
-    svg.contents_take_stack();
+    svg.contents_take_stack().unwrap();
 
     let b = BezierPath::of_ellipse([0., 0.].into(), 10., 1.40, 35.0);
     let mut e = SvgPath::new_path(b, true);
     e.add_color("fill", (0., 1.0, 0.));
     e.apply_transform(&(Transform::of_rotation(30.0) + [20., 20.].into()));
     svg.stack_push(e);
-    svg.contents_take_stack();
+    svg.contents_take_stack().unwrap();
 
     let mut stdout = std::io::stdout();
     use indent_display::IndentedDisplay;
     let mut ind = indent_display::Indenter::new(&mut stdout, "  ", &svg_nd::IndentOpt());
-    svg.finalize();
+    svg.finalize().unwrap();
     svg.indent(&mut ind);
     svg.generate_diagram();
     for e in svg.iter_events() {